@@ -4,6 +4,7 @@ OpenAI-compatible API Handler
 对应 One API 的 router/relay.go + controller/relay.go
 """
 import json
+import gzip
 import asyncio
 import sys
 import os
@@ -143,7 +144,8 @@ class AIRequestHandler(BaseHTTPRequestHandler):
             elif path == '/health':
                 # 健康检查静默处理（减少日志）
                 try:
-                    self._send_json_response({"status": "ok"})
+                    # accept_encoding: 声明支持 gzip 压缩的请求体
+                    self._send_json_response({"status": "ok", "accept_encoding": ["gzip"]})
                 except Exception as e:
                     elapsed = (time.time() - request_start_time) * 1000
                     print(f"[HANDLER] [HEALTH] [ERROR] /health 响应发送失败 (耗时: {elapsed:.2f}ms): {type(e).__name__}: {e}", file=sys.stderr, flush=True)
@@ -260,6 +262,8 @@ class AIRequestHandler(BaseHTTPRequestHandler):
                     return
                 
                 request_body = self.rfile.read(content_length)
+                if self.headers.get('Content-Encoding', '').strip().lower() == 'gzip':
+                    request_body = gzip.decompress(request_body)
                 request_data = json.loads(request_body.decode('utf-8'))
                 
                 model_id = request_data.get('model')
@@ -284,7 +288,7 @@ class AIRequestHandler(BaseHTTPRequestHandler):
                     user=request_data.get('user')
                 )
                 
-            except (ValueError, KeyError, json.JSONDecodeError, UnicodeDecodeError) as e:
+            except (ValueError, KeyError, json.JSONDecodeError, UnicodeDecodeError, OSError, EOFError) as e:
                 print(f"[REQUEST-{request_id}] [STEP-1] 请求解析错误: {type(e).__name__}: {e}", file=sys.stderr, flush=True)
                 traceback.print_exc(file=sys.stderr)
                 self._send_error(400, f"Invalid request: {self._sanitize_error(str(e))}")
@@ -6,6 +6,7 @@ AI Gateway 主服务
 import sys
 import io
 import os
+import socket
 import traceback
 from http.server import HTTPServer
 from typing import Optional
@@ -48,6 +49,9 @@ class GatewayHTTPServer(HTTPServer):
     
     def __init__(self, server_address, router: Router):
         self.router = router
+        # IPv6 地址需要使用 AF_INET6 套接字
+        if ':' in server_address[0]:
+            self.address_family = socket.AF_INET6
         super().__init__(server_address, self._make_handler)
     
     def _make_handler(self, *args, **kwargs):
@@ -65,13 +69,14 @@ def safe_print(*args, **kwargs):
         pass
 
 
-def run_server(port: int = 8765, config_path: Optional[str] = None):
+def run_server(port: int = 8765, config_path: Optional[str] = None, host: str = '127.0.0.1'):
     """
     启动 AI Gateway 服务
     
     Args:
         port: 服务端口
         config_path: 配置文件路径
+        host: 绑定地址（IPv4 或 IPv6）
     """
     try:
         # 静默初始化（减少启动日志）
@@ -96,7 +101,7 @@ def run_server(port: int = 8765, config_path: Optional[str] = None):
             sys.exit(1)
         
         # 启动 HTTP 服务器
-        server_address = ('127.0.0.1', port)
+        server_address = (host, port)
         
         try:
             httpd = GatewayHTTPServer(server_address, router)
@@ -206,7 +211,8 @@ if __name__ == '__main__':
     parser = argparse.ArgumentParser(description='AI Gateway Service')
     parser.add_argument('--port', type=int, default=8765, help='服务端口 (默认: 8765)')
     parser.add_argument('--config', type=str, default=None, help='配置文件路径')
+    parser.add_argument('--host', type=str, default='127.0.0.1', help='绑定地址 (默认: 127.0.0.1)')
     args = parser.parse_args()
     
-    run_server(port=args.port, config_path=args.config)
+    run_server(port=args.port, config_path=args.config, host=args.host)
 
@@ -0,0 +1,64 @@
+// Worker 能力缓存：/v1/models 获取的能力信息按网关版本（脚本 + 模型配置的哈希）保存到磁盘，
+// 版本未变化时重启后直接恢复，无需重新探测
+use super::pool::WorkerCapability;
+use crate::utils::{get_config_dir, read_json_with_backup, sha256_file, write_file_atomic};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 能力缓存文件名（位于 .config 目录下）
+const CAPABILITY_CACHE_FILE: &str = "worker_capability.json";
+
+/// 磁盘上的能力缓存
+#[derive(Debug, Serialize, Deserialize)]
+struct CapabilityCacheFile {
+    /// 生成缓存时的网关版本
+    version: String,
+    capability: WorkerCapability,
+}
+
+fn cache_path() -> PathBuf {
+    get_config_dir().join(CAPABILITY_CACHE_FILE)
+}
+
+/// 网关版本：脚本与模型配置（存在时）内容哈希的组合，任一变化都会使缓存失效
+pub(crate) fn gateway_version(script_path: &Path, models_config_path: &Path) -> Option<String> {
+    let script_hash = sha256_file(script_path).ok()?;
+    let config_hash = if models_config_path.is_file() {
+        sha256_file(models_config_path).ok()?
+    } else {
+        String::new()
+    };
+    Some(format!(
+        "{}-{}",
+        &script_hash[..16.min(script_hash.len())],
+        &config_hash[..16.min(config_hash.len())]
+    ))
+}
+
+/// 读取与指定版本匹配的能力缓存
+pub(crate) fn load_capability(version: &str) -> Option<WorkerCapability> {
+    let cached = read_json_with_backup::<CapabilityCacheFile>(&cache_path())?;
+    (cached.version == version && !cached.capability.supported_models.is_empty())
+        .then_some(cached.capability)
+}
+
+/// 保存能力缓存（内容未变化时跳过写入）
+pub(crate) fn save_capability(version: &str, capability: &WorkerCapability) {
+    if load_capability(version).is_some_and(|cached| {
+        cached.supported_models == capability.supported_models
+            && cached.supports_tools == capability.supports_tools
+            && cached.max_context == capability.max_context
+    }) {
+        return;
+    }
+    let cached = CapabilityCacheFile {
+        version: version.to_string(),
+        capability: capability.clone(),
+    };
+    let result = serde_json::to_string_pretty(&cached)
+        .map_err(|e| format!("序列化能力缓存失败: {}", e))
+        .and_then(|content| write_file_atomic(&cache_path(), content.as_bytes()));
+    if let Err(e) = result {
+        log::warn!("[Gateway Pool] 保存 Worker 能力缓存失败: {}", e);
+    }
+}
@@ -0,0 +1,125 @@
+// 转发请求的内容编码：按调用方要求 gzip 压缩请求体（仅在 Worker 声明支持时），
+// 并透明解压 gzip / deflate 编码的响应体
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// 小于该大小的请求体不压缩（压缩收益不抵开销）
+pub const GZIP_MIN_BODY_BYTES: usize = 1024;
+
+/// 请求压缩时附加的 Accept-Encoding
+pub const ACCEPT_ENCODING: &str = "gzip, deflate";
+
+/// gzip 压缩请求体
+pub fn gzip_body(body: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder
+        .write_all(body)
+        .and_then(|()| encoder.finish())
+        .map_err(|e| format!("压缩请求体失败: {}", e))
+}
+
+/// 响应体的大小上限（32 MB，原始响应体与解压后的响应体分别适用），防止超大或高压缩比的响应耗尽内存
+pub const MAX_BODY_BYTES: u64 = 32 * 1024 * 1024;
+
+/// 读取原始响应体，超过 MAX_BODY_BYTES 时返回错误
+pub fn read_body(reader: impl Read) -> Result<Vec<u8>, String> {
+    read_limited(reader, MAX_BODY_BYTES).map_err(|e| format!("读取响应体失败: {}", e))?
+}
+
+/// 按 Content-Encoding 解压响应体（identity 或未设置时原样返回）；
+/// 不支持的编码、解压失败或解压后超过 MAX_BODY_BYTES 时返回错误
+/// （不能把仍是压缩格式的响应体当作明文返回给调用方）
+pub fn decode_body(content_encoding: Option<&str>, body: Vec<u8>) -> Result<Vec<u8>, String> {
+    decode_body_limited(content_encoding, body, MAX_BODY_BYTES)
+}
+
+/// 最多读取 limit 字节；外层 Err 为读取失败，内层 Err 为超过上限
+fn read_limited(reader: impl Read, limit: u64) -> std::io::Result<Result<Vec<u8>, String>> {
+    let mut out = Vec::new();
+    // 多读一个字节用于判断是否超过上限
+    let len = reader.take(limit + 1).read_to_end(&mut out)?;
+    if len as u64 > limit {
+        return Ok(Err(format!("响应体超过 {} MB", limit / 1024 / 1024)));
+    }
+    Ok(Ok(out))
+}
+
+fn decode_body_limited(
+    content_encoding: Option<&str>,
+    body: Vec<u8>,
+    limit: u64,
+) -> Result<Vec<u8>, String> {
+    let encoding = content_encoding
+        .map(|e| e.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    let decoded = match encoding.as_str() {
+        "" | "identity" => return Ok(body),
+        "gzip" | "x-gzip" => read_limited(GzDecoder::new(body.as_slice()), limit),
+        // deflate 按规范为 zlib 格式，部分服务端发送裸 deflate 流，zlib 解码失败时再尝试
+        "deflate" => read_limited(ZlibDecoder::new(body.as_slice()), limit)
+            .or_else(|_| read_limited(DeflateDecoder::new(body.as_slice()), limit)),
+        other => return Err(format!("不支持的响应编码 {}", other)),
+    };
+    match decoded {
+        Ok(result) => result.map_err(|e| format!("{} {}（解压后）", encoding, e)),
+        Err(e) => Err(format!("解压 {} 响应体失败: {}", encoding, e)),
+    }
+}
+
+/// /health 响应是否声明支持 gzip 请求体（{"accept_encoding": ["gzip"]}）
+pub fn health_accepts_gzip(data: &serde_json::Value) -> bool {
+    data.get("accept_encoding")
+        .and_then(|v| v.as_array())
+        .is_some_and(|encodings| {
+            encodings
+                .iter()
+                .filter_map(|e| e.as_str())
+                .any(|e| e.eq_ignore_ascii_case("gzip"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gzip_body_round_trips() {
+        let body = b"{\"choices\": []}".repeat(100);
+        let encoded = gzip_body(&body).unwrap();
+        assert_eq!(decode_body(Some(" GZIP "), encoded).unwrap(), body);
+        assert_eq!(decode_body(None, body.clone()).unwrap(), body);
+    }
+
+    #[test]
+    fn decoded_body_over_limit_is_rejected() {
+        let encoded = gzip_body(&vec![b'a'; 64 * 1024]).unwrap();
+        assert!(encoded.len() < 1024, "高压缩比样本");
+        assert!(decode_body_limited(Some("gzip"), encoded.clone(), 4096).is_err());
+        assert_eq!(
+            decode_body_limited(Some("gzip"), encoded, 64 * 1024)
+                .unwrap()
+                .len(),
+            64 * 1024
+        );
+
+        let mut zlib = flate2::write::ZlibEncoder::new(Vec::new(), Compression::fast());
+        zlib.write_all(&vec![0; 64 * 1024]).unwrap();
+        let deflated = zlib.finish().unwrap();
+        assert!(decode_body_limited(Some("deflate"), deflated, 4096).is_err());
+    }
+
+    #[test]
+    fn undecodable_or_oversized_body_is_an_error() {
+        assert!(decode_body(Some("gzip"), b"not gzip".to_vec()).is_err());
+        assert!(decode_body(Some("deflate"), b"not deflate".to_vec()).is_err());
+        assert!(decode_body(Some("br"), b"brotli".to_vec()).is_err());
+
+        assert!(read_limited(&[0u8; 4097][..], 4096).unwrap().is_err());
+        assert_eq!(
+            read_limited(&[0u8; 4096][..], 4096).unwrap().unwrap().len(),
+            4096
+        );
+    }
+}
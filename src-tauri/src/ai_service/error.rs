@@ -0,0 +1,174 @@
+// Gateway 连接池错误：区分端口占用、脚本缺失、无可用 Worker 等情况，
+// 序列化为 { code, message, trace_id } 返回前端，Display 保留原有的中文提示用于日志
+use serde::ser::SerializeStruct;
+use serde::Serialize;
+use std::fmt;
+
+/// Gateway 连接池错误
+#[derive(Debug, Clone)]
+pub enum GatewayError {
+    /// 端口被占用且没有可切换的空闲端口
+    PortUnavailable {
+        worker_id: usize,
+        port: u16,
+        reason: String,
+    },
+    /// Python 解释器或 Gateway 脚本不存在
+    ScriptNotFound {
+        worker_id: usize,
+        python_path: String,
+        python_exists: bool,
+        script_path: String,
+        script_exists: bool,
+    },
+    /// 没有可用的 Worker
+    NoHealthyWorker,
+    /// 所有 Worker 均已失效（Disabled / FailedPermanent）
+    AllWorkersDown,
+    /// 请求的模型没有 Worker 提供
+    ModelNotFound {
+        model: String,
+        available: Vec<String>,
+    },
+    /// 指定的 Worker 不存在
+    WorkerNotFound(usize),
+    /// 上游请求超时
+    UpstreamTimeout(String),
+    /// 上游请求失败
+    UpstreamFailed(String),
+    /// 所有重试都失败
+    AllRetriesFailed,
+    /// 连接池正在运行，不能执行该操作
+    PoolRunning,
+    /// 配置无效
+    InvalidConfig(String),
+    /// 其他错误
+    Internal(String),
+    /// 附带请求追踪 ID 的错误（code 与原错误相同）
+    Traced {
+        trace_id: String,
+        source: Box<GatewayError>,
+    },
+}
+
+impl GatewayError {
+    /// 错误类型标识（供前端判断）
+    pub fn code(&self) -> &'static str {
+        match self {
+            GatewayError::PortUnavailable { .. } => "PortUnavailable",
+            GatewayError::ScriptNotFound { .. } => "ScriptNotFound",
+            GatewayError::NoHealthyWorker => "NoHealthyWorker",
+            GatewayError::AllWorkersDown => "AllWorkersDown",
+            GatewayError::ModelNotFound { .. } => "ModelNotFound",
+            GatewayError::WorkerNotFound(_) => "WorkerNotFound",
+            GatewayError::UpstreamTimeout(_) => "UpstreamTimeout",
+            GatewayError::UpstreamFailed(_) => "UpstreamFailed",
+            GatewayError::AllRetriesFailed => "AllRetriesFailed",
+            GatewayError::PoolRunning => "PoolRunning",
+            GatewayError::InvalidConfig(_) => "InvalidConfig",
+            GatewayError::Internal(_) => "Internal",
+            GatewayError::Traced { source, .. } => source.code(),
+        }
+    }
+
+    /// 附加请求追踪 ID（已附加时保持不变）
+    pub fn with_trace(self, trace_id: &str) -> Self {
+        match self {
+            GatewayError::Traced { .. } => self,
+            source => GatewayError::Traced {
+                trace_id: trace_id.to_string(),
+                source: Box::new(source),
+            },
+        }
+    }
+
+    /// 请求追踪 ID（仅转发请求的错误携带）
+    pub fn trace_id(&self) -> Option<&str> {
+        match self {
+            GatewayError::Traced { trace_id, .. } => Some(trace_id),
+            _ => None,
+        }
+    }
+
+    /// 根据 reqwest 错误区分超时与其他上游失败
+    pub fn upstream(e: &reqwest::Error) -> Self {
+        if e.is_timeout() {
+            GatewayError::UpstreamTimeout(e.to_string())
+        } else {
+            GatewayError::UpstreamFailed(e.to_string())
+        }
+    }
+}
+
+impl fmt::Display for GatewayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let describe = |exists: bool| if exists { "存在" } else { "不存在" };
+        match self {
+            GatewayError::PortUnavailable {
+                worker_id,
+                port,
+                reason,
+            } => write!(
+                f,
+                "端口 {} 已被占用，拒绝启动 Worker-{}: {}",
+                port, worker_id, reason
+            ),
+            GatewayError::ScriptNotFound {
+                worker_id,
+                python_path,
+                python_exists,
+                script_path,
+                script_exists,
+            } => write!(
+                f,
+                "启动 Worker-{} 失败，运行时路径无效（可在 gateway_pool.json 中设置 python_path / gateway_script_path）: Python 解释器 {}（{}）；Gateway 脚本 {}（{}）",
+                worker_id,
+                python_path,
+                describe(*python_exists),
+                script_path,
+                describe(*script_exists)
+            ),
+            GatewayError::NoHealthyWorker => write!(f, "没有可用的 Gateway Worker"),
+            GatewayError::AllWorkersDown => write!(
+                f,
+                "所有 Gateway Worker 均已失效（Disabled/FailedPermanent），请重启连接池"
+            ),
+            GatewayError::ModelNotFound { model, available } => write!(
+                f,
+                "模型 {} 不存在或没有 Worker 提供，可用模型: {}",
+                model,
+                available.join(", ")
+            ),
+            GatewayError::WorkerNotFound(worker_id) => write!(f, "Worker-{} 不存在", worker_id),
+            GatewayError::UpstreamTimeout(e) => write!(f, "请求超时: {}", e),
+            GatewayError::UpstreamFailed(e) => write!(f, "请求失败: {}", e),
+            GatewayError::AllRetriesFailed => write!(f, "所有重试都失败了"),
+            GatewayError::PoolRunning => {
+                write!(f, "连接池正在运行，请先停止连接池再调整规模或端口")
+            }
+            GatewayError::InvalidConfig(msg) | GatewayError::Internal(msg) => write!(f, "{}", msg),
+            GatewayError::Traced { trace_id, source } => {
+                write!(f, "{}（trace: {}）", source, trace_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GatewayError {}
+
+impl Serialize for GatewayError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("GatewayError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("trace_id", &self.trace_id())?;
+        state.end()
+    }
+}
+
+/// 尚未细分类型的错误信息归为 Internal
+impl From<String> for GatewayError {
+    fn from(message: String) -> Self {
+        GatewayError::Internal(message)
+    }
+}
@@ -0,0 +1,171 @@
+// 幂等键缓存：同一幂等键在 TTL 内已成功完成的请求直接返回缓存的响应，
+// 避免网络错误后的重试导致重复生成 / 重复计费（容量有限，按最近使用淘汰）
+// 每个键记录请求指纹（方法 + 路径 + 请求体摘要）：同一键用于不同请求时返回 422，
+// 同一请求仍在处理中时返回 409（转发不再持有连接池锁，并发的重复请求不会被串行化）
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// 最多缓存的幂等键数量
+const IDEMPOTENCY_CAPACITY: usize = 128;
+
+/// 已完成响应的缓存时间（同时作为处理中标记的最长保留时间，防止异常退出的请求永久占用键）
+const IDEMPOTENCY_TTL: Duration = Duration::from_secs(600);
+
+/// 单个响应体超过该大小时不缓存（避免少量大响应占用过多内存）
+const IDEMPOTENCY_MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// 请求指纹：方法 + 路径 + 请求体的 SHA-256
+pub type RequestFingerprint = [u8; 32];
+
+/// 计算请求指纹（方法不区分大小写）
+pub fn request_fingerprint(method: &str, path: &str, body: &[u8]) -> RequestFingerprint {
+    let mut hasher = Sha256::new();
+    hasher.update(method.to_ascii_uppercase().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hasher.finalize().into()
+}
+
+/// 已完成请求的响应
+#[derive(Debug)]
+struct CachedResponse {
+    fingerprint: RequestFingerprint,
+    status: u16,
+    content_type: Option<String>,
+    body: Vec<u8>,
+    completed_at: Instant,
+}
+
+/// 幂等键查找结果
+#[derive(Debug)]
+pub enum IdempotencyLookup {
+    /// 没有记录：调用方应转发请求，并在结束后调用 finish
+    Proceed,
+    /// 已完成的同一请求：(状态码, Content-Type, 响应体)
+    Completed(u16, Option<String>, Vec<u8>),
+    /// 同一请求仍在处理中
+    InFlight,
+    /// 幂等键已用于不同的请求
+    Mismatch,
+}
+
+/// 幂等键 -> 响应的 LRU 缓存
+#[derive(Debug, Default)]
+pub struct IdempotencyCache {
+    entries: HashMap<String, CachedResponse>,
+    /// 访问顺序（队首最久未使用）
+    order: VecDeque<String>,
+    /// 处理中的请求（幂等键 -> 指纹与开始时间）
+    in_flight: HashMap<String, (RequestFingerprint, Instant)>,
+}
+
+impl IdempotencyCache {
+    /// 开始一个带幂等键的请求：命中已完成的同一请求时返回缓存的响应（刷新为最近使用）；
+    /// 没有记录时标记为处理中并返回 Proceed
+    pub fn begin(&mut self, key: &str, fingerprint: &RequestFingerprint) -> IdempotencyLookup {
+        if let Some((pending, started_at)) = self.in_flight.get(key) {
+            if started_at.elapsed() < IDEMPOTENCY_TTL {
+                return if pending == fingerprint {
+                    IdempotencyLookup::InFlight
+                } else {
+                    IdempotencyLookup::Mismatch
+                };
+            }
+            self.in_flight.remove(key);
+        }
+
+        let expired = self
+            .entries
+            .get(key)
+            .map(|cached| cached.completed_at.elapsed() >= IDEMPOTENCY_TTL);
+        match expired {
+            Some(true) => self.remove(key),
+            Some(false) => {
+                self.touch(key);
+                if let Some(cached) = self.entries.get(key) {
+                    if &cached.fingerprint != fingerprint {
+                        return IdempotencyLookup::Mismatch;
+                    }
+                    return IdempotencyLookup::Completed(
+                        cached.status,
+                        cached.content_type.clone(),
+                        cached.body.clone(),
+                    );
+                }
+            }
+            None => {}
+        }
+
+        self.in_flight
+            .insert(key.to_string(), (*fingerprint, Instant::now()));
+        IdempotencyLookup::Proceed
+    }
+
+    /// 结束处理中的请求：清除处理中标记；response 为 2xx 响应时缓存（其他响应或失败表示请求未完成，允许重试），
+    /// 超出容量时淘汰最久未使用的键
+    pub fn finish(
+        &mut self,
+        key: &str,
+        fingerprint: &RequestFingerprint,
+        response: Option<(u16, Option<&str>, &[u8])>,
+    ) {
+        if self
+            .in_flight
+            .get(key)
+            .is_some_and(|(pending, _)| pending == fingerprint)
+        {
+            self.in_flight.remove(key);
+        }
+        let Some((status, content_type, body)) = response else {
+            return;
+        };
+        if !(200..300).contains(&status) {
+            return;
+        }
+        if body.len() > IDEMPOTENCY_MAX_BODY_BYTES {
+            log::debug!(
+                "[Gateway Pool] 响应体过大（{} 字节），不缓存幂等键 {}",
+                body.len(),
+                key
+            );
+            return;
+        }
+        self.remove(key);
+        while self.order.len() >= IDEMPOTENCY_CAPACITY {
+            match self.order.pop_front() {
+                Some(oldest) => {
+                    self.entries.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+        self.order.push_back(key.to_string());
+        self.entries.insert(
+            key.to_string(),
+            CachedResponse {
+                fingerprint: *fingerprint,
+                status,
+                content_type: content_type.map(str::to_string),
+                body: body.to_vec(),
+                completed_at: Instant::now(),
+            },
+        );
+    }
+
+    fn touch(&mut self, key: &str) {
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            if let Some(k) = self.order.remove(pos) {
+                self.order.push_back(k);
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if self.entries.remove(key).is_some() {
+            self.order.retain(|k| k != key);
+        }
+    }
+}
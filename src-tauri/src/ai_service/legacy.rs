@@ -275,7 +275,7 @@ pub fn check_ai_service_status(state: State<AIServiceState>) -> Result<bool, Str
 }
 
 /// 获取 models.json 配置文件路径
-fn get_models_config_path() -> std::path::PathBuf {
+pub(crate) fn get_models_config_path() -> std::path::PathBuf {
     let base_dir = get_app_base_dir();
     let config_path = base_dir
         .join("ai_service")
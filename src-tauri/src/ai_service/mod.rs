@@ -1,100 +1,1165 @@
+mod capability_cache;
+mod compression;
+mod error;
+mod idempotency;
 pub mod legacy;
+mod orphan;
 pub mod pool;
+mod pool_config;
+mod request_stats;
 mod service_wrapper;
+mod state_events;
+mod watchdog;
 
+pub use error::GatewayError;
+use idempotency::IdempotencyLookup;
 pub use pool::GatewayPool;
+pub use pool_config::GatewayPoolConfig;
+use pool_config::{DEFAULT_BASE_PORT, DEFAULT_POOL_SIZE};
+pub use request_stats::GatewayErrorStats;
 pub use service_wrapper::GatewayPoolService;
+pub use state_events::start_worker_state_emitter;
+pub use watchdog::start_stall_watchdog;
 
 // 连接池状态管理
-use crate::ai_service::pool::WorkerState;
+use crate::ai_service::pool::{
+    GatewayState, LayeredHealthReport, PoolAlert, PoolStatusView, SelectionExplanation,
+    SmokeTestResult, WarmupResult, WorkerEndpoint, WorkerLogLine, WorkerProbe, WorkerStartResult,
+    WorkerState, WorkerStatsSnapshot,
+};
 use crate::service::circuit_breaker::CircuitBreakerState;
-use std::sync::{Arc, Mutex, OnceLock};
+pub use pool_config::{
+    CircuitBreakerSettings, HealthCheckConfig, ModelCacheConfig, RequestPolicy,
+    StartupTimeoutConfig,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use tauri::ipc::Channel;
 use tauri::State;
 
 static GLOBAL_POOL: OnceLock<Arc<Mutex<GatewayPool>>> = OnceLock::new();
 
+/// 当前连接池的状态视图（build_pool 时更新），供状态查询绕过连接池锁
+static POOL_STATUS_VIEW: RwLock<Option<PoolStatusView>> = RwLock::new(None);
+
+/// 按配置创建连接池（应用全部失效策略及 Worker-0 隔离配置）；端口范围越界时返回错误
+fn build_pool(
+    config: &GatewayPoolConfig,
+    pool_size: usize,
+    base_port: u16,
+) -> Result<GatewayPool, String> {
+    let mut pool = GatewayPool::new(pool_size, base_port)?;
+    match config.resolve_bind_host() {
+        Ok(host) => pool.set_bind_host(host),
+        Err(e) => log::warn!(
+            "[Gateway Pool] {}，使用默认地址 {}",
+            e,
+            pool_config::DEFAULT_BIND_HOST
+        ),
+    }
+    pool.set_all_down_policy(config.all_down.clone());
+    pool.set_drain_timeout(std::time::Duration::from_secs(config.drain_timeout_secs));
+    pool.set_auto_warmup(config.warmup_on_start);
+    match config.health_check.validate() {
+        Ok(()) => pool.set_health_check_config(config.health_check.clone()),
+        Err(e) => log::warn!("[Gateway Pool] 健康检查配置无效（{}），使用默认值", e),
+    }
+    match config.startup_timeout.validate() {
+        Ok(()) => pool.set_startup_timeout_config(config.startup_timeout.clone()),
+        Err(e) => log::warn!("[Gateway Pool] 启动超时配置无效（{}），使用默认值", e),
+    }
+    match config.request_policy.validate() {
+        Ok(()) => pool.set_request_policy(config.request_policy.clone()),
+        Err(e) => log::warn!("[Gateway Pool] 请求重试策略无效（{}），使用默认值", e),
+    }
+    match config.circuit_breaker.validate() {
+        Ok(()) => pool.set_circuit_breaker_config(&config.circuit_breaker, false),
+        Err(e) => log::warn!("[Gateway Pool] 熔断器配置无效（{}），使用默认值", e),
+    }
+    match config.model_cache.validate() {
+        Ok(()) => pool.set_model_cache_config(&config.model_cache),
+        Err(e) => log::warn!("[Gateway Pool] 模型列表缓存配置无效（{}），使用默认值", e),
+    }
+    match pool_config::validate_model_aliases(&config.model_aliases) {
+        Ok(()) => pool.set_model_aliases(config.model_aliases.clone()),
+        Err(e) => log::warn!("[Gateway Pool] 模型别名配置无效（{}），忽略别名", e),
+    }
+    // 临时缓解措施：按配置永久隔离 Worker-0
+    if config.disable_worker_0 {
+        pool.isolate_worker(0);
+    }
+    *POOL_STATUS_VIEW
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(pool.status_view());
+    Ok(pool)
+}
+
+/// 退出时等待连接池后台线程结束的最长时间（所有线程共用）
+const SHUTDOWN_JOIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// 应用是否正在退出（后台线程据此尽快退出）
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// 连接池后台线程（健康检查、状态事件、死锁监视），退出时在限定时间内等待结束
+static BACKGROUND_THREADS: Mutex<Vec<(&'static str, std::thread::JoinHandle<()>)>> =
+    Mutex::new(Vec::new());
+
+/// 应用是否正在退出
+pub(crate) fn shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+/// 登记后台线程（同时清理已结束的线程，如连接池重建后退出的旧健康检查线程）
+pub(crate) fn register_background_thread(name: &'static str, handle: std::thread::JoinHandle<()>) {
+    let mut threads = crate::utils::lock_or_recover(&BACKGROUND_THREADS, "BackgroundThreads");
+    threads.retain(|(_, handle)| !handle.is_finished());
+    threads.push((name, handle));
+}
+
+/// 应用退出时停止连接池：通知后台线程退出，终止所有 Worker 进程（连接池未创建时跳过），
+/// 再在 SHUTDOWN_JOIN_TIMEOUT 内等待后台线程结束
+pub fn shutdown_gateway_pool() {
+    SHUTTING_DOWN.store(true, Ordering::SeqCst);
+    GatewayPool::retire_health_check_thread();
+    if let Some(pool) = GLOBAL_POOL.get() {
+        let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+        match pool_guard.stop_all() {
+            Ok(results) => log::info!(
+                "[Gateway Pool] 退出时停止连接池: {:?}",
+                WorkerStartResult::log_lines(&results)
+            ),
+            Err(e) => log::warn!("[Gateway Pool] 退出时停止连接池失败: {}", e),
+        }
+    }
+
+    let threads = std::mem::take(&mut *crate::utils::lock_or_recover(
+        &BACKGROUND_THREADS,
+        "BackgroundThreads",
+    ));
+    let deadline = std::time::Instant::now() + SHUTDOWN_JOIN_TIMEOUT;
+    let total = threads.len();
+    let joined = threads
+        .into_iter()
+        .map(|(name, handle)| crate::utils::join_until(name, handle, deadline))
+        .filter(|joined| *joined)
+        .count();
+    log::info!("[Gateway Pool] 后台线程已退出 {}/{}", joined, total);
+}
+
+/// 已创建连接池中的 Worker（连接池未创建时返回 None，不触发创建）
+fn existing_worker(worker_id: usize) -> Option<Arc<Mutex<pool::GatewayWorker>>> {
+    let pool = GLOBAL_POOL.get()?;
+    crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool").get_worker(worker_id)
+}
+
+/// Worker 当前进程是否为 pid 且仍在运行（供进程注册表核对）
+pub(crate) fn worker_process_alive(worker_id: usize, pid: u32) -> bool {
+    existing_worker(worker_id).is_some_and(|worker| GatewayPool::worker_process_alive(&worker, pid))
+}
+
+/// 终止 Worker 的当前进程（供进程注册表使用，PID 不一致时拒绝）
+pub(crate) fn kill_worker_process(worker_id: usize, pid: u32) -> Result<(), String> {
+    let worker =
+        existing_worker(worker_id).ok_or_else(|| format!("Worker-{} 不存在", worker_id))?;
+    GatewayPool::kill_worker_process(&worker, pid)
+}
+
 pub(crate) fn get_global_pool() -> Arc<Mutex<GatewayPool>> {
     GLOBAL_POOL
         .get_or_init(|| {
-            let pool_size = 3;
-            let base_port = 8765;
-            let pool = GatewayPool::new(pool_size, base_port);
+            let config = GatewayPoolConfig::load();
+            let built = config
+                .validate_pool_size(config.pool_size, config.base_port)
+                .and_then(|()| build_pool(&config, config.pool_size, config.base_port));
+            let pool = match built {
+                Ok(pool) => pool,
+                Err(e) => {
+                    log::warn!(
+                        "[Gateway Pool] 连接池规模配置无效（{}），使用默认值 {} 个 Worker / 端口 {}",
+                        e,
+                        DEFAULT_POOL_SIZE,
+                        DEFAULT_BASE_PORT
+                    );
+                    build_pool(&config, DEFAULT_POOL_SIZE, DEFAULT_BASE_PORT)
+                        .expect("默认端口范围有效")
+                }
+            };
             Arc::new(Mutex::new(pool))
         })
         .clone()
 }
 
+/// 连接池 Worker 级别的 Prometheus 指标
+/// 只在复制 Worker 列表时持有连接池锁，逐个短暂锁定 Worker；锁被污染时照常恢复读取
+pub(crate) fn gateway_prometheus_metrics() -> String {
+    let workers = {
+        let pool = get_global_pool();
+        let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+        pool_guard.get_workers().clone()
+    };
+
+    let mut state_lines = String::new();
+    let mut active_lines = String::new();
+    let mut failure_lines = String::new();
+    let mut circuit_lines = String::new();
+    for worker in &workers {
+        let (id, port, state, circuit_open, metrics) = {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            (
+                wg.id,
+                wg.port,
+                wg.status(),
+                wg.circuit_breaker.state() == CircuitBreakerState::Open,
+                Arc::clone(&wg.metrics),
+            )
+        };
+        let (active_requests, consecutive_failures) = {
+            let m = crate::utils::lock_or_recover(metrics.as_ref(), "GatewayWorker.metrics");
+            (m.active_requests, m.consecutive_failures)
+        };
+
+        let labels = format!("worker=\"{}\",port=\"{}\"", id, port);
+        for candidate in WorkerState::ALL {
+            state_lines.push_str(&format!(
+                "gateway_worker_state{{{},state=\"{:?}\"}} {}\n",
+                labels,
+                candidate,
+                u8::from(candidate == state)
+            ));
+        }
+        active_lines.push_str(&format!(
+            "gateway_worker_active_requests{{{}}} {}\n",
+            labels, active_requests
+        ));
+        failure_lines.push_str(&format!(
+            "gateway_worker_consecutive_failures{{{}}} {}\n",
+            labels, consecutive_failures
+        ));
+        circuit_lines.push_str(&format!(
+            "gateway_worker_circuit_open{{{}}} {}\n",
+            labels,
+            u8::from(circuit_open)
+        ));
+    }
+
+    format!(
+        "# HELP gateway_worker_state Current worker state (1 for the active state label, 0 otherwise)\n\
+         # TYPE gateway_worker_state gauge\n\
+         {}\
+         # HELP gateway_worker_active_requests Number of in-flight requests on the worker\n\
+         # TYPE gateway_worker_active_requests gauge\n\
+         {}\
+         # HELP gateway_worker_consecutive_failures Consecutive failed requests on the worker\n\
+         # TYPE gateway_worker_consecutive_failures gauge\n\
+         {}\
+         # HELP gateway_worker_circuit_open Whether the worker circuit breaker is open (1) or not (0)\n\
+         # TYPE gateway_worker_circuit_open gauge\n\
+         {}",
+        state_lines, active_lines, failure_lines, circuit_lines
+    )
+}
+
 /// AI 服务连接池状态
 #[derive(Default)]
 pub struct AIServicePoolState {
     _unused: (),
 }
 
-/// 初始化连接池（默认 3 个 Worker，端口从 8765 开始）
+/// 初始化连接池（规模与端口见 .config/gateway_pool.json，默认 3 个 Worker，端口从 8765 开始）
+/// 返回各 Worker 的启动结果（端口、是否成功、状态），供前端展示启动情况
 #[tauri::command]
-pub fn init_gateway_pool(state: State<AIServicePoolState>) -> Result<String, String> {
+pub fn init_gateway_pool(
+    state: State<AIServicePoolState>,
+) -> Result<Vec<WorkerStartResult>, GatewayError> {
     let _ = state;
     let pool = get_global_pool();
-    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
-    match pool_guard.start_all() {
+    let orphan_scope = {
+        let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+        GatewayPoolConfig::load()
+            .validate_pool_size(pool_guard.pool_size(), pool_guard.base_port())
+            .map_err(|e| GatewayError::InvalidConfig(format!("初始化连接池失败: {}", e)))?;
+        orphan::OrphanScope::of(&pool_guard)
+    };
+    // 先清理上次异常退出遗留的 Worker 进程，避免端口被占用导致 Worker 端口后移
+    // （终止进程并等待端口释放最长需要数秒，期间不持有连接池锁）
+    orphan::reap_orphan_workers(&orphan_scope);
+    // 启动并等待 Worker 就绪期间同样不持有连接池锁（每个 Worker 最长等待数秒，否则会触发卡死告警）
+    match GatewayPool::start_all(pool.as_ref()) {
         Ok(results) => {
-            log::info!("[Gateway Pool] 初始化成功: {:?}", results);
-            pool_guard.start_health_check_thread();
-            Ok("连接池已初始化".to_string())
+            log::info!(
+                "[Gateway Pool] 初始化成功: {:?}",
+                WorkerStartResult::log_lines(&results)
+            );
+            crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool").start_health_check_thread();
+            Ok(results)
         }
-        Err(e) => Err(format!("初始化连接池失败: {}", e)),
+        Err(e) => Err(format!("初始化连接池失败: {}", e).into()),
     }
 }
 
+/// 清理上次异常退出遗留的 Worker 进程（命令行包含 main_gateway.py 且端口在本连接池范围内），返回清理数量
+#[tauri::command]
+pub fn cleanup_orphan_workers(state: State<AIServicePoolState>) -> Result<usize, GatewayError> {
+    let _ = state;
+    let pool = get_global_pool();
+    let orphan_scope =
+        orphan::OrphanScope::of(&crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool"));
+    Ok(orphan::reap_orphan_workers(&orphan_scope))
+}
+
 /// 启动连接池
 #[tauri::command]
-pub fn start_gateway_pool(state: State<AIServicePoolState>) -> Result<String, String> {
+pub fn start_gateway_pool(state: State<AIServicePoolState>) -> Result<String, GatewayError> {
     init_gateway_pool(state).map(|_| "连接池已启动".to_string())
 }
 
 /// 停止连接池
 #[tauri::command]
-pub fn stop_gateway_pool(state: State<AIServicePoolState>) -> Result<String, String> {
+pub fn stop_gateway_pool(state: State<AIServicePoolState>) -> Result<String, GatewayError> {
     let _ = state;
     let pool = get_global_pool();
     let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
     match pool_guard.stop_all() {
         Ok(results) => {
-            log::info!("[Gateway Pool] 停止成功: {:?}", results);
+            log::info!(
+                "[Gateway Pool] 停止成功: {:?}",
+                WorkerStartResult::log_lines(&results)
+            );
             Ok("连接池已停止".to_string())
         }
-        Err(e) => Err(format!("停止连接池失败: {}", e)),
+        Err(e) => Err(format!("停止连接池失败: {}", e).into()),
+    }
+}
+
+/// 调整连接池规模与基础端口（仅限连接池未运行时），并持久化到 .config/gateway_pool.json
+#[tauri::command]
+pub fn configure_gateway_pool(
+    state: State<AIServicePoolState>,
+    pool_size: usize,
+    base_port: u16,
+) -> Result<String, GatewayError> {
+    let _ = state;
+    let mut config = GatewayPoolConfig::load();
+    config
+        .validate_pool_size(pool_size, base_port)
+        .map_err(GatewayError::InvalidConfig)?;
+
+    let pool = get_global_pool();
+    let mut pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    if pool_guard.is_running() {
+        return Err(GatewayError::PoolRunning);
     }
+
+    // 提交前确认整个端口范围均可绑定
+    let bind_host = pool_guard.bind_host();
+    let occupied: Vec<u16> = (0..pool_size as u16)
+        .map(|i| base_port + i)
+        .filter(|port| std::net::TcpListener::bind((bind_host, *port)).is_err())
+        .collect();
+    if !occupied.is_empty() {
+        return Err(GatewayError::InvalidConfig(format!(
+            "以下端口已被占用，请更换基础端口: {}",
+            occupied
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+
+    config.pool_size = pool_size;
+    config.base_port = base_port;
+    config.save()?;
+
+    GatewayPool::retire_health_check_thread();
+    *pool_guard = build_pool(&config, pool_size, base_port).map_err(GatewayError::InvalidConfig)?;
+    log::info!(
+        "[Gateway Pool] 连接池已重建: {} 个 Worker，端口 {}-{}",
+        pool_size,
+        base_port,
+        base_port as usize + pool_size - 1
+    );
+    Ok(format!(
+        "连接池已配置为 {} 个 Worker（端口 {}-{}）",
+        pool_size,
+        base_port,
+        base_port as usize + pool_size - 1
+    ))
+}
+
+/// 获取健康检查间隔与超时配置
+#[tauri::command]
+pub fn get_health_check_config(
+    state: State<AIServicePoolState>,
+) -> Result<HealthCheckConfig, GatewayError> {
+    let _ = state;
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    Ok(pool_guard.health_check_config())
+}
+
+/// 调整健康检查间隔与超时，并持久化到 .config/gateway_pool.json（运行中的健康检查线程下一轮生效）
+#[tauri::command]
+pub fn set_health_check_config(
+    state: State<AIServicePoolState>,
+    config: HealthCheckConfig,
+) -> Result<String, GatewayError> {
+    let _ = state;
+    config.validate().map_err(GatewayError::InvalidConfig)?;
+    let mut pool_config = GatewayPoolConfig::load();
+    pool_config.health_check = config.clone();
+    pool_config.save()?;
+
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    pool_guard.set_health_check_config(config.clone());
+    log::info!("[Gateway Pool] 健康检查配置已更新: {:?}", config);
+    Ok(format!(
+        "健康检查配置已更新：间隔 {} 秒，心跳超时 {} 秒，启动宽限 {} 秒，HTTP 超时 {} 秒",
+        config.interval_secs,
+        config.heartbeat_timeout_secs,
+        config.startup_grace_secs,
+        config.http_timeout_secs
+    ))
+}
+
+/// 跳过缓存立即重新获取模型列表（修改 Gateway 模型配置后使用），返回最新的模型 ID 列表
+#[tauri::command]
+pub fn refresh_models(state: State<AIServicePoolState>) -> Result<Vec<String>, GatewayError> {
+    let _ = state;
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    pool_guard
+        .refresh_models()
+        .map_err(GatewayError::UpstreamFailed)
+}
+
+/// 获取模型列表缓存有效期与限频配置
+#[tauri::command]
+pub fn get_model_cache_config(
+    state: State<AIServicePoolState>,
+) -> Result<ModelCacheConfig, GatewayError> {
+    let _ = state;
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    Ok(pool_guard.model_cache_config())
+}
+
+/// 调整模型列表缓存有效期与限频，并持久化到 .config/gateway_pool.json（对之后的请求生效）
+#[tauri::command]
+pub fn set_model_cache_config(
+    state: State<AIServicePoolState>,
+    config: ModelCacheConfig,
+) -> Result<String, GatewayError> {
+    let _ = state;
+    config.validate().map_err(GatewayError::InvalidConfig)?;
+    let mut pool_config = GatewayPoolConfig::load();
+    pool_config.model_cache = config.clone();
+    pool_config.save()?;
+
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    pool_guard.set_model_cache_config(&config);
+    log::info!("[Gateway Pool] 模型列表缓存配置已更新: {:?}", config);
+    Ok(format!(
+        "模型列表缓存配置已更新：有效期 {} 秒，最小请求间隔 {} 秒",
+        config.ttl_secs, config.min_interval_secs
+    ))
+}
+
+/// 获取 Worker 启动超时配置
+#[tauri::command]
+pub fn get_startup_timeout_config(
+    state: State<AIServicePoolState>,
+) -> Result<StartupTimeoutConfig, GatewayError> {
+    let _ = state;
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    Ok(pool_guard.startup_timeout_config())
+}
+
+/// 调整 Worker 启动超时（整体或按 Worker 单独设置），并持久化到 .config/gateway_pool.json
+/// 加载大模型需要数分钟时延长，避免 Worker 在就绪前被标记为 Degraded；
+/// 超时越长，加载失败或卡死的 Worker 越晚被发现，只应为确实加载缓慢的 Worker 延长
+/// 启动超时延长的是端口探测与健康检查宽限期；初始化连接池命令最多同步等待 10 秒即返回
+/// （Worker 状态为 Ready / Init），之后在超时内就绪的 Worker 由后台转为可用，不阻塞命令数分钟
+#[tauri::command]
+pub fn set_startup_timeout_config(
+    state: State<AIServicePoolState>,
+    config: StartupTimeoutConfig,
+) -> Result<String, GatewayError> {
+    let _ = state;
+    config.validate().map_err(GatewayError::InvalidConfig)?;
+    let mut pool_config = GatewayPoolConfig::load();
+    pool_config.startup_timeout = config.clone();
+    pool_config.save()?;
+
+    let pool = get_global_pool();
+    let mut pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    pool_guard.set_startup_timeout_config(config.clone());
+    log::info!("[Gateway Pool] 启动超时配置已更新: {:?}", config);
+    Ok(format!(
+        "启动超时配置已更新：默认 {}，{} 个 Worker 单独设置",
+        if config.timeout_secs > 0 {
+            format!("{} 秒", config.timeout_secs)
+        } else {
+            "使用启动宽限期".to_string()
+        },
+        config.worker_overrides.len()
+    ))
+}
+
+/// 获取 Worker 熔断器阈值
+#[tauri::command]
+pub fn get_circuit_breaker_config(
+    state: State<AIServicePoolState>,
+) -> Result<CircuitBreakerSettings, GatewayError> {
+    let _ = state;
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    Ok(pool_guard.circuit_breaker_config())
+}
+
+/// 调整 Worker 熔断器阈值，并持久化到 .config/gateway_pool.json（立即应用到所有 Worker）
+/// reset 为 true 时同时把熔断器重置为关闭状态，否则保留当前打开 / 关闭状态
+#[tauri::command]
+pub fn set_circuit_breaker_config(
+    state: State<AIServicePoolState>,
+    config: CircuitBreakerSettings,
+    reset: Option<bool>,
+) -> Result<String, GatewayError> {
+    let _ = state;
+    config.validate().map_err(GatewayError::InvalidConfig)?;
+    let mut pool_config = GatewayPoolConfig::load();
+    pool_config.circuit_breaker = config.clone();
+    pool_config.save()?;
+
+    let reset = reset.unwrap_or(false);
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    pool_guard.set_circuit_breaker_config(&config, reset);
+    log::info!(
+        "[Gateway Pool] 熔断器配置已更新: {:?}（重置状态: {}）",
+        config,
+        reset
+    );
+    Ok(format!(
+        "熔断器配置已更新：连续失败 {} 次打开，打开 {} 秒后半开，半开成功 {} 次关闭",
+        config.failure_threshold, config.timeout_secs, config.success_threshold
+    ))
+}
+
+/// 获取转发请求的重试与退避策略
+#[tauri::command]
+pub fn get_request_policy(state: State<AIServicePoolState>) -> Result<RequestPolicy, GatewayError> {
+    let _ = state;
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    Ok(pool_guard.request_policy())
+}
+
+/// 调整转发请求的重试与退避策略，并持久化到 .config/gateway_pool.json（对之后的请求生效）
+#[tauri::command]
+pub fn set_request_policy(
+    state: State<AIServicePoolState>,
+    policy: RequestPolicy,
+) -> Result<String, GatewayError> {
+    let _ = state;
+    policy.validate().map_err(GatewayError::InvalidConfig)?;
+    let mut pool_config = GatewayPoolConfig::load();
+    pool_config.request_policy = policy.clone();
+    pool_config.save()?;
+
+    let pool = get_global_pool();
+    let mut pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    pool_guard.set_request_policy(policy.clone());
+    log::info!("[Gateway Pool] 请求重试策略已更新: {:?}", policy);
+    Ok(format!(
+        "请求重试策略已更新：最多重试 {} 次，重试间隔 {}-{} ms（抖动 {} ms）",
+        policy.max_retries, policy.base_delay_ms, policy.max_delay_ms, policy.jitter_ms
+    ))
+}
+
+/// 获取模型别名表（请求的模型 -> 实际使用的模型）
+#[tauri::command]
+pub fn get_model_aliases(
+    state: State<AIServicePoolState>,
+) -> Result<BTreeMap<String, String>, GatewayError> {
+    let _ = state;
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    Ok(pool_guard.model_aliases())
+}
+
+/// 替换模型别名表并持久化到 .config/gateway_pool.json（对之后的请求生效，无需重启连接池）
+#[tauri::command]
+pub fn set_model_aliases(
+    state: State<AIServicePoolState>,
+    aliases: BTreeMap<String, String>,
+) -> Result<String, GatewayError> {
+    let _ = state;
+    pool_config::validate_model_aliases(&aliases).map_err(GatewayError::InvalidConfig)?;
+    let mut pool_config = GatewayPoolConfig::load();
+    pool_config.model_aliases = aliases.clone();
+    pool_config.save()?;
+
+    let pool = get_global_pool();
+    let mut pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    pool_guard.set_model_aliases(aliases.clone());
+    log::info!("[Gateway Pool] 模型别名已更新: {:?}", aliases);
+    Ok(format!("模型别名已更新，共 {} 条", aliases.len()))
+}
+
+/// 从 .config/gateway_pool.json 重新加载模型别名（手动编辑配置文件后调用，无需重启连接池）
+#[tauri::command]
+pub fn reload_model_aliases(
+    state: State<AIServicePoolState>,
+) -> Result<BTreeMap<String, String>, GatewayError> {
+    let _ = state;
+    let aliases = GatewayPoolConfig::load().model_aliases;
+    pool_config::validate_model_aliases(&aliases).map_err(GatewayError::InvalidConfig)?;
+
+    let pool = get_global_pool();
+    let mut pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    pool_guard.set_model_aliases(aliases.clone());
+    log::info!("[Gateway Pool] 已重新加载模型别名: {:?}", aliases);
+    Ok(aliases)
+}
+
+/// forward_ai_request 的转发结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ForwardResponse {
+    /// 上游状态码
+    pub status: u16,
+    /// 上游 Content-Type（缺失时为空）
+    pub content_type: Option<String>,
+    /// 响应体原始字节
+    pub body: Vec<u8>,
+    /// 本次请求的追踪 ID，对应日志中的 [trace ...]
+    pub trace_id: String,
+}
+
+/// 排队等待可用 Worker 的最长时间上限（毫秒）
+const MAX_QUEUE_WAIT_MS: u64 = 60_000;
+
+/// 幂等键请求头
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// 调用方自带追踪 ID 的最大长度（超出时忽略并重新生成）
+const MAX_TRACE_ID_LEN: usize = 128;
+
+/// 本次请求的追踪 ID：优先使用调用方 X-Trace-Id 请求头，否则生成 16 位十六进制 ID
+fn request_trace_id(headers: &[(String, String)]) -> String {
+    headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(pool::TRACE_ID_HEADER))
+        .map(|(_, value)| value.trim())
+        .filter(|value| !value.is_empty() && value.len() <= MAX_TRACE_ID_LEN)
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string()[..16].to_string())
 }
 
 /// 转发 HTTP 请求到连接池
+/// queue_wait_ms 为空时快速失败；指定时排队等待可用 Worker（最长 60 秒），超时返回 429
+/// idempotency_key 以 Idempotency-Key 请求头转发；同一幂等键在 TTL 内已成功完成时直接返回缓存的响应，
+/// 幂等键已用于不同的请求（方法、路径或请求体不同）时返回 422，同一请求仍在处理中时返回 409
+/// compress 为 true 时较大的请求体以 gzip 发送（Worker 声明支持时），适合粘贴长文档的对话
+/// 返回 ForwardResponse，前端据 content_type 区分 JSON、SSE 片段与上游返回的 HTML 错误页；
+/// 追踪 ID 取自 X-Trace-Id 请求头或自动生成，出现在本次请求的日志与返回的错误中
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn forward_ai_request(
     state: State<AIServicePoolState>,
     method: String,
     path: String,
     body: Option<Vec<u8>>,
     headers: Option<Vec<(String, String)>>,
-) -> Result<(u16, Vec<u8>), String> {
+    client_id: Option<String>,
+    queue_wait_ms: Option<u64>,
+    idempotency_key: Option<String>,
+    compress: Option<bool>,
+) -> Result<ForwardResponse, GatewayError> {
     let _ = state;
+    let mut headers = headers.unwrap_or_default();
+    let trace_id = request_trace_id(&headers);
+    // 方法名无效时直接报错，不占用 Worker
+    let method = GatewayPool::parse_method(&method)
+        .map_err(|e| GatewayError::from(e).with_trace(&trace_id))?
+        .to_string();
+    let queue_wait =
+        queue_wait_ms.map(|ms| std::time::Duration::from_millis(ms.min(MAX_QUEUE_WAIT_MS)));
+    let idempotency_key = idempotency_key
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty());
 
-    // 转换 headers 并转发请求
-    let headers_opt = headers.as_ref().map(|h| {
-        h.iter()
+    // 转换 headers 并转发请求（调用方未自行设置时附加幂等键请求头）
+    if let Some(key) = idempotency_key.as_deref() {
+        if !headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case(IDEMPOTENCY_KEY_HEADER))
+        {
+            headers.push((IDEMPOTENCY_KEY_HEADER.to_string(), key.to_string()));
+        }
+    }
+    let headers_opt = (!headers.is_empty()).then(|| {
+        headers
+            .iter()
             .map(|(k, v)| (k.as_str(), v.as_str()))
             .collect::<Vec<_>>()
     });
 
+    let pool = get_global_pool();
+    let fingerprint = idempotency_key
+        .as_ref()
+        .map(|_| idempotency::request_fingerprint(&method, &path, body.as_deref().unwrap_or(&[])));
     let result = {
+        if let (Some(key), Some(fingerprint)) = (idempotency_key.as_deref(), fingerprint.as_ref()) {
+            let lookup = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool")
+                .begin_idempotent_request(key, fingerprint);
+            match lookup {
+                IdempotencyLookup::Proceed => {}
+                IdempotencyLookup::Completed(status, content_type, body_bytes) => {
+                    log::info!(
+                        "[Gateway Pool] [trace {}] 幂等键 {} 已完成，返回缓存的响应",
+                        trace_id,
+                        key
+                    );
+                    return Ok(ForwardResponse {
+                        status,
+                        content_type,
+                        body: body_bytes,
+                        trace_id,
+                    });
+                }
+                IdempotencyLookup::InFlight => {
+                    log::warn!(
+                        "[Gateway Pool] [trace {}] 幂等键 {} 的请求仍在处理中",
+                        trace_id,
+                        key
+                    );
+                    return Ok(idempotency_error_response(
+                        409,
+                        "idempotency_key_in_use",
+                        "使用该幂等键的请求仍在处理中，请稍后重试",
+                        trace_id,
+                    ));
+                }
+                IdempotencyLookup::Mismatch => {
+                    log::warn!(
+                        "[Gateway Pool] [trace {}] 幂等键 {} 已用于不同的请求",
+                        trace_id,
+                        key
+                    );
+                    return Ok(idempotency_error_response(
+                        422,
+                        "idempotency_key_mismatch",
+                        "该幂等键已用于不同的请求（方法、路径或请求体不同）",
+                        trace_id,
+                    ));
+                }
+            }
+        }
+        let result = GatewayPool::forward_request(
+            pool.as_ref(),
+            &method,
+            &path,
+            body.as_deref(),
+            headers_opt.as_deref(),
+            client_id.as_deref(),
+            queue_wait,
+            compress.unwrap_or(false),
+            &trace_id,
+        );
+        if let (Some(key), Some(fingerprint)) = (idempotency_key.as_deref(), fingerprint.as_ref()) {
+            let response = result
+                .as_ref()
+                .ok()
+                .map(|(status, content_type, body_bytes)| {
+                    (
+                        status.as_u16(),
+                        content_type.as_deref(),
+                        body_bytes.as_slice(),
+                    )
+                });
+            crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool").finish_idempotent_request(
+                key,
+                fingerprint,
+                response,
+            );
+        }
+        result
+    };
+
+    match result {
+        Ok((status, content_type, body_bytes)) => Ok(ForwardResponse {
+            status: status.as_u16(),
+            content_type,
+            body: body_bytes,
+            trace_id,
+        }),
+        Err(e) => {
+            let e = e.with_trace(&trace_id);
+            log::warn!("[Gateway Pool] 转发请求失败: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// 幂等键冲突时返回的 JSON 错误响应（与 OpenAI 兼容接口的错误格式一致）
+fn idempotency_error_response(
+    status: u16,
+    code: &str,
+    message: &str,
+    trace_id: String,
+) -> ForwardResponse {
+    let body = serde_json::json!({
+        "error": {
+            "message": message,
+            "type": "invalid_request_error",
+            "code": code,
+        }
+    });
+    ForwardResponse {
+        status,
+        content_type: Some("application/json".to_string()),
+        body: body.to_string().into_bytes(),
+        trace_id,
+    }
+}
+
+/// 转发 HTTP 请求到连接池，只返回 (状态码, 响应体)
+/// 已废弃：仅为兼容旧调用方保留，请改用返回 Content-Type 的 forward_ai_request
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn forward_ai_request_legacy(
+    state: State<AIServicePoolState>,
+    method: String,
+    path: String,
+    body: Option<Vec<u8>>,
+    headers: Option<Vec<(String, String)>>,
+    client_id: Option<String>,
+    queue_wait_ms: Option<u64>,
+    idempotency_key: Option<String>,
+    compress: Option<bool>,
+) -> Result<(u16, Vec<u8>), GatewayError> {
+    forward_ai_request(
+        state,
+        method,
+        path,
+        body,
+        headers,
+        client_id,
+        queue_wait_ms,
+        idempotency_key,
+        compress,
+    )
+    .map(|response| (response.status, response.body))
+}
+
+/// 流式转发事件（通过 Channel 逐条推送给前端）
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum AiStreamEvent {
+    /// 已选定 Worker，开始转发，携带本次请求的追踪 ID
+    Started { worker_id: usize, trace_id: String },
+    /// 上游响应数据块（原样透传的 SSE 文本，不会截断多字节字符）
+    Chunk { text: String },
+    /// 转发结束，携带上游状态码
+    Done { status: u16 },
+    /// 转发失败，message 中带有 [trace ...] 前缀
+    Error { message: String },
+}
+
+/// 从缓冲区取出完整的 UTF-8 前缀，不完整的尾部字节留待下一块拼接
+fn take_utf8_prefix(pending: &mut Vec<u8>) -> String {
+    let valid_len = match std::str::from_utf8(pending) {
+        Ok(_) => pending.len(),
+        // error_len 为 None 表示尾部是被截断的多字节字符
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => {
+            let text = String::from_utf8_lossy(pending).into_owned();
+            pending.clear();
+            return text;
+        }
+    };
+    let rest = pending.split_off(valid_len);
+    String::from_utf8(std::mem::replace(pending, rest)).unwrap_or_default()
+}
+
+/// 流式转发 HTTP 请求到连接池（用于 stream: true 的对话补全）
+/// 选定 Worker 后立即返回其 ID，数据块通过 on_event 推送，最后以 done / error 事件结束
+/// 追踪 ID 与 forward_ai_request 相同：取自 X-Trace-Id 请求头或自动生成，随 started 事件返回并转发给 Worker
+#[tauri::command]
+pub fn forward_ai_request_stream(
+    state: State<AIServicePoolState>,
+    method: String,
+    path: String,
+    body: Option<Vec<u8>>,
+    headers: Option<Vec<(String, String)>>,
+    client_id: Option<String>,
+    on_event: Channel<AiStreamEvent>,
+) -> Result<usize, GatewayError> {
+    let _ = state;
+    let headers = headers.unwrap_or_default();
+    let trace_id = request_trace_id(&headers);
+    let method = GatewayPool::parse_method(&method)
+        .map_err(|e| GatewayError::from(e).with_trace(&trace_id))?
+        .to_string();
+    let mut body = body;
+    let mut model = GatewayPool::requested_model(&method, &path, body.as_deref());
+    let (worker, request_stats) = {
         let pool = get_global_pool();
         let mut pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
-        pool_guard.forward_request(&method, &path, body.as_deref(), headers_opt.as_deref())
+        // 请求的模型不可用时按别名表改写为可用模型
+        let aliased = match (model.as_deref(), body.as_deref()) {
+            (Some(requested), Some(data)) => pool_guard.apply_model_alias(data, requested),
+            _ => None,
+        };
+        if let Some((target, rewritten)) = aliased {
+            model = Some(target);
+            body = Some(rewritten);
+        }
+        let worker = pool_guard
+            .acquire_stream_worker(client_id.as_deref(), model.as_deref())
+            .map_err(|e| e.with_trace(&trace_id))?;
+        (worker, pool_guard.request_stats())
     };
+    let worker_id = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker").id;
+    let _ = on_event.send(AiStreamEvent::Started {
+        worker_id,
+        trace_id: trace_id.clone(),
+    });
 
-    match result {
-        Ok((status, body_bytes)) => Ok((status.as_u16(), body_bytes)),
-        Err(e) => Err(format!("转发请求失败: {}", e)),
+    // 流式读取在后台线程进行，不阻塞命令调用方
+    std::thread::spawn(move || {
+        let headers_opt = (!headers.is_empty()).then(|| {
+            headers
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect::<Vec<_>>()
+        });
+        let mut pending = Vec::new();
+        let result = GatewayPool::stream_request(
+            &worker,
+            &request_stats,
+            &method,
+            &path,
+            body,
+            headers_opt.as_deref(),
+            &trace_id,
+            |chunk| {
+                pending.extend_from_slice(chunk);
+                let text = take_utf8_prefix(&mut pending);
+                // 前端已关闭 Channel 时中止读取，释放 Worker
+                text.is_empty() || on_event.send(AiStreamEvent::Chunk { text }).is_ok()
+            },
+        );
+        if !pending.is_empty() {
+            let text = String::from_utf8_lossy(&pending).into_owned();
+            let _ = on_event.send(AiStreamEvent::Chunk { text });
+        }
+        let final_event = match result {
+            Ok(status) => AiStreamEvent::Done {
+                status: status.as_u16(),
+            },
+            Err(e) => AiStreamEvent::Error {
+                message: format!("[trace {}] 转发请求失败: {}", trace_id, e),
+            },
+        };
+        let _ = on_event.send(final_event);
+    });
+
+    Ok(worker_id)
+}
+
+/// 直接请求指定 Worker（绕过调度，用于隔离排查单个 Worker 的问题）
+#[tauri::command]
+pub fn query_worker_direct(
+    state: State<AIServicePoolState>,
+    worker_id: usize,
+    method: String,
+    path: String,
+    body: Option<Vec<u8>>,
+    force: Option<bool>,
+) -> Result<(u16, Vec<u8>), GatewayError> {
+    let _ = state;
+    let worker = {
+        let pool = get_global_pool();
+        let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+        pool_guard
+            .get_worker(worker_id)
+            .ok_or(GatewayError::WorkerNotFound(worker_id))?
+    };
+
+    GatewayPool::query_worker_direct(
+        &worker,
+        &method,
+        &path,
+        body.as_deref(),
+        force.unwrap_or(false),
+    )
+    .map(|(status, body_bytes)| (status.as_u16(), body_bytes))
+    .map_err(GatewayError::from)
+}
+
+/// 排空并停止指定 Worker（等待在途请求完成，超时后强制停止）
+#[tauri::command]
+pub fn drain_gateway_worker(
+    state: State<AIServicePoolState>,
+    worker_id: usize,
+) -> Result<String, GatewayError> {
+    let _ = state;
+    // 排空期间不持有连接池锁，避免阻塞其他请求的调度
+    let worker = {
+        let pool = get_global_pool();
+        let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+        pool_guard
+            .get_worker(worker_id)
+            .ok_or(GatewayError::WorkerNotFound(worker_id))?
+    };
+    GatewayPool::drain_worker(&worker)
+}
+
+/// 手动重启指定 Worker（重置自动重启预算，FailedPermanent / Disabled 状态拒绝重启）
+#[tauri::command]
+pub fn restart_gateway_worker(
+    state: State<AIServicePoolState>,
+    worker_id: usize,
+) -> Result<String, GatewayError> {
+    let _ = state;
+    // 重启期间不持有连接池锁，避免阻塞其他请求的调度
+    let worker = {
+        let pool = get_global_pool();
+        let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+        pool_guard
+            .get_worker(worker_id)
+            .ok_or(GatewayError::WorkerNotFound(worker_id))?
+    };
+    GatewayPool::restart_worker(&worker)
+}
+
+/// 手动重新启用 Disabled / FailedPermanent 状态的 Worker（重置熔断器与重启预算后尝试启动）
+#[tauri::command]
+pub fn reenable_gateway_worker(
+    state: State<AIServicePoolState>,
+    worker_id: usize,
+) -> Result<String, GatewayError> {
+    let _ = state;
+    let worker = {
+        let pool = get_global_pool();
+        let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+        pool_guard
+            .get_worker(worker_id)
+            .ok_or(GatewayError::WorkerNotFound(worker_id))?
+    };
+    GatewayPool::reenable_worker(&worker)
+}
+
+/// 设置 Worker 维护模式：开启后不参与调度、不被健康检查重启，进程保持运行（便于挂调试器）
+#[tauri::command]
+pub fn set_worker_maintenance(
+    state: State<AIServicePoolState>,
+    worker_id: usize,
+    enabled: bool,
+) -> Result<String, GatewayError> {
+    let _ = state;
+    let worker = {
+        let pool = get_global_pool();
+        let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+        pool_guard
+            .get_worker(worker_id)
+            .ok_or(GatewayError::WorkerNotFound(worker_id))?
+    };
+    GatewayPool::set_worker_maintenance(&worker, enabled)
+}
+
+/// 预热 Idle 状态的 Worker：发送一次最小对话补全请求触发模型加载，返回使用的模型与耗时
+#[tauri::command]
+pub fn warmup_worker(
+    state: State<AIServicePoolState>,
+    worker_id: usize,
+) -> Result<WarmupResult, GatewayError> {
+    let _ = state;
+    let worker = {
+        let pool = get_global_pool();
+        let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+        pool_guard
+            .get_worker(worker_id)
+            .ok_or(GatewayError::WorkerNotFound(worker_id))?
+    };
+    GatewayPool::warmup_worker(&worker)
+}
+
+/// 冒烟测试：向当前调度会选中的 Worker 发送一次最小对话补全请求，返回状态码、耗时与回复片段
+/// 只查询调度结果而不占用 Worker，不建立粘性会话映射，也不计入请求指标
+#[tauri::command]
+pub fn gateway_smoke_test(
+    state: State<AIServicePoolState>,
+) -> Result<SmokeTestResult, GatewayError> {
+    let _ = state;
+    let worker = {
+        let pool = get_global_pool();
+        let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+        let worker_id = pool_guard
+            .explain_selection(None, None)
+            .selected_worker
+            .ok_or(GatewayError::NoHealthyWorker)?;
+        pool_guard
+            .get_worker(worker_id)
+            .ok_or(GatewayError::WorkerNotFound(worker_id))?
+    };
+    GatewayPool::smoke_test_worker(&worker)
+}
+
+/// 按需对指定 Worker 执行分层健康检查（level 0-3：进程、TCP、/health、模型响应），
+/// 返回是否通过及各层检查结果；不影响 Worker 指标与状态
+#[tauri::command]
+pub fn check_worker_health(
+    state: State<AIServicePoolState>,
+    worker_id: usize,
+    level: u8,
+) -> Result<LayeredHealthReport, GatewayError> {
+    let _ = state;
+    if level > 3 {
+        return Err(GatewayError::InvalidConfig(format!(
+            "健康检查级别必须为 0-3，当前为 {}",
+            level
+        )));
     }
+    let worker = {
+        let pool = get_global_pool();
+        let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+        pool_guard
+            .get_worker(worker_id)
+            .ok_or(GatewayError::WorkerNotFound(worker_id))?
+    };
+    Ok(GatewayPool::health_check_layered(&worker, level))
+}
+
+/// 对指定 Worker 发起只读 HTTP 探测（默认 /health），返回状态码、耗时与响应体片段
+/// 不经过调度器、不影响 Worker 指标与状态，用于确认 Worker 实际是否响应
+#[tauri::command]
+pub fn probe_worker_http(
+    state: State<AIServicePoolState>,
+    worker_id: usize,
+    path: Option<String>,
+) -> Result<WorkerProbe, GatewayError> {
+    let _ = state;
+    let worker = {
+        let pool = get_global_pool();
+        let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+        pool_guard
+            .get_worker(worker_id)
+            .ok_or(GatewayError::WorkerNotFound(worker_id))?
+    };
+    let path = path
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .unwrap_or("/health");
+    GatewayPool::probe_worker_http(&worker, path).map_err(GatewayError::from)
 }
 
 /// 诊断指定 Worker（用于排查问题，特别是 Worker-0）
@@ -102,42 +1167,257 @@ pub fn forward_ai_request(
 pub fn diagnose_worker(
     state: State<AIServicePoolState>,
     worker_id: usize,
-) -> Result<String, String> {
+) -> Result<String, GatewayError> {
     let _ = state;
     let pool = get_global_pool();
     let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
     Ok(pool_guard.diagnose_worker(worker_id))
 }
 
-/// 获取连接池状态
+/// 获取 Worker 最近的 stderr 输出（默认最后 100 行，最多 200 行）
 #[tauri::command]
-pub fn get_gateway_pool_status(
+pub fn get_worker_logs(
+    state: State<AIServicePoolState>,
+    worker_id: usize,
+    limit: Option<usize>,
+) -> Result<Vec<WorkerLogLine>, GatewayError> {
+    let _ = state;
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    pool_guard
+        .worker_logs(worker_id, limit.unwrap_or(100))
+        .ok_or(GatewayError::WorkerNotFound(worker_id))
+}
+
+/// 解释当前调度决策（各 Worker 得分、跳过原因及最终选择，不实际占用 Worker）
+#[tauri::command]
+pub fn explain_selection(
+    state: State<AIServicePoolState>,
+    client_id: Option<String>,
+    model: Option<String>,
+) -> Result<SelectionExplanation, GatewayError> {
+    let _ = state;
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    Ok(pool_guard.explain_selection(client_id.as_deref(), model.as_deref()))
+}
+
+/// 获取连接池告警（所有 Worker 均失效时返回告警信息，否则为 null）
+#[tauri::command]
+pub fn get_gateway_pool_alert(
+    state: State<AIServicePoolState>,
+) -> Result<Option<PoolAlert>, GatewayError> {
+    let _ = state;
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    Ok(pool_guard.all_down_alert())
+}
+
+/// 列出运行中 Worker 的 OpenAI 兼容端点（供外部客户端直连指定 Worker）
+#[tauri::command]
+pub fn get_worker_endpoints(
     state: State<AIServicePoolState>,
-) -> Result<Vec<serde_json::Value>, String> {
+) -> Result<Vec<WorkerEndpoint>, GatewayError> {
     let _ = state;
     let pool = get_global_pool();
     let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
-    let workers = pool_guard.get_workers();
+    Ok(pool_guard.worker_endpoints())
+}
+
+/// 连接池整体计数（各 Worker 之和）
+#[derive(Debug, Default, Serialize)]
+pub struct GatewayTotals {
+    pub total_requests: u64,
+    pub total_errors: u64,
+    pub total_active: u32,
+    /// 累计输入 / 输出 token 数（来自对话补全响应的 usage）
+    pub total_prompt_tokens: u64,
+    pub total_completion_tokens: u64,
+}
+
+/// 连接池快照（get_gateway_snapshot 返回）
+#[derive(Debug, Serialize)]
+pub struct GatewaySnapshot {
+    /// 单调递增的快照序号
+    pub snapshot_id: u64,
+    /// 生成时间（Unix 毫秒）
+    pub timestamp: u64,
+    pub state: GatewayState,
+    #[serde(flatten)]
+    pub totals: GatewayTotals,
+    pub workers: Vec<serde_json::Value>,
+}
+
+/// 快照序号
+static SNAPSHOT_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// 通过状态视图汇总各 Worker 状态（不需要连接池锁与 Worker 锁）
+fn collect_worker_status(view: &PoolStatusView) -> (Vec<serde_json::Value>, GatewayTotals) {
+    let heartbeat_timeout = view.heartbeat_timeout();
     let mut status_list = Vec::new();
+    let mut totals = GatewayTotals::default();
 
-    for worker in workers {
-        let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
-        let status = match wg.status() {
+    for worker in &view.workers {
+        let status = match worker.status() {
             WorkerState::FailedPermanent => "FATAL".to_string(),
             WorkerState::Disabled => "DISABLED".to_string(),
             other => format!("{:?}", other),
         };
+        let total_requests = worker.counters.total_requests.load(Ordering::Relaxed);
+        let total_errors = worker.counters.total_errors.load(Ordering::Relaxed);
+        // 一次短暂的指标锁内读取所需的全部指标
+        let (
+            active_requests,
+            consecutive_failures,
+            heartbeat_age,
+            latency,
+            prompt_tokens,
+            completion_tokens,
+        ) = {
+            let metrics = crate::utils::lock_or_recover(&worker.metrics, "GatewayWorker.metrics");
+            (
+                metrics.active_requests,
+                metrics.consecutive_failures,
+                metrics.last_heartbeat.map(|t| t.elapsed()),
+                metrics.latency_window.percentiles(),
+                metrics.prompt_tokens,
+                metrics.completion_tokens,
+            )
+        };
+        let breaker_state = worker.circuit_breaker.state();
+        totals.total_requests += total_requests;
+        totals.total_errors += total_errors;
+        totals.total_active += active_requests;
+        totals.total_prompt_tokens += prompt_tokens;
+        totals.total_completion_tokens += completion_tokens;
         status_list.push(serde_json::json!({
-            "id": wg.id,
-            "port": wg.port,
+            "id": worker.id,
+            "port": worker.counters.port.load(Ordering::Relaxed),
             "status": status,
-            "active_requests": wg.active_requests(),
-            "total_requests": wg.total_requests,
-            "total_errors": wg.total_errors,
-            "consecutive_failures": crate::utils::lock_or_recover(&wg.metrics, "GatewayWorker.metrics").consecutive_failures,
-            "circuit_breaker_open": wg.circuit_breaker.state() == CircuitBreakerState::Open,
+            // 维护模式：不参与调度、不被自动重启
+            "maintenance": worker.maintenance.load(Ordering::Relaxed),
+            // 当前进程是否已完成预热（预热进行中时 status 为 Warmup）
+            "warmed_up": worker.warmed_up.load(Ordering::Relaxed),
+            "active_requests": active_requests,
+            // 正在处理的转发请求的追踪 ID（与日志中的 [trace ...] 对应）
+            "current_trace_id": crate::utils::lock_or_recover(&worker.current_trace_id, "GatewayWorker.current_trace_id").clone(),
+            "total_requests": total_requests,
+            "total_errors": total_errors,
+            "consecutive_failures": consecutive_failures,
+            // 从未收到心跳时为 null（可能刚启动）
+            "last_heartbeat_secs_ago": heartbeat_age.map(|d| d.as_secs()),
+            "heartbeat_stale": heartbeat_age.is_some_and(|d| d >= heartbeat_timeout),
+            "circuit_breaker_open": breaker_state == CircuitBreakerState::Open,
+            "circuit_breaker_state": format!("{:?}", breaker_state),
+            // 当前（或最近一次）半开窗口内的试探请求统计
+            "half_open_trials": worker.circuit_breaker.half_open_trials(),
+            // 最近 128 个请求的延迟分位数（毫秒）
+            "latency_p50_ms": latency.p50,
+            "latency_p95_ms": latency.p95,
+            "latency_p99_ms": latency.p99,
+            "latency_samples": latency.samples,
+            // 累计 token 用量（对话补全响应的 usage）
+            "prompt_tokens": prompt_tokens,
+            "completion_tokens": completion_tokens,
+            // 能力信息（/v1/models 获取或从磁盘缓存恢复）
+            "capability": crate::utils::lock_or_recover(&worker.capability, "GatewayWorker.capability").clone(),
         }));
     }
 
-    Ok(status_list)
+    (status_list, totals)
+}
+
+/// 当前连接池的状态视图（连接池创建 / 重建时更新）
+fn pool_status_view() -> PoolStatusView {
+    // 确保连接池已创建（首次调用时创建并发布状态视图）
+    let pool = get_global_pool();
+    let published = POOL_STATUS_VIEW
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    published.unwrap_or_else(|| {
+        crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool").status_view()
+    })
+}
+
+/// 重置指定 Worker 的累计请求 / 错误计数、最近请求历史、失败率与退化系数，返回重置前的统计；
+/// Worker 当前状态与熔断器保持不变（用于问题修复后清除仪表盘上的历史错误）
+#[tauri::command]
+pub fn reset_worker_stats(
+    state: State<AIServicePoolState>,
+    worker_id: usize,
+) -> Result<WorkerStatsSnapshot, GatewayError> {
+    let _ = state;
+    let view = pool_status_view();
+    let worker = view
+        .workers
+        .iter()
+        .find(|worker| worker.id == worker_id)
+        .ok_or(GatewayError::WorkerNotFound(worker_id))?;
+    let snapshot = worker.reset_stats();
+    log::info!(
+        "[Gateway Pool] Worker-{} 统计已重置（重置前 {} 个请求，{} 个错误）",
+        worker_id,
+        snapshot.total_requests,
+        snapshot.total_errors
+    );
+    Ok(snapshot)
+}
+
+/// 重置所有 Worker 的统计（同 reset_worker_stats），返回各 Worker 重置前的统计
+#[tauri::command]
+pub fn reset_gateway_pool_stats(
+    state: State<AIServicePoolState>,
+) -> Result<Vec<WorkerStatsSnapshot>, GatewayError> {
+    let _ = state;
+    let snapshots: Vec<WorkerStatsSnapshot> = pool_status_view()
+        .workers
+        .iter()
+        .map(|worker| worker.reset_stats())
+        .collect();
+    log::info!("[Gateway Pool] 已重置 {} 个 Worker 的统计", snapshots.len());
+    Ok(snapshots)
+}
+
+/// 获取连接池状态
+/// 通过状态视图读取原子状态与计数，不获取连接池锁与 Worker 锁；
+/// 每个 Worker 仍需短暂获取一次指标锁与能力信息锁，转发过程中更新指标时会与之竞争
+#[tauri::command]
+pub fn get_gateway_pool_status(
+    state: State<AIServicePoolState>,
+) -> Result<Vec<serde_json::Value>, GatewayError> {
+    let _ = state;
+    Ok(collect_worker_status(&pool_status_view()).0)
+}
+
+/// 获取最近 window_secs 秒（按分钟统计，最长 1 小时）的请求数、错误数、错误率及失败最多的 Worker
+#[tauri::command]
+pub fn get_gateway_error_stats(
+    state: State<AIServicePoolState>,
+    window_secs: u64,
+) -> Result<GatewayErrorStats, GatewayError> {
+    let _ = state;
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    Ok(pool_guard.error_stats(window_secs))
+}
+
+/// 获取连接池整体快照：Gateway 状态、汇总计数与各 Worker 状态
+/// 在同一次连接池锁内计算，保证各部分相互一致
+#[tauri::command]
+pub fn get_gateway_snapshot(
+    state: State<AIServicePoolState>,
+) -> Result<GatewaySnapshot, GatewayError> {
+    let _ = state;
+    let pool = get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    let gateway_state = pool_guard.get_gateway_state();
+    let (workers, totals) = collect_worker_status(&pool_guard.status_view());
+    Ok(GatewaySnapshot {
+        snapshot_id: SNAPSHOT_SEQ.fetch_add(1, Ordering::SeqCst) + 1,
+        timestamp: crate::service::events::current_timestamp(),
+        state: gateway_state,
+        totals,
+        workers,
+    })
 }
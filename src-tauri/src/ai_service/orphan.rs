@@ -0,0 +1,229 @@
+// 残留 Worker 进程清理：应用被强制结束后遗留的 main_gateway.py 进程会继续占用端口，
+// 导致下次启动时 Worker 端口整体后移，启动前先按命令行识别并终止这些进程
+use super::pool::{GatewayPool, PORT_SHIFT_RANGE};
+use std::net::IpAddr;
+use std::ops::RangeInclusive;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+/// Worker 脚本名（用于从进程命令行中识别 Worker 进程）
+const WORKER_SCRIPT_NAME: &str = "main_gateway.py";
+
+/// 终止后等待端口释放的最长时间
+const PORT_RELEASE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 残留的 Worker 进程
+#[derive(Debug, Clone)]
+struct OrphanProcess {
+    pid: u32,
+    port: u16,
+}
+
+/// 从命令行中解析 --port 参数（支持 `--port 8765` 与 `--port=8765`）
+fn parse_port_arg(cmdline: &str) -> Option<u16> {
+    let mut tokens = cmdline.split_whitespace();
+    while let Some(token) = tokens.next() {
+        let token = token.trim_matches('"');
+        if token == "--port" {
+            return tokens.next()?.trim_matches('"').parse().ok();
+        }
+        if let Some(value) = token.strip_prefix("--port=") {
+            return value.parse().ok();
+        }
+    }
+    None
+}
+
+/// 列出命令行包含 Worker 脚本名的进程（PID, 命令行）
+#[cfg(target_os = "windows")]
+fn list_worker_processes() -> Result<Vec<(u32, String)>, String> {
+    let script = format!(
+        "Get-CimInstance Win32_Process -Filter \"CommandLine like '%{}%'\" | ForEach-Object {{ \"$($_.ProcessId)|$($_.CommandLine)\" }}",
+        WORKER_SCRIPT_NAME
+    );
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output()
+        .map_err(|e| format!("执行 PowerShell 失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "查询进程列表失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (pid, cmdline) = line.split_once('|')?;
+            Some((pid.trim().parse().ok()?, cmdline.trim().to_string()))
+        })
+        .collect())
+}
+
+/// 列出命令行包含 Worker 脚本名的进程（PID, 命令行）
+#[cfg(not(target_os = "windows"))]
+fn list_worker_processes() -> Result<Vec<(u32, String)>, String> {
+    let output = Command::new("ps")
+        .args(["-eo", "pid=,args="])
+        .output()
+        .map_err(|e| format!("执行 ps 失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "查询进程列表失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.contains(WORKER_SCRIPT_NAME))
+        .filter_map(|line| {
+            let (pid, cmdline) = line.trim().split_once(char::is_whitespace)?;
+            Some((pid.parse().ok()?, cmdline.trim().to_string()))
+        })
+        .collect())
+}
+
+/// 终止进程（taskkill /T 优先，失败时回退到 TerminateProcess API）
+#[cfg(target_os = "windows")]
+fn terminate_pid(pid: u32) -> bool {
+    let taskkill_ok = Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &pid.to_string()])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if taskkill_ok {
+        return true;
+    }
+    match super::pool::terminate_process_native(pid) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("[Gateway Pool] 终止残留进程 {} 失败: {}", pid, e);
+            false
+        }
+    }
+}
+
+/// 终止进程（先 SIGTERM，未退出时再 SIGKILL）
+#[cfg(not(target_os = "windows"))]
+fn terminate_pid(pid: u32) -> bool {
+    let send = |signal: &str| {
+        Command::new("kill")
+            .args([signal, &pid.to_string()])
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    };
+    // kill -0 成功表示进程仍存在
+    let alive = || send("-0");
+
+    if !send("-TERM") {
+        return !alive();
+    }
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(1) {
+        if !alive() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    if !send("-KILL") && alive() {
+        log::warn!("[Gateway Pool] 终止残留进程 {} 失败", pid);
+        return false;
+    }
+    true
+}
+
+/// 查找端口范围内的残留 Worker 进程（排除当前进程及本连接池正在管理的 Worker）
+fn find_orphans(port_range: &RangeInclusive<u16>, own_pids: &[u32]) -> Vec<OrphanProcess> {
+    let processes = match list_worker_processes() {
+        Ok(processes) => processes,
+        Err(e) => {
+            log::warn!("[Gateway Pool] 扫描残留 Worker 进程失败: {}", e);
+            return Vec::new();
+        }
+    };
+    let self_pid = std::process::id();
+    processes
+        .into_iter()
+        .filter(|(pid, _)| *pid != self_pid && !own_pids.contains(pid))
+        .filter_map(|(pid, cmdline)| {
+            let port = parse_port_arg(&cmdline)?;
+            port_range
+                .contains(&port)
+                .then_some(OrphanProcess { pid, port })
+        })
+        .collect()
+}
+
+/// 清理范围：本连接池端口范围（含端口被占用时的后移范围）、已启动 Worker 的 PID 与绑定地址
+/// 在持有连接池锁时收集，清理（终止进程、等待端口释放）期间不持有连接池锁
+pub(crate) struct OrphanScope {
+    port_range: RangeInclusive<u16>,
+    own_pids: Vec<u32>,
+    bind_host: IpAddr,
+}
+
+impl OrphanScope {
+    pub(crate) fn of(pool: &GatewayPool) -> Self {
+        let base_port = pool.base_port();
+        let last_port = base_port
+            .saturating_add(pool.pool_size().saturating_sub(1) as u16)
+            .saturating_add(PORT_SHIFT_RANGE);
+        let own_pids = pool
+            .get_workers()
+            .iter()
+            .filter_map(|worker| {
+                crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker")
+                    .process
+                    .as_ref()
+                    .map(|child| child.id())
+            })
+            .collect();
+        Self {
+            port_range: base_port..=last_port,
+            own_pids,
+            bind_host: pool.bind_host(),
+        }
+    }
+}
+
+/// 终止清理范围内的残留 Worker 进程，返回终止的进程数；本连接池已启动的 Worker 不受影响
+pub(crate) fn reap_orphan_workers(scope: &OrphanScope) -> usize {
+    let orphans = find_orphans(&scope.port_range, &scope.own_pids);
+    if orphans.is_empty() {
+        return 0;
+    }
+
+    log::warn!(
+        "[Gateway Pool] 发现 {} 个残留 Worker 进程（上次未正常退出），正在清理: {:?}",
+        orphans.len(),
+        orphans
+    );
+    let reaped: Vec<OrphanProcess> = orphans
+        .into_iter()
+        .filter(|orphan| terminate_pid(orphan.pid))
+        .collect();
+
+    // 等待端口释放，避免随后启动的 Worker 仍然切换到后移端口
+    let bind_host = scope.bind_host;
+    let start = Instant::now();
+    while start.elapsed() < PORT_RELEASE_TIMEOUT
+        && reaped
+            .iter()
+            .any(|orphan| std::net::TcpListener::bind((bind_host, orphan.port)).is_err())
+    {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    log::info!(
+        "[Gateway Pool] 已清理 {} 个残留 Worker 进程（端口 {}），耗时 {} ms",
+        reaped.len(),
+        reaped
+            .iter()
+            .map(|orphan| orphan.port.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        start.elapsed().as_millis()
+    );
+    reaped.len()
+}
@@ -1,19 +1,129 @@
 use reqwest::blocking::Client;
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::io::{BufRead, BufReader};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::process::{Child, Command, Stdio};
 use std::sync::{
-    atomic::{AtomicBool, AtomicU8, Ordering},
-    Arc, Mutex, OnceLock,
+    atomic::{AtomicBool, AtomicU16, AtomicU64, AtomicU8, Ordering},
+    Arc, Condvar, Mutex, OnceLock, Weak,
 };
 use std::thread;
 use std::time::{Duration, Instant};
 
-use crate::service::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerState};
+use crate::ai_service::capability_cache;
+use crate::ai_service::compression;
+use crate::ai_service::error::GatewayError;
+use crate::ai_service::idempotency::{IdempotencyCache, IdempotencyLookup, RequestFingerprint};
+use crate::ai_service::pool_config::{
+    AllDownAction, AllDownPolicy, CircuitBreakerSettings, HealthCheckConfig, ModelCacheConfig,
+    RequestPolicy, StartupTimeoutConfig, DEFAULT_MODEL_ALIAS,
+};
+use crate::ai_service::request_stats::{GatewayErrorStats, RequestStats};
+use crate::ai_service::state_events::{publish_pool_alert, publish_state_change};
+use crate::service::circuit_breaker::{CircuitBreaker, CircuitBreakerState};
 use crate::utils::get_app_base_dir;
 
+/// 健康检查时刷新 Worker 模型列表（/v1/models）的间隔
+const WORKER_MODELS_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// 端口被占用时向后查找空闲端口的范围
+pub(super) const PORT_SHIFT_RANGE: u16 = 50;
+
+/// 启动时端口被其他进程抢占（检查空闲后、Python 绑定前）时换用新端口的最大重试次数
+const BIND_RACE_MAX_RETRIES: u32 = 2;
+
+/// 进程在启动阶段退出后，等待 stderr 读取线程识别绑定错误的最长时间
+const BIND_ERROR_GRACE: Duration = Duration::from_millis(300);
+
+/// 启动 Worker 时持有 Worker 锁等待其就绪的时间（足以识别启动阶段的端口绑定失败，之后由健康检查线程接管）
+const DEFAULT_STARTUP_WAIT: Duration = Duration::from_millis(1500);
+
+/// start_all 同步等待配置了启动超时的 Worker 就绪的上限（不持有任何锁；
+/// 之后仍未就绪的由端口探测线程与健康检查线程按启动超时接管，避免初始化命令阻塞数分钟）
+const MAX_STARTUP_WAIT: Duration = Duration::from_secs(10);
+
+/// 未配置启动超时时，端口探测线程等待 Worker 监听端口的时间
+const PORT_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 连接池自行生成的响应（模型列表缓存、错误信息）的 Content-Type
+const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// 每个 Worker 保留的最近 stderr 行数
+const WORKER_LOG_CAPACITY: usize = 200;
+
+/// 默认排空超时（等待在途请求完成的最长时间）
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 排空期间检查在途请求数的间隔
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 手动 HTTP 探测的超时时间
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 手动 HTTP 探测返回的响应体最大字节数
+const PROBE_BODY_PREVIEW_BYTES: u64 = 2048;
+
+/// 预热请求的超时时间（首次加载模型可能较慢）
+const WARMUP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// 冒烟测试请求的超时时间
+const SMOKE_TEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 请求追踪 ID 请求头（调用方可自带，未设置时由连接池生成并附加到转发的请求中）
+pub const TRACE_ID_HEADER: &str = "X-Trace-Id";
+
+/// 分层健康检查 L1 的 TCP 连接超时
+const LAYERED_TCP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 冒烟测试返回的回复片段最大字符数
+const SMOKE_TEST_SNIPPET_CHARS: usize = 200;
+
+/// 排队等待时即使没有收到释放信号也按该间隔重新选择（熔断冷却结束等变化不会发出信号）
+const QUEUE_RECHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Worker 释放信号：请求结束或 Worker 状态变化时递增代数并唤醒排队等待的请求
+struct WorkerReleaseSignal {
+    generation: Mutex<u64>,
+    condvar: Condvar,
+}
+
+impl WorkerReleaseSignal {
+    fn generation(&self) -> u64 {
+        *crate::utils::lock_or_recover(&self.generation, "WorkerReleaseSignal")
+    }
+
+    fn notify(&self) {
+        *crate::utils::lock_or_recover(&self.generation, "WorkerReleaseSignal") += 1;
+        self.condvar.notify_all();
+    }
+
+    /// 等待代数不再等于 seen，最多等待 timeout
+    fn wait_changed(&self, seen: u64, timeout: Duration) {
+        let guard = crate::utils::lock_or_recover(&self.generation, "WorkerReleaseSignal");
+        let _ = self
+            .condvar
+            .wait_timeout_while(guard, timeout, |generation| *generation == seen);
+    }
+}
+
+static WORKER_RELEASED: WorkerReleaseSignal = WorkerReleaseSignal {
+    generation: Mutex::new(0),
+    condvar: Condvar::new(),
+};
+
+/// 通知排队等待的请求重新选择 Worker
+fn notify_worker_released() {
+    WORKER_RELEASED.notify();
+}
+
+/// 健康检查线程是否已启动
+static HEALTH_CHECK_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 健康检查线程代次（连接池重建后旧线程据此退出）
+static HEALTH_CHECK_GENERATION: AtomicU64 = AtomicU64::new(0);
+
 static UNAVAILABLE_MODELS: OnceLock<Mutex<BTreeSet<String>>> = OnceLock::new();
 static UNAVAILABLE_MODELS_LOGGER_STARTED: AtomicBool = AtomicBool::new(false);
 
@@ -34,6 +144,99 @@ fn extract_unavailable_model_id(line: &str) -> Option<String> {
     }
 }
 
+/// 是否为进程级退出标记（[EXIT] / [FATAL] / [MAIN] [FATAL] 开头）；
+/// 请求级的 [REQUEST-x] [FATAL] 等日志不会导致进程退出，不算在内
+fn is_process_exit_marker(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("[EXIT]") || line.starts_with("[FATAL]") || line.starts_with("[MAIN] [FATAL]")
+}
+
+/// 是否为端口绑定失败的 stderr 输出（Linux EADDRINUSE 98 / Windows WSAEADDRINUSE 10048）
+fn is_bind_error_line(line: &str) -> bool {
+    let line = line.to_ascii_lowercase();
+    line.contains("address already in use")
+        || line.contains("errno 98]")
+        || line.contains("error 10048]")
+        || line.contains("errno 10048]")
+}
+
+/// 原子地将状态从 from 转为 to 并发布状态变化事件（stderr 读取线程与端口探测线程都可能执行就绪转换，
+/// 只有一个线程成功）；状态已被其他线程改变时返回 false
+fn transition_state(
+    worker_id: usize,
+    worker_state: &AtomicU8,
+    from: WorkerState,
+    to: WorkerState,
+    reason: &str,
+) -> bool {
+    if worker_state
+        .compare_exchange(from as u8, to as u8, Ordering::Relaxed, Ordering::Relaxed)
+        .is_err()
+    {
+        return false;
+    }
+    publish_state_change(worker_id, from, to, Some(reason.to_string()));
+    true
+}
+
+/// READY 后检测到进程退出标记（如加载模型时 OOM）：仍处于可服务状态时立即转为 Dead 并安排重启，
+/// 不等待下一轮健康检查；重启在独立线程中安排，避免 stderr 读取线程等待 Worker 锁时
+/// 与终止进程（等待管道读完）相互等待
+fn handle_exit_after_ready(
+    worker_id: usize,
+    worker_state: &AtomicU8,
+    worker_ref: &Weak<Mutex<GatewayWorker>>,
+) {
+    let previous = WorkerState::from(worker_state.load(Ordering::Relaxed));
+    if !matches!(
+        previous,
+        WorkerState::Ready
+            | WorkerState::Warmup
+            | WorkerState::Idle
+            | WorkerState::BusyStreaming
+            | WorkerState::BusyBlocked
+            | WorkerState::Degraded
+            | WorkerState::Unhealthy
+    ) {
+        return;
+    }
+    if worker_state
+        .compare_exchange(
+            previous as u8,
+            WorkerState::Dead as u8,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        )
+        .is_err()
+    {
+        return;
+    }
+    log::error!(
+        "[Gateway Pool] [Worker-{}] READY 后检测到进程退出标记，立即标记为 Dead 并安排重启",
+        worker_id
+    );
+    publish_state_change(
+        worker_id,
+        previous,
+        WorkerState::Dead,
+        Some(HealthSignal::ProcessExit.as_str().to_string()),
+    );
+
+    let Some(worker) = worker_ref.upgrade() else {
+        return;
+    };
+    thread::spawn(move || {
+        {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            if wg.status() != WorkerState::Dead {
+                return;
+            }
+            wg.circuit_breaker.force_open();
+        }
+        schedule_restart_for_worker(worker, HealthSignal::ProcessExit);
+    });
+}
+
 /// Gateway Worker 状态（优化后的状态机）
 /// 状态转换：INIT -> READY -> (IDLE | BUSY) -> (DEGRADED | UNHEALTHY) -> DEAD
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -59,10 +262,14 @@ pub enum WorkerState {
     Dead = 8,
     FailedPermanent = 9,
     Disabled = 10,
+    /// 排空中：不再接新请求，等待在途请求完成后重启 / 停止
+    Draining = 11,
+    /// 预热中：已发送最小请求触发模型加载，完成前不参与调度
+    Warmup = 12,
 }
 
 /// Gateway 状态（调度核心）
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum GatewayState {
     /// 有可用 worker
     Healthy,
@@ -74,6 +281,33 @@ pub enum GatewayState {
     Unavailable,
 }
 
+/// 计算 Gateway 状态所需的 Worker 计数
+#[derive(Debug, Clone, Copy, Default)]
+struct GatewayStateCounts {
+    /// 空闲 Worker
+    idle: usize,
+    /// 忙碌但熔断未打开的 Worker（请求可以排队等待它们）
+    busy_queueable: usize,
+    /// 降级但熔断未打开、仍可接请求的 Worker
+    degraded_usable: usize,
+}
+
+impl GatewayStateCounts {
+    /// 有空闲 Worker 为 Healthy；否则有可排队的忙碌 Worker 为 Busy；
+    /// 否则有仍可用的降级 Worker 为 Degraded；都没有为 Unavailable
+    fn classify(self) -> GatewayState {
+        if self.idle > 0 {
+            GatewayState::Healthy
+        } else if self.busy_queueable > 0 {
+            GatewayState::Busy
+        } else if self.degraded_usable > 0 {
+            GatewayState::Degraded
+        } else {
+            GatewayState::Unavailable
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum HealthSignal {
     HeartbeatTimeout,
@@ -82,6 +316,18 @@ enum HealthSignal {
     PanicDetected,
 }
 
+impl HealthSignal {
+    /// 状态变化事件中使用的原因标识
+    fn as_str(&self) -> &'static str {
+        match self {
+            HealthSignal::HeartbeatTimeout => "heartbeat_timeout",
+            HealthSignal::RequestTimeout => "request_timeout",
+            HealthSignal::ProcessExit => "process_exit",
+            HealthSignal::PanicDetected => "panic_detected",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct RestartPolicy {
     max_retries: u32,
@@ -121,11 +367,213 @@ impl RestartBudget {
     }
 }
 
-fn find_free_port(start: u16, end: u16) -> Option<u16> {
+fn find_free_port(host: IpAddr, start: u16, end: u16) -> Option<u16> {
     if start > end {
         return None;
     }
-    (start..=end).find(|p| std::net::TcpListener::bind(("127.0.0.1", *p)).is_ok())
+    (start..=end).find(|p| std::net::TcpListener::bind((host, *p)).is_ok())
+}
+
+/// 连接 Worker 使用的地址：绑定通配地址（0.0.0.0 / ::）时改用同协议族的回环地址
+pub(crate) fn worker_connect_host(bind_host: IpAddr) -> IpAddr {
+    match bind_host {
+        IpAddr::V4(ip) if ip.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        IpAddr::V6(ip) if ip.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        ip => ip,
+    }
+}
+
+/// Worker 的 HTTP 基础 URL（IPv6 地址加方括号）
+pub(crate) fn worker_base_url(bind_host: IpAddr, port: u16) -> String {
+    format!(
+        "http://{}",
+        SocketAddr::new(worker_connect_host(bind_host), port)
+    )
+}
+
+/// 使用 Windows TerminateProcess API 终止进程（taskkill 不可用或失败时的兜底）
+#[cfg(target_os = "windows")]
+pub(super) fn terminate_process_native(pid: u32) -> Result<(), String> {
+    use windows::Win32::Foundation::{CloseHandle, BOOL};
+    use windows::Win32::System::Threading::{OpenProcess, TerminateProcess, PROCESS_TERMINATE};
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE, BOOL::from(false), pid)
+            .map_err(|e| format!("OpenProcess 失败: {}", e))?;
+        let result =
+            TerminateProcess(handle, 1).map_err(|e| format!("TerminateProcess 失败: {}", e));
+        let _ = CloseHandle(handle);
+        result
+    }
+}
+
+/// 终止 Worker 进程（taskkill /T 优先，失败时回退到 TerminateProcess API）
+/// 返回是否确认终止，并记录最终生效的终止方式
+#[cfg(target_os = "windows")]
+fn terminate_worker_process(child: &mut Child, worker_id: usize) -> bool {
+    let pid = child.id();
+    if let Err(e) = child.kill() {
+        log::warn!("Worker-{} kill() 失败: {}", worker_id, e);
+    }
+
+    match Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &pid.to_string()])
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            log::info!("Worker-{} 进程已通过 taskkill 终止", worker_id);
+            return true;
+        }
+        Ok(output) => {
+            log::warn!(
+                "Worker-{} taskkill 失败，状态码: {:?}，尝试 TerminateProcess",
+                worker_id,
+                output.status.code()
+            );
+        }
+        Err(e) => {
+            log::warn!(
+                "Worker-{} taskkill 执行失败: {}，尝试 TerminateProcess",
+                worker_id,
+                e
+            );
+        }
+    }
+
+    // kill() 可能已经结束了进程（此时 taskkill 会因找不到进程而失败）
+    if let Ok(Some(status)) = child.try_wait() {
+        log::info!(
+            "Worker-{} 进程已通过 kill() 终止 (状态: {:?})",
+            worker_id,
+            status
+        );
+        return true;
+    }
+
+    match terminate_process_native(pid) {
+        Ok(()) => {
+            log::info!("Worker-{} 进程已通过 TerminateProcess 终止", worker_id);
+            true
+        }
+        Err(e) => {
+            log::error!("Worker-{} TerminateProcess 终止失败: {}", worker_id, e);
+            false
+        }
+    }
+}
+
+/// 延迟样本环形缓冲区容量
+const LATENCY_WINDOW: usize = 128;
+
+/// 最近请求延迟的定长环形缓冲区（内存恒定，记录时不分配）
+#[derive(Debug, Clone)]
+pub struct LatencyWindow {
+    samples: [u64; LATENCY_WINDOW],
+    next: usize,
+    len: usize,
+}
+
+/// 延迟分位数（毫秒）
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct LatencyPercentiles {
+    pub p50: u64,
+    pub p95: u64,
+    pub p99: u64,
+    /// 参与统计的样本数
+    pub samples: usize,
+}
+
+impl Default for LatencyWindow {
+    fn default() -> Self {
+        Self {
+            samples: [0; LATENCY_WINDOW],
+            next: 0,
+            len: 0,
+        }
+    }
+}
+
+impl LatencyWindow {
+    /// 记录一个延迟样本（覆盖最旧的样本）
+    pub fn record(&mut self, latency_ms: u64) {
+        self.samples[self.next] = latency_ms;
+        self.next = (self.next + 1) % LATENCY_WINDOW;
+        self.len = (self.len + 1).min(LATENCY_WINDOW);
+    }
+
+    /// 计算 p50/p95/p99（最近邻法，无样本时全为 0）
+    pub fn percentiles(&self) -> LatencyPercentiles {
+        if self.len == 0 {
+            return LatencyPercentiles::default();
+        }
+        let mut sorted = self.samples;
+        let sorted = &mut sorted[..self.len];
+        sorted.sort_unstable();
+        let pick = |p: usize| sorted[((self.len * p).div_ceil(100)).saturating_sub(1)];
+        LatencyPercentiles {
+            p50: pick(50),
+            p95: pick(95),
+            p99: pick(99),
+            samples: self.len,
+        }
+    }
+}
+
+/// 流式响应保留的尾部字节数（用于解析最后的 usage 数据块）
+const STREAM_USAGE_TAIL_BYTES: usize = 16 * 1024;
+
+/// 对话补全的 token 用量（来自响应中的 usage 对象）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl TokenUsage {
+    /// 是否为需要统计用量的对话补全请求
+    fn applies_to(method: &str, path: &str) -> bool {
+        method.eq_ignore_ascii_case("POST") && path.starts_with("/v1/chat/completions")
+    }
+
+    /// 从 JSON 对象的 usage 字段读取用量（usage 缺失或为 null 时返回 None）
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let usage = value.get("usage").filter(|u| u.is_object())?;
+        let field = |name: &str| usage.get(name).and_then(|v| v.as_u64()).unwrap_or(0);
+        Some(Self {
+            prompt_tokens: field("prompt_tokens"),
+            completion_tokens: field("completion_tokens"),
+        })
+    }
+
+    /// 解析非流式响应体（格式异常时返回 None，不影响转发）
+    fn from_body(body: &[u8]) -> Option<Self> {
+        serde_json::from_slice::<serde_json::Value>(body)
+            .ok()
+            .and_then(|value| Self::from_json(&value))
+    }
+
+    /// 解析 SSE 流尾部：从后往前找最后一个带 usage 的 data: 数据块
+    fn from_sse_tail(tail: &[u8]) -> Option<Self> {
+        String::from_utf8_lossy(tail)
+            .lines()
+            .rev()
+            .filter_map(|line| line.trim().strip_prefix("data:"))
+            .map(str::trim)
+            .filter(|data| *data != "[DONE]")
+            .find_map(|data| {
+                serde_json::from_str::<serde_json::Value>(data)
+                    .ok()
+                    .and_then(|value| Self::from_json(&value))
+            })
+    }
+}
+
+/// Worker stderr 输出行（带时间戳）
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerLogLine {
+    /// 时间戳（毫秒）
+    pub timestamp: u64,
+    pub line: String,
 }
 
 /// Worker 核心指标
@@ -150,6 +598,12 @@ pub struct WorkerMetrics {
     pub panic_detected: bool,
     /// 最近请求历史（用于计算失败率，保留最近 10 个）
     recent_requests: Vec<bool>, // true=成功, false=失败
+    /// 最近 128 个请求的延迟（用于计算分位数）
+    pub latency_window: LatencyWindow,
+    /// 累计输入 token 数（来自对话补全响应的 usage）
+    pub prompt_tokens: u64,
+    /// 累计输出 token 数
+    pub completion_tokens: u64,
 }
 
 impl Default for WorkerMetrics {
@@ -166,6 +620,9 @@ impl Default for WorkerMetrics {
             last_timeout_at: None,
             panic_detected: false,
             recent_requests: Vec::new(),
+            latency_window: LatencyWindow::default(),
+            prompt_tokens: 0,
+            completion_tokens: 0,
         }
     }
 }
@@ -198,6 +655,21 @@ impl WorkerMetrics {
         }
     }
 
+    /// 清除最近请求历史与据此计算的失败率、退化系数（不影响活跃请求数、心跳与延迟统计）
+    pub fn reset_request_history(&mut self) {
+        self.recent_requests.clear();
+        self.recent_fail_rate = 0.0;
+        self.degrade_score = 0.0;
+    }
+
+    /// 累加对话补全的 token 用量
+    pub fn record_usage(&mut self, usage: TokenUsage) {
+        self.prompt_tokens = self.prompt_tokens.saturating_add(usage.prompt_tokens);
+        self.completion_tokens = self
+            .completion_tokens
+            .saturating_add(usage.completion_tokens);
+    }
+
     /// 检查是否应该跳过（预测失败）
     pub fn should_skip(&self) -> bool {
         self.recent_fail_rate > 0.3 || self.degrade_score > 0.7
@@ -213,7 +685,7 @@ impl WorkerMetrics {
     }
 }
 
-/// Worker 能力标识（未来扩展）
+/// Worker 能力标识（由 /v1/models 填充，按网关版本缓存到磁盘）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkerCapability {
     /// 支持的模型列表
@@ -258,12 +730,31 @@ impl From<u8> for WorkerState {
             8 => WorkerState::Dead,
             9 => WorkerState::FailedPermanent,
             10 => WorkerState::Disabled,
+            11 => WorkerState::Draining,
+            12 => WorkerState::Warmup,
             _ => WorkerState::Dead,
         }
     }
 }
 
 impl WorkerState {
+    /// 全部状态（按编码顺序，用于指标导出）
+    pub const ALL: [WorkerState; 13] = [
+        WorkerState::Init,
+        WorkerState::Ready,
+        WorkerState::Idle,
+        WorkerState::BusyStreaming,
+        WorkerState::BusyBlocked,
+        WorkerState::Degraded,
+        WorkerState::Unhealthy,
+        WorkerState::Restarting,
+        WorkerState::Dead,
+        WorkerState::FailedPermanent,
+        WorkerState::Disabled,
+        WorkerState::Draining,
+        WorkerState::Warmup,
+    ];
+
     /// 检查状态是否可以接受请求
     pub fn can_accept_request(&self) -> bool {
         matches!(self, WorkerState::Idle | WorkerState::Degraded)
@@ -305,6 +796,8 @@ pub struct GatewayWorker {
     pub id: usize,
     /// 端口号
     pub port: u16,
+    /// 绑定地址
+    pub bind_host: IpAddr,
     /// 进程句柄
     pub process: Option<Child>,
     /// 状态（使用原子类型，轻量级锁）
@@ -319,24 +812,390 @@ pub struct GatewayWorker {
     pub last_success: Option<Instant>,
     /// 最近一次启动时间（用于启动宽限/超时判定）
     pub started_at: Option<Instant>,
-    /// 总请求数
-    pub total_requests: u64,
-    /// 总错误数
-    pub total_errors: u64,
+    /// 端口与累计请求 / 错误数（原子计数，状态查询时无需 Worker 锁）
+    pub counters: Arc<WorkerCounters>,
     pub circuit_breaker: CircuitBreaker,
-    /// 半开熔断状态（只接 1 个请求测试）
-    pub half_open_testing: bool,
     pub port_bound: Arc<AtomicBool>,
     pub model_ready: Arc<AtomicBool>,
-    /// Trace ID（当前请求的追踪 ID）
-    #[allow(dead_code)]
-    pub current_trace_id: Option<String>,
+    /// Worker 是否声明支持 gzip 请求体（由 /health 响应中的 accept_encoding 更新）
+    pub accepts_gzip: Arc<AtomicBool>,
+    /// 本次启动的 stderr 中是否出现端口绑定失败（用于识别端口抢占并换端口重试）
+    pub bind_conflict: Arc<AtomicBool>,
+    /// 正在处理的请求的追踪 ID（空闲时为 None，状态查询据此定位卡住 Worker 的请求）
+    pub current_trace_id: Arc<Mutex<Option<String>>>,
+    /// 是否被配置永久隔离（disable_worker_0），隔离后不启动、不调度、不重启
+    pub isolated: bool,
+    /// 维护模式：不参与调度，健康检查线程不检查、不重启，进程保持运行（便于挂调试器）
+    pub maintenance: Arc<AtomicBool>,
+    /// 当前进程是否已完成预热（每次启动时清除）
+    pub warmed_up: Arc<AtomicBool>,
+    /// 正在进行的预热的开始时间（预热结束后清除，健康检查据此跳过预热中的 Worker）
+    warmup_started: Arc<Mutex<Option<Instant>>>,
+    /// 启动就绪后是否自动预热（就绪后先进入 Warmup，预热请求返回后再转为 Idle）
+    auto_warmup: bool,
+    /// 启动超时（None 时使用健康检查的启动宽限期）
+    startup_timeout: Option<Duration>,
+    /// 指向自身的弱引用（连接池创建时设置），供 stderr 读取线程在进程退出时安排重启
+    self_ref: Weak<Mutex<GatewayWorker>>,
     restart_budget: RestartBudget,
     next_restart_at: Option<Instant>,
     pending_restart: Option<HealthSignal>,
     restart_policy: RestartPolicy,
     restart_failures: u32,
     last_restart_failure: Option<Instant>,
+    /// 重启 / 停止前等待在途请求完成的最长时间
+    drain_timeout: Duration,
+    /// 最近的 stderr 输出（跨重启保留，便于查看 Worker 反复退出的原因）
+    log_buffer: Arc<Mutex<VecDeque<WorkerLogLine>>>,
+    /// 最近一次从 /v1/models 刷新 supported_models 的时间（为空表示需要刷新）
+    models_refreshed_at: Option<Instant>,
+    /// 当前进程运行的网关版本（脚本与模型配置的哈希，用作能力缓存的键）
+    gateway_version: Option<String>,
+}
+
+/// Worker 的端口与累计计数（原子类型，状态查询时无需 Worker 锁）
+#[derive(Debug, Default)]
+pub struct WorkerCounters {
+    /// 当前端口（与 GatewayWorker.port 同步，端口被占用切换时更新）
+    pub port: AtomicU16,
+    /// 总请求数
+    pub total_requests: AtomicU64,
+    /// 总错误数
+    pub total_errors: AtomicU64,
+}
+
+/// Worker 状态的只读句柄：共享 Worker 的原子状态、计数、指标与熔断器，
+/// 读取时不需要连接池锁与 Worker 锁（转发请求期间连接池锁可能被长时间占用）
+#[derive(Clone)]
+pub struct WorkerStatusHandle {
+    pub id: usize,
+    pub state: Arc<AtomicU8>,
+    pub maintenance: Arc<AtomicBool>,
+    pub warmed_up: Arc<AtomicBool>,
+    pub counters: Arc<WorkerCounters>,
+    pub metrics: Arc<Mutex<WorkerMetrics>>,
+    pub capability: Arc<Mutex<WorkerCapability>>,
+    pub circuit_breaker: CircuitBreaker,
+    pub current_trace_id: Arc<Mutex<Option<String>>>,
+}
+
+impl WorkerStatusHandle {
+    pub fn status(&self) -> WorkerState {
+        WorkerState::from(self.state.load(Ordering::Relaxed))
+    }
+
+    /// 重置累计请求 / 错误计数与最近请求历史，返回重置前的数据；
+    /// 不改变 Worker 状态与熔断器
+    pub fn reset_stats(&self) -> WorkerStatsSnapshot {
+        let mut metrics = crate::utils::lock_or_recover(&self.metrics, "GatewayWorker.metrics");
+        let snapshot = WorkerStatsSnapshot {
+            worker_id: self.id,
+            total_requests: self.counters.total_requests.swap(0, Ordering::Relaxed),
+            total_errors: self.counters.total_errors.swap(0, Ordering::Relaxed),
+            recent_requests: metrics.recent_requests.clone(),
+            recent_fail_rate: metrics.recent_fail_rate,
+            degrade_score: metrics.degrade_score,
+        };
+        metrics.reset_request_history();
+        snapshot
+    }
+}
+
+/// 重置前的 Worker 统计（reset_worker_stats 返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerStatsSnapshot {
+    pub worker_id: usize,
+    pub total_requests: u64,
+    pub total_errors: u64,
+    /// 最近请求结果（true 为成功，按时间先后）
+    pub recent_requests: Vec<bool>,
+    pub recent_fail_rate: f64,
+    pub degrade_score: f64,
+}
+
+/// 单个 Worker 的启动 / 停止结果（start_all / stop_all 返回，供前端按 Worker 展示）
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerStartResult {
+    pub worker_id: usize,
+    /// 实际使用的端口（启动时端口被占用会切换到空闲端口）
+    pub port: u16,
+    pub ok: bool,
+    pub message: String,
+    /// 操作完成后的状态
+    pub state: WorkerState,
+}
+
+impl WorkerStartResult {
+    fn new(worker: &GatewayWorker, ok: bool, message: String) -> Self {
+        Self {
+            worker_id: worker.id,
+            port: worker.port,
+            ok,
+            message,
+            state: worker.status(),
+        }
+    }
+
+    /// 渲染为文本形式（用于日志）
+    pub fn log_lines(results: &[WorkerStartResult]) -> Vec<String> {
+        results.iter().map(|r| r.message.clone()).collect()
+    }
+}
+
+/// 预热结果（warmup_worker 返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmupResult {
+    pub worker_id: usize,
+    /// 预热使用的模型
+    pub model: String,
+    pub elapsed_ms: u64,
+}
+
+/// 分层健康检查结果（check_worker_health 返回），未执行的检查项为 None
+#[derive(Debug, Clone, Serialize)]
+pub struct LayeredHealthReport {
+    pub worker_id: usize,
+    /// 请求的检查级别（0-3）
+    pub level: u8,
+    /// 已执行的检查是否全部通过
+    pub passed: bool,
+    /// 检查开始时的 Worker 状态
+    pub state: WorkerState,
+    /// L0: 进程是否存在
+    pub process_alive: Option<bool>,
+    /// L1: TCP 端口是否可连接
+    pub tcp_ok: Option<bool>,
+    /// L2: /health 是否返回 200
+    pub http_ok: Option<bool>,
+    /// L3: 模型是否能完成对话补全
+    pub model_warm: Option<bool>,
+    /// Worker 忙碌，跳过了 L2 及以上检查
+    pub skipped_busy: bool,
+    pub elapsed_ms: u64,
+    /// 失败原因
+    pub detail: Option<String>,
+}
+
+/// 冒烟测试结果（gateway_smoke_test 返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct SmokeTestResult {
+    /// 是否收到 2xx 响应
+    pub ok: bool,
+    pub worker_id: usize,
+    /// 测试使用的模型（获取模型列表失败时为空）
+    pub model: Option<String>,
+    /// HTTP 状态码（请求失败时为空）
+    pub status: Option<u16>,
+    pub elapsed_ms: u64,
+    /// 模型回复内容（无法解析时为响应体前若干字符）
+    pub snippet: String,
+    /// 失败原因（连接失败、超时、无可用模型等）
+    pub error: Option<String>,
+}
+
+/// 预热所需的 Worker 共享状态，可在不持有 Worker 锁的线程中执行预热
+#[derive(Clone)]
+struct WarmupTarget {
+    worker_id: usize,
+    api_url: String,
+    state: Arc<AtomicU8>,
+    capability: Arc<Mutex<WorkerCapability>>,
+    warmed_up: Arc<AtomicBool>,
+    warmup_started: Arc<Mutex<Option<Instant>>>,
+}
+
+impl WarmupTarget {
+    /// 记录预热开始时间（已记录时保留原值）
+    fn mark_started(&self) {
+        crate::utils::lock_or_recover(self.warmup_started.as_ref(), "GatewayWorker.warmup_started")
+            .get_or_insert_with(Instant::now);
+    }
+
+    /// 选择预热模型：优先使用已知模型列表，为空时请求 /v1/models
+    fn pick_model(&self, client: &Client) -> Result<String, String> {
+        let known =
+            crate::utils::lock_or_recover(self.capability.as_ref(), "GatewayWorker.capability")
+                .supported_models
+                .first()
+                .cloned();
+        if let Some(model) = known {
+            return Ok(model);
+        }
+        let data = client
+            .get(format!("{}/v1/models", self.api_url))
+            .timeout(PROBE_TIMEOUT)
+            .send()
+            .map_err(|e| format!("获取模型列表失败: {}", e))?
+            .json::<serde_json::Value>()
+            .map_err(|e| format!("解析模型列表失败: {}", e))?;
+        parse_model_ids(&data)
+            .into_iter()
+            .next()
+            .ok_or_else(|| "Worker 没有可用模型，无法预热".to_string())
+    }
+
+    /// 发送一次最小对话补全请求触发模型加载；
+    /// 完成后（无论成功与否）若仍处于 Warmup 则转为 Idle，失败时 Worker 照常可用，只是首个请求可能较慢
+    fn run(&self) -> Result<WarmupResult, String> {
+        let start = Instant::now();
+        self.mark_started();
+        let result = Client::builder()
+            .timeout(WARMUP_TIMEOUT)
+            .build()
+            .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))
+            .and_then(|client| {
+                let model = self.pick_model(&client)?;
+                let body = serde_json::json!({
+                    "model": model,
+                    "messages": [{ "role": "user", "content": "ping" }],
+                    "max_tokens": 1,
+                    "stream": false,
+                });
+                let response = client
+                    .post(format!("{}/v1/chat/completions", self.api_url))
+                    .json(&body)
+                    .send()
+                    .map_err(|e| format!("预热请求失败: {}", e))?;
+                if !response.status().is_success() {
+                    return Err(format!("预热请求返回 HTTP {}", response.status()));
+                }
+                Ok(model)
+            });
+
+        if self
+            .state
+            .compare_exchange(
+                WorkerState::Warmup as u8,
+                WorkerState::Idle as u8,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+        {
+            publish_state_change(
+                self.worker_id,
+                WorkerState::Warmup,
+                WorkerState::Idle,
+                Some("warmup".to_string()),
+            );
+            notify_worker_released();
+        }
+        *crate::utils::lock_or_recover(
+            self.warmup_started.as_ref(),
+            "GatewayWorker.warmup_started",
+        ) = None;
+
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+        match result {
+            Ok(model) => {
+                self.warmed_up.store(true, Ordering::Relaxed);
+                log::info!(
+                    "[Gateway Pool] [Worker-{}] 预热完成（模型 {}，耗时 {} ms）",
+                    self.worker_id,
+                    model,
+                    elapsed_ms
+                );
+                Ok(WarmupResult {
+                    worker_id: self.worker_id,
+                    model,
+                    elapsed_ms,
+                })
+            }
+            Err(e) => {
+                log::warn!(
+                    "[Gateway Pool] [Worker-{}] 预热失败（耗时 {} ms）: {}",
+                    self.worker_id,
+                    elapsed_ms,
+                    e
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// 在后台线程中预热（自动预热使用）
+    fn spawn(self) {
+        // 在线程启动前记录，避免健康检查在预热线程运行前就检查 Warmup 状态的 Worker
+        self.mark_started();
+        thread::spawn(move || {
+            let _ = self.run();
+        });
+    }
+
+    /// 冒烟测试：发送一次单词提示的对话补全请求，不改变 Worker 状态与指标
+    fn smoke_test(&self) -> SmokeTestResult {
+        let start = Instant::now();
+        let mut result = SmokeTestResult {
+            ok: false,
+            worker_id: self.worker_id,
+            model: None,
+            status: None,
+            elapsed_ms: 0,
+            snippet: String::new(),
+            error: None,
+        };
+        let outcome = Client::builder()
+            .timeout(SMOKE_TEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))
+            .and_then(|client| {
+                let model = self.pick_model(&client)?;
+                result.model = Some(model.clone());
+                let body = serde_json::json!({
+                    "model": model,
+                    "messages": [{ "role": "user", "content": "ping" }],
+                    "max_tokens": 16,
+                    "stream": false,
+                });
+                let response = client
+                    .post(format!("{}/v1/chat/completions", self.api_url))
+                    .json(&body)
+                    .send()
+                    .map_err(|e| format!("请求失败: {}", e))?;
+                result.status = Some(response.status().as_u16());
+                result.ok = response.status().is_success();
+                response
+                    .bytes()
+                    .map_err(|e| format!("读取响应体失败: {}", e))
+            });
+        result.elapsed_ms = start.elapsed().as_millis() as u64;
+        match outcome {
+            Ok(body) => result.snippet = smoke_test_snippet(&body),
+            Err(e) => {
+                result.ok = false;
+                result.error = Some(e);
+            }
+        }
+        result
+    }
+}
+
+/// 提取冒烟测试的回复片段：优先取 choices[0].message.content，否则取响应体开头
+fn smoke_test_snippet(body: &[u8]) -> String {
+    let content = serde_json::from_slice::<serde_json::Value>(body)
+        .ok()
+        .and_then(|data| {
+            data.pointer("/choices/0/message/content")
+                .and_then(|c| c.as_str())
+                .map(str::to_string)
+        });
+    let text = content.unwrap_or_else(|| String::from_utf8_lossy(body).to_string());
+    text.trim().chars().take(SMOKE_TEST_SNIPPET_CHARS).collect()
+}
+
+/// 连接池状态视图（连接池创建时生成，状态查询用它代替连接池锁）
+#[derive(Clone)]
+pub struct PoolStatusView {
+    pub workers: Vec<WorkerStatusHandle>,
+    health_check: Arc<Mutex<HealthCheckConfig>>,
+}
+
+impl PoolStatusView {
+    /// 当前心跳超时（与连接池共享健康检查配置，修改后立即生效）
+    pub fn heartbeat_timeout(&self) -> Duration {
+        crate::utils::lock_or_recover(self.health_check.as_ref(), "GatewayPool.health_check")
+            .heartbeat_timeout()
+    }
 }
 
 /// 兼容旧版字段访问
@@ -346,9 +1205,61 @@ impl GatewayWorker {
         WorkerState::from(self.state.load(Ordering::Relaxed))
     }
 
-    /// 设置状态
+    /// 设置状态（状态变化时发布 gateway-worker-state-changed 事件，原因取触发重启的健康信号）
     pub fn set_state(&self, state: WorkerState) {
-        self.state.store(state as u8, Ordering::Relaxed);
+        let previous = WorkerState::from(self.state.swap(state as u8, Ordering::Relaxed));
+        if previous != state {
+            notify_worker_released();
+        }
+        publish_state_change(
+            self.id,
+            previous,
+            state,
+            self.pending_restart
+                .map(|signal| signal.as_str().to_string()),
+        );
+    }
+
+    /// 是否处于维护模式
+    pub fn in_maintenance(&self) -> bool {
+        self.maintenance.load(Ordering::Relaxed)
+    }
+
+    /// 预热所需的共享状态
+    fn warmup_target(&self) -> WarmupTarget {
+        WarmupTarget {
+            worker_id: self.id,
+            api_url: self.api_url(),
+            state: Arc::clone(&self.state),
+            capability: Arc::clone(&self.capability),
+            warmed_up: Arc::clone(&self.warmed_up),
+            warmup_started: Arc::clone(&self.warmup_started),
+        }
+    }
+
+    /// 是否正在预热（处于 Warmup 状态且预热开始后未超过 WARMUP_TIMEOUT）
+    fn warmup_in_progress(&self) -> bool {
+        self.status() == WorkerState::Warmup
+            && crate::utils::lock_or_recover(
+                self.warmup_started.as_ref(),
+                "GatewayWorker.warmup_started",
+            )
+            .is_some_and(|started| started.elapsed() < WARMUP_TIMEOUT)
+    }
+
+    /// 状态只读句柄（共享原子状态与指标）
+    pub fn status_handle(&self) -> WorkerStatusHandle {
+        WorkerStatusHandle {
+            id: self.id,
+            state: Arc::clone(&self.state),
+            maintenance: Arc::clone(&self.maintenance),
+            warmed_up: Arc::clone(&self.warmed_up),
+            counters: Arc::clone(&self.counters),
+            metrics: Arc::clone(&self.metrics),
+            capability: Arc::clone(&self.capability),
+            circuit_breaker: self.circuit_breaker.clone(),
+            current_trace_id: Arc::clone(&self.current_trace_id),
+        }
     }
 
     /// 获取活跃请求数
@@ -390,7 +1301,7 @@ impl GatewayWorker {
             metrics.consecutive_timeouts = next;
             metrics.last_timeout_at = Some(now);
         }
-        self.total_errors += 1;
+        self.counters.total_errors.fetch_add(1, Ordering::Relaxed);
         // 使用默认延迟 0
         metrics.record_request(false, 0);
         self.circuit_breaker.record_failure();
@@ -407,6 +1318,14 @@ impl GatewayWorker {
         self.pending_restart = Some(signal);
     }
 
+    /// 标记为永久失败：打开熔断并取消待执行的重启
+    fn mark_failed_permanent(&mut self) {
+        self.circuit_breaker.force_open();
+        self.pending_restart = None;
+        self.next_restart_at = None;
+        self.set_state(WorkerState::FailedPermanent);
+    }
+
     fn record_restart_failure(&mut self, now: Instant) {
         self.restart_failures = self.restart_failures.saturating_add(1);
         self.last_restart_failure = Some(now);
@@ -431,8 +1350,26 @@ impl GatewayWorker {
         }
     }
 
-    fn should_mark_fatal_for_restart(&self, now: Instant) -> bool {
-        if self.restart_failures < self.restart_policy.max_retries {
+    /// 清空重启预算、熔断与退化状态，供整池自动恢复或手动重新启用后重新启动
+    fn reset_for_recovery(&mut self) {
+        self.restart_budget.history.clear();
+        self.reset_restart_failures();
+        self.pending_restart = None;
+        self.next_restart_at = None;
+        self.circuit_breaker.reset();
+        {
+            let mut metrics =
+                crate::utils::lock_or_recover(self.metrics.as_ref(), "GatewayWorker.metrics");
+            metrics.consecutive_failures = 0;
+            metrics.consecutive_timeouts = 0;
+            metrics.degrade_score = 0.0;
+            metrics.panic_detected = false;
+        }
+        self.set_state(WorkerState::Dead);
+    }
+
+    fn should_mark_fatal_for_restart(&self, now: Instant) -> bool {
+        if self.restart_failures < self.restart_policy.max_retries {
             return false;
         }
         match self.last_restart_failure {
@@ -476,16 +1413,12 @@ impl Drop for GatewayWorker {
 
 impl GatewayWorker {
     pub fn new(id: usize, port: u16) -> Self {
-        let circuit_breaker = CircuitBreaker::new(CircuitBreakerConfig {
-            failure_threshold: 5,
-            success_threshold: 1,
-            timeout: Duration::from_secs(30),
-            time_window: Duration::from_secs(60),
-            min_requests: 10,
-        });
+        let circuit_breaker =
+            CircuitBreaker::new(CircuitBreakerSettings::default().to_breaker_config());
         Self {
             id,
             port,
+            bind_host: IpAddr::V4(Ipv4Addr::LOCALHOST),
             process: None,
             state: Arc::new(AtomicU8::new(WorkerState::Dead as u8)),
             metrics: Arc::new(Mutex::new(WorkerMetrics::default())),
@@ -493,13 +1426,23 @@ impl GatewayWorker {
             last_health_check: None,
             last_success: None,
             started_at: None,
-            total_requests: 0,
-            total_errors: 0,
+            counters: Arc::new(WorkerCounters {
+                port: AtomicU16::new(port),
+                ..WorkerCounters::default()
+            }),
             circuit_breaker,
-            half_open_testing: false,
             port_bound: Arc::new(AtomicBool::new(false)),
             model_ready: Arc::new(AtomicBool::new(false)),
-            current_trace_id: None,
+            accepts_gzip: Arc::new(AtomicBool::new(false)),
+            bind_conflict: Arc::new(AtomicBool::new(false)),
+            current_trace_id: Arc::new(Mutex::new(None)),
+            isolated: false,
+            maintenance: Arc::new(AtomicBool::new(false)),
+            warmed_up: Arc::new(AtomicBool::new(false)),
+            warmup_started: Arc::new(Mutex::new(None)),
+            auto_warmup: false,
+            startup_timeout: None,
+            self_ref: Weak::new(),
             restart_budget: RestartBudget::new(Duration::from_secs(300), 2),
             next_restart_at: None,
             pending_restart: None,
@@ -511,6 +1454,10 @@ impl GatewayWorker {
             },
             restart_failures: 0,
             last_restart_failure: None,
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+            log_buffer: Arc::new(Mutex::new(VecDeque::with_capacity(WORKER_LOG_CAPACITY))),
+            models_refreshed_at: None,
+            gateway_version: None,
         }
     }
 
@@ -552,14 +1499,33 @@ impl GatewayWorker {
 
     /// 状态转换：Idle -> BusyStreaming
     #[allow(dead_code)]
-    pub fn transition_to_busy_streaming(&self, _trace_id: String) {
+    pub fn transition_to_busy_streaming(&self, trace_id: String) {
         self.set_state(WorkerState::BusyStreaming);
         let mut metrics =
             crate::utils::lock_or_recover(self.metrics.as_ref(), "GatewayWorker.metrics");
         metrics.active_requests += 1;
         metrics.last_token_at = Some(Instant::now());
-        // 注意：current_trace_id 需要是 Arc<Mutex<Option<String>>> 或使用其他方式
-        // 暂时简化，后续优化
+        drop(metrics);
+        self.set_trace_id(Some(trace_id));
+    }
+
+    /// 设置正在处理的请求的追踪 ID
+    pub fn set_trace_id(&self, trace_id: Option<String>) {
+        *crate::utils::lock_or_recover(
+            self.current_trace_id.as_ref(),
+            "GatewayWorker.current_trace_id",
+        ) = trace_id;
+    }
+
+    /// 请求结束时清除追踪 ID（已被并发的其他请求覆盖时保留）
+    pub fn clear_trace_id(&self, trace_id: &str) {
+        let mut current = crate::utils::lock_or_recover(
+            self.current_trace_id.as_ref(),
+            "GatewayWorker.current_trace_id",
+        );
+        if current.as_deref() == Some(trace_id) {
+            *current = None;
+        }
     }
 
     /// 状态转换：BusyStreaming -> Idle（正常完成）
@@ -569,6 +1535,7 @@ impl GatewayWorker {
             crate::utils::lock_or_recover(self.metrics.as_ref(), "GatewayWorker.metrics");
         metrics.active_requests = metrics.active_requests.saturating_sub(1);
         metrics.record_request(success, latency_ms);
+        metrics.latency_window.record(latency_ms);
 
         if success {
             self.set_state(WorkerState::Idle);
@@ -621,7 +1588,6 @@ impl GatewayWorker {
             return;
         }
         self.set_state(WorkerState::Unhealthy);
-        self.half_open_testing = false;
         self.circuit_breaker.force_open();
     }
 
@@ -641,13 +1607,82 @@ impl GatewayWorker {
     /// 获取健康检查 URL
     #[allow(dead_code)]
     pub fn health_url(&self) -> String {
-        format!("http://127.0.0.1:{}/health", self.port)
+        format!("{}/health", self.api_url())
     }
 
     /// 获取 API URL
     pub fn api_url(&self) -> String {
-        format!("http://127.0.0.1:{}", self.port)
+        worker_base_url(self.bind_host, self.port)
+    }
+
+    /// 是否声明支持指定模型（模型列表尚未获取时为空）
+    pub fn supports_model(&self, model: &str) -> Option<bool> {
+        let capability =
+            crate::utils::lock_or_recover(self.capability.as_ref(), "GatewayWorker.capability");
+        if capability.supported_models.is_empty() {
+            None
+        } else {
+            Some(capability.supported_models.iter().any(|m| m == model))
+        }
+    }
+
+    /// 模型列表是否需要刷新（从未获取、为空或已超过刷新间隔）
+    fn models_refresh_due(&self) -> bool {
+        match self.models_refreshed_at {
+            Some(at) => {
+                at.elapsed() >= WORKER_MODELS_REFRESH_INTERVAL
+                    || crate::utils::lock_or_recover(
+                        self.capability.as_ref(),
+                        "GatewayWorker.capability",
+                    )
+                    .supported_models
+                    .is_empty()
+            }
+            None => true,
+        }
+    }
+}
+
+/// 从 /v1/models 响应构建 Worker 能力：模型列表，以及模型条目中可选的上下文长度 / 工具调用声明
+fn capability_from_models(data: &serde_json::Value, version: Option<&str>) -> WorkerCapability {
+    let mut capability = WorkerCapability {
+        supported_models: parse_model_ids(data),
+        model_version: version.unwrap_or("unknown").to_string(),
+        ..WorkerCapability::default()
+    };
+    if let Some(items) = data.get("data").and_then(|d| d.as_array()) {
+        let max_context = items
+            .iter()
+            .filter_map(|item| {
+                item.get("context_length")
+                    .or_else(|| item.get("max_context_length"))
+                    .and_then(|v| v.as_u64())
+            })
+            .max();
+        if let Some(max_context) = max_context {
+            capability.max_context = max_context as usize;
+        }
+        capability.supports_tools = items.iter().any(|item| {
+            item.get("supports_tools")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        });
     }
+    capability
+}
+
+/// 从 OpenAI 格式的 /v1/models 响应中提取模型 ID 列表
+fn parse_model_ids(data: &serde_json::Value) -> Vec<String> {
+    data.get("data")
+        .and_then(|d| d.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.get("id").and_then(|id| id.as_str()))
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 /// 模型列表缓存（限频 + 缓存）
@@ -665,14 +1700,18 @@ struct ModelListCache {
 
 impl ModelListCache {
     fn new() -> Self {
+        let config = ModelCacheConfig::default();
         Self {
             cached: None,
-            cache_ttl: Duration::from_secs(300), // 5 分钟缓存
+            cache_ttl: config.ttl(),
             last_request: None,
-            min_request_interval: Duration::from_secs(30), // 30 秒限频（避免频繁请求）
+            min_request_interval: config.min_interval(),
         }
     }
 
+    /// 手动刷新时的最小间隔：短时间内重复刷新直接返回刚获取的列表
+    const FORCED_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
     /// 获取缓存的模型列表（如果有效）
     fn get_cached(&self) -> Option<Vec<String>> {
         if let Some((models, cached_at)) = &self.cached {
@@ -683,6 +1722,16 @@ impl ModelListCache {
         None
     }
 
+    /// 刚刚（FORCED_REFRESH_INTERVAL 内）获取过的模型列表，供手动刷新复用
+    fn just_fetched(&self) -> Option<Vec<String>> {
+        match &self.cached {
+            Some((models, cached_at)) if cached_at.elapsed() < Self::FORCED_REFRESH_INTERVAL => {
+                Some(models.clone())
+            }
+            _ => None,
+        }
+    }
+
     /// 检查是否可以请求（限频）
     fn can_request(&self) -> bool {
         if let Some(last) = self.last_request {
@@ -708,10 +1757,148 @@ pub struct GatewayPool {
     current_index: usize,
     /// 基础端口
     base_port: u16,
+    /// Worker 绑定地址
+    bind_host: IpAddr,
     /// Worker 数量
     pool_size: usize,
     /// 模型列表缓存（限频 + 缓存）
     model_cache: Arc<Mutex<ModelListCache>>,
+    /// 全部 Worker 失效时的处理策略
+    all_down_policy: AllDownPolicy,
+    /// 全部失效告警与自动恢复记录
+    all_down: Arc<Mutex<AllDownTracker>>,
+    /// 健康检查间隔与超时（与健康检查线程共享，修改后下一轮生效）
+    health_check: Arc<Mutex<HealthCheckConfig>>,
+    /// 转发请求的重试与退避策略
+    request_policy: RequestPolicy,
+    /// Worker 启动超时
+    startup_timeout: StartupTimeoutConfig,
+    /// 模型别名（请求的模型不可用时改写为目标模型）
+    model_aliases: BTreeMap<String, String>,
+    /// 按分钟分桶的请求 / 错误计数（窗口错误率）
+    request_stats: Arc<Mutex<RequestStats>>,
+    /// 幂等键 -> 已完成响应（带幂等键的重试直接返回缓存结果）
+    idempotency: Arc<Mutex<IdempotencyCache>>,
+}
+
+/// 全部 Worker 失效的告警与自动恢复记录
+#[derive(Debug, Default)]
+struct AllDownTracker {
+    /// 进入全部失效状态的时间（自动恢复后重新计时）
+    since: Option<Instant>,
+    /// 自动恢复历史（用于窗口内次数上限）
+    recoveries: Vec<Instant>,
+    /// 已提示自动恢复次数耗尽（避免重复刷日志）
+    budget_exhausted_logged: bool,
+    /// 后台自动恢复线程正在重启 Worker
+    recovering: bool,
+}
+
+impl AllDownTracker {
+    /// 当前告警（未处于全部失效状态时为空）
+    fn alert(&mut self, policy: &AllDownPolicy) -> Option<PoolAlert> {
+        let since = self.since?;
+        let now = Instant::now();
+        let window = Duration::from_secs(policy.window_secs);
+        self.recoveries.retain(|t| now.duration_since(*t) < window);
+        let auto_restart = policy.action == AllDownAction::AutoRestart;
+        let message = if !auto_restart {
+            "所有 Gateway Worker 均已失效，AI 服务不可用，请手动重启连接池".to_string()
+        } else if self.recoveries.len() >= policy.max_recoveries as usize {
+            "所有 Gateway Worker 均已失效，自动恢复次数已用尽，请手动重启连接池".to_string()
+        } else {
+            "所有 Gateway Worker 均已失效，将在冷却后自动重启连接池".to_string()
+        };
+        Some(PoolAlert {
+            message,
+            down_secs: since.elapsed().as_secs(),
+            auto_restart,
+            recoveries_in_window: self.recoveries.len() as u32,
+            max_recoveries: policy.max_recoveries,
+        })
+    }
+}
+
+/// 连接池告警（所有 Worker 均失效时返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolAlert {
+    pub message: String,
+    /// 已持续的秒数
+    pub down_secs: u64,
+    /// 是否启用了自动恢复
+    pub auto_restart: bool,
+    /// 统计窗口内已自动恢复的次数
+    pub recoveries_in_window: u32,
+    pub max_recoveries: u32,
+}
+
+/// 调度候选 Worker 的评估结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SelectionCandidate {
+    pub worker_id: usize,
+    pub port: u16,
+    pub state: WorkerState,
+    /// 调度得分（越低越优），被跳过时为空
+    pub score: Option<f64>,
+    pub skipped: bool,
+    /// 跳过原因（不健康、熔断打开、失败率过高、Worker-0 特殊处理等）
+    pub skip_reason: Option<String>,
+    /// 是否声明支持请求的模型（未指定模型或能力未知时为空）
+    pub model_supported: Option<bool>,
+}
+
+/// 调度决策解释（explain_selection 返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct SelectionExplanation {
+    pub client_id: Option<String>,
+    pub model: Option<String>,
+    /// 最终采用的策略：sticky / least_active / none
+    pub strategy: String,
+    /// 粘性会话映射到的 Worker
+    pub sticky_worker: Option<usize>,
+    /// 粘性会话未命中的原因
+    pub sticky_miss_reason: Option<String>,
+    /// 最终选择的 Worker
+    pub selected_worker: Option<usize>,
+    pub candidates: Vec<SelectionCandidate>,
+}
+
+/// 手动 HTTP 探测结果（probe_worker_http 返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerProbe {
+    pub worker_id: usize,
+    pub url: String,
+    /// HTTP 状态码（请求失败时为空）
+    pub status: Option<u16>,
+    pub elapsed_ms: u64,
+    /// 响应体前 PROBE_BODY_PREVIEW_BYTES 字节（按 UTF-8 宽松解码）
+    pub body_preview: String,
+    /// 响应体是否被截断
+    pub truncated: bool,
+    /// 请求失败原因（连接失败、超时等）
+    pub error: Option<String>,
+}
+
+/// Worker 的 OpenAI 兼容端点（get_worker_endpoints 返回，供外部客户端直连）
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerEndpoint {
+    pub worker_id: usize,
+    pub port: u16,
+    pub api_url: String,
+    pub state: WorkerState,
+    /// 是否健康（可放心直连）
+    pub healthy: bool,
+    /// 不健康的原因（健康时为空）
+    pub reason: Option<String>,
+}
+
+/// FNV-1a 64 位哈希（结果跨进程稳定，同一 client_id 重启后仍映射到同一 Worker）
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
 }
 
 fn jitter_duration(max_ms: u64) -> Duration {
@@ -731,18 +1918,70 @@ fn backoff_with_jitter(base: Duration, max: Duration, attempt: u32, jitter_ms: u
     backoff.saturating_add(jitter_duration(jitter_ms))
 }
 
+/// 排空 Worker 的在途请求：标记 Draining（不再接新请求），等待 active_requests 归零
+/// 返回 true 表示已排空（或进程已不存在），false 表示超时或排空期间状态被其他操作改变
+fn drain_in_flight(worker: &Arc<Mutex<GatewayWorker>>) -> bool {
+    let (worker_id, timeout) = {
+        let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+        if wg.process.is_none() {
+            return true;
+        }
+        wg.set_state(WorkerState::Draining);
+        (wg.id, wg.drain_timeout)
+    };
+
+    let start = Instant::now();
+    loop {
+        let (state, active) = {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            (wg.status(), wg.active_requests())
+        };
+        if state != WorkerState::Draining {
+            log::warn!(
+                "[Gateway Pool] Worker-{} 排空期间状态变为 {:?}，停止等待",
+                worker_id,
+                state
+            );
+            return false;
+        }
+        if active == 0 {
+            log::info!(
+                "[Gateway Pool] Worker-{} 在途请求已排空（耗时 {:?}）",
+                worker_id,
+                start.elapsed()
+            );
+            return true;
+        }
+        if start.elapsed() >= timeout {
+            log::warn!(
+                "[Gateway Pool] Worker-{} 排空超时（{:?}），仍有 {} 个在途请求，将强制终止",
+                worker_id,
+                timeout,
+                active
+            );
+            return false;
+        }
+        thread::sleep(DRAIN_POLL_INTERVAL);
+    }
+}
+
 fn schedule_restart_for_worker(worker: Arc<Mutex<GatewayWorker>>, signal: HealthSignal) {
     let (delay, worker_id) = {
         let mut wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
-        if matches!(
-            wg.status(),
-            WorkerState::FailedPermanent | WorkerState::Disabled
-        ) {
+        if wg.in_maintenance()
+            || matches!(
+                wg.status(),
+                WorkerState::FailedPermanent | WorkerState::Disabled
+            )
+        {
             return;
         }
         if matches!(wg.status(), WorkerState::Restarting) && wg.next_restart_at.is_some() {
             return;
         }
+        if wg.status() == WorkerState::Draining {
+            return;
+        }
         if matches!(wg.status(), WorkerState::Init | WorkerState::Ready)
             && !matches!(
                 signal,
@@ -789,15 +2028,24 @@ fn schedule_restart_for_worker(worker: Arc<Mutex<GatewayWorker>>, signal: Health
 
     thread::spawn(move || {
         thread::sleep(delay);
-        let mut wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
-        if wg.status() != WorkerState::Restarting {
-            return;
-        }
-        if let Some(until) = wg.next_restart_at {
-            if Instant::now() < until {
+        {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            if wg.status() != WorkerState::Restarting {
                 return;
             }
+            if let Some(until) = wg.next_restart_at {
+                if Instant::now() < until {
+                    return;
+                }
+            }
+        }
+        // 先排空在途请求，避免流式响应被中途切断；超时则照常强制终止
+        drain_in_flight(&worker);
+        let mut wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+        if !matches!(wg.status(), WorkerState::Draining | WorkerState::Restarting) {
+            return;
         }
+        wg.set_state(WorkerState::Restarting);
         match GatewayPool::restart_worker_guard(&mut wg, worker_id) {
             Ok(_) => {
                 wg.pending_restart = None;
@@ -841,221 +2089,782 @@ fn schedule_restart_for_worker(worker: Arc<Mutex<GatewayWorker>>, signal: Health
 }
 
 impl GatewayPool {
-    /// 创建新的连接池
-    pub fn new(pool_size: usize, base_port: u16) -> Self {
+    /// 创建新的连接池；端口范围 base_port..base_port + pool_size 超出 u16 时返回错误（不创建部分 Worker）
+    pub fn new(pool_size: usize, base_port: u16) -> Result<Self, String> {
+        if base_port as usize + pool_size > u16::MAX as usize + 1 {
+            return Err(format!(
+                "端口范围越界: 基础端口 {} + {} 个 Worker 超出最大端口 {}",
+                base_port,
+                pool_size,
+                u16::MAX
+            ));
+        }
         let mut workers = Vec::new();
-        for i in 0..pool_size {
-            let port = base_port + i as u16;
-            workers.push(Arc::new(Mutex::new(GatewayWorker::new(i, port))));
+        for (i, port) in (base_port..=u16::MAX).take(pool_size).enumerate() {
+            workers.push(Arc::new_cyclic(|self_ref| {
+                let mut worker = GatewayWorker::new(i, port);
+                worker.self_ref = self_ref.clone();
+                Mutex::new(worker)
+            }));
         }
 
-        Self {
+        Ok(Self {
+            pool_size: workers.len(),
             workers,
             current_index: 0,
             base_port,
-            pool_size,
+            bind_host: IpAddr::V4(Ipv4Addr::LOCALHOST),
             model_cache: Arc::new(Mutex::new(ModelListCache::new())),
-        }
+            all_down_policy: AllDownPolicy::default(),
+            all_down: Arc::new(Mutex::new(AllDownTracker::default())),
+            health_check: Arc::new(Mutex::new(HealthCheckConfig::default())),
+            request_policy: RequestPolicy::default(),
+            startup_timeout: StartupTimeoutConfig::default(),
+            model_aliases: BTreeMap::new(),
+            request_stats: Arc::new(Mutex::new(RequestStats::default())),
+            idempotency: Arc::new(Mutex::new(IdempotencyCache::default())),
+        })
     }
 
-    /// 获取所有 Worker
-    pub fn get_workers(&self) -> &Vec<Arc<Mutex<GatewayWorker>>> {
-        &self.workers
+    /// 是否有 Worker 进程在运行（重建连接池前需先停止）
+    pub fn is_running(&self) -> bool {
+        self.workers.iter().any(|worker| {
+            crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker")
+                .process
+                .is_some()
+        })
     }
 
-    /// 选择一个可用的 Worker（优化调度：最少活跃请求 + 退化系数）
-    /// 特殊处理：跳过有问题的 Worker-0（如果它处于 Unhealthy 状态）
-    pub fn select_worker(&mut self, client_id: Option<&str>) -> Option<Arc<Mutex<GatewayWorker>>> {
-        // 边界检查，防止数组越界
-        if self.pool_size == 0 || self.workers.is_empty() {
-            log::warn!("[Gateway Pool] Worker 池为空，无法选择 Worker");
-            return None;
-        }
+    /// 让当前健康检查线程在下一轮退出（连接池重建后由新连接池重新启动）
+    pub fn retire_health_check_thread() {
+        HEALTH_CHECK_GENERATION.fetch_add(1, Ordering::SeqCst);
+        HEALTH_CHECK_STARTED.store(false, Ordering::SeqCst);
+    }
 
-        // 粘性会话：如果有 client_id，优先选择同一个 worker
-        if let Some(cid) = client_id {
-            let hash = cid.len() % self.pool_size;
-            // 确保 hash 在有效范围内（虽然取模已经保证，但双重检查更安全）
-            if hash >= self.workers.len() {
-                log::warn!("[Gateway Pool] Worker 索引 {} 超出范围", hash);
-                // 回退到轮询算法
-            } else {
-                let worker = &self.workers[hash];
-                let mut worker_guard =
-                    crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
-                let state = worker_guard.status();
-
-                if worker_guard.id == 0
-                    && matches!(
-                        state,
-                        WorkerState::Unhealthy
-                            | WorkerState::FailedPermanent
-                            | WorkerState::Disabled
-                    )
-                {
-                    log::debug!("[Gateway Pool] 跳过 Worker-0（处于 Unhealthy 状态）");
-                } else if state.can_accept_request() && worker_guard.is_healthy() {
-                    let (recent_fail_rate, active_requests) = {
-                        let metrics = crate::utils::lock_or_recover(
-                            worker_guard.metrics.as_ref(),
-                            "GatewayWorker.metrics",
-                        );
-                        (metrics.recent_fail_rate, metrics.active_requests)
-                    };
-                    if recent_fail_rate < 0.3 {
-                        if worker_guard.circuit_breaker.state() == CircuitBreakerState::HalfOpen {
-                            if !worker_guard.half_open_testing && active_requests == 0 {
-                                worker_guard.half_open_testing = true;
-                                return Some(Arc::clone(worker));
-                            }
-                        } else {
-                            return Some(Arc::clone(worker));
-                        }
-                    }
-                }
-            }
-        }
+    /// 设置全部 Worker 失效时的处理策略（需在启动健康检查线程前设置）
+    pub fn set_all_down_policy(&mut self, policy: AllDownPolicy) {
+        self.all_down_policy = policy;
+    }
 
-        // 最少活跃请求算法（考虑退化系数）
-        let mut best_worker: Option<Arc<Mutex<GatewayWorker>>> = None;
-        let mut best_score = f64::MAX;
+    /// 设置健康检查间隔与超时（健康检查线程在下一轮检查时生效）
+    pub fn set_health_check_config(&self, config: HealthCheckConfig) {
+        *crate::utils::lock_or_recover(self.health_check.as_ref(), "GatewayPool.health_check") =
+            config;
+    }
+
+    /// 当前健康检查配置
+    pub fn health_check_config(&self) -> HealthCheckConfig {
+        crate::utils::lock_or_recover(self.health_check.as_ref(), "GatewayPool.health_check")
+            .clone()
+    }
+
+    /// 设置转发请求的重试与退避策略（对之后的请求生效）
+    pub fn set_request_policy(&mut self, policy: RequestPolicy) {
+        self.request_policy = policy;
+    }
+
+    /// 当前转发请求的重试与退避策略
+    pub fn request_policy(&self) -> RequestPolicy {
+        self.request_policy.clone()
+    }
 
+    /// 设置 Worker 启动超时（健康检查立即按新值判定；启动等待时间对之后启动的 Worker 生效）
+    pub fn set_startup_timeout_config(&mut self, config: StartupTimeoutConfig) {
         for worker in &self.workers {
-            let worker_guard = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
-            let state = worker_guard.status();
+            let mut wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            wg.startup_timeout = config.timeout_for(wg.id);
+        }
+        self.startup_timeout = config;
+    }
 
-            // 特殊检查：如果 Worker-0 处于 Unhealthy 状态，明确跳过
-            if worker_guard.id == 0 && state == WorkerState::Unhealthy {
-                log::debug!("[Gateway Pool] 跳过 Worker-0（处于 Unhealthy 状态，等待恢复）");
-                continue;
-            }
+    /// 当前 Worker 启动超时配置
+    pub fn startup_timeout_config(&self) -> StartupTimeoutConfig {
+        self.startup_timeout.clone()
+    }
 
-            // 只选择可以接受请求的状态
-            if !state.can_accept_request() {
-                continue;
-            }
+    /// 设置 Worker 绑定地址（需在启动 Worker 前设置）
+    pub fn set_bind_host(&mut self, host: IpAddr) {
+        self.bind_host = host;
+        for worker in &self.workers {
+            crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker").bind_host = host;
+        }
+    }
 
-            // 跳过不健康的 worker
-            if !worker_guard.is_healthy() {
-                continue;
-            }
+    /// Worker 绑定地址
+    pub fn bind_host(&self) -> IpAddr {
+        self.bind_host
+    }
 
-            // 跳过最近失败率过高的 worker
-            let metrics = crate::utils::lock_or_recover(
-                worker_guard.metrics.as_ref(),
-                "GatewayWorker.metrics",
-            );
-            if metrics.recent_fail_rate > 0.3 {
-                continue;
-            }
-            if worker_guard.circuit_breaker.state() == CircuitBreakerState::HalfOpen {
-                if worker_guard.half_open_testing || metrics.active_requests > 0 {
-                    continue;
-                }
-            }
+    /// 替换模型别名表（对之后的请求生效）
+    pub fn set_model_aliases(&mut self, aliases: BTreeMap<String, String>) {
+        self.model_aliases = aliases;
+    }
 
-            let mut score = 1.0 + metrics.active_requests as f64;
-            score *= 1.0 + metrics.degrade_score;
-            if state == WorkerState::Degraded {
-                score *= 5.0;
-            }
+    /// 当前模型别名表
+    pub fn model_aliases(&self) -> BTreeMap<String, String> {
+        self.model_aliases.clone()
+    }
 
-            if score < best_score {
-                best_score = score;
-                best_worker = Some(Arc::clone(worker));
-            }
+    /// 按别名表改写请求体中的模型：请求的模型可用时不改写；
+    /// 不可用时依次查找同名别名与 default 别名，目标模型可用时返回（目标模型，改写后的请求体）
+    pub(crate) fn apply_model_alias(&self, body: &[u8], model: &str) -> Option<(String, Vec<u8>)> {
+        if self.model_aliases.is_empty() || self.unavailable_model_alternatives(model).is_none() {
+            return None;
         }
-
-        if let Some(worker) = best_worker.as_ref() {
-            let mut worker_guard = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
-            if worker_guard.circuit_breaker.state() == CircuitBreakerState::HalfOpen
-                && !worker_guard.half_open_testing
-            {
-                let active_requests = crate::utils::lock_or_recover(
-                    worker_guard.metrics.as_ref(),
-                    "GatewayWorker.metrics",
-                )
-                .active_requests;
-                if active_requests == 0 {
-                    worker_guard.half_open_testing = true;
-                }
-            }
+        let target = self
+            .model_aliases
+            .get(model)
+            .or_else(|| self.model_aliases.get(DEFAULT_MODEL_ALIAS))?;
+        if target == model || self.unavailable_model_alternatives(target).is_some() {
+            return None;
         }
+        let mut json_value = serde_json::from_slice::<serde_json::Value>(body).ok()?;
+        json_value.as_object_mut()?.insert(
+            "model".to_string(),
+            serde_json::Value::String(target.clone()),
+        );
+        let rewritten = serde_json::to_vec(&json_value).ok()?;
+        log::info!(
+            "[Gateway Pool] 模型 {} 不可用，按别名改写为 {}",
+            model,
+            target
+        );
+        Some((target.clone(), rewritten))
+    }
 
-        // 如果找到合适的 worker，更新轮询索引
-        if best_worker.is_some() {
-            self.current_index = (self.current_index + 1) % self.pool_size;
-        }
+    /// 窗口请求统计（供不持有连接池锁的流式转发记录结果）
+    pub(crate) fn request_stats(&self) -> Arc<Mutex<RequestStats>> {
+        Arc::clone(&self.request_stats)
+    }
 
-        best_worker
+    /// 最近 window_secs 秒内的请求数、错误数与错误率
+    pub fn error_stats(&self, window_secs: u64) -> GatewayErrorStats {
+        crate::utils::lock_or_recover(self.request_stats.as_ref(), "GatewayPool.request_stats")
+            .summarize(window_secs)
     }
 
-    /// 软队列等待（等待可用 worker，最多等待指定时间）
-    #[allow(dead_code)]
-    pub fn select_worker_with_queue(
-        &mut self,
-        client_id: Option<&str>,
-        max_wait_ms: u64,
-    ) -> Option<Arc<Mutex<GatewayWorker>>> {
-        let start = Instant::now();
-        let max_wait = Duration::from_millis(max_wait_ms);
+    /// 是否所有 Worker 均已失效（Disabled / FailedPermanent）
+    fn all_workers_down(workers: &[Arc<Mutex<GatewayWorker>>]) -> bool {
+        !workers.is_empty()
+            && workers.iter().all(|worker| {
+                matches!(
+                    crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker").status(),
+                    WorkerState::FailedPermanent | WorkerState::Disabled
+                )
+            })
+    }
 
-        loop {
-            if let Some(worker) = self.select_worker(client_id) {
-                return Some(worker);
+    /// 获取当前告警（所有 Worker 均失效时返回；进入与解除时另行发送 gateway-pool-alert 事件）
+    pub fn all_down_alert(&self) -> Option<PoolAlert> {
+        crate::utils::lock_or_recover(self.all_down.as_ref(), "GatewayPool.all_down")
+            .alert(&self.all_down_policy)
+    }
+
+    /// 检查是否全部失效，并按策略告警或自动重启（健康检查线程每轮调用）
+    /// 告警进入与解除时发送事件；自动重启在后台线程执行，不阻塞健康检查
+    fn handle_all_down(
+        workers: &[Arc<Mutex<GatewayWorker>>],
+        tracker: &Arc<Mutex<AllDownTracker>>,
+        policy: &AllDownPolicy,
+    ) {
+        let all_down = Self::all_workers_down(workers);
+        let mut guard = crate::utils::lock_or_recover(tracker.as_ref(), "GatewayPool.all_down");
+        if !all_down {
+            if guard.since.take().is_some() {
+                guard.budget_exhausted_logged = false;
+                drop(guard);
+                log::info!("[Gateway Pool] 已有 Worker 恢复，解除全部失效告警");
+                publish_pool_alert(None);
             }
+            return;
+        }
 
-            if start.elapsed() >= max_wait {
-                return None; // 超时，返回 429
+        let now = Instant::now();
+        let since = match guard.since {
+            Some(since) => since,
+            None => {
+                guard.since = Some(now);
+                let alert = guard.alert(policy);
+                drop(guard);
+                log::error!(
+                    "[Gateway Pool] 所有 Worker 均已失效（Disabled/FailedPermanent），AI 服务不可用"
+                );
+                publish_pool_alert(alert);
+                return;
             }
+        };
+
+        if guard.recovering
+            || policy.action != AllDownAction::AutoRestart
+            || now.duration_since(since) < Duration::from_secs(policy.cooldown_secs)
+        {
+            return;
+        }
 
-            thread::sleep(Duration::from_millis(50)); // 等待 50ms 后重试
+        let window = Duration::from_secs(policy.window_secs);
+        guard.recoveries.retain(|t| now.duration_since(*t) < window);
+        if guard.recoveries.len() >= policy.max_recoveries as usize {
+            if !guard.budget_exhausted_logged {
+                guard.budget_exhausted_logged = true;
+                log::error!(
+                    "[Gateway Pool] 自动恢复次数已达上限（{} 次 / {} 秒），保持停止，需手动重启",
+                    policy.max_recoveries,
+                    policy.window_secs
+                );
+            }
+            return;
         }
-    }
 
-    /// 启动所有 Worker
-    pub fn start_all(&self) -> Result<Vec<String>, String> {
-        let mut results = Vec::new();
+        guard.recoveries.push(now);
+        // 重新计时：本次恢复失败时需再等待一个冷却周期
+        guard.since = Some(now);
+        guard.recovering = true;
+        let attempt = guard.recoveries.len();
+        drop(guard);
+
+        log::warn!(
+            "[Gateway Pool] 所有 Worker 均已失效，自动重启连接池（窗口内第 {}/{} 次）",
+            attempt,
+            policy.max_recoveries
+        );
+        let workers = workers.to_vec();
+        let tracker = Arc::clone(tracker);
+        thread::spawn(move || {
+            Self::restart_all_workers(&workers);
+            crate::utils::lock_or_recover(tracker.as_ref(), "GatewayPool.all_down").recovering =
+                false;
+        });
+    }
 
-        for worker in &self.workers {
-            // 关键修复：安全锁定 Mutex，避免 poisoned 导致 panic
-            let mut worker_guard = match worker.lock() {
-                Ok(guard) => guard,
-                Err(poisoned) => {
-                    log::error!("[Gateway Pool] Worker Mutex 被污染，尝试恢复");
-                    poisoned.into_inner()
-                }
-            };
-            if matches!(
-                worker_guard.status(),
-                WorkerState::FailedPermanent | WorkerState::Disabled
-            ) {
-                results.push(format!(
-                    "Worker-{} 已标记为不可自动管理，跳过启动",
-                    worker_guard.id
-                ));
+    /// 自动恢复：终止残留进程并逐个重启 Worker（跳过隔离与维护中的 Worker）
+    fn restart_all_workers(workers: &[Arc<Mutex<GatewayWorker>>]) {
+        for worker in workers {
+            let mut wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            if wg.isolated || wg.in_maintenance() {
                 continue;
             }
-            if worker_guard.process.is_none() {
-                match Self::start_worker(&mut worker_guard) {
-                    Ok(msg) => {
-                        let msg_clone = msg.clone();
-                        results.push(msg);
-                        log::info!(
+            let worker_id = wg.id;
+            if let Some(mut child) = wg.process.take() {
+                #[cfg(target_os = "windows")]
+                {
+                    if !terminate_worker_process(&mut child, worker_id) {
+                        wg.process = Some(child);
+                        log::error!(
+                            "[Gateway Pool] Worker-{} 残留进程无法终止，跳过自动恢复",
+                            worker_id
+                        );
+                        continue;
+                    }
+                }
+                #[cfg(not(target_os = "windows"))]
+                {
+                    if let Err(e) = child.kill() {
+                        log::warn!("终止 Worker-{} 失败: {}", worker_id, e);
+                    }
+                    let _ = child.wait();
+                }
+            }
+            wg.reset_for_recovery();
+            match Self::start_worker(&mut wg) {
+                Ok(msg) => log::info!("[Gateway Pool] Worker-{} 自动恢复: {}", worker_id, msg),
+                Err(e) => {
+                    log::error!("[Gateway Pool] Worker-{} 自动恢复失败: {}", worker_id, e);
+                    if wg.status() != WorkerState::FailedPermanent {
+                        wg.set_state(WorkerState::Dead);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 永久隔离指定 Worker（标记 Disabled 并打开熔断器，停止后也不会恢复为 Dead）
+    pub fn isolate_worker(&self, worker_id: usize) {
+        let Some(worker) = self.workers.get(worker_id) else {
+            log::warn!("[Gateway Pool] 隔离失败：Worker-{} 不存在", worker_id);
+            return;
+        };
+        let mut wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+        wg.isolated = true;
+        wg.pending_restart = None;
+        wg.next_restart_at = None;
+        wg.circuit_breaker.force_open();
+        wg.set_state(WorkerState::Disabled);
+        log::warn!(
+            "[Gateway Pool] [Worker-{}] 已按配置永久隔离（DISABLED），不会被启动或调度",
+            worker_id
+        );
+    }
+
+    /// 设置 Worker 维护模式：开启后不参与调度、健康检查线程不再检查与重启（进程保持运行）；
+    /// 关闭时刷新心跳时间，避免维护期间累积的心跳超时立即触发重启
+    pub fn set_worker_maintenance(
+        worker: &Arc<Mutex<GatewayWorker>>,
+        enabled: bool,
+    ) -> Result<String, GatewayError> {
+        let mut wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+        let worker_id = wg.id;
+        if wg.isolated {
+            return Err(format!(
+                "Worker-{} 已按配置永久隔离（disable_worker_0），无需设置维护模式",
+                worker_id
+            )
+            .into());
+        }
+        if wg.maintenance.swap(enabled, Ordering::SeqCst) == enabled {
+            return Ok(format!(
+                "Worker-{} 已{}维护模式",
+                worker_id,
+                if enabled { "处于" } else { "退出" }
+            ));
+        }
+
+        if enabled {
+            wg.pending_restart = None;
+            wg.next_restart_at = None;
+            log::warn!(
+                "[Gateway Pool] [Worker-{}] 进入维护模式：不再调度与自动重启",
+                worker_id
+            );
+            Ok(format!("Worker-{} 已进入维护模式", worker_id))
+        } else {
+            crate::utils::lock_or_recover(wg.metrics.as_ref(), "GatewayWorker.metrics")
+                .last_heartbeat = Some(Instant::now());
+            wg.last_health_check = None;
+            log::info!(
+                "[Gateway Pool] [Worker-{}] 退出维护模式，恢复健康检查与调度",
+                worker_id
+            );
+            Ok(format!("Worker-{} 已退出维护模式", worker_id))
+        }
+    }
+
+    /// 获取所有 Worker
+    pub fn get_workers(&self) -> &Vec<Arc<Mutex<GatewayWorker>>> {
+        &self.workers
+    }
+
+    /// 状态视图（各 Worker 的只读句柄与共享的健康检查配置）
+    pub fn status_view(&self) -> PoolStatusView {
+        PoolStatusView {
+            workers: self
+                .workers
+                .iter()
+                .map(|worker| {
+                    crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker").status_handle()
+                })
+                .collect(),
+            health_check: Arc::clone(&self.health_check),
+        }
+    }
+
+    /// Worker 数量
+    pub fn pool_size(&self) -> usize {
+        self.pool_size
+    }
+
+    /// 基础端口
+    pub fn base_port(&self) -> u16 {
+        self.base_port
+    }
+
+    /// 获取指定 Worker
+    pub fn get_worker(&self, worker_id: usize) -> Option<Arc<Mutex<GatewayWorker>>> {
+        self.workers.get(worker_id).map(Arc::clone)
+    }
+
+    /// 粘性会话对应的 Worker 索引（client_id 内容的 FNV-1a 哈希对 Worker 数取模）
+    fn sticky_index(&self, client_id: &str) -> usize {
+        (fnv1a_hash(client_id.as_bytes()) % self.pool_size as u64) as usize
+    }
+
+    /// 检查 Worker 状态是否允许调度（返回不可调度的原因）
+    fn unschedulable_reason(wg: &GatewayWorker) -> Option<String> {
+        if wg.in_maintenance() {
+            return Some("维护模式".to_string());
+        }
+        let state = wg.status();
+        if !state.can_accept_request() {
+            return Some(format!("状态 {:?} 不可接单", state));
+        }
+        if !wg.circuit_breaker.can_execute() {
+            return Some("熔断器打开".to_string());
+        }
+        let should_skip =
+            crate::utils::lock_or_recover(wg.metrics.as_ref(), "GatewayWorker.metrics")
+                .should_skip();
+        if should_skip {
+            return Some("预测失败（失败率或退化系数过高）".to_string());
+        }
+        None
+    }
+
+    /// 半开熔断检查：试探名额已满时跳过
+    fn check_half_open_trial(wg: &GatewayWorker) -> Result<(), String> {
+        if wg.circuit_breaker.half_open_trial_available() {
+            return Ok(());
+        }
+        let trials = wg.circuit_breaker.half_open_trials();
+        Err(format!(
+            "熔断器半开，试探请求进行中 ({}/{})",
+            trials.in_flight, trials.max_trials
+        ))
+    }
+
+    /// 粘性会话检查：Worker 能否直接承接该客户端的请求
+    fn check_sticky(wg: &GatewayWorker) -> Result<(), String> {
+        let state = wg.status();
+        if wg.id == 0
+            && matches!(
+                state,
+                WorkerState::Unhealthy | WorkerState::FailedPermanent | WorkerState::Disabled
+            )
+        {
+            return Err(format!("Worker-0 特殊处理：处于 {:?} 状态", state));
+        }
+        if let Some(reason) = Self::unschedulable_reason(wg) {
+            return Err(reason);
+        }
+        let recent_fail_rate =
+            crate::utils::lock_or_recover(wg.metrics.as_ref(), "GatewayWorker.metrics")
+                .recent_fail_rate;
+        if recent_fail_rate >= 0.3 {
+            return Err(format!("最近失败率过高 ({:.0}%)", recent_fail_rate * 100.0));
+        }
+        Self::check_half_open_trial(wg)
+    }
+
+    /// 模型检查：Worker 已知模型列表中不包含请求的模型时跳过
+    fn check_model(wg: &GatewayWorker, model: Option<&str>) -> Result<(), String> {
+        match model {
+            Some(m) if wg.supports_model(m) == Some(false) => Err(format!("不支持模型 {}", m)),
+            _ => Ok(()),
+        }
+    }
+
+    /// 评估候选 Worker：返回调度得分（越低越优）或跳过原因
+    fn evaluate_candidate(wg: &GatewayWorker) -> Result<f64, String> {
+        let state = wg.status();
+
+        // 特殊检查：如果 Worker-0 处于 Unhealthy 状态，明确跳过
+        if wg.id == 0 && state == WorkerState::Unhealthy {
+            return Err("Worker-0 特殊处理：处于 Unhealthy 状态，等待恢复".to_string());
+        }
+
+        // 只选择可以接受请求的健康 worker
+        if let Some(reason) = Self::unschedulable_reason(wg) {
+            return Err(reason);
+        }
+
+        // 跳过最近失败率过高的 worker
+        let metrics = crate::utils::lock_or_recover(wg.metrics.as_ref(), "GatewayWorker.metrics");
+        if metrics.recent_fail_rate > 0.3 {
+            return Err(format!(
+                "最近失败率过高 ({:.0}%)",
+                metrics.recent_fail_rate * 100.0
+            ));
+        }
+        Self::check_half_open_trial(wg)?;
+
+        let mut score = 1.0 + metrics.active_requests as f64;
+        score *= 1.0 + metrics.degrade_score;
+        if state == WorkerState::Degraded {
+            score *= 5.0;
+        }
+        Ok(score)
+    }
+
+    /// 选择一个可用的 Worker（优化调度：最少活跃请求 + 退化系数）
+    /// 特殊处理：跳过有问题的 Worker-0（如果它处于 Unhealthy 状态）
+    /// 指定 model 时跳过已知模型列表中不包含该模型的 Worker（模型列表未知的 Worker 仍可调度）
+    pub fn select_worker(
+        &mut self,
+        client_id: Option<&str>,
+        model: Option<&str>,
+    ) -> Option<Arc<Mutex<GatewayWorker>>> {
+        // 边界检查，防止数组越界
+        if self.pool_size == 0 || self.workers.is_empty() {
+            log::warn!("[Gateway Pool] Worker 池为空，无法选择 Worker");
+            return None;
+        }
+
+        // 粘性会话：如果有 client_id，优先选择同一个 worker
+        if let Some(cid) = client_id {
+            let index = self.sticky_index(cid);
+            if let Some(worker) = self.workers.get(index) {
+                let worker_guard = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+                match Self::check_model(&worker_guard, model)
+                    .and_then(|()| Self::check_sticky(&worker_guard))
+                {
+                    Ok(()) => {
+                        worker_guard.circuit_breaker.begin_half_open_trial();
+                        return Some(Arc::clone(worker));
+                    }
+                    Err(reason) => {
+                        log::debug!(
+                            "[Gateway Pool] 粘性会话未命中 Worker-{}: {}",
+                            worker_guard.id,
+                            reason
+                        );
+                    }
+                }
+            } else {
+                log::warn!("[Gateway Pool] Worker 索引 {} 超出范围", index);
+            }
+        }
+
+        // 最少活跃请求算法（考虑退化系数）
+        let mut best_worker: Option<Arc<Mutex<GatewayWorker>>> = None;
+        let mut best_score = f64::MAX;
+
+        for worker in &self.workers {
+            let worker_guard = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            match Self::check_model(&worker_guard, model)
+                .and_then(|()| Self::evaluate_candidate(&worker_guard))
+            {
+                Ok(score) => {
+                    if score < best_score {
+                        best_score = score;
+                        best_worker = Some(Arc::clone(worker));
+                    }
+                }
+                Err(reason) => {
+                    log::debug!("[Gateway Pool] 跳过 Worker-{}: {}", worker_guard.id, reason);
+                }
+            }
+        }
+
+        if let Some(worker) = best_worker.as_ref() {
+            crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker")
+                .circuit_breaker
+                .begin_half_open_trial();
+        }
+
+        // 如果找到合适的 worker，更新轮询索引
+        if best_worker.is_some() {
+            self.current_index = (self.current_index + 1) % self.pool_size;
+        }
+
+        best_worker
+    }
+
+    /// 解释调度决策（与 select_worker 使用相同的判定逻辑，但不修改任何调度状态）
+    /// 指定 model 时，已知模型列表中不包含该模型的 Worker 会被跳过
+    pub fn explain_selection(
+        &self,
+        client_id: Option<&str>,
+        model: Option<&str>,
+    ) -> SelectionExplanation {
+        let mut explanation = SelectionExplanation {
+            client_id: client_id.map(|s| s.to_string()),
+            model: model.map(|s| s.to_string()),
+            strategy: "none".to_string(),
+            sticky_worker: None,
+            sticky_miss_reason: None,
+            selected_worker: None,
+            candidates: Vec::new(),
+        };
+
+        if self.pool_size == 0 || self.workers.is_empty() {
+            return explanation;
+        }
+
+        if let Some(cid) = client_id {
+            let index = self.sticky_index(cid);
+            explanation.sticky_worker = Some(index);
+            match self.workers.get(index) {
+                Some(worker) => {
+                    let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+                    match Self::check_model(&wg, model).and_then(|()| Self::check_sticky(&wg)) {
+                        Ok(()) => {
+                            explanation.strategy = "sticky".to_string();
+                            explanation.selected_worker = Some(wg.id);
+                        }
+                        Err(reason) => explanation.sticky_miss_reason = Some(reason),
+                    }
+                }
+                None => {
+                    explanation.sticky_miss_reason = Some(format!("Worker 索引 {} 超出范围", index))
+                }
+            }
+        }
+
+        let mut best: Option<(usize, f64)> = None;
+        for worker in &self.workers {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            let model_supported = model.and_then(|m| wg.supports_model(m));
+            let (score, skip_reason) =
+                match Self::check_model(&wg, model).and_then(|()| Self::evaluate_candidate(&wg)) {
+                    Ok(score) => {
+                        if best.map_or(true, |(_, best_score)| score < best_score) {
+                            best = Some((wg.id, score));
+                        }
+                        (Some(score), None)
+                    }
+                    Err(reason) => (None, Some(reason)),
+                };
+            explanation.candidates.push(SelectionCandidate {
+                worker_id: wg.id,
+                port: wg.port,
+                state: wg.status(),
+                score,
+                skipped: skip_reason.is_some(),
+                skip_reason,
+                model_supported,
+            });
+        }
+
+        if explanation.selected_worker.is_none() {
+            if let Some((worker_id, _)) = best {
+                explanation.strategy = "least_active".to_string();
+                explanation.selected_worker = Some(worker_id);
+            }
+        }
+
+        explanation
+    }
+
+    /// 列出有进程在运行的 Worker 端点（排除 Dead / Disabled / FailedPermanent），并标注是否健康
+    pub fn worker_endpoints(&self) -> Vec<WorkerEndpoint> {
+        self.workers
+            .iter()
+            .filter_map(|worker| {
+                let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+                let state = wg.status();
+                if matches!(
+                    state,
+                    WorkerState::Dead | WorkerState::Disabled | WorkerState::FailedPermanent
+                ) {
+                    return None;
+                }
+                let healthy = wg.is_healthy();
+                let reason = if healthy {
+                    None
+                } else {
+                    Some(
+                        Self::unschedulable_reason(&wg)
+                            .unwrap_or_else(|| format!("状态 {:?} 不可用", state)),
+                    )
+                };
+                Some(WorkerEndpoint {
+                    worker_id: wg.id,
+                    port: wg.port,
+                    api_url: wg.api_url(),
+                    state,
+                    healthy,
+                    reason,
+                })
+            })
+            .collect()
+    }
+
+    /// 软队列等待（等待可用 worker，最多等待指定时间）
+    /// 只在选择时短暂持有连接池锁，等待期间释放锁并等待 Worker 释放信号
+    pub fn select_worker_with_queue(
+        pool: &Mutex<Self>,
+        client_id: Option<&str>,
+        model: Option<&str>,
+        max_wait: Duration,
+    ) -> Option<Arc<Mutex<GatewayWorker>>> {
+        let start = Instant::now();
+
+        loop {
+            // 先记下代数再选择：选择失败后到开始等待之间的释放不会丢失
+            let seen = WORKER_RELEASED.generation();
+            if let Some(worker) =
+                crate::utils::lock_or_recover(pool, "GatewayPool").select_worker(client_id, model)
+            {
+                return Some(worker);
+            }
+
+            let elapsed = start.elapsed();
+            if elapsed >= max_wait {
+                return None; // 超时，返回 429
+            }
+
+            WORKER_RELEASED.wait_changed(seen, (max_wait - elapsed).min(QUEUE_RECHECK_INTERVAL));
+        }
+    }
+
+    /// 启动所有 Worker
+    /// 不持有连接池锁调用：只在复制 Worker 列表时短暂加锁，逐个启动并等待就绪期间不阻塞调度与状态查询
+    pub fn start_all(pool: &Mutex<Self>) -> Result<Vec<WorkerStartResult>, String> {
+        let workers = crate::utils::lock_or_recover(pool, "GatewayPool")
+            .workers
+            .clone();
+        let mut results = Vec::new();
+        // 配置了启动超时、启动后尚未就绪的 Worker：(结果下标, 状态, 启动超时)
+        let mut pending_ready = Vec::new();
+
+        for worker in &workers {
+            // 关键修复：安全锁定 Mutex，避免 poisoned 导致 panic
+            let mut worker_guard = match worker.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => {
+                    log::error!("[Gateway Pool] Worker Mutex 被污染，尝试恢复");
+                    poisoned.into_inner()
+                }
+            };
+            if matches!(
+                worker_guard.status(),
+                WorkerState::FailedPermanent | WorkerState::Disabled
+            ) {
+                let message = format!("Worker-{} 已标记为不可自动管理，跳过启动", worker_guard.id);
+                results.push(WorkerStartResult::new(&worker_guard, false, message));
+                continue;
+            }
+            if worker_guard.process.is_none() {
+                match Self::start_worker(&mut worker_guard) {
+                    Ok(msg) => {
+                        log::info!(
                             "[Gateway Pool] Worker-{} 启动成功: {}",
                             worker_guard.id,
-                            msg_clone
+                            msg
                         );
+                        if let Some(timeout) = worker_guard.startup_timeout {
+                            if !matches!(
+                                worker_guard.status(),
+                                WorkerState::Idle | WorkerState::Warmup
+                            ) {
+                                pending_ready.push((
+                                    results.len(),
+                                    Arc::clone(&worker_guard.state),
+                                    timeout,
+                                ));
+                            }
+                        }
+                        results.push(WorkerStartResult::new(&worker_guard, true, msg));
                     }
                     Err(e) => {
                         let error_msg = format!("Worker-{} 启动失败: {}", worker_guard.id, e);
-                        results.push(error_msg.clone());
                         log::error!("[Gateway Pool] {}", error_msg);
                         if worker_guard.status() != WorkerState::FailedPermanent {
                             worker_guard.set_state(WorkerState::Dead);
                         }
+                        results.push(WorkerStartResult::new(&worker_guard, false, error_msg));
                     }
                 }
+            } else {
+                let message = format!("Worker-{} 已在运行", worker_guard.id);
+                results.push(WorkerStartResult::new(&worker_guard, true, message));
+            }
+        }
+
+        // 在 Worker 锁外同时等待加载缓慢的 Worker 就绪（只读取原子状态，不阻塞调度）
+        if !pending_ready.is_empty() {
+            let start = Instant::now();
+            let serving = |state: &AtomicU8| {
+                matches!(
+                    WorkerState::from(state.load(Ordering::Relaxed)),
+                    WorkerState::Idle | WorkerState::Warmup | WorkerState::Dead
+                )
+            };
+            while pending_ready.iter().any(|(_, state, timeout)| {
+                !serving(state) && start.elapsed() < (*timeout).min(MAX_STARTUP_WAIT)
+            }) {
+                thread::sleep(Duration::from_millis(50));
+            }
+            for (index, state, _) in &pending_ready {
+                results[*index].state = WorkerState::from(state.load(Ordering::Relaxed));
             }
         }
 
@@ -1078,40 +2887,18 @@ impl GatewayPool {
     }
 
     /// 停止所有 Worker
-    pub fn stop_all(&self) -> Result<Vec<String>, String> {
+    pub fn stop_all(&self) -> Result<Vec<WorkerStartResult>, String> {
         let mut results = Vec::new();
 
         for worker in &self.workers {
             let mut worker_guard = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
             let was_fatal = worker_guard.status() == WorkerState::FailedPermanent;
             let mut stop_ok = true;
+            let mut stopped = None;
             if let Some(mut child) = worker_guard.process.take() {
                 #[cfg(target_os = "windows")]
                 {
-                    if let Err(e) = child.kill() {
-                        log::warn!("终止 Worker-{} 失败: {}", worker_guard.id, e);
-                    }
-
-                    let taskkill_output = Command::new("taskkill")
-                        .args(&["/F", "/T", "/PID", &child.id().to_string()])
-                        .output();
-                    match taskkill_output {
-                        Ok(output) if output.status.success() => {
-                            log::info!("Worker-{} 进程已终止", worker_guard.id);
-                        }
-                        Ok(output) => {
-                            log::warn!(
-                                "Worker-{} taskkill 失败，状态码: {:?}",
-                                worker_guard.id,
-                                output.status.code()
-                            );
-                            stop_ok = false;
-                        }
-                        Err(e) => {
-                            log::warn!("Worker-{} taskkill 执行失败: {}", worker_guard.id, e);
-                            stop_ok = false;
-                        }
-                    };
+                    stop_ok = terminate_worker_process(&mut child, worker_guard.id);
                 }
 
                 #[cfg(not(target_os = "windows"))]
@@ -1122,20 +2909,22 @@ impl GatewayPool {
                 }
 
                 if stop_ok {
-                    results.push(format!("Worker-{} 已停止", worker_guard.id));
+                    stopped = Some((true, format!("Worker-{} 已停止", worker_guard.id)));
                 } else {
                     worker_guard.process = Some(child);
                     worker_guard.circuit_breaker.force_open();
                     worker_guard.pending_restart = None;
                     worker_guard.next_restart_at = None;
                     worker_guard.set_state(WorkerState::Disabled);
-                    results.push(format!("Worker-{} 停止失败，已隔离", worker_guard.id));
+                    stopped = Some((
+                        false,
+                        format!("Worker-{} 停止失败，已隔离", worker_guard.id),
+                    ));
                 }
             }
 
             worker_guard.pending_restart = None;
             worker_guard.next_restart_at = None;
-            worker_guard.half_open_testing = false;
             if worker_guard.status() == WorkerState::Disabled {
                 worker_guard.circuit_breaker.force_open();
             } else {
@@ -1155,16 +2944,20 @@ impl GatewayPool {
                 *metrics = WorkerMetrics::default();
             }
 
-            if !was_fatal && stop_ok {
+            if !was_fatal && stop_ok && !worker_guard.isolated {
                 worker_guard.set_state(WorkerState::Dead);
             }
+            // 结果在状态更新后生成，反映停止后的最终状态
+            if let Some((ok, message)) = stopped {
+                results.push(WorkerStartResult::new(&worker_guard, ok, message));
+            }
         }
 
         Ok(results)
     }
 
     /// 启动单个 Worker（检查是否已启动，避免重复启动）
-    fn start_worker(worker: &mut GatewayWorker) -> Result<String, String> {
+    fn start_worker(worker: &mut GatewayWorker) -> Result<String, GatewayError> {
         // 检查 Worker 是否已启动
         if let Some(ref mut child) = worker.process {
             // 检查进程是否仍在运行
@@ -1185,57 +2978,96 @@ impl GatewayPool {
             }
         }
 
-        {
-            let bind_result = std::net::TcpListener::bind(("127.0.0.1", worker.port));
-            match bind_result {
-                Ok(listener) => drop(listener),
-                Err(e) => {
-                    let start = worker.port.saturating_add(1);
-                    let end = worker.port.saturating_add(50);
-                    if let Some(new_port) = find_free_port(start, end) {
-                        log::warn!(
-                            "[Gateway Pool] [Worker-{}] 端口 {} 被占用，切换到 {}: {}",
-                            worker.id,
-                            worker.port,
-                            new_port,
-                            e
-                        );
-                        worker.port = new_port;
-                    } else {
-                        worker.circuit_breaker.force_open();
-                        worker.pending_restart = None;
-                        worker.next_restart_at = None;
-                        worker.set_state(WorkerState::FailedPermanent);
+        if let Err(e) = std::net::TcpListener::bind((worker.bind_host, worker.port)) {
+            Self::switch_to_free_port(worker, &e.to_string())?;
+        }
+
+        // 检查空闲到 Python 绑定之间端口可能被其他进程抢占：进程因绑定失败退出时换用新端口重试
+        let mut bind_retries = 0;
+        loop {
+            match Self::spawn_worker_process(worker) {
+                Err(e) if worker.bind_conflict.load(Ordering::Relaxed) => {
+                    worker.process = None;
+                    if bind_retries >= BIND_RACE_MAX_RETRIES {
+                        worker.mark_failed_permanent();
                         log::error!(
-                            "[Gateway Pool] [Worker-{}] FATAL: 端口 {} 已被占用且无可用端口，禁用该 Worker: {}",
+                            "[Gateway Pool] [Worker-{}] FATAL: 连续 {} 次启动时端口被抢占，禁用该 Worker",
                             worker.id,
-                            worker.port,
-                            e
+                            bind_retries + 1
                         );
-                        return Err(format!(
-                            "端口 {} 已被占用，拒绝启动 Worker-{}: {}",
-                            worker.port, worker.id, e
-                        ));
+                        return Err(GatewayError::PortUnavailable {
+                            worker_id: worker.id,
+                            port: worker.port,
+                            reason: format!(
+                                "连续 {} 次启动时端口被其他进程抢占（{}）",
+                                bind_retries + 1,
+                                e
+                            ),
+                        });
                     }
+                    bind_retries += 1;
+                    log::warn!(
+                        "[Gateway Pool] [Worker-{}] 端口 {} 绑定冲突，换用新端口重试（{}/{}）",
+                        worker.id,
+                        worker.port,
+                        bind_retries,
+                        BIND_RACE_MAX_RETRIES
+                    );
+                    Self::switch_to_free_port(worker, "启动时端口被其他进程抢占")?;
                 }
+                result => return result,
             }
         }
-        let python_path = Self::get_python_path();
-        let service_path = Self::get_ai_service_path();
-        let base_dir = get_app_base_dir();
+    }
 
-        if !python_path.exists() {
-            return Err(format!(
-                "Python 可执行文件不存在: {}",
-                python_path.display()
-            ));
+    /// 当前端口不可用时向后查找空闲端口并切换；找不到时标记为永久失败
+    fn switch_to_free_port(worker: &mut GatewayWorker, reason: &str) -> Result<(), GatewayError> {
+        let start = worker.port.saturating_add(1);
+        let end = worker.port.saturating_add(PORT_SHIFT_RANGE);
+        if let Some(new_port) = find_free_port(worker.bind_host, start, end) {
+            log::warn!(
+                "[Gateway Pool] [Worker-{}] 端口 {} 被占用，切换到 {}: {}",
+                worker.id,
+                worker.port,
+                new_port,
+                reason
+            );
+            worker.port = new_port;
+            worker.counters.port.store(new_port, Ordering::Relaxed);
+            return Ok(());
         }
+        worker.mark_failed_permanent();
+        log::error!(
+            "[Gateway Pool] [Worker-{}] FATAL: 端口 {} 已被占用且无可用端口，禁用该 Worker: {}",
+            worker.id,
+            worker.port,
+            reason
+        );
+        Err(GatewayError::PortUnavailable {
+            worker_id: worker.id,
+            port: worker.port,
+            reason: reason.to_string(),
+        })
+    }
 
-        if !service_path.exists() {
-            return Err(format!(
-                "AI Gateway 服务脚本不存在: {}",
-                service_path.display()
-            ));
+    /// 在当前端口上启动 Worker 进程并在 Worker 锁内短暂等待其就绪（1.5 秒）
+    /// 配置的启动超时由 start_all 在锁外等待、端口探测线程与健康检查宽限期使用
+    fn spawn_worker_process(worker: &mut GatewayWorker) -> Result<String, GatewayError> {
+        let config = crate::ai_service::GatewayPoolConfig::load();
+        let python_path = config.resolve_python_path();
+        let service_path = config.resolve_gateway_script_path();
+        let base_dir = get_app_base_dir();
+
+        let python_ok = python_path.is_file();
+        let service_ok = service_path.is_file();
+        if !python_ok || !service_ok {
+            return Err(GatewayError::ScriptNotFound {
+                worker_id: worker.id,
+                python_path: python_path.display().to_string(),
+                python_exists: python_ok,
+                script_path: service_path.display().to_string(),
+                script_exists: service_ok,
+            });
         }
 
         log::info!(
@@ -1247,16 +3079,45 @@ impl GatewayPool {
         worker.started_at = Some(Instant::now());
         worker.port_bound.store(false, Ordering::Relaxed);
         worker.model_ready.store(false, Ordering::Relaxed);
+        worker.warmed_up.store(false, Ordering::Relaxed);
+        worker.accepts_gzip.store(false, Ordering::Relaxed);
+        worker.bind_conflict.store(false, Ordering::Relaxed);
 
         let config_path = base_dir
             .join("ai_service")
             .join("config")
             .join("models.json");
 
+        // 网关脚本与模型配置未变化时从磁盘恢复能力信息，否则下次健康检查时重新获取
+        worker.gateway_version = capability_cache::gateway_version(&service_path, &config_path);
+        match worker
+            .gateway_version
+            .as_deref()
+            .and_then(capability_cache::load_capability)
+        {
+            Some(cached) => {
+                log::info!(
+                    "[Gateway Pool] Worker-{} 从缓存恢复能力信息（{} 个模型）",
+                    worker.id,
+                    cached.supported_models.len()
+                );
+                *crate::utils::lock_or_recover(
+                    worker.capability.as_ref(),
+                    "GatewayWorker.capability",
+                ) = cached;
+                worker.models_refreshed_at = Some(Instant::now());
+            }
+            None => worker.models_refreshed_at = None,
+        }
+
         let mut cmd = Command::new(&python_path);
         cmd.arg(&service_path)
             .arg("--port")
             .arg(worker.port.to_string());
+        // 仅在非默认地址时传递 --host，兼容不支持该参数的自定义 Gateway 脚本
+        if worker.bind_host != IpAddr::V4(Ipv4Addr::LOCALHOST) {
+            cmd.arg("--host").arg(worker.bind_host.to_string());
+        }
 
         if config_path.exists() {
             cmd.arg("--config").arg(config_path.to_str().unwrap_or(""));
@@ -1266,17 +3127,30 @@ impl GatewayPool {
             .stdout(Stdio::null())
             .stderr(Stdio::piped())
             .spawn()
-            .map_err(|e| format!("启动 Worker-{} 失败: {}", worker.id, e))?;
+            .map_err(|e| {
+                format!(
+                    "启动 Worker-{} 失败: {}（Python 解释器 {}，Gateway 脚本 {}）",
+                    worker.id,
+                    e,
+                    python_path.display(),
+                    service_path.display()
+                )
+            })?;
 
         // 启动后台线程读取 stderr（检测 READY 状态，更新心跳，避免 HTTP 健康检查）
         // 关键修复：使用 panic::catch_unwind 捕获所有 panic，避免读取线程崩溃导致主进程退出
         if let Some(stderr) = child.stderr.take() {
             let worker_id = worker.id;
             let worker_port = worker.port;
+            // 开启自动预热时，就绪后先进入 Warmup，预热请求返回后再转为 Idle
+            let mut warmup = worker.auto_warmup.then(|| worker.warmup_target());
             let worker_state = Arc::clone(&worker.state);
             let worker_metrics = Arc::clone(&worker.metrics);
             let port_bound = Arc::clone(&worker.port_bound);
             let model_ready = Arc::clone(&worker.model_ready);
+            let log_buffer = Arc::clone(&worker.log_buffer);
+            let bind_conflict = Arc::clone(&worker.bind_conflict);
+            let worker_ref = worker.self_ref.clone();
             let stderr_reader = BufReader::new(stderr);
 
             thread::spawn(move || {
@@ -1291,6 +3165,25 @@ impl GatewayPool {
                         // 安全处理每一行，避免单个错误导致整个线程退出
                         match line {
                             Ok(line) => {
+                                if !line.trim().is_empty() {
+                                    let mut buffer = crate::utils::lock_or_recover(
+                                        log_buffer.as_ref(),
+                                        "GatewayWorker.log_buffer",
+                                    );
+                                    if buffer.len() >= WORKER_LOG_CAPACITY {
+                                        buffer.pop_front();
+                                    }
+                                    buffer.push_back(WorkerLogLine {
+                                        timestamp: crate::service::events::current_timestamp(),
+                                        line: line.clone(),
+                                    });
+                                }
+
+                                if !model_ready.load(Ordering::Relaxed) && is_bind_error_line(&line)
+                                {
+                                    bind_conflict.store(true, Ordering::Relaxed);
+                                }
+
                                 if line.contains("⚠️ 模型 ") && line.contains("不可用") {
                                     if let Some(model_id) = extract_unavailable_model_id(&line) {
                                         let set = UNAVAILABLE_MODELS
@@ -1313,28 +3206,49 @@ impl GatewayPool {
                                         || current_state == WorkerState::Init
                                     {
                                         if port_bound.load(Ordering::Relaxed) {
-                                            worker_state
-                                                .store(WorkerState::Idle as u8, Ordering::Relaxed);
-                                            match worker_metrics.lock() {
-                                                Ok(mut metrics) => {
-                                                    metrics.last_heartbeat = Some(Instant::now());
-                                                    log::info!(
-                                                        "[Gateway Pool] [Worker-{}] READY + 端口可用，状态转为 IDLE",
-                                                        worker_id
-                                                    );
+                                            let next_state = if warmup.is_some() {
+                                                WorkerState::Warmup
+                                            } else {
+                                                WorkerState::Idle
+                                            };
+                                            if transition_state(
+                                                worker_id,
+                                                &worker_state,
+                                                current_state,
+                                                next_state,
+                                                "ready",
+                                            ) {
+                                                if let Some(target) = warmup.take() {
+                                                    target.spawn();
                                                 }
-                                                Err(poisoned) => {
-                                                    log::error!(
-                                                        "[Gateway Pool] [Worker-{}] metrics Mutex 被污染，尝试恢复",
-                                                        worker_id
-                                                    );
-                                                    let mut metrics = poisoned.into_inner();
-                                                    metrics.last_heartbeat = Some(Instant::now());
+                                                match worker_metrics.lock() {
+                                                    Ok(mut metrics) => {
+                                                        metrics.last_heartbeat =
+                                                            Some(Instant::now());
+                                                        log::info!(
+                                                            "[Gateway Pool] [Worker-{}] READY + 端口可用，状态转为 IDLE",
+                                                            worker_id
+                                                        );
+                                                    }
+                                                    Err(poisoned) => {
+                                                        log::error!(
+                                                            "[Gateway Pool] [Worker-{}] metrics Mutex 被污染，尝试恢复",
+                                                            worker_id
+                                                        );
+                                                        let mut metrics = poisoned.into_inner();
+                                                        metrics.last_heartbeat =
+                                                            Some(Instant::now());
+                                                    }
                                                 }
                                             }
                                         } else {
-                                            worker_state
-                                                .store(WorkerState::Ready as u8, Ordering::Relaxed);
+                                            transition_state(
+                                                worker_id,
+                                                &worker_state,
+                                                current_state,
+                                                WorkerState::Ready,
+                                                "ready",
+                                            );
                                             log::info!(
                                                 "[Gateway Pool] [Worker-{}] 检测到 READY，等待端口 {} bind 后注册",
                                                 worker_id,
@@ -1344,6 +3258,13 @@ impl GatewayPool {
                                     }
                                 }
 
+                                // READY 后进程报告退出：立即标记 Dead，不等待健康检查发现
+                                if model_ready.load(Ordering::Relaxed)
+                                    && is_process_exit_marker(&line)
+                                {
+                                    handle_exit_after_ready(worker_id, &worker_state, &worker_ref);
+                                }
+
                                 if line.contains("[UNHANDLED]")
                                     || line.contains("Traceback (most recent call last)")
                                     || line.contains("panic")
@@ -1406,11 +3327,21 @@ impl GatewayPool {
                         panic_info
                     );
                     // 标记 Worker 为异常状态，但不 panic
-                    worker_state.store(WorkerState::Unhealthy as u8, Ordering::Relaxed);
+                    let previous = WorkerState::from(
+                        worker_state.swap(WorkerState::Unhealthy as u8, Ordering::Relaxed),
+                    );
+                    publish_state_change(
+                        worker_id,
+                        previous,
+                        WorkerState::Unhealthy,
+                        Some(HealthSignal::PanicDetected.as_str().to_string()),
+                    );
                 }
             });
         }
 
+        crate::process_registry::track_worker(child.id(), worker.id, worker.port);
+
         // 关键修复：先将 child 保存到 worker，避免被 drop 导致进程终止
         // 在 Windows 上，如果 Child 被 drop，子进程会被立即终止
         worker.process = Some(child);
@@ -1421,28 +3352,53 @@ impl GatewayPool {
         {
             let worker_id = worker.id;
             let worker_port = worker.port;
+            let worker_host = worker.bind_host;
+            let mut warmup = worker.auto_warmup.then(|| worker.warmup_target());
             let worker_state = Arc::clone(&worker.state);
             let worker_metrics = Arc::clone(&worker.metrics);
             let port_bound = Arc::clone(&worker.port_bound);
             let model_ready = Arc::clone(&worker.model_ready);
+            let counters = Arc::clone(&worker.counters);
+            // 与启动超时一致：加载缓慢的模型在超时内监听端口时仍能转为可用
+            let timeout = worker
+                .startup_timeout
+                .map_or(PORT_PROBE_TIMEOUT, |timeout| {
+                    timeout.max(PORT_PROBE_TIMEOUT)
+                });
             thread::spawn(move || {
                 let start = Instant::now();
-                let timeout = Duration::from_secs(5);
                 let connect_timeout = Duration::from_millis(200);
 
-                let addr = std::net::SocketAddr::from((
-                    std::net::Ipv4Addr::new(127, 0, 0, 1),
-                    worker_port,
-                ));
+                let addr = SocketAddr::new(worker_connect_host(worker_host), worker_port);
 
                 while start.elapsed() < timeout {
+                    // 绑定冲突后已换用新端口：旧端口上的监听者不是本 Worker
+                    if counters.port.load(Ordering::Relaxed) != worker_port {
+                        return;
+                    }
                     if std::net::TcpStream::connect_timeout(&addr, connect_timeout).is_ok() {
                         port_bound.store(true, Ordering::Relaxed);
                         let current_state = WorkerState::from(worker_state.load(Ordering::Relaxed));
                         if matches!(current_state, WorkerState::Init | WorkerState::Ready)
                             && model_ready.load(Ordering::Relaxed)
                         {
-                            worker_state.store(WorkerState::Idle as u8, Ordering::Relaxed);
+                            let next_state = if warmup.is_some() {
+                                WorkerState::Warmup
+                            } else {
+                                WorkerState::Idle
+                            };
+                            if !transition_state(
+                                worker_id,
+                                &worker_state,
+                                current_state,
+                                next_state,
+                                "ready",
+                            ) {
+                                return;
+                            }
+                            if let Some(target) = warmup.take() {
+                                target.spawn();
+                            }
                             match worker_metrics.lock() {
                                 Ok(mut metrics) => {
                                     metrics.last_heartbeat = Some(Instant::now());
@@ -1487,10 +3443,9 @@ impl GatewayPool {
         }
 
         worker.circuit_breaker.reset();
-        worker.half_open_testing = false;
 
         let start = Instant::now();
-        let max_wait = Duration::from_millis(1500);
+        let max_wait = DEFAULT_STARTUP_WAIT;
         let mut saw_ready = false;
 
         loop {
@@ -1502,11 +3457,19 @@ impl GatewayPool {
                             worker.id,
                             status
                         );
+                        // 等待 stderr 读取线程处理剩余输出，以便识别绑定失败
+                        let exited_at = Instant::now();
+                        while !worker.bind_conflict.load(Ordering::Relaxed)
+                            && exited_at.elapsed() < BIND_ERROR_GRACE
+                        {
+                            thread::sleep(Duration::from_millis(20));
+                        }
                         worker.set_state(WorkerState::Dead);
                         return Err(format!(
                             "Worker-{} 进程在启动阶段退出，退出状态: {:?}",
                             worker.id, status
-                        ));
+                        )
+                        .into());
                     }
                     Ok(None) => {}
                     Err(e) => {
@@ -1520,7 +3483,7 @@ impl GatewayPool {
             } else {
                 log::error!("[Gateway Pool] Worker-{} child 进程句柄丢失", worker.id);
                 worker.set_state(WorkerState::Dead);
-                return Err(format!("Worker-{} child 进程句柄丢失", worker.id));
+                return Err(format!("Worker-{} child 进程句柄丢失", worker.id).into());
             }
 
             let current_state = worker.status();
@@ -1544,66 +3507,320 @@ impl GatewayPool {
         Ok(format!("Worker-{} 已启动在端口 {}", worker.id, worker.port))
     }
 
-    /// 重启单个 Worker
-    #[allow(dead_code)]
-    pub fn restart_worker(&self, worker_id: usize) -> Result<String, String> {
-        if worker_id >= self.workers.len() {
-            return Err(format!("Worker ID {} 不存在", worker_id));
-        }
-
-        let worker = &self.workers[worker_id];
+    /// 手动重启单个 Worker
+    /// 操作员发起的恢复不受自动重启预算限制：重启前清空重启预算与退避状态
+    /// 不持有连接池锁调用，避免重启期间阻塞调度
+    pub fn restart_worker(worker: &Arc<Mutex<GatewayWorker>>) -> Result<String, GatewayError> {
         let mut worker_guard = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+        let worker_id = worker_guard.id;
         if matches!(
             worker_guard.status(),
             WorkerState::FailedPermanent | WorkerState::Disabled
         ) {
-            return Err(format!(
-                "Worker-{} 已标记为不可自动管理，拒绝重启",
-                worker_id
-            ));
+            return Err(format!("Worker-{} 已标记为不可自动管理，拒绝重启", worker_id).into());
         }
+        worker_guard.restart_budget.history.clear();
+        worker_guard.reset_restart_failures();
+        worker_guard.pending_restart = None;
+        worker_guard.next_restart_at = None;
         Self::restart_worker_guard(&mut worker_guard, worker_id)?;
+        log::info!("[Gateway Pool] Worker-{} 已手动重启", worker_id);
         Ok(format!("Worker-{} 已重启", worker_id))
     }
 
+    /// 对 Worker 端口发起只读 GET 探测（如 /health、/v1/models）
+    /// 只在读取端口时短暂持有 Worker 锁，不更新指标、心跳或状态，不影响调度
+    pub fn probe_worker_http(
+        worker: &Arc<Mutex<GatewayWorker>>,
+        path: &str,
+    ) -> Result<WorkerProbe, String> {
+        if !path.starts_with('/') {
+            return Err(format!("探测路径必须以 / 开头: {}", path));
+        }
+        let (worker_id, url) = {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            (wg.id, format!("{}{}", wg.api_url(), path))
+        };
+        let client = Client::builder()
+            .timeout(PROBE_TIMEOUT)
+            .build()
+            .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+
+        let start = Instant::now();
+        let mut probe = WorkerProbe {
+            worker_id,
+            url: url.clone(),
+            status: None,
+            elapsed_ms: 0,
+            body_preview: String::new(),
+            truncated: false,
+            error: None,
+        };
+        match client.get(&url).send() {
+            Ok(response) => {
+                probe.status = Some(response.status().as_u16());
+                let mut body = Vec::new();
+                // 多读 1 字节用于判断是否截断
+                let read = {
+                    use std::io::Read;
+                    response
+                        .take(PROBE_BODY_PREVIEW_BYTES + 1)
+                        .read_to_end(&mut body)
+                };
+                if let Err(e) = read {
+                    probe.error = Some(format!("读取响应体失败: {}", e));
+                }
+                probe.truncated = body.len() as u64 > PROBE_BODY_PREVIEW_BYTES;
+                body.truncate(PROBE_BODY_PREVIEW_BYTES as usize);
+                probe.body_preview = String::from_utf8_lossy(&body).to_string();
+            }
+            Err(e) => probe.error = Some(e.to_string()),
+        }
+        probe.elapsed_ms = start.elapsed().as_millis() as u64;
+        Ok(probe)
+    }
+
+    /// 手动重新启用 Disabled / FailedPermanent 状态的 Worker
+    /// 清空熔断器与重启预算后尝试干净启动（端口被占用时自动切换到空闲端口）；
+    /// 阻塞原因仍存在（残留进程无法终止、无空闲端口、脚本缺失等）时返回错误并保持不可用状态
+    pub fn reenable_worker(worker: &Arc<Mutex<GatewayWorker>>) -> Result<String, GatewayError> {
+        let mut worker_guard = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+        let worker_id = worker_guard.id;
+        if worker_guard.isolated {
+            return Err(format!(
+                "Worker-{} 已按配置永久隔离（disable_worker_0），请修改配置后重启连接池",
+                worker_id
+            )
+            .into());
+        }
+        let previous_state = worker_guard.status();
+        if !matches!(
+            previous_state,
+            WorkerState::Disabled | WorkerState::FailedPermanent
+        ) {
+            return Err(format!(
+                "Worker-{} 当前状态为 {:?}，无需重新启用（可使用 restart_gateway_worker 重启）",
+                worker_id, previous_state
+            )
+            .into());
+        }
+
+        // 先清理残留进程（Disabled 通常由进程无法终止导致）
+        Self::kill_worker_guard(&mut worker_guard, worker_id)
+            .map_err(|e| format!("重新启用失败，残留进程仍无法终止: {}", e))?;
+
+        worker_guard.reset_for_recovery();
+        worker_guard.started_at = None;
+        worker_guard.last_health_check = None;
+        worker_guard.last_success = None;
+        match Self::start_worker(&mut worker_guard) {
+            Ok(msg) => {
+                log::info!(
+                    "[Gateway Pool] Worker-{} 已从 {:?} 手动重新启用: {}",
+                    worker_id,
+                    previous_state,
+                    msg
+                );
+                Ok(format!(
+                    "Worker-{} 已重新启用（端口 {}）",
+                    worker_id, worker_guard.port
+                ))
+            }
+            Err(e) => {
+                // 启动失败说明阻塞原因仍在，恢复为不可用状态，避免被自动重启反复尝试
+                worker_guard.circuit_breaker.force_open();
+                worker_guard.pending_restart = None;
+                worker_guard.next_restart_at = None;
+                worker_guard.set_state(previous_state);
+                log::error!(
+                    "[Gateway Pool] Worker-{} 重新启用失败，保持 {:?}: {}",
+                    worker_id,
+                    previous_state,
+                    e
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// 排空并停止 Worker：不再分配新请求，等待在途请求完成（最长为排空超时）后终止进程
+    /// 超时后回退为强制终止
+    /// 不持有连接池锁调用，避免排空期间阻塞调度
+    pub fn drain_worker(worker: &Arc<Mutex<GatewayWorker>>) -> Result<String, GatewayError> {
+        let worker_id = {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            if wg.process.is_none() {
+                return Err(format!("Worker-{} 未运行，无需排空", wg.id).into());
+            }
+            if matches!(
+                wg.status(),
+                WorkerState::FailedPermanent | WorkerState::Disabled | WorkerState::Draining
+            ) {
+                return Err(
+                    format!("Worker-{} 当前状态为 {:?}，无法排空", wg.id, wg.status()).into(),
+                );
+            }
+            wg.id
+        };
+
+        let drained = drain_in_flight(worker);
+        let mut wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+        if wg.status() != WorkerState::Draining {
+            return Err(format!(
+                "Worker-{} 排空期间状态变为 {:?}，已中止",
+                worker_id,
+                wg.status()
+            )
+            .into());
+        }
+        wg.pending_restart = None;
+        wg.next_restart_at = None;
+        Self::kill_worker_guard(&mut wg, worker_id)?;
+        Ok(if drained {
+            format!("Worker-{} 已排空并停止", worker_id)
+        } else {
+            format!("Worker-{} 排空超时，已强制停止", worker_id)
+        })
+    }
+
+    /// 设置排空超时（应用到所有 Worker）
+    pub fn set_drain_timeout(&self, timeout: Duration) {
+        for worker in &self.workers {
+            crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker").drain_timeout = timeout;
+        }
+    }
+
+    /// 调整所有 Worker 的熔断器阈值（对之后的失败统计立即生效）；
+    /// reset 为 true 时同时重置熔断器为关闭状态，否则保留当前打开 / 关闭状态
+    pub fn set_circuit_breaker_config(&self, settings: &CircuitBreakerSettings, reset: bool) {
+        let config = settings.to_breaker_config();
+        for worker in &self.workers {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            wg.circuit_breaker.set_config(config.clone());
+            if reset {
+                wg.circuit_breaker.reset();
+            }
+        }
+    }
+
+    /// 当前熔断器阈值（各 Worker 相同，取第一个 Worker 的配置）
+    pub fn circuit_breaker_config(&self) -> CircuitBreakerSettings {
+        self.workers
+            .first()
+            .map(|worker| {
+                let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+                CircuitBreakerSettings::from(&wg.circuit_breaker.config())
+            })
+            .unwrap_or_default()
+    }
+
+    /// 设置启动就绪后是否自动预热（对之后启动的 Worker 生效）
+    pub fn set_auto_warmup(&self, enabled: bool) {
+        for worker in &self.workers {
+            crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker").auto_warmup = enabled;
+        }
+    }
+
+    /// 手动预热 Idle 状态的 Worker：预热期间处于 Warmup 状态、不参与调度，请求返回后恢复 Idle
+    pub fn warmup_worker(worker: &Arc<Mutex<GatewayWorker>>) -> Result<WarmupResult, GatewayError> {
+        let target = {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            let state = wg.status();
+            if state != WorkerState::Idle {
+                return Err(format!(
+                    "Worker-{} 处于 {:?} 状态，仅 Idle 状态可以预热",
+                    wg.id, state
+                )
+                .into());
+            }
+            wg.set_state(WorkerState::Warmup);
+            wg.warmup_target()
+        };
+        target.run().map_err(GatewayError::UpstreamFailed)
+    }
+
+    /// 对指定 Worker 执行冒烟测试（直接请求，不经过调度、不计入请求指标）
+    pub fn smoke_test_worker(
+        worker: &Arc<Mutex<GatewayWorker>>,
+    ) -> Result<SmokeTestResult, GatewayError> {
+        let target = {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            let state = wg.status();
+            if matches!(
+                state,
+                WorkerState::Dead | WorkerState::Disabled | WorkerState::FailedPermanent
+            ) {
+                return Err(
+                    format!("Worker-{} 处于 {:?} 状态，无法执行冒烟测试", wg.id, state).into(),
+                );
+            }
+            wg.warmup_target()
+        };
+        let result = target.smoke_test();
+        if result.ok {
+            log::info!(
+                "[Gateway Pool] [Worker-{}] 冒烟测试通过（耗时 {} ms）",
+                result.worker_id,
+                result.elapsed_ms
+            );
+        } else {
+            log::warn!(
+                "[Gateway Pool] [Worker-{}] 冒烟测试失败（HTTP {:?}，耗时 {} ms）: {}",
+                result.worker_id,
+                result.status,
+                result.elapsed_ms,
+                result.error.as_deref().unwrap_or(&result.snippet)
+            );
+        }
+        Ok(result)
+    }
+
     fn restart_worker_guard(worker: &mut GatewayWorker, worker_id: usize) -> Result<(), String> {
+        Self::kill_worker_guard(worker, worker_id)?;
+        std::thread::sleep(Duration::from_millis(500));
+        Self::start_worker(worker).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Worker 当前进程是否为 pid 且仍在运行
+    pub(crate) fn worker_process_alive(worker: &Arc<Mutex<GatewayWorker>>, pid: u32) -> bool {
+        let mut wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+        match wg.process.as_mut() {
+            Some(child) if child.id() == pid => matches!(child.try_wait(), Ok(None)),
+            _ => false,
+        }
+    }
+
+    /// 终止 Worker 的当前进程（PID 必须一致，避免误杀已被系统回收复用的 PID），
+    /// 终止后标记为 Dead，由健康检查按重启策略处理
+    pub(crate) fn kill_worker_process(
+        worker: &Arc<Mutex<GatewayWorker>>,
+        pid: u32,
+    ) -> Result<(), String> {
+        let mut wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+        let worker_id = wg.id;
+        if wg.process.as_ref().map(Child::id) != Some(pid) {
+            return Err(format!(
+                "进程 {} 已不是 Worker-{} 的当前进程",
+                pid, worker_id
+            ));
+        }
+        Self::kill_worker_guard(&mut wg, worker_id)
+    }
+
+    /// 终止 Worker 进程并标记为 Dead（无法确认退出时进入 Disabled 隔离）
+    fn kill_worker_guard(worker: &mut GatewayWorker, worker_id: usize) -> Result<(), String> {
         if let Some(mut child) = worker.process.take() {
             #[cfg(target_os = "windows")]
             {
-                if let Err(e) = child.kill() {
-                    log::warn!("Worker-{} kill() 失败: {}", worker_id, e);
-                }
-                let taskkill_output = Command::new("taskkill")
-                    .args(&["/F", "/T", "/PID", &child.id().to_string()])
-                    .output();
-
-                let taskkill_ok = match taskkill_output {
-                    Ok(output) if output.status.success() => {
-                        log::info!("Worker-{} 进程已通过 taskkill 终止", worker_id);
-                        true
-                    }
-                    Ok(output) => {
-                        log::warn!(
-                            "Worker-{} taskkill 失败，状态码: {:?}",
-                            worker_id,
-                            output.status.code()
-                        );
-                        false
-                    }
-                    Err(e) => {
-                        log::warn!("Worker-{} taskkill 执行失败: {}", worker_id, e);
-                        false
-                    }
-                };
-
-                if !taskkill_ok {
+                if !terminate_worker_process(&mut child, worker_id) {
                     worker.process = Some(child);
                     worker.circuit_breaker.force_open();
                     worker.pending_restart = None;
                     worker.next_restart_at = None;
                     worker.set_state(WorkerState::Disabled);
                     return Err(format!(
-                        "Worker-{} taskkill 失败，进入 Disabled（隔离）",
+                        "Worker-{} taskkill 与 TerminateProcess 均失败，进入 Disabled（隔离）",
                         worker_id
                     ));
                 }
@@ -1649,21 +3866,27 @@ impl GatewayPool {
         }
 
         worker.set_state(WorkerState::Dead);
-        std::thread::sleep(Duration::from_millis(500));
-        Self::start_worker(worker)?;
         Ok(())
     }
 
-    /// 获取 Python 路径
-    fn get_python_path() -> std::path::PathBuf {
-        let base_dir = get_app_base_dir();
-        base_dir.join("python313").join("python.exe")
+    /// 获取 Worker 最近的 stderr 输出（按时间顺序，最多 limit 行）
+    pub fn worker_logs(&self, worker_id: usize, limit: usize) -> Option<Vec<WorkerLogLine>> {
+        let worker = self.get_worker(worker_id)?;
+        let log_buffer =
+            Arc::clone(&crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker").log_buffer);
+        let buffer = crate::utils::lock_or_recover(log_buffer.as_ref(), "GatewayWorker.log_buffer");
+        let skip = buffer.len().saturating_sub(limit);
+        Some(buffer.iter().skip(skip).cloned().collect())
     }
 
-    /// 获取 AI Gateway 服务脚本路径
-    fn get_ai_service_path() -> std::path::PathBuf {
-        let base_dir = get_app_base_dir();
-        base_dir.join("ai_service").join("main_gateway.py")
+    /// 获取 Python 路径（gateway_pool.json 中的 python_path，缺省为平台默认值）
+    pub(crate) fn get_python_path() -> std::path::PathBuf {
+        crate::ai_service::GatewayPoolConfig::load().resolve_python_path()
+    }
+
+    /// 获取 AI Gateway 服务脚本路径（gateway_pool.json 中的 gateway_script_path，缺省为 ai_service/main_gateway.py）
+    pub(crate) fn get_ai_service_path() -> std::path::PathBuf {
+        crate::ai_service::GatewayPoolConfig::load().resolve_gateway_script_path()
     }
 
     /// 健康检查单个 Worker
@@ -1688,13 +3911,31 @@ impl GatewayPool {
             diagnostics.push("  进程状态: 存在（具体状态需要可变引用才能检查）".to_string());
         }
 
+        let breaker = &worker_guard.circuit_breaker;
         diagnostics.push(format!(
             "  熔断器打开: {}",
-            worker_guard.circuit_breaker.state() == CircuitBreakerState::Open
+            breaker.state() == CircuitBreakerState::Open
         ));
-        if let Some(elapsed) = worker_guard.circuit_breaker.opened_elapsed() {
+        if let Some(elapsed) = breaker.opened_elapsed() {
             diagnostics.push(format!("  熔断器打开时间: {:?} 前", elapsed));
         }
+        if let Some(remaining) = breaker.time_until_half_open() {
+            if remaining.is_zero() {
+                diagnostics.push("  熔断恢复: 已到期，下一个请求将进入半开试探".to_string());
+            } else {
+                diagnostics.push(format!(
+                    "  熔断恢复: {} 秒后尝试半开",
+                    remaining.as_secs_f64().ceil() as u64
+                ));
+            }
+        }
+        let breaker_config = breaker.config();
+        diagnostics.push(format!(
+            "  熔断阈值: 连续失败 {} 次打开 / 打开 {} 秒后半开 / 半开成功 {} 次关闭",
+            breaker_config.failure_threshold,
+            breaker_config.timeout.as_secs(),
+            breaker_config.success_threshold
+        ));
 
         let metrics =
             crate::utils::lock_or_recover(worker_guard.metrics.as_ref(), "GatewayWorker.metrics");
@@ -1710,6 +3951,23 @@ impl GatewayPool {
             diagnostics.push(format!("  最后健康检查: {:?} 前", last_check.elapsed()));
         }
 
+        // 附带最近的 stderr 输出（通常包含 Python traceback）
+        let buffer = crate::utils::lock_or_recover(
+            worker_guard.log_buffer.as_ref(),
+            "GatewayWorker.log_buffer",
+        );
+        if !buffer.is_empty() {
+            diagnostics.push(format!(
+                "  最近 stderr 输出（{} 行中的最后 20 行）:",
+                buffer.len()
+            ));
+            let skip = buffer.len().saturating_sub(20);
+            for entry in buffer.iter().skip(skip) {
+                diagnostics.push(format!("    {}", entry.line));
+            }
+        }
+        drop(buffer);
+
         diagnostics.join("\n")
     }
 
@@ -1787,7 +4045,7 @@ impl GatewayPool {
                 "GatewayWorker.metrics",
             );
             if let Some(last_heartbeat) = metrics.last_heartbeat {
-                last_heartbeat.elapsed() < Duration::from_secs(60) // 心跳在60秒内有效
+                last_heartbeat.elapsed() < self.health_check_config().heartbeat_timeout()
             } else {
                 // 如果没有心跳记录，但进程运行中，认为正常（可能是刚启动）
                 true
@@ -1811,7 +4069,6 @@ impl GatewayPool {
             metrics.consecutive_failures = 0;
             drop(metrics);
             worker_guard.record_success();
-            worker_guard.half_open_testing = false;
             worker_guard.circuit_breaker.reset();
 
             if worker_id == 0 {
@@ -1853,6 +4110,7 @@ impl GatewayPool {
                     | WorkerState::FailedPermanent
                     | WorkerState::Disabled
                     | WorkerState::Restarting
+                    | WorkerState::Draining
             ) {
                 drop(worker_guard);
                 self.health_check_worker(idx);
@@ -1862,8 +4120,6 @@ impl GatewayPool {
 
     /// 启动后台健康检查线程（使用静态变量确保只启动一次）
     pub fn start_health_check_thread(&self) {
-        static HEALTH_CHECK_STARTED: AtomicBool = AtomicBool::new(false);
-
         // 使用 compare_and_swap 确保只启动一次
         if HEALTH_CHECK_STARTED
             .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
@@ -1875,9 +4131,14 @@ impl GatewayPool {
 
         let workers = self.workers.clone();
         let pool_size = self.pool_size;
-
-        thread::spawn(move || {
-            let client = match Client::builder().timeout(Duration::from_secs(5)).build() {
+        let all_down = Arc::clone(&self.all_down);
+        let all_down_policy = self.all_down_policy.clone();
+        let health_check = Arc::clone(&self.health_check);
+        let generation = HEALTH_CHECK_GENERATION.load(Ordering::SeqCst);
+
+        let handle = thread::spawn(move || {
+            // 超时在每个请求上单独设置，便于运行中调整
+            let client = match Client::builder().build() {
                 Ok(c) => c,
                 Err(e) => {
                     log::error!("[Gateway Pool] 创建健康检查 HTTP 客户端失败: {}", e);
@@ -1888,21 +4149,41 @@ impl GatewayPool {
             log::info!("[Gateway Pool] 健康检查线程已启动（HTTP /health + 进程状态 + 心跳）");
 
             loop {
-                thread::sleep(Duration::from_secs(10));
+                let interval = crate::utils::lock_or_recover(
+                    health_check.as_ref(),
+                    "GatewayPool.health_check",
+                )
+                .interval();
+                // 连接池重建或应用退出时（代次变化）不等待本轮间隔结束
+                let retired = || HEALTH_CHECK_GENERATION.load(Ordering::SeqCst) != generation;
+                if !crate::utils::sleep_unless_stopped(interval, retired) || retired() {
+                    log::info!("[Gateway Pool] 连接池已重建或应用退出，旧健康检查线程退出");
+                    break;
+                }
+                crate::service::drift::record_loop_tick("Gateway Pool 健康检查", interval);
+                // 每轮读取一次配置，修改后下一轮生效
+                let config = crate::utils::lock_or_recover(
+                    health_check.as_ref(),
+                    "GatewayPool.health_check",
+                )
+                .clone();
 
                 for idx in 0..pool_size {
                     let worker = &workers[idx];
                     let mut worker_guard =
                         crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
 
-                    // 跳过 Dead / FailedPermanent / Restarting 状态的 Worker
-                    if matches!(
-                        worker_guard.status(),
-                        WorkerState::Dead
-                            | WorkerState::FailedPermanent
-                            | WorkerState::Disabled
-                            | WorkerState::Restarting
-                    ) {
+                    // 跳过维护模式及 Dead / FailedPermanent / Restarting / Draining 状态的 Worker
+                    if worker_guard.in_maintenance()
+                        || matches!(
+                            worker_guard.status(),
+                            WorkerState::Dead
+                                | WorkerState::FailedPermanent
+                                | WorkerState::Disabled
+                                | WorkerState::Restarting
+                                | WorkerState::Draining
+                        )
+                    {
                         continue;
                     }
 
@@ -1950,7 +4231,6 @@ impl GatewayPool {
                     // 检查熔断器是否应该尝试恢复（进入半开状态）
                     if worker_guard.should_attempt_recovery() {
                         log::info!("[Gateway Pool] Worker-{} 熔断器半开，尝试恢复", idx);
-                        worker_guard.half_open_testing = false;
                         let mut metrics = crate::utils::lock_or_recover(
                             worker_guard.metrics.as_ref(),
                             "GatewayWorker.metrics",
@@ -1975,7 +4255,7 @@ impl GatewayPool {
                             "GatewayWorker.metrics",
                         );
                         if let Some(last_heartbeat) = metrics.last_heartbeat {
-                            last_heartbeat.elapsed() < Duration::from_secs(60) // 心跳在60秒内有效
+                            last_heartbeat.elapsed() < config.heartbeat_timeout()
                         } else {
                             // 如果没有心跳记录，但进程运行中，认为正常（可能是刚启动）
                             is_alive
@@ -1996,6 +4276,18 @@ impl GatewayPool {
                         continue;
                     }
 
+                    // 预热中（与 Init/Ready 的启动宽限期相同）：模型加载期间心跳与 HTTP 检查可能超时，
+                    // 不记录失败、不降级或重启；预热结束或超过 WARMUP_TIMEOUT 后恢复正常检查。
+                    // 刷新心跳，避免预热结束后立即因预热期间的旧心跳判定超时
+                    if worker_guard.warmup_in_progress() {
+                        crate::utils::lock_or_recover(
+                            worker_guard.metrics.as_ref(),
+                            "GatewayWorker.metrics",
+                        )
+                        .last_heartbeat = Some(Instant::now());
+                        continue;
+                    }
+
                     if !heartbeat_ok {
                         let (failures, _timeouts) = worker_guard.record_failure(false);
                         let degrade_at = worker_guard.restart_policy.degrade_threshold;
@@ -2024,7 +4316,12 @@ impl GatewayPool {
 
                     let current_state = worker_guard.status();
                     let health_url = worker_guard.health_url();
+                    let models_url = format!("{}/v1/models", worker_guard.api_url());
+                    let models_due = worker_guard.models_refresh_due();
+                    let gateway_version = worker_guard.gateway_version.clone();
+                    let accepts_gzip = Arc::clone(&worker_guard.accepts_gzip);
                     let started_at = worker_guard.started_at;
+                    let startup_timeout = worker_guard.startup_timeout;
                     let last_heartbeat = crate::utils::lock_or_recover(
                         worker_guard.metrics.as_ref(),
                         "GatewayWorker.metrics",
@@ -2034,10 +4331,10 @@ impl GatewayPool {
 
                     if matches!(current_state, WorkerState::Init | WorkerState::Ready) {
                         if let Some(started_at) = started_at {
-                            if started_at.elapsed() < Duration::from_secs(30) {
+                            if started_at.elapsed() < config.startup_grace(startup_timeout) {
                                 continue;
                             }
-                            if started_at.elapsed() > Duration::from_secs(90)
+                            if started_at.elapsed() > config.startup_deadline(startup_timeout)
                                 && last_heartbeat.is_none()
                             {
                                 let worker_guard =
@@ -2061,11 +4358,58 @@ impl GatewayPool {
                         }
                     }
 
-                    let (health_ok, is_timeout) = match client.get(&health_url).send() {
-                        Ok(resp) => (resp.status().is_success(), false),
+                    let (health_ok, is_timeout) = match client
+                        .get(&health_url)
+                        .timeout(config.http_timeout())
+                        .send()
+                    {
+                        Ok(resp) => {
+                            let ok = resp.status().is_success();
+                            if ok {
+                                // 同时记录 Worker 是否声明支持 gzip 请求体
+                                let gzip = resp
+                                    .json::<serde_json::Value>()
+                                    .is_ok_and(|data| compression::health_accepts_gzip(&data));
+                                accepts_gzip.store(gzip, Ordering::Relaxed);
+                            }
+                            (ok, false)
+                        }
                         Err(e) => (false, e.is_timeout()),
                     };
 
+                    // 健康时刷新能力信息并写入磁盘缓存（不持有 Worker 锁，避免阻塞调度）
+                    let refreshed_capability = if health_ok && models_due {
+                        let fetched = client
+                            .get(&models_url)
+                            .timeout(config.http_timeout())
+                            .send()
+                            .map_err(|e| format!("请求失败: {}", e))
+                            .and_then(|resp| {
+                                if resp.status().is_success() {
+                                    resp.json::<serde_json::Value>()
+                                        .map_err(|e| format!("解析 JSON 失败: {}", e))
+                                } else {
+                                    Err(format!("HTTP 状态码: {}", resp.status()))
+                                }
+                            });
+                        match fetched {
+                            Ok(data) => {
+                                let capability =
+                                    capability_from_models(&data, gateway_version.as_deref());
+                                if let Some(version) = gateway_version.as_deref() {
+                                    capability_cache::save_capability(version, &capability);
+                                }
+                                Some(capability)
+                            }
+                            Err(e) => {
+                                log::debug!("[Gateway Pool] 获取 Worker 模型列表失败: {}", e);
+                                None
+                            }
+                        }
+                    } else {
+                        None
+                    };
+
                     let mut worker_guard =
                         crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
                     if matches!(
@@ -2074,6 +4418,7 @@ impl GatewayPool {
                             | WorkerState::FailedPermanent
                             | WorkerState::Disabled
                             | WorkerState::Restarting
+                            | WorkerState::Draining
                     ) {
                         continue;
                     }
@@ -2096,8 +4441,19 @@ impl GatewayPool {
                             metrics.last_heartbeat = Some(Instant::now());
                         }
                         worker_guard.record_success();
-                        worker_guard.half_open_testing = false;
                         worker_guard.circuit_breaker.reset();
+                        if let Some(capability) = refreshed_capability {
+                            log::debug!(
+                                "[Gateway Pool] Worker-{} 能力信息已更新: {:?}",
+                                worker_guard.id,
+                                capability
+                            );
+                            *crate::utils::lock_or_recover(
+                                worker_guard.capability.as_ref(),
+                                "GatewayWorker.capability",
+                            ) = capability;
+                            worker_guard.models_refreshed_at = Some(Instant::now());
+                        }
                     } else {
                         let (failures, _timeouts) = worker_guard.record_failure(is_timeout);
                         let degrade_at = worker_guard.restart_policy.degrade_threshold;
@@ -2125,8 +4481,11 @@ impl GatewayPool {
                         }
                     }
                 }
+
+                Self::handle_all_down(&workers, &all_down, &all_down_policy);
             }
         });
+        crate::ai_service::register_background_thread("gateway-health-check", handle);
     }
 
     /// 获取模型列表（带缓存和限频，避免频繁请求导致阻塞）
@@ -2146,7 +4505,49 @@ impl GatewayPool {
             }
         }
 
-        // 3. 选择一个健康的 Worker（跳过 Worker-0 如果它处于 Unhealthy 状态）
+        self.fetch_models()
+    }
+
+    /// 跳过缓存与限频，立即从 Worker 重新获取模型列表（修改模型配置后使用）；
+    /// 2 秒内重复刷新时直接返回刚获取的列表，避免连点时反复请求。
+    /// 同时让各 Worker 在下一轮健康检查时刷新能力信息（按模型路由使用）
+    pub fn refresh_models(&self) -> Result<Vec<String>, String> {
+        if let Some(models) =
+            crate::utils::lock_or_recover(self.model_cache.as_ref(), "GatewayPool.model_cache")
+                .just_fetched()
+        {
+            return Ok(models);
+        }
+        let models = self.fetch_models()?;
+        for worker in &self.workers {
+            crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker").models_refreshed_at =
+                None;
+        }
+        log::info!("[Gateway Pool] 已刷新模型列表: {:?}", models);
+        Ok(models)
+    }
+
+    /// 设置模型列表缓存有效期与限频（对之后的请求生效，不清空已缓存的列表）
+    pub fn set_model_cache_config(&self, config: &ModelCacheConfig) {
+        let mut cache_guard =
+            crate::utils::lock_or_recover(self.model_cache.as_ref(), "GatewayPool.model_cache");
+        cache_guard.cache_ttl = config.ttl();
+        cache_guard.min_request_interval = config.min_interval();
+    }
+
+    /// 当前模型列表缓存配置
+    pub fn model_cache_config(&self) -> ModelCacheConfig {
+        let cache_guard =
+            crate::utils::lock_or_recover(self.model_cache.as_ref(), "GatewayPool.model_cache");
+        ModelCacheConfig {
+            ttl_secs: cache_guard.cache_ttl.as_secs(),
+            min_interval_secs: cache_guard.min_request_interval.as_secs(),
+        }
+    }
+
+    /// 从健康的 Worker 获取模型列表并更新缓存
+    fn fetch_models(&self) -> Result<Vec<String>, String> {
+        // 1. 选择一个健康的 Worker（跳过 Worker-0 如果它处于 Unhealthy 状态）
         // 注意：由于 self 是不可变的，我们需要通过其他方式选择 Worker
         // 这里我们遍历所有 Worker，找到第一个健康的
         let worker = self
@@ -2168,18 +4569,18 @@ impl GatewayPool {
             })
             .ok_or("没有可用的 Worker")?;
 
-        let (_worker_id, port) = {
+        let (_worker_id, api_url) = {
             let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
-            (wg.id, wg.port)
+            (wg.id, wg.api_url())
         };
 
-        // 4. 请求模型列表（使用短超时，避免阻塞）
+        // 2. 请求模型列表（使用短超时，避免阻塞）
         let client = Client::builder()
             .timeout(Duration::from_secs(5))
             .build()
             .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
 
-        let url = format!("http://127.0.0.1:{}/v1/models", port);
+        let url = format!("{}/v1/models", api_url);
         let response = client
             .get(&url)
             .send()
@@ -2193,21 +4594,9 @@ impl GatewayPool {
             .json()
             .map_err(|e| format!("解析 JSON 失败: {}", e))?;
 
-        let models: Vec<String> =
-            if let Some(data_array) = data.get("data").and_then(|d| d.as_array()) {
-                data_array
-                    .iter()
-                    .filter_map(|item| {
-                        item.get("id")
-                            .and_then(|id| id.as_str())
-                            .map(|s| s.to_string())
-                    })
-                    .collect()
-            } else {
-                vec![]
-            };
+        let models = parse_model_ids(&data);
 
-        // 5. 更新缓存
+        // 3. 更新缓存
         {
             let mut cache_guard =
                 crate::utils::lock_or_recover(self.model_cache.as_ref(), "GatewayPool.model_cache");
@@ -2217,18 +4606,165 @@ impl GatewayPool {
         Ok(models)
     }
 
-    /// 转发 HTTP 请求到可用的 Worker（带超时和重试）
+    /// 提取请求体中指定的模型（仅 /v1/ 下除 /v1/models 以外的非 GET 请求）
+    pub(crate) fn requested_model(method: &str, path: &str, body: Option<&[u8]>) -> Option<String> {
+        if method == "GET" || !path.starts_with("/v1/") || path == "/v1/models" {
+            return None;
+        }
+        let json_value = serde_json::from_slice::<serde_json::Value>(body?).ok()?;
+        json_value
+            .get("model")
+            .and_then(|m| m.as_str())
+            .map(|s| s.to_string())
+    }
+
+    /// 检查是否有 Worker 提供指定模型：确定没有时返回可用模型列表，可用或无法判断时返回 None
+    /// 优先使用健康检查获取的各 Worker 模型列表；存在模型列表未知的 Worker 时参考 /v1/models 缓存
+    pub(crate) fn unavailable_model_alternatives(&self, model: &str) -> Option<Vec<String>> {
+        let mut known: Vec<String> = Vec::new();
+        let mut any_unknown = false;
+        for worker in &self.workers {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            if wg.isolated
+                || matches!(
+                    wg.status(),
+                    WorkerState::Disabled | WorkerState::FailedPermanent
+                )
+            {
+                continue;
+            }
+            let capability =
+                crate::utils::lock_or_recover(wg.capability.as_ref(), "GatewayWorker.capability");
+            if capability.supported_models.is_empty() {
+                any_unknown = true;
+            } else if capability.supported_models.iter().any(|m| m == model) {
+                return None;
+            } else {
+                known.extend(capability.supported_models.iter().cloned());
+            }
+        }
+
+        if any_unknown {
+            let cached = self.get_models_cached().unwrap_or_default();
+            if cached.is_empty() || cached.iter().any(|m| m == model) {
+                return None;
+            }
+            known.extend(cached);
+        } else if known.is_empty() {
+            return None;
+        }
+
+        known.sort();
+        known.dedup();
+        Some(known)
+    }
+
+    /// 构建排队超时的 429 错误响应体（retry_after_secs 为建议的重试间隔）
+    fn queue_timeout_body(max_wait: Duration) -> Vec<u8> {
+        let retry_after_secs = max_wait.as_secs_f64().ceil().max(1.0) as u64;
+        let body = serde_json::json!({
+            "error": {
+                "message": format!(
+                    "所有 Worker 均繁忙，排队 {} ms 后仍无可用 Worker，请稍后重试",
+                    max_wait.as_millis()
+                ),
+                "type": "rate_limit_error",
+                "code": "all_workers_busy",
+                "retry_after_secs": retry_after_secs,
+            }
+        });
+        serde_json::to_vec(&body).unwrap_or_default()
+    }
+
+    /// 构建模型不存在时的 OpenAI 风格 404 错误响应体
+    fn model_not_found_body(model: &str, available: &[String]) -> Vec<u8> {
+        let body = serde_json::json!({
+            "error": {
+                "message": format!(
+                    "模型 {} 不存在或没有 Worker 提供，可用模型: {}",
+                    model,
+                    available.join(", ")
+                ),
+                "type": "invalid_request_error",
+                "code": "model_not_found",
+                "available_models": available,
+            }
+        });
+        serde_json::to_vec(&body).unwrap_or_default()
+    }
+
+    /// 解析 HTTP 方法（不区分大小写，支持 PATCH / OPTIONS 等任意合法方法名）
+    pub(crate) fn parse_method(method: &str) -> Result<reqwest::Method, String> {
+        let normalized = method.trim().to_ascii_uppercase();
+        if normalized.is_empty() {
+            return Err("HTTP 方法不能为空".to_string());
+        }
+        reqwest::Method::from_bytes(normalized.as_bytes())
+            .map_err(|_| format!("无效的 HTTP 方法: {:?}", method))
+    }
+
+    /// 按 HTTP 方法构建请求
+    fn build_request(
+        client: &Client,
+        method: &str,
+        url: &str,
+    ) -> Result<reqwest::blocking::RequestBuilder, String> {
+        Ok(client.request(Self::parse_method(method)?, url))
+    }
+
+    /// 开始带幂等键的请求：返回 TTL 内已完成的响应，或标记为处理中
+    pub fn begin_idempotent_request(
+        &self,
+        key: &str,
+        fingerprint: &RequestFingerprint,
+    ) -> IdempotencyLookup {
+        crate::utils::lock_or_recover(self.idempotency.as_ref(), "GatewayPool.idempotency")
+            .begin(key, fingerprint)
+    }
+
+    /// 结束带幂等键的请求并记录响应（仅缓存 2xx 响应；response 为 None 表示请求失败）
+    pub fn finish_idempotent_request(
+        &self,
+        key: &str,
+        fingerprint: &RequestFingerprint,
+        response: Option<(u16, Option<&str>, &[u8])>,
+    ) {
+        crate::utils::lock_or_recover(self.idempotency.as_ref(), "GatewayPool.idempotency").finish(
+            key,
+            fingerprint,
+            response,
+        );
+    }
+
+    /// 转发 HTTP 请求到可用的 Worker（带超时和重试，重试次数与退避间隔见 RequestPolicy）
     /// 特殊处理：对于 /v1/models 请求，使用缓存和限频，避免阻塞
+    /// 请求体中的 model 用于选择提供该模型的 Worker，请求体原样转发
+    /// client_id 用于粘性会话：同一客户端优先路由到同一 Worker（多轮对话复用 KV 缓存）
+    /// queue_wait 为空时没有可用 Worker 会按固定间隔重试后报错；
+    /// 指定时改为排队等待可用 Worker（等待期间不持有连接池锁），超时返回 429 及建议的重试间隔
+    /// compress 为 true 时，请求体达到 GZIP_MIN_BODY_BYTES 且 Worker 声明支持时以 gzip 发送；
+    /// gzip / deflate 编码的响应体总是解压后返回
+    /// trace_id 为本次请求的追踪 ID：写入日志、处理期间记录在 Worker 上，
+    /// 调用方未设置 X-Trace-Id 请求头时附加到转发的请求中
+    /// 返回状态码、上游 Content-Type（连接池自行生成的响应为 application/json）与响应体
+    #[allow(clippy::too_many_arguments)]
     pub fn forward_request(
-        &mut self,
+        pool: &Mutex<Self>,
         method: &str,
         path: &str,
         body: Option<&[u8]>,
         headers: Option<&[(&str, &str)]>,
-    ) -> Result<(StatusCode, Vec<u8>), String> {
+        client_id: Option<&str>,
+        queue_wait: Option<Duration>,
+        compress: bool,
+        trace_id: &str,
+    ) -> Result<(StatusCode, Option<String>, Vec<u8>), GatewayError> {
+        // 只在准备阶段与选择 Worker 时持有连接池锁，上游请求、重试退避与排队期间不持有，
+        // 慢速的本地模型补全不会阻塞其他连接池操作（也不会触发死锁监视告警）
+        let guard = crate::utils::lock_or_recover(pool, "GatewayPool");
         // 特殊处理：/v1/models 请求使用缓存和限频
         if method == "GET" && path == "/v1/models" {
-            match self.get_models_cached() {
+            match guard.get_models_cached() {
                 Ok(models) => {
                     // 构建 OpenAI 格式的响应
                     let response_data = serde_json::json!({
@@ -2244,7 +4780,11 @@ impl GatewayPool {
                     });
                     let body_bytes = serde_json::to_vec(&response_data)
                         .map_err(|e| format!("序列化 JSON 失败: {}", e))?;
-                    return Ok((StatusCode::OK, body_bytes));
+                    return Ok((
+                        StatusCode::OK,
+                        Some(JSON_CONTENT_TYPE.to_string()),
+                        body_bytes,
+                    ));
                 }
                 Err(e) => {
                     // 如果缓存获取失败，降级到直接转发（但跳过 Worker-0）
@@ -2253,45 +4793,47 @@ impl GatewayPool {
             }
         }
 
-        let effective_body: Option<Vec<u8>> = match body {
-            Some(body_data)
-                if method != "GET" && path.starts_with("/v1/") && path != "/v1/models" =>
-            {
-                let mut replaced = None;
-                if let Ok(mut json_value) = serde_json::from_slice::<serde_json::Value>(body_data) {
-                    let requested_model = json_value
-                        .get("model")
-                        .and_then(|m| m.as_str())
-                        .map(|s| s.to_string());
-
-                    if let Some(requested_model) = requested_model {
-                        if requested_model != "deepseek-chat" {
-                            if let Ok(models) = self.get_models_cached() {
-                                if !models.is_empty()
-                                    && models.iter().any(|m| m == "deepseek-chat")
-                                    && !models.iter().any(|m| m == &requested_model)
-                                {
-                                    if let Some(obj) = json_value.as_object_mut() {
-                                        obj.insert(
-                                            "model".to_string(),
-                                            serde_json::Value::String("deepseek-chat".to_string()),
-                                        );
-                                        if let Ok(bytes) = serde_json::to_vec(&json_value) {
-                                            replaced = Some(bytes);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-                replaced.or_else(|| Some(body_data.to_vec()))
-            }
-            Some(body_data) => Some(body_data.to_vec()),
-            None => None,
+        // 请求的模型不可用时按别名表改写为可用模型
+        let mut model = Self::requested_model(method, path, body);
+        let aliased = match (model.as_deref(), body) {
+            (Some(requested), Some(data)) => guard.apply_model_alias(data, requested),
+            _ => None,
         };
+        let aliased_body = aliased.map(|(target, rewritten)| {
+            model = Some(target);
+            rewritten
+        });
+        let body = aliased_body.as_deref().or(body);
+
+        // 按模型路由：没有 Worker 提供请求的模型时直接返回 404，而不是转发到其他模型
+        if let Some(model) = model.as_deref() {
+            if let Some(available) = guard.unavailable_model_alternatives(model) {
+                log::warn!(
+                    "[Gateway Pool] 请求的模型 {} 不可用，可用模型: {:?}",
+                    model,
+                    available
+                );
+                return Ok((
+                    StatusCode::NOT_FOUND,
+                    Some(JSON_CONTENT_TYPE.to_string()),
+                    Self::model_not_found_body(model, &available),
+                ));
+            }
+        }
 
-        let max_retries = 3;
+        let policy = guard.request_policy.clone();
+        let request_stats = Arc::clone(&guard.request_stats);
+        drop(guard);
+        let max_attempts = policy.max_attempts();
+        // 第 n 次重试前的等待时间（指数退避 + 随机抖动）
+        let retry_delay = |attempt: u32| {
+            backoff_with_jitter(
+                policy.base_delay(),
+                policy.max_delay(),
+                attempt + 1,
+                policy.jitter_ms,
+            )
+        };
         // 对于 /v1/models 请求，使用更短的超时时间（10秒），避免 Worker-0 阻塞
         let timeout = if path == "/v1/models" {
             Duration::from_secs(10)
@@ -2299,9 +4841,21 @@ impl GatewayPool {
             Duration::from_secs(60) // 其他请求使用 60 秒超时
         };
 
-        for attempt in 0..max_retries {
+        // 压缩后的请求体（首次发往支持 gzip 的 Worker 时生成，重试时复用）
+        let compress =
+            compress && body.is_some_and(|b| b.len() >= compression::GZIP_MIN_BODY_BYTES);
+        let mut gzipped_body: Option<Vec<u8>> = None;
+
+        for attempt in 0..max_attempts {
             // 选择可用的 Worker（对于 /v1/models 请求，明确跳过 Worker-0 如果它处于 Unhealthy 状态）
-            let worker = match self.select_worker(None) {
+            let selected = match queue_wait {
+                Some(max_wait) => {
+                    Self::select_worker_with_queue(pool, client_id, model.as_deref(), max_wait)
+                }
+                None => crate::utils::lock_or_recover(pool, "GatewayPool")
+                    .select_worker(client_id, model.as_deref()),
+            };
+            let worker = match selected {
                 Some(w) => {
                     let wg = crate::utils::lock_or_recover(w.as_ref(), "GatewayWorker");
                     if path == "/v1/models"
@@ -2312,13 +4866,11 @@ impl GatewayPool {
                         log::warn!("[Gateway Pool] /v1/models 请求跳过 Worker-0（Unhealthy 状态），尝试其他 Worker");
                         drop(wg);
                         // 继续循环，尝试选择其他 Worker
-                        if attempt < max_retries - 1 {
-                            thread::sleep(Duration::from_millis(500));
+                        if attempt + 1 < max_attempts {
+                            thread::sleep(retry_delay(attempt));
                             continue;
                         } else {
-                            return Err(
-                                "Worker-0 处于 Unhealthy 状态，且没有其他可用的 Worker".to_string()
-                            );
+                            return Err(GatewayError::NoHealthyWorker);
                         }
                     } else {
                         drop(wg);
@@ -2326,26 +4878,45 @@ impl GatewayPool {
                     }
                 }
                 None => {
-                    if attempt < max_retries - 1 {
+                    if Self::all_workers_down(
+                        &crate::utils::lock_or_recover(pool, "GatewayPool").workers,
+                    ) {
+                        return Err(GatewayError::AllWorkersDown);
+                    }
+                    if let Some(max_wait) = queue_wait {
+                        log::warn!(
+                            "[Gateway Pool] [trace {}] 排队 {} ms 后仍没有可用的 Worker，返回 429",
+                            trace_id,
+                            max_wait.as_millis()
+                        );
+                        return Ok((
+                            StatusCode::TOO_MANY_REQUESTS,
+                            Some(JSON_CONTENT_TYPE.to_string()),
+                            Self::queue_timeout_body(max_wait),
+                        ));
+                    }
+                    if attempt + 1 < max_attempts {
                         log::warn!(
-                            "[Gateway Pool] 没有可用的 Worker，等待后重试 ({}/{})",
+                            "[Gateway Pool] [trace {}] 没有可用的 Worker，等待后重试 ({}/{})",
+                            trace_id,
                             attempt + 1,
-                            max_retries
+                            max_attempts
                         );
-                        thread::sleep(Duration::from_millis(1000));
+                        thread::sleep(retry_delay(attempt));
                         continue;
                     }
-                    return Err("没有可用的 Gateway Worker".to_string());
+                    return Err(GatewayError::NoHealthyWorker);
                 }
             };
 
-            let (worker_id, api_url) = {
+            let (worker_id, api_url, accepts_gzip) = {
                 let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
-                (wg.id, wg.api_url())
+                (wg.id, wg.api_url(), wg.accepts_gzip.load(Ordering::Relaxed))
             };
 
             log::debug!(
-                "[Gateway Pool] 转发请求到 Worker-{}: {} {}",
+                "[Gateway Pool] [trace {}] 转发请求到 Worker-{}: {} {}",
+                trace_id,
                 worker_id,
                 method,
                 path
@@ -2353,13 +4924,14 @@ impl GatewayPool {
 
             // 标记 Worker 为忙碌（使用 BusyStreaming 状态）
             {
-                let mut wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+                let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
                 wg.set_state(WorkerState::BusyStreaming);
                 let mut metrics =
                     crate::utils::lock_or_recover(wg.metrics.as_ref(), "GatewayWorker.metrics");
                 metrics.active_requests += 1;
                 drop(metrics);
-                wg.total_requests += 1;
+                wg.counters.total_requests.fetch_add(1, Ordering::Relaxed);
+                wg.set_trace_id(Some(trace_id.to_string()));
             }
 
             // 构建请求
@@ -2369,13 +4941,7 @@ impl GatewayPool {
                 .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
 
             let url = format!("{}{}", api_url, path);
-            let mut request_builder = match method {
-                "GET" => client.get(&url),
-                "POST" => client.post(&url),
-                "PUT" => client.put(&url),
-                "DELETE" => client.delete(&url),
-                _ => return Err(format!("不支持的 HTTP 方法: {}", method)),
-            };
+            let mut request_builder = Self::build_request(&client, method, &url)?;
 
             // 添加请求头
             if let Some(headers_list) = headers {
@@ -2383,10 +4949,35 @@ impl GatewayPool {
                     request_builder = request_builder.header(*key, *value);
                 }
             }
+            if !headers.is_some_and(|list| {
+                list.iter()
+                    .any(|(key, _)| key.eq_ignore_ascii_case(TRACE_ID_HEADER))
+            }) {
+                request_builder = request_builder.header(TRACE_ID_HEADER, trace_id);
+            }
 
-            // 添加请求体
-            if let Some(body_data) = &effective_body {
-                request_builder = request_builder.body(body_data.clone());
+            // 添加请求体（按需 gzip 压缩，压缩失败时发送原始请求体）
+            if let Some(body_data) = body {
+                if compress && accepts_gzip && gzipped_body.is_none() {
+                    match compression::gzip_body(body_data) {
+                        Ok(gzipped) => gzipped_body = Some(gzipped),
+                        Err(e) => log::warn!("[Gateway Pool] {}，发送未压缩的请求体", e),
+                    }
+                }
+                match gzipped_body.as_ref().filter(|_| compress && accepts_gzip) {
+                    Some(gzipped) => {
+                        request_builder = request_builder
+                            .header(reqwest::header::CONTENT_ENCODING, "gzip")
+                            .body(gzipped.clone());
+                    }
+                    None => request_builder = request_builder.body(body_data.to_vec()),
+                }
+            }
+            if compress {
+                request_builder = request_builder.header(
+                    reqwest::header::ACCEPT_ENCODING,
+                    compression::ACCEPT_ENCODING,
+                );
             }
 
             let start_time = Instant::now();
@@ -2396,28 +4987,61 @@ impl GatewayPool {
             // 先处理响应，再更新状态（避免长时间持有锁）
 
             // 先处理响应，再更新状态（避免长时间持有锁导致任务堆积）
+            // 上游已开始返回响应（读取响应体时失败说明可能已生成部分内容）
+            let mut response_started = false;
             let (response_result, elapsed) = match result {
                 Ok(response) => {
+                    response_started = true;
                     let status = response.status();
-                    let body_bytes = response
-                        .bytes()
-                        .map_err(|e| format!("读取响应体失败: {}", e))?
-                        .to_vec();
-                    let elapsed = start_time.elapsed();
-                    (Ok((status, body_bytes)), elapsed)
+                    let header = |name: reqwest::header::HeaderName| {
+                        response
+                            .headers()
+                            .get(name)
+                            .and_then(|v| v.to_str().ok())
+                            .map(str::to_string)
+                    };
+                    let encoding = header(reqwest::header::CONTENT_ENCODING);
+                    let content_type = header(reqwest::header::CONTENT_TYPE);
+                    // 响应体过大或无法解压同样计为失败的尝试
+                    let body_result = compression::read_body(response)
+                        .and_then(|bytes| compression::decode_body(encoding.as_deref(), bytes))
+                        .map(|bytes| (status, content_type, bytes))
+                        .map_err(GatewayError::UpstreamFailed);
+                    (body_result, start_time.elapsed())
                 }
-                Err(e) => {
-                    let elapsed = start_time.elapsed();
-                    (Err(e), elapsed)
+                Err(e) => (Err(GatewayError::upstream(&e)), start_time.elapsed()),
+            };
+
+            // 对话补全响应中的 token 用量（在加锁前解析）
+            let usage = match &response_result {
+                Ok((status, _, body_bytes))
+                    if status.is_success() && TokenUsage::applies_to(method, path) =>
+                {
+                    TokenUsage::from_body(body_bytes)
                 }
+                _ => None,
             };
 
+            // 窗口统计：上游 5xx 同样计为错误
+            crate::utils::lock_or_recover(request_stats.as_ref(), "GatewayPool.request_stats")
+                .record(
+                    worker_id,
+                    matches!(&response_result, Ok((status, _, _)) if !status.is_server_error()),
+                );
+
             // 恢复 Worker 状态（快速更新，避免阻塞）
             {
                 let mut wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+                wg.clear_trace_id(trace_id);
+                // 排空 / 重启中的 Worker 不恢复为可接单状态，由排空流程接管
+                let winding_down =
+                    matches!(wg.status(), WorkerState::Draining | WorkerState::Restarting);
                 let mut metrics =
                     crate::utils::lock_or_recover(wg.metrics.as_ref(), "GatewayWorker.metrics");
                 metrics.active_requests = metrics.active_requests.saturating_sub(1);
+                notify_worker_released();
+                // 失败请求同样计入延迟分布（超时等尾延迟正是关注点）
+                metrics.latency_window.record(elapsed.as_millis() as u64);
 
                 let _consecutive_failures_before = metrics.consecutive_failures;
 
@@ -2427,9 +5051,14 @@ impl GatewayPool {
                         metrics.consecutive_failures = 0;
                         metrics.consecutive_timeouts = 0;
                         metrics.last_timeout_at = None;
+                        if let Some(usage) = usage {
+                            metrics.record_usage(usage);
+                        }
                         drop(metrics);
                         wg.record_success();
-                        wg.set_state(WorkerState::Idle);
+                        if !winding_down {
+                            wg.set_state(WorkerState::Idle);
+                        }
                     }
                     Err(_) => {
                         // 请求失败
@@ -2438,7 +5067,7 @@ impl GatewayPool {
                         drop(metrics);
                         let (_failures, timeouts) = wg.record_failure(elapsed >= timeout);
 
-                        if consecutive_failures >= 5 {
+                        if consecutive_failures >= 5 && !winding_down {
                             wg.set_state(WorkerState::Degraded);
                         }
                         if timeouts >= 5 {
@@ -2451,176 +5080,738 @@ impl GatewayPool {
             }
 
             match response_result {
-                Ok((status, body_bytes)) => {
+                Ok((status, content_type, body_bytes)) => {
                     log::debug!(
-                        "[Gateway Pool] Worker-{} 响应时间: {:?}, 状态码: {}",
+                        "[Gateway Pool] [trace {}] Worker-{} 响应时间: {:?}, 状态码: {}",
+                        trace_id,
                         worker_id,
                         elapsed,
                         status
                     );
-                    return Ok((status, body_bytes));
+                    return Ok((status, content_type, body_bytes));
                 }
                 Err(e) => {
                     log::warn!(
-                        "[Gateway Pool] Worker-{} 请求失败 (耗时: {:?}): {}",
+                        "[Gateway Pool] [trace {}] Worker-{} 请求失败 (耗时: {:?}): {}",
+                        trace_id,
                         worker_id,
                         elapsed,
                         e
                     );
 
                     // 记录失败（已在上面处理，这里只需要更新 total_errors）
-                    {
-                        let mut wg =
-                            crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
-                        wg.total_errors += 1;
-                    }
+                    crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker")
+                        .counters
+                        .total_errors
+                        .fetch_add(1, Ordering::Relaxed);
 
                     // 如果是最后一次尝试，返回错误
-                    if attempt >= max_retries - 1 {
-                        return Err(format!("请求失败: {}", e));
+                    if attempt + 1 >= max_attempts {
+                        return Err(e);
+                    }
+                    // 非 GET 请求在上游已开始返回后不再重试，避免上游重复执行（重复生成、重复调用工具等）
+                    if response_started && !method.eq_ignore_ascii_case("GET") {
+                        log::warn!(
+                            "[Gateway Pool] [trace {}] Worker-{} 已开始返回响应，{} 请求不重试",
+                            trace_id,
+                            worker_id,
+                            method
+                        );
+                        return Err(e);
                     }
 
-                    // 等待后重试
-                    thread::sleep(Duration::from_millis(500));
+                    // 等待后重试（指数退避）
+                    thread::sleep(retry_delay(attempt));
                 }
             }
         }
 
-        Err("所有重试都失败了".to_string())
+        Err(GatewayError::AllRetriesFailed)
     }
 
-    /// 获取 Gateway 状态（调度核心）
-    pub fn get_gateway_state(&self) -> GatewayState {
-        let mut idle_count = 0;
-        let mut busy_count = 0;
-        let mut available_count = 0;
+    /// 为流式转发选择 Worker 并标记为 BusyStreaming
+    /// 调用方随后在不持有连接池锁的情况下调用 stream_request，避免长时间流式输出阻塞调度
+    /// 指定 model 时只选择提供该模型的 Worker，没有 Worker 提供时返回可用模型列表
+    pub fn acquire_stream_worker(
+        &mut self,
+        client_id: Option<&str>,
+        model: Option<&str>,
+    ) -> Result<Arc<Mutex<GatewayWorker>>, GatewayError> {
+        if let Some(model) = model {
+            if let Some(available) = self.unavailable_model_alternatives(model) {
+                return Err(GatewayError::ModelNotFound {
+                    model: model.to_string(),
+                    available,
+                });
+            }
+        }
+        let Some(worker) = self.select_worker(client_id, model) else {
+            if Self::all_workers_down(&self.workers) {
+                return Err(GatewayError::AllWorkersDown);
+            }
+            return Err(GatewayError::NoHealthyWorker);
+        };
 
-        for worker in &self.workers {
+        {
             let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
-            let state = wg.status();
+            wg.set_state(WorkerState::BusyStreaming);
+            let mut metrics =
+                crate::utils::lock_or_recover(wg.metrics.as_ref(), "GatewayWorker.metrics");
+            metrics.active_requests += 1;
+            metrics.last_token_at = Some(Instant::now());
+            drop(metrics);
+            wg.counters.total_requests.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(worker)
+    }
 
-            match state {
-                WorkerState::Idle => {
-                    idle_count += 1;
-                    available_count += 1;
+    /// 流式转发请求到已占用的 Worker（需先调用 acquire_stream_worker）
+    /// 每收到一块上游数据调用 on_chunk 并刷新 last_token_at（供 check_blocked 检测卡死）；
+    /// on_chunk 返回 false 时中止读取。结束后释放 Worker、计入窗口统计并返回上游状态码
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn stream_request(
+        worker: &Arc<Mutex<GatewayWorker>>,
+        request_stats: &Mutex<RequestStats>,
+        method: &str,
+        path: &str,
+        body: Option<Vec<u8>>,
+        headers: Option<&[(&str, &str)]>,
+        trace_id: &str,
+        mut on_chunk: impl FnMut(&[u8]) -> bool,
+    ) -> Result<StatusCode, String> {
+        let (worker_id, api_url) = {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            wg.set_trace_id(Some(trace_id.to_string()));
+            (wg.id, wg.api_url())
+        };
+        let start_time = Instant::now();
+        // 阻塞客户端的超时作用于每次读取，即两个数据块之间的最长间隔
+        let timeout = Duration::from_secs(60);
+        // 对话补全保留响应流尾部，结束后解析最后的 usage 数据块
+        let track_usage = TokenUsage::applies_to(method, path);
+        let mut tail: Vec<u8> = Vec::new();
+
+        let result = (|| -> Result<StatusCode, (String, bool)> {
+            let client = Client::builder()
+                .timeout(timeout)
+                .build()
+                .map_err(|e| (format!("创建 HTTP 客户端失败: {}", e), false))?;
+            let url = format!("{}{}", api_url, path);
+            let mut request_builder =
+                Self::build_request(&client, method, &url).map_err(|e| (e, false))?;
+            if let Some(headers_list) = headers {
+                for (key, value) in headers_list {
+                    request_builder = request_builder.header(*key, *value);
                 }
-                WorkerState::BusyStreaming | WorkerState::BusyBlocked => {
-                    busy_count += 1;
+            }
+            if !headers.is_some_and(|list| {
+                list.iter()
+                    .any(|(key, _)| key.eq_ignore_ascii_case(TRACE_ID_HEADER))
+            }) {
+                request_builder = request_builder.header(TRACE_ID_HEADER, trace_id);
+            }
+            if let Some(body_data) = body {
+                request_builder = request_builder.body(body_data);
+            }
+
+            let mut response = request_builder
+                .send()
+                .map_err(|e| (format!("请求失败: {}", e), e.is_timeout()))?;
+            let status = response.status();
+
+            let mut buf = [0u8; 8192];
+            loop {
+                let n = std::io::Read::read(&mut response, &mut buf).map_err(|e| {
+                    let is_timeout = e.kind() == std::io::ErrorKind::TimedOut;
+                    (format!("读取响应流失败: {}", e), is_timeout)
+                })?;
+                if n == 0 {
+                    break;
                 }
-                WorkerState::Degraded
-                    if wg.circuit_breaker.state() != CircuitBreakerState::Open =>
                 {
-                    available_count += 1;
+                    let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+                    crate::utils::lock_or_recover(wg.metrics.as_ref(), "GatewayWorker.metrics")
+                        .last_token_at = Some(Instant::now());
                 }
-                _ => {}
+                if track_usage {
+                    tail.extend_from_slice(&buf[..n]);
+                    let excess = tail.len().saturating_sub(STREAM_USAGE_TAIL_BYTES);
+                    tail.drain(..excess);
+                }
+                if !on_chunk(&buf[..n]) {
+                    log::info!("[Gateway Pool] Worker-{} 流式转发被调用方中止", worker_id);
+                    break;
+                }
+            }
+            Ok(status)
+        })();
+
+        let elapsed = start_time.elapsed();
+        let usage = match &result {
+            Ok(status) if status.is_success() && track_usage => TokenUsage::from_sse_tail(&tail),
+            _ => None,
+        };
+        // 窗口统计：与普通转发一致，上游 5xx 同样计为错误
+        crate::utils::lock_or_recover(request_stats, "GatewayPool.request_stats").record(
+            worker_id,
+            matches!(&result, Ok(status) if !status.is_server_error()),
+        );
+        let mut wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+        wg.clear_trace_id(trace_id);
+        let winding_down = matches!(wg.status(), WorkerState::Draining | WorkerState::Restarting);
+        {
+            let mut metrics =
+                crate::utils::lock_or_recover(wg.metrics.as_ref(), "GatewayWorker.metrics");
+            metrics.active_requests = metrics.active_requests.saturating_sub(1);
+            notify_worker_released();
+            metrics.latency_window.record(elapsed.as_millis() as u64);
+            if let Some(usage) = usage {
+                metrics.record_usage(usage);
             }
         }
 
-        // 状态判定逻辑
-        if idle_count > 0 {
-            GatewayState::Healthy
-        } else if busy_count > 0 {
-            GatewayState::Busy
-        } else if available_count > 0 {
-            GatewayState::Degraded
-        } else {
-            GatewayState::Unavailable
+        match result {
+            Ok(status) => {
+                wg.record_success();
+                if !winding_down {
+                    wg.set_state(WorkerState::Idle);
+                }
+                log::debug!(
+                    "[Gateway Pool] [trace {}] Worker-{} 流式响应完成: {:?}, 状态码: {}",
+                    trace_id,
+                    worker_id,
+                    elapsed,
+                    status
+                );
+                Ok(status)
+            }
+            Err((e, is_timeout)) => {
+                let (failures, timeouts) = wg.record_failure(is_timeout);
+                if failures >= 5 && !winding_down {
+                    wg.set_state(WorkerState::Degraded);
+                } else if !winding_down {
+                    wg.set_state(WorkerState::Idle);
+                }
+                log::warn!(
+                    "[Gateway Pool] [trace {}] Worker-{} 流式转发失败 (耗时: {:?}): {}",
+                    trace_id,
+                    worker_id,
+                    elapsed,
+                    e
+                );
+                if timeouts >= 5 {
+                    let worker_clone = Arc::clone(worker);
+                    drop(wg);
+                    schedule_restart_for_worker(worker_clone, HealthSignal::RequestTimeout);
+                }
+                Err(e)
+            }
         }
     }
 
-    /// 分层健康检查（L0-L3）
-    pub fn health_check_layered(&self, worker_id: usize, level: u8) -> bool {
-        if worker_id >= self.workers.len() {
-            return false;
+    /// 直接请求指定 Worker（绕过调度，用于诊断）
+    /// 默认拒绝向 Dead / Disabled / FailedPermanent 的 Worker 发送请求，force 为 true 时跳过该检查
+    /// 不更新 Worker 指标，不影响熔断器
+    pub fn query_worker_direct(
+        worker: &Arc<Mutex<GatewayWorker>>,
+        method: &str,
+        path: &str,
+        body: Option<&[u8]>,
+        force: bool,
+    ) -> Result<(StatusCode, Vec<u8>), String> {
+        if !path.starts_with('/') {
+            return Err(format!("无效的请求路径: {}", path));
         }
 
-        let worker = &self.workers[worker_id];
-        let mut worker_guard = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+        let (worker_id, api_url) = {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            let state = wg.status();
+            if !force
+                && matches!(
+                    state,
+                    WorkerState::Dead | WorkerState::Disabled | WorkerState::FailedPermanent
+                )
+            {
+                return Err(format!(
+                    "Worker-{} 处于 {:?} 状态，拒绝直接请求（可使用 force 强制发送）",
+                    wg.id, state
+                ));
+            }
+            (wg.id, wg.api_url())
+        };
 
-        // L0: 进程是否存在
-        if level == 0 {
-            if let Some(ref mut child) = worker_guard.process {
-                match child.try_wait() {
-                    Ok(Some(_)) => {
-                        worker_guard.set_state(WorkerState::Dead);
-                        return false;
-                    }
-                    Ok(None) => return true,
-                    Err(_) => return false,
+        log::info!(
+            "[Gateway Pool] 直接请求 Worker-{}: {} {}",
+            worker_id,
+            method,
+            path
+        );
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+
+        let url = format!("{}{}", api_url, path);
+        let mut request_builder = Self::build_request(&client, method, &url)?;
+        if let Some(body_data) = body {
+            request_builder = request_builder
+                .header("Content-Type", "application/json")
+                .body(body_data.to_vec());
+        }
+
+        let response = request_builder
+            .send()
+            .map_err(|e| format!("请求 Worker-{} 失败: {}", worker_id, e))?;
+        let status = response.status();
+        let body_bytes = response
+            .bytes()
+            .map_err(|e| format!("读取响应体失败: {}", e))?
+            .to_vec();
+        Ok((status, body_bytes))
+    }
+
+    /// 获取 Gateway 状态（调度核心），判定规则见 GatewayStateCounts::classify
+    pub fn get_gateway_state(&self) -> GatewayState {
+        let mut counts = GatewayStateCounts::default();
+
+        for worker in &self.workers {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            // 维护模式的 Worker 不接请求，不计入任何可用类别
+            if wg.in_maintenance() {
+                continue;
+            }
+            let breaker_open = wg.circuit_breaker.state() == CircuitBreakerState::Open;
+
+            match wg.status() {
+                WorkerState::Idle => counts.idle += 1,
+                // 熔断打开的忙碌 Worker 完成当前请求后也不会再接新请求，不能排队
+                WorkerState::BusyStreaming | WorkerState::BusyBlocked if !breaker_open => {
+                    counts.busy_queueable += 1
                 }
-            } else {
-                worker_guard.set_state(WorkerState::Dead);
-                return false;
+                WorkerState::Degraded if !breaker_open => counts.degraded_usable += 1,
+                _ => {}
             }
         }
 
-        // L1: TCP 能否连接（简单端口检查）
+        counts.classify()
+    }
+
+    /// 按需分层健康检查：level 为 0-3，检查逐层累加，任一层失败即停止
+    /// L0 进程存在、L1 TCP 端口可连接、L2 /health 返回 200、L3 模型能完成一次最小对话补全；
+    /// 忙碌的 Worker 只做 L0/L1（HTTP 请求会排在推理之后甚至超时，误判为不健康）。
+    /// 只在读取状态时短暂持有 Worker 锁，不更新指标、心跳或状态，状态处理仍由周期健康检查负责
+    pub fn health_check_layered(
+        worker: &Arc<Mutex<GatewayWorker>>,
+        level: u8,
+    ) -> LayeredHealthReport {
+        let start = Instant::now();
+        let (mut report, addr, health_url, busy, target) = {
+            let mut wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            let process_alive = match wg.process {
+                Some(ref mut child) => matches!(child.try_wait(), Ok(None)),
+                None => false,
+            };
+            let report = LayeredHealthReport {
+                worker_id: wg.id,
+                level,
+                passed: false,
+                state: wg.status(),
+                process_alive: Some(process_alive),
+                tcp_ok: None,
+                http_ok: None,
+                model_warm: None,
+                skipped_busy: false,
+                elapsed_ms: 0,
+                detail: None,
+            };
+            (
+                report,
+                SocketAddr::new(worker_connect_host(wg.bind_host), wg.port),
+                wg.health_url(),
+                wg.status().is_busy(),
+                wg.warmup_target(),
+            )
+        };
+        let finish = |mut report: LayeredHealthReport, passed: bool, detail: Option<String>| {
+            report.passed = passed;
+            report.detail = detail;
+            report.elapsed_ms = start.elapsed().as_millis() as u64;
+            report
+        };
+
+        // L0: 进程是否存在
+        if report.process_alive != Some(true) {
+            return finish(report, false, Some("Worker 进程不存在或已退出".to_string()));
+        }
+        if level == 0 {
+            return finish(report, true, None);
+        }
+
+        // L1: TCP 端口能否连接
+        if let Err(e) = std::net::TcpStream::connect_timeout(&addr, LAYERED_TCP_TIMEOUT) {
+            report.tcp_ok = Some(false);
+            return finish(report, false, Some(format!("连接 {} 失败: {}", addr, e)));
+        }
+        report.tcp_ok = Some(true);
         if level == 1 {
-            // 这里可以添加 TCP 连接检查
-            // 暂时跳过，直接进入 L2
+            return finish(report, true, None);
+        }
+
+        // Busy 时只允许 L0/L1，跳过 L2+
+        if busy {
+            report.skipped_busy = true;
+            return finish(report, true, None);
         }
 
-        // L2: /ping 秒回（或 /health）
-        if level >= 2 {
-            // Busy 时只允许 L0/L1，跳过 L2+
-            if worker_guard.status().is_busy() {
-                return true; // Busy 时认为健康，不进行 HTTP 检查
+        // L2: /health 是否返回 200
+        let http_result = Client::builder()
+            .timeout(PROBE_TIMEOUT)
+            .build()
+            .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))
+            .and_then(|client| {
+                client
+                    .get(&health_url)
+                    .send()
+                    .map_err(|e| format!("请求 {} 失败: {}", health_url, e))
+            });
+        match http_result {
+            Ok(response) if response.status() == StatusCode::OK => report.http_ok = Some(true),
+            Ok(response) => {
+                report.http_ok = Some(false);
+                let detail = format!("{} 返回 HTTP {}", health_url, response.status());
+                return finish(report, false, Some(detail));
+            }
+            Err(e) => {
+                report.http_ok = Some(false);
+                return finish(report, false, Some(e));
             }
+        }
+        if level == 2 {
+            return finish(report, true, None);
+        }
 
-            let client = Client::builder()
-                .timeout(Duration::from_secs(5))
-                .build()
-                .unwrap_or_else(|_| Client::new());
+        // L3: 模型能否实际响应
+        let smoke = target.smoke_test();
+        report.model_warm = Some(smoke.ok);
+        if !smoke.ok {
+            let detail = smoke.error.unwrap_or_else(|| match smoke.status {
+                Some(status) => format!("对话补全请求返回 HTTP {}", status),
+                None => "对话补全请求失败".to_string(),
+            });
+            return finish(report, false, Some(detail));
+        }
+        finish(report, true, None)
+    }
+}
 
-            let health_url = worker_guard.health_url();
-            match client.get(&health_url).send() {
-                Ok(response) => {
-                    if response.status() == StatusCode::OK {
-                        worker_guard.last_health_check = Some(Instant::now());
-                        worker_guard.last_success = Some(Instant::now());
-                        let mut metrics = crate::utils::lock_or_recover(
-                            worker_guard.metrics.as_ref(),
-                            "GatewayWorker.metrics",
-                        );
-                        metrics.consecutive_failures = 0;
-                        metrics.last_heartbeat = Some(Instant::now());
-                        drop(metrics);
-                        worker_guard.set_state(WorkerState::Idle);
-                        worker_guard.record_success();
-                        worker_guard.half_open_testing = false;
-                        worker_guard.circuit_breaker.reset();
-                        return true;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 模拟 READY 后 stderr 读取线程对一行输出的处理，返回处理后的状态
+    fn state_after_line(initial: WorkerState, line: &str) -> WorkerState {
+        let state = AtomicU8::new(initial as u8);
+        if is_process_exit_marker(line) {
+            handle_exit_after_ready(0, &state, &Weak::new());
+        }
+        WorkerState::from(state.load(Ordering::Relaxed))
+    }
+
+    #[test]
+    fn exit_markers_after_ready_mark_worker_dead() {
+        for line in [
+            "[EXIT] 进程退出",
+            "[FATAL] CUDA out of memory",
+            "[MAIN] [FATAL] 模型加载失败",
+            "  [EXIT] code=137",
+        ] {
+            assert_eq!(
+                state_after_line(WorkerState::Idle, line),
+                WorkerState::Dead,
+                "{}",
+                line
+            );
+        }
+        assert_eq!(
+            state_after_line(WorkerState::BusyStreaming, "[FATAL] oom"),
+            WorkerState::Dead
+        );
+    }
+
+    #[test]
+    fn request_scoped_fatal_does_not_mark_worker_dead() {
+        for line in [
+            "[REQUEST-1] [FATAL] 生成失败",
+            "[REQUEST-abc] [EXIT] 客户端断开",
+            "INFO 请求完成 [FATAL]",
+        ] {
+            assert!(!is_process_exit_marker(line), "{}", line);
+            assert_eq!(state_after_line(WorkerState::Idle, line), WorkerState::Idle);
+        }
+    }
+
+    #[test]
+    fn exit_marker_ignored_outside_serving_states() {
+        for initial in [
+            WorkerState::Init,
+            WorkerState::Restarting,
+            WorkerState::Disabled,
+        ] {
+            assert_eq!(state_after_line(initial, "[EXIT]"), initial);
+        }
+    }
+
+    #[test]
+    fn parse_method_accepts_any_case_and_rejects_invalid() {
+        assert_eq!(
+            GatewayPool::parse_method("patch").unwrap(),
+            reqwest::Method::PATCH
+        );
+        assert_eq!(
+            GatewayPool::parse_method("OPTIONS").unwrap(),
+            reqwest::Method::OPTIONS
+        );
+        assert_eq!(
+            GatewayPool::parse_method(" delete ").unwrap(),
+            reqwest::Method::DELETE
+        );
+        assert!(GatewayPool::parse_method("GE T").is_err());
+        assert!(GatewayPool::parse_method("").is_err());
+        assert!(GatewayPool::parse_method("   ").is_err());
+    }
+
+    /// 本地桩服务：接受一个连接，返回 (请求行, 请求体)，并回复 200 {}
+    fn spawn_stub_upstream() -> (u16, thread::JoinHandle<(String, Vec<u8>)>) {
+        use std::io::{BufRead, BufReader, Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
                     }
                 }
-                Err(_) => {}
             }
-        }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let mut stream = stream;
+            stream
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}",
+                )
+                .unwrap();
+            (request_line.trim_end().to_string(), body)
+        });
+        (port, handle)
+    }
+
+    #[test]
+    fn patch_request_is_forwarded_with_body() {
+        let (port, upstream) = spawn_stub_upstream();
+        let pool = GatewayPool::new(1, port).unwrap();
+        crate::utils::lock_or_recover(pool.workers[0].as_ref(), "GatewayWorker")
+            .set_state(WorkerState::Idle);
+        let pool = Mutex::new(pool);
+
+        let body = br#"{"name":"renamed","tags":["a","b"]}"#;
+        let (status, content_type, response) = GatewayPool::forward_request(
+            &pool,
+            "patch",
+            "/v1/files/file-1",
+            Some(body),
+            Some(&[("Content-Type", "application/json")]),
+            None,
+            None,
+            false,
+            "test-trace",
+        )
+        .unwrap();
+
+        let (request_line, received) = upstream.join().unwrap();
+        assert_eq!(request_line, "PATCH /v1/files/file-1 HTTP/1.1");
+        assert_eq!(received, body);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type.as_deref(), Some("application/json"));
+        assert_eq!(response, b"{}");
+    }
+
+    /// 本地慢速桩服务：接受一个连接后通知调用方，保持 hold 时长再回复 200 {}
+    fn spawn_slow_upstream(
+        hold: Duration,
+    ) -> (u16, std::sync::mpsc::Receiver<()>, thread::JoinHandle<()>) {
+        use std::io::{BufRead, BufReader, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (accepted_tx, accepted_rx) = std::sync::mpsc::channel();
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+            }
+            accepted_tx.send(()).unwrap();
+            thread::sleep(hold);
+            let mut stream = stream;
+            stream
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}",
+                )
+                .unwrap();
+        });
+        (port, accepted_rx, handle)
+    }
 
-        // L3: 模型 warm / latency（未来扩展）
-        if level >= 3 {
-            // 可以添加模型预热检查
+    #[test]
+    fn status_returns_quickly_while_forward_is_in_flight() {
+        let (port, accepted, upstream) = spawn_slow_upstream(Duration::from_millis(800));
+        let pool = GatewayPool::new(1, port).unwrap();
+        crate::utils::lock_or_recover(pool.workers[0].as_ref(), "GatewayWorker")
+            .set_state(WorkerState::Idle);
+        let view = pool.status_view();
+        let pool = Arc::new(Mutex::new(pool));
+
+        let forwarding = {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || {
+                GatewayPool::forward_request(
+                    &pool,
+                    "GET",
+                    "/v1/slow",
+                    None,
+                    None,
+                    None,
+                    None,
+                    false,
+                    "busy-trace",
+                )
+            })
+        };
+        accepted.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        // 同时占住连接池锁与 Worker 锁：状态查询不应依赖它们
+        let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+        let worker_guard =
+            crate::utils::lock_or_recover(pool_guard.workers[0].as_ref(), "GatewayWorker");
+        for _ in 0..20 {
+            let started = Instant::now();
+            let (workers, totals) = crate::ai_service::collect_worker_status(&view);
+            let elapsed = started.elapsed();
+            assert!(
+                elapsed < Duration::from_millis(20),
+                "状态查询耗时 {:?}",
+                elapsed
+            );
+            assert_eq!(totals.total_active, 1);
+            assert_eq!(workers[0]["current_trace_id"], "busy-trace");
         }
+        drop(worker_guard);
+        drop(pool_guard);
 
-        // 健康检查失败
-        worker_guard.last_health_check = Some(Instant::now());
-        let (failures, _timeouts) = worker_guard.record_failure(false);
-        let degrade_at = worker_guard.restart_policy.degrade_threshold;
-        let restart_at = degrade_at.saturating_add(worker_guard.restart_policy.restart_threshold);
-        if failures >= degrade_at {
-            let mut metrics = crate::utils::lock_or_recover(
-                worker_guard.metrics.as_ref(),
-                "GatewayWorker.metrics",
+        let (status, _, body) = forwarding.join().unwrap().unwrap();
+        upstream.join().unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"{}");
+    }
+
+    #[test]
+    fn pool_ports_at_upper_boundary() {
+        let pool = GatewayPool::new(2, 65534).unwrap();
+        let ports: Vec<u16> = pool
+            .workers
+            .iter()
+            .map(|w| crate::utils::lock_or_recover(w.as_ref(), "GatewayWorker").port)
+            .collect();
+        assert_eq!(ports, vec![65534, 65535]);
+
+        let err = GatewayPool::new(3, 65534).err().unwrap();
+        assert!(err.contains("65534"), "{}", err);
+    }
+
+    #[test]
+    fn sticky_index_is_stable_and_spreads_clients() {
+        const WORKERS: usize = 4;
+        const CLIENTS: usize = 1000;
+        let pool = GatewayPool::new(WORKERS, 20000).unwrap();
+
+        let mut counts = [0usize; WORKERS];
+        for i in 0..CLIENTS {
+            let client_id = format!("client-{}", i);
+            let index = pool.sticky_index(&client_id);
+            assert!(index < WORKERS);
+            assert_eq!(pool.sticky_index(&client_id), index);
+            counts[index] += 1;
+        }
+        // 每个 Worker 分到的客户端数与平均值的偏差不超过 25%
+        let expected = CLIENTS / WORKERS;
+        for (worker, count) in counts.iter().enumerate() {
+            assert!(
+                count.abs_diff(expected) * 4 <= expected,
+                "Worker-{} 分到 {} 个客户端: {:?}",
+                worker,
+                count,
+                counts
             );
-            metrics.degrade_score = 0.6;
-            drop(metrics);
-            worker_guard.set_state(WorkerState::Degraded);
         }
-        if failures >= restart_at {
-            let worker_clone = Arc::clone(worker);
-            drop(worker_guard);
-            schedule_restart_for_worker(worker_clone, HealthSignal::HeartbeatTimeout);
+    }
+
+    #[test]
+    fn fnv1a_hash_matches_reference_values() {
+        // FNV-1a 64 位参考值：哈希值跨进程、跨版本保持不变，粘性会话重启后仍路由到同一 Worker
+        assert_eq!(fnv1a_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a_hash(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn gateway_state_classification() {
+        // (空闲, 可排队的忙碌, 可用的降级) -> 状态
+        let cases = [
+            ((0, 0, 0), GatewayState::Unavailable),
+            ((1, 0, 0), GatewayState::Healthy),
+            ((1, 3, 2), GatewayState::Healthy),
+            ((0, 1, 0), GatewayState::Busy),
+            ((0, 2, 5), GatewayState::Busy),
+            ((0, 0, 1), GatewayState::Degraded),
+            ((3, 0, 1), GatewayState::Healthy),
+        ];
+        for ((idle, busy_queueable, degraded_usable), expected) in cases {
+            let counts = GatewayStateCounts {
+                idle,
+                busy_queueable,
+                degraded_usable,
+            };
+            assert_eq!(counts.classify(), expected, "{:?}", counts);
         }
+    }
 
-        false
+    #[test]
+    fn worker_urls_bracket_ipv6_and_map_unspecified_hosts() {
+        let v6_loopback = IpAddr::V6(Ipv6Addr::LOCALHOST);
+        assert_eq!(worker_connect_host(v6_loopback), v6_loopback);
+        assert_eq!(worker_base_url(v6_loopback, 8080), "http://[::1]:8080");
+
+        let v6_any = IpAddr::V6(Ipv6Addr::UNSPECIFIED);
+        assert_eq!(worker_connect_host(v6_any), v6_loopback);
+        assert_eq!(worker_base_url(v6_any, 8080), "http://[::1]:8080");
+
+        let v4_any = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+        assert_eq!(worker_connect_host(v4_any), IpAddr::V4(Ipv4Addr::LOCALHOST));
+        assert_eq!(worker_base_url(v4_any, 8080), "http://127.0.0.1:8080");
     }
 }
@@ -0,0 +1,595 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::service::circuit_breaker::CircuitBreakerConfig;
+use crate::utils::{
+    find_in_path, get_app_base_dir, get_config_dir, read_json_with_backup, write_file_atomic,
+};
+
+/// 连接池配置文件名（位于 .config 目录下）
+const POOL_CONFIG_FILE: &str = "gateway_pool.json";
+
+/// 默认 Worker 数量上限
+const DEFAULT_MAX_POOL_SIZE: usize = 16;
+
+/// Worker 数量硬上限（max_pool_size 配置得再大也不超过该值）
+pub const POOL_SIZE_HARD_LIMIT: usize = 32;
+
+/// 默认 Worker 数量
+pub const DEFAULT_POOL_SIZE: usize = 3;
+
+/// 默认基础端口
+pub const DEFAULT_BASE_PORT: u16 = 8765;
+
+/// 默认 Worker 绑定地址（仅本机回环）
+pub const DEFAULT_BIND_HOST: &str = "127.0.0.1";
+
+/// 默认排空超时（秒）
+const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 30;
+
+/// 单次请求最多重试次数上限（防止误配置导致请求长时间挂起）
+const MAX_REQUEST_RETRIES: u32 = 10;
+
+/// 熔断打开时长与统计窗口的上限（秒）
+const MAX_CIRCUIT_BREAKER_SECS: u64 = 3600;
+
+/// 启动超时上限（秒）
+const MAX_STARTUP_TIMEOUT_SECS: u64 = 3600;
+
+/// 模型别名表中的兜底键：请求的模型不可用且没有专门的别名时使用
+pub const DEFAULT_MODEL_ALIAS: &str = "default";
+
+/// 所有 Worker 均失效时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AllDownAction {
+    /// 保持停止并发出告警（默认）
+    #[default]
+    Alert,
+    /// 冷却后自动重启整个连接池
+    AutoRestart,
+}
+
+/// 所有 Worker 均失效（Disabled / FailedPermanent）时的处理策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AllDownPolicy {
+    pub action: AllDownAction,
+    /// 全部失效后等待多久再自动重启（秒）
+    #[serde(alias = "cooldownSecs")]
+    pub cooldown_secs: u64,
+    /// 统计窗口内最多自动重启次数
+    #[serde(alias = "maxRecoveries")]
+    pub max_recoveries: u32,
+    /// 自动重启次数的统计窗口（秒）
+    #[serde(alias = "windowSecs")]
+    pub window_secs: u64,
+}
+
+impl Default for AllDownPolicy {
+    fn default() -> Self {
+        Self {
+            action: AllDownAction::Alert,
+            cooldown_secs: 60,
+            max_recoveries: 3,
+            window_secs: 3600,
+        }
+    }
+}
+
+/// 健康检查线程的间隔与超时（修改后在下一轮检查生效，无需重启线程）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthCheckConfig {
+    /// 检查间隔（秒）
+    #[serde(alias = "intervalSecs")]
+    pub interval_secs: u64,
+    /// 超过该时间未收到心跳视为心跳失效（秒）
+    #[serde(alias = "heartbeatTimeoutSecs")]
+    pub heartbeat_timeout_secs: u64,
+    /// 启动宽限期（秒）：期间不做 HTTP 检查；超过 3 倍宽限期仍无心跳则标记为 Degraded
+    #[serde(alias = "startupGraceSecs")]
+    pub startup_grace_secs: u64,
+    /// /health 与 /v1/models 请求超时（秒）
+    #[serde(alias = "httpTimeoutSecs")]
+    pub http_timeout_secs: u64,
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 10,
+            heartbeat_timeout_secs: 60,
+            startup_grace_secs: 30,
+            http_timeout_secs: 5,
+        }
+    }
+}
+
+impl HealthCheckConfig {
+    /// 校验各项取值（均须大于 0，HTTP 超时不能超过检查间隔的 3 倍，避免单轮检查过长）
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval_secs == 0
+            || self.heartbeat_timeout_secs == 0
+            || self.startup_grace_secs == 0
+            || self.http_timeout_secs == 0
+        {
+            return Err("健康检查间隔与各项超时必须大于 0".to_string());
+        }
+        if self.http_timeout_secs > self.interval_secs.saturating_mul(3) {
+            return Err(format!(
+                "HTTP 超时 {} 秒过长，不能超过检查间隔的 3 倍（{} 秒）",
+                self.http_timeout_secs,
+                self.interval_secs.saturating_mul(3)
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    pub fn heartbeat_timeout(&self) -> Duration {
+        Duration::from_secs(self.heartbeat_timeout_secs)
+    }
+
+    /// 启动宽限期（Worker 配置了更长的启动超时时使用启动超时）
+    pub fn startup_grace(&self, startup_timeout: Option<Duration>) -> Duration {
+        let grace = Duration::from_secs(self.startup_grace_secs);
+        startup_timeout.map_or(grace, |timeout| timeout.max(grace))
+    }
+
+    /// 启动后超过该时间仍无心跳则标记为 Degraded（Worker 配置了更长的启动超时时使用启动超时）
+    pub fn startup_deadline(&self, startup_timeout: Option<Duration>) -> Duration {
+        let deadline = Duration::from_secs(self.startup_grace_secs.saturating_mul(3));
+        startup_timeout.map_or(deadline, |timeout| timeout.max(deadline))
+    }
+
+    pub fn http_timeout(&self) -> Duration {
+        Duration::from_secs(self.http_timeout_secs)
+    }
+}
+
+/// Worker 启动超时（加载大模型需要数分钟时延长）：超时前健康检查不做 HTTP 检查、
+/// 不因没有心跳判定为 Degraded，启动时确认进程就绪的等待时间也相应延长（有上限）
+/// 代价：超时越长，加载失败或卡死的 Worker 越晚被发现和重启
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StartupTimeoutConfig {
+    /// 所有 Worker 的启动超时（秒），0 表示使用健康检查的启动宽限期
+    #[serde(alias = "timeoutSecs")]
+    pub timeout_secs: u64,
+    /// 按 Worker ID 单独设置的启动超时（秒），优先于 timeout_secs
+    #[serde(alias = "workerOverrides")]
+    pub worker_overrides: BTreeMap<usize, u64>,
+}
+
+impl StartupTimeoutConfig {
+    /// 校验各项取值（均不超过 1 小时，单独设置的值须大于 0）
+    pub fn validate(&self) -> Result<(), String> {
+        if self.timeout_secs > MAX_STARTUP_TIMEOUT_SECS {
+            return Err(format!(
+                "启动超时 {} 秒过长，不能超过 {} 秒",
+                self.timeout_secs, MAX_STARTUP_TIMEOUT_SECS
+            ));
+        }
+        for (worker_id, secs) in &self.worker_overrides {
+            if *secs == 0 || *secs > MAX_STARTUP_TIMEOUT_SECS {
+                return Err(format!(
+                    "Worker-{} 的启动超时 {} 秒无效，必须在 1 到 {} 秒之间",
+                    worker_id, secs, MAX_STARTUP_TIMEOUT_SECS
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 指定 Worker 的启动超时（未设置时返回 None，使用健康检查的启动宽限期）
+    pub fn timeout_for(&self, worker_id: usize) -> Option<Duration> {
+        self.worker_overrides
+            .get(&worker_id)
+            .copied()
+            .or((self.timeout_secs > 0).then_some(self.timeout_secs))
+            .map(Duration::from_secs)
+    }
+}
+
+/// /v1/models 模型列表缓存（缓存有效期与最小请求间隔）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelCacheConfig {
+    /// 缓存有效期（秒）
+    #[serde(alias = "ttlSecs")]
+    pub ttl_secs: u64,
+    /// 缓存过期后两次请求 Worker 的最小间隔（秒，限频）
+    #[serde(alias = "minIntervalSecs")]
+    pub min_interval_secs: u64,
+}
+
+impl Default for ModelCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl_secs: 300,
+            min_interval_secs: 30,
+        }
+    }
+}
+
+impl ModelCacheConfig {
+    /// 校验各项取值（有效期须大于 0，最小请求间隔不能超过有效期）
+    pub fn validate(&self) -> Result<(), String> {
+        if self.ttl_secs == 0 {
+            return Err("模型列表缓存有效期必须大于 0".to_string());
+        }
+        if self.min_interval_secs > self.ttl_secs {
+            return Err(format!(
+                "模型列表最小请求间隔 {} 秒不能超过缓存有效期 {} 秒",
+                self.min_interval_secs, self.ttl_secs
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn ttl(&self) -> Duration {
+        Duration::from_secs(self.ttl_secs)
+    }
+
+    pub fn min_interval(&self) -> Duration {
+        Duration::from_secs(self.min_interval_secs)
+    }
+}
+
+/// Worker 熔断器阈值（修改后立即应用到所有 Worker，不改变当前打开 / 关闭状态）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CircuitBreakerSettings {
+    /// 连续失败多少次后打开熔断器
+    #[serde(alias = "failureThreshold")]
+    pub failure_threshold: u32,
+    /// 半开状态下成功多少次后关闭熔断器
+    #[serde(alias = "successThreshold")]
+    pub success_threshold: u32,
+    /// 打开状态持续多久后进入半开（秒）
+    #[serde(alias = "timeoutSecs")]
+    pub timeout_secs: u64,
+    /// 统计失败率的时间窗口（秒）
+    #[serde(alias = "timeWindowSecs")]
+    pub time_window_secs: u64,
+    /// 时间窗口内至少多少个请求才统计失败率
+    #[serde(alias = "minRequests")]
+    pub min_requests: u32,
+}
+
+impl Default for CircuitBreakerSettings {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            success_threshold: 1,
+            timeout_secs: 30,
+            time_window_secs: 60,
+            min_requests: 10,
+        }
+    }
+}
+
+impl CircuitBreakerSettings {
+    /// 校验各项取值（阈值与请求数须大于 0，打开时长与统计窗口在 1 秒到 1 小时之间）
+    pub fn validate(&self) -> Result<(), String> {
+        if self.failure_threshold == 0 || self.success_threshold == 0 {
+            return Err("熔断器失败阈值与成功阈值必须大于 0".to_string());
+        }
+        if self.min_requests == 0 {
+            return Err("熔断器最小请求数必须大于 0".to_string());
+        }
+        for (name, secs) in [
+            ("熔断打开时长", self.timeout_secs),
+            ("统计时间窗口", self.time_window_secs),
+        ] {
+            if secs == 0 || secs > MAX_CIRCUIT_BREAKER_SECS {
+                return Err(format!(
+                    "{} {} 秒无效，必须在 1 到 {} 秒之间",
+                    name, secs, MAX_CIRCUIT_BREAKER_SECS
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 转换为熔断器配置（半开状态同时只允许 1 个试探请求）
+    pub fn to_breaker_config(&self) -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            failure_threshold: self.failure_threshold,
+            success_threshold: self.success_threshold,
+            timeout: Duration::from_secs(self.timeout_secs),
+            time_window: Duration::from_secs(self.time_window_secs),
+            min_requests: self.min_requests,
+            half_open_max_trials: 1,
+        }
+    }
+}
+
+impl From<&CircuitBreakerConfig> for CircuitBreakerSettings {
+    fn from(config: &CircuitBreakerConfig) -> Self {
+        Self {
+            failure_threshold: config.failure_threshold,
+            success_threshold: config.success_threshold,
+            timeout_secs: config.timeout.as_secs(),
+            time_window_secs: config.time_window.as_secs(),
+            min_requests: config.min_requests,
+        }
+    }
+}
+
+/// 转发请求的重试与退避策略（重试间隔按指数退避并附加随机抖动）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestPolicy {
+    /// 首次请求失败后最多重试次数（0 表示不重试）
+    #[serde(alias = "maxRetries")]
+    pub max_retries: u32,
+    /// 首次重试前的等待时间（毫秒），之后每次翻倍
+    #[serde(alias = "baseDelayMs")]
+    pub base_delay_ms: u64,
+    /// 重试等待时间上限（毫秒，不含抖动）
+    #[serde(alias = "maxDelayMs")]
+    pub max_delay_ms: u64,
+    /// 随机抖动上限（毫秒），避免多个请求同时重试
+    #[serde(alias = "jitterMs")]
+    pub jitter_ms: u64,
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            base_delay_ms: 500,
+            max_delay_ms: 5000,
+            jitter_ms: 100,
+        }
+    }
+}
+
+impl RequestPolicy {
+    /// 校验各项取值（重试次数不超过上限，基础间隔不能大于间隔上限）
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_retries > MAX_REQUEST_RETRIES {
+            return Err(format!(
+                "重试次数 {} 超过上限 {}",
+                self.max_retries, MAX_REQUEST_RETRIES
+            ));
+        }
+        if self.base_delay_ms > self.max_delay_ms {
+            return Err(format!(
+                "基础重试间隔 {} ms 不能大于重试间隔上限 {} ms",
+                self.base_delay_ms, self.max_delay_ms
+            ));
+        }
+        Ok(())
+    }
+
+    /// 总尝试次数（首次请求 + 重试）
+    pub fn max_attempts(&self) -> u32 {
+        self.max_retries.saturating_add(1)
+    }
+
+    pub fn base_delay(&self) -> Duration {
+        Duration::from_millis(self.base_delay_ms)
+    }
+
+    pub fn max_delay(&self) -> Duration {
+        Duration::from_millis(self.max_delay_ms)
+    }
+}
+
+/// 校验模型别名表（别名与目标模型均不能为空，目标不能是别名本身）
+pub fn validate_model_aliases(aliases: &BTreeMap<String, String>) -> Result<(), String> {
+    for (alias, target) in aliases {
+        if alias.trim().is_empty() {
+            return Err("模型别名不能为空".to_string());
+        }
+        if target.trim().is_empty() {
+            return Err(format!("模型别名 {} 的目标模型不能为空", alias));
+        }
+        if alias == target {
+            return Err(format!("模型别名 {} 不能指向自身", alias));
+        }
+    }
+    Ok(())
+}
+
+/// Gateway 连接池配置（.config/gateway_pool.json，缺省字段使用默认值）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GatewayPoolConfig {
+    /// 启动时永久隔离 Worker-0（标记 Disabled，永不调度）
+    /// 临时缓解措施：用于规避 Worker-0 反复异常的问题，根因修复后应移除
+    #[serde(alias = "disableWorker0")]
+    pub disable_worker_0: bool,
+    /// Worker 数量
+    #[serde(alias = "poolSize")]
+    pub pool_size: usize,
+    /// 基础端口（Worker-i 使用 base_port + i）
+    #[serde(alias = "basePort")]
+    pub base_port: u16,
+    /// Worker 绑定地址（IPv4 或 IPv6 字面量），端口探测、启动检测与请求转发均使用该地址
+    #[serde(alias = "bindHost")]
+    pub bind_host: String,
+    /// Worker 数量上限（防止误配置耗尽端口/内存）
+    #[serde(alias = "maxPoolSize")]
+    pub max_pool_size: usize,
+    /// 重启 / 停止 Worker 前等待在途请求完成的最长时间（秒）
+    #[serde(alias = "drainTimeoutSecs")]
+    pub drain_timeout_secs: u64,
+    /// Worker 启动就绪后自动发送一次最小请求预热模型（预热完成前不参与调度）
+    #[serde(alias = "warmupOnStart")]
+    pub warmup_on_start: bool,
+    /// 所有 Worker 均失效时的处理策略
+    #[serde(alias = "allDown")]
+    pub all_down: AllDownPolicy,
+    /// 健康检查间隔与超时
+    #[serde(alias = "healthCheck")]
+    pub health_check: HealthCheckConfig,
+    /// Worker 启动超时（慢速加载的大模型）
+    #[serde(alias = "startupTimeout")]
+    pub startup_timeout: StartupTimeoutConfig,
+    /// 转发请求的重试与退避策略
+    #[serde(alias = "requestPolicy")]
+    pub request_policy: RequestPolicy,
+    /// Worker 熔断器阈值
+    #[serde(alias = "circuitBreaker")]
+    pub circuit_breaker: CircuitBreakerSettings,
+    /// 模型列表缓存有效期与限频
+    #[serde(alias = "modelCache")]
+    pub model_cache: ModelCacheConfig,
+    /// 模型别名（请求的模型 -> 实际使用的模型），请求的模型不可用时生效；
+    /// 键 "default" 作为其余不可用模型的兜底
+    #[serde(alias = "modelAliases")]
+    pub model_aliases: BTreeMap<String, String>,
+    /// 自定义 Python 解释器（绝对路径、相对应用根目录的路径或 PATH 中的命令名）
+    /// 为空时 Windows 使用内置 python313/python.exe，其他平台使用 PATH 中的 python3
+    #[serde(alias = "pythonPath", skip_serializing_if = "Option::is_none")]
+    pub python_path: Option<String>,
+    /// 自定义 Gateway 脚本路径（绝对路径或相对应用根目录的路径），为空时使用 ai_service/main_gateway.py
+    #[serde(alias = "gatewayScriptPath", skip_serializing_if = "Option::is_none")]
+    pub gateway_script_path: Option<String>,
+}
+
+impl Default for GatewayPoolConfig {
+    fn default() -> Self {
+        Self {
+            disable_worker_0: false,
+            pool_size: DEFAULT_POOL_SIZE,
+            base_port: DEFAULT_BASE_PORT,
+            bind_host: DEFAULT_BIND_HOST.to_string(),
+            max_pool_size: DEFAULT_MAX_POOL_SIZE,
+            drain_timeout_secs: DEFAULT_DRAIN_TIMEOUT_SECS,
+            warmup_on_start: false,
+            all_down: AllDownPolicy::default(),
+            health_check: HealthCheckConfig::default(),
+            startup_timeout: StartupTimeoutConfig::default(),
+            request_policy: RequestPolicy::default(),
+            circuit_breaker: CircuitBreakerSettings::default(),
+            model_cache: ModelCacheConfig::default(),
+            model_aliases: BTreeMap::new(),
+            python_path: None,
+            gateway_script_path: None,
+        }
+    }
+}
+
+impl GatewayPoolConfig {
+    /// 配置文件路径
+    pub fn path() -> PathBuf {
+        get_config_dir().join(POOL_CONFIG_FILE)
+    }
+
+    /// 解析实际使用的 Python 解释器路径
+    pub fn resolve_python_path(&self) -> PathBuf {
+        match self.python_path.as_deref().map(str::trim) {
+            Some(custom) if !custom.is_empty() => resolve_runtime_path(custom, true),
+            _ if cfg!(target_os = "windows") => {
+                get_app_base_dir().join("python313").join("python.exe")
+            }
+            _ => resolve_runtime_path("python3", true),
+        }
+    }
+
+    /// 解析实际使用的 Gateway 脚本路径
+    pub fn resolve_gateway_script_path(&self) -> PathBuf {
+        match self.gateway_script_path.as_deref().map(str::trim) {
+            Some(custom) if !custom.is_empty() => resolve_runtime_path(custom, false),
+            _ => get_app_base_dir()
+                .join("ai_service")
+                .join("main_gateway.py"),
+        }
+    }
+
+    /// 解析 Worker 绑定地址（需为 IP 字面量，不支持主机名）
+    pub fn resolve_bind_host(&self) -> Result<IpAddr, String> {
+        let host = self.bind_host.trim();
+        // 兼容带方括号的 IPv6 写法（如 [::1]）
+        let host = host
+            .strip_prefix('[')
+            .and_then(|h| h.strip_suffix(']'))
+            .unwrap_or(host);
+        host.parse::<IpAddr>()
+            .map_err(|_| format!("绑定地址无效（需为 IPv4 或 IPv6 地址）: {}", self.bind_host))
+    }
+
+    /// 校验 Worker 数量：不能为 0、不能超过上限，且端口范围不能超出 u16
+    pub fn validate_pool_size(&self, pool_size: usize, base_port: u16) -> Result<(), String> {
+        if pool_size == 0 {
+            return Err("Worker 数量不能为 0".to_string());
+        }
+        if pool_size > POOL_SIZE_HARD_LIMIT {
+            return Err(format!(
+                "Worker 数量 {} 超过硬上限 {}",
+                pool_size, POOL_SIZE_HARD_LIMIT
+            ));
+        }
+        if base_port == 0 {
+            return Err("基础端口不能为 0".to_string());
+        }
+        if pool_size > self.max_pool_size {
+            return Err(format!(
+                "Worker 数量 {} 超过上限 {}（可在 {} 中调整 max_pool_size）",
+                pool_size, self.max_pool_size, POOL_CONFIG_FILE
+            ));
+        }
+        let last_port = base_port as usize + pool_size - 1;
+        if last_port > u16::MAX as usize {
+            return Err(format!(
+                "端口范围越界: 基础端口 {} + {} 个 Worker 超出最大端口 {}",
+                base_port,
+                pool_size,
+                u16::MAX
+            ));
+        }
+        Ok(())
+    }
+
+    /// 加载配置（文件不存在或损坏时回退到 .bak 备份，再回退到默认值）
+    pub fn load() -> Self {
+        let path = Self::path();
+        match read_json_with_backup::<Self>(&path) {
+            Some(config) => config,
+            None => {
+                if path.exists() {
+                    log::warn!(
+                        "[Gateway Pool] 连接池配置不可用，使用默认值: {}",
+                        path.display()
+                    );
+                }
+                Self::default()
+            }
+        }
+    }
+
+    /// 保存配置（原子写入，并保留上一版本为 .bak）
+    pub fn save(&self) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("序列化连接池配置失败: {}", e))?;
+        write_file_atomic(&Self::path(), content.as_bytes())
+    }
+}
+
+/// 解析配置中的路径：绝对路径原样使用，相对路径基于应用根目录；
+/// 允许命令名时，不含路径分隔符的值在 PATH 中查找（找不到时原样返回，由调用方报告不存在）
+fn resolve_runtime_path(value: &str, allow_command: bool) -> PathBuf {
+    let path = PathBuf::from(value);
+    if path.is_absolute() {
+        return path;
+    }
+    if allow_command && path.components().count() == 1 {
+        if let Some(found) = find_in_path(value) {
+            return found;
+        }
+        let in_base = get_app_base_dir().join(&path);
+        return if in_base.is_file() { in_base } else { path };
+    }
+    get_app_base_dir().join(path)
+}
@@ -0,0 +1,135 @@
+// 转发请求的窗口统计：按分钟分桶的环形计数器，用于计算最近 N 秒的错误率
+// （Worker 上的 total_requests / total_errors 是累计值，无法反映“当前”情况）
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 每个桶覆盖的秒数
+const BUCKET_SECS: u64 = 60;
+
+/// 桶数量（最多统计最近 1 小时）
+const BUCKET_COUNT: usize = 60;
+
+/// 可查询的最长窗口（秒）
+pub const MAX_STATS_WINDOW_SECS: u64 = BUCKET_SECS * BUCKET_COUNT as u64;
+
+/// 单个分钟桶
+#[derive(Debug, Clone, Default)]
+struct MinuteBucket {
+    /// 桶对应的分钟序号（Unix 秒 / 60），用于判断桶是否过期
+    minute: u64,
+    requests: u64,
+    errors: u64,
+    /// 各 Worker 在该分钟内的（请求数，错误数）
+    per_worker: HashMap<usize, (u64, u64)>,
+}
+
+/// 窗口内失败最多的 Worker
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerErrorCount {
+    pub worker_id: usize,
+    pub requests: u64,
+    pub errors: u64,
+}
+
+/// 窗口错误率统计（get_gateway_error_stats 返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayErrorStats {
+    /// 实际统计的窗口（秒，已按上限裁剪）
+    pub window_secs: u64,
+    pub total_requests: u64,
+    pub total_errors: u64,
+    /// 错误率（0.0-1.0），窗口内没有请求时为 0
+    pub error_rate: f64,
+    /// 窗口内错误最多的 Worker（没有错误时为空）
+    pub top_failing_worker: Option<WorkerErrorCount>,
+}
+
+/// 按分钟分桶的请求计数环
+#[derive(Debug)]
+pub struct RequestStats {
+    buckets: Vec<MinuteBucket>,
+}
+
+impl Default for RequestStats {
+    fn default() -> Self {
+        Self {
+            buckets: vec![MinuteBucket::default(); BUCKET_COUNT],
+        }
+    }
+}
+
+fn current_minute() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / BUCKET_SECS)
+        .unwrap_or(0)
+}
+
+impl RequestStats {
+    /// 记录一次转发到 Worker 的请求结果
+    pub fn record(&mut self, worker_id: usize, success: bool) {
+        let minute = current_minute();
+        let bucket = &mut self.buckets[(minute % BUCKET_COUNT as u64) as usize];
+        if bucket.minute != minute {
+            // 环形复用：该位置上是一小时前的旧数据
+            *bucket = MinuteBucket {
+                minute,
+                ..MinuteBucket::default()
+            };
+        }
+        let errors = u64::from(!success);
+        bucket.requests += 1;
+        bucket.errors += errors;
+        let entry = bucket.per_worker.entry(worker_id).or_default();
+        entry.0 += 1;
+        entry.1 += errors;
+    }
+
+    /// 汇总最近 window_secs 秒（按分钟取整，含当前分钟）的请求与错误
+    pub fn summarize(&self, window_secs: u64) -> GatewayErrorStats {
+        let window_secs = window_secs.clamp(1, MAX_STATS_WINDOW_SECS);
+        let minutes = window_secs.div_ceil(BUCKET_SECS);
+        let now = current_minute();
+        let oldest = now.saturating_sub(minutes - 1);
+
+        let mut total_requests = 0;
+        let mut total_errors = 0;
+        let mut per_worker: HashMap<usize, (u64, u64)> = HashMap::new();
+        for bucket in self
+            .buckets
+            .iter()
+            .filter(|b| b.requests > 0 && b.minute >= oldest && b.minute <= now)
+        {
+            total_requests += bucket.requests;
+            total_errors += bucket.errors;
+            for (worker_id, (requests, errors)) in &bucket.per_worker {
+                let entry = per_worker.entry(*worker_id).or_default();
+                entry.0 += requests;
+                entry.1 += errors;
+            }
+        }
+
+        let top_failing_worker = per_worker
+            .into_iter()
+            .filter(|(_, (_, errors))| *errors > 0)
+            .max_by_key(|(worker_id, (_, errors))| (*errors, std::cmp::Reverse(*worker_id)))
+            .map(|(worker_id, (requests, errors))| WorkerErrorCount {
+                worker_id,
+                requests,
+                errors,
+            });
+
+        GatewayErrorStats {
+            window_secs,
+            total_requests,
+            total_errors,
+            error_rate: if total_requests == 0 {
+                0.0
+            } else {
+                total_errors as f64 / total_requests as f64
+            },
+            top_failing_worker,
+        }
+    }
+}
@@ -82,7 +82,7 @@ impl Service for GatewayPoolService {
             return Ok(());
         }
 
-        let result = {
+        let all_started = {
             let pool_guard =
                 crate::utils::lock_or_recover(self.pool.as_ref(), "GatewayPoolService.pool");
             let workers = pool_guard.get_workers();
@@ -102,21 +102,23 @@ impl Service for GatewayPoolService {
                     break;
                 }
             }
+            all_started
+        };
 
-            if all_started {
-                info!("[GatewayPoolService] 所有 Worker 已启动，跳过重复启动");
-                Ok(vec!["Workers already started".to_string()])
-            } else {
-                // 启动所有 Worker（只启动一次）
-                let result = pool_guard.start_all();
-                if result.is_ok() {
-                    info!("[GatewayPoolService] 连接池初始化成功");
-
-                    // 启动健康检查线程（只启动一次）
-                    pool_guard.start_health_check_thread();
-                }
-                result
+        let result = if all_started {
+            info!("[GatewayPoolService] 所有 Worker 已启动，跳过重复启动");
+            Ok(Vec::new())
+        } else {
+            // 启动所有 Worker（只启动一次，等待就绪期间不持有连接池锁）
+            let result = GatewayPool::start_all(self.pool.as_ref());
+            if result.is_ok() {
+                info!("[GatewayPoolService] 连接池初始化成功");
+
+                // 启动健康检查线程（只启动一次）
+                crate::utils::lock_or_recover(self.pool.as_ref(), "GatewayPoolService.pool")
+                    .start_health_check_thread();
             }
+            result
         };
 
         match result {
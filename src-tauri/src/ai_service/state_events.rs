@@ -0,0 +1,100 @@
+// Worker 状态变化与连接池告警通知：状态机只把变化写入通道，由独立线程发送 Tauri 事件，
+// 避免在持有 Worker 锁时调用 emit 造成阻塞或死锁
+use super::pool::{PoolAlert, WorkerState};
+use serde::Serialize;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// Worker 状态变化事件名
+pub const WORKER_STATE_CHANGED_EVENT: &str = "gateway-worker-state-changed";
+
+/// 连接池告警事件名（全部 Worker 失效时负载为告警，有 Worker 恢复时为 null）
+pub const GATEWAY_POOL_ALERT_EVENT: &str = "gateway-pool-alert";
+
+/// 没有事件时检查应用是否退出的间隔
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Worker 状态变化（事件负载）
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerStateChange {
+    pub worker_id: usize,
+    pub from: WorkerState,
+    pub to: WorkerState,
+    /// 触发原因（如触发重启的健康信号），未知时为空
+    pub reason: Option<String>,
+}
+
+/// 通道中的事件
+enum GatewayEvent {
+    WorkerState(WorkerStateChange),
+    PoolAlert(Option<PoolAlert>),
+}
+
+static STATE_CHANGE_TX: OnceLock<Sender<GatewayEvent>> = OnceLock::new();
+
+/// 记录一次状态变化（状态未变化或事件线程未启动时忽略）；只写入通道，不会阻塞
+pub(crate) fn publish_state_change(
+    worker_id: usize,
+    from: WorkerState,
+    to: WorkerState,
+    reason: Option<String>,
+) {
+    if from == to {
+        return;
+    }
+    if let Some(tx) = STATE_CHANGE_TX.get() {
+        let _ = tx.send(GatewayEvent::WorkerState(WorkerStateChange {
+            worker_id,
+            from,
+            to,
+            reason,
+        }));
+    }
+}
+
+/// 记录连接池告警的变化：进入全部失效时为 Some，解除时为 None（事件线程未启动时忽略）
+pub(crate) fn publish_pool_alert(alert: Option<PoolAlert>) {
+    if let Some(tx) = STATE_CHANGE_TX.get() {
+        let _ = tx.send(GatewayEvent::PoolAlert(alert));
+    }
+}
+
+/// 启动状态变化与告警事件发送线程（重复调用时忽略）
+pub fn start_worker_state_emitter(app: AppHandle) {
+    let (tx, rx) = mpsc::channel::<GatewayEvent>();
+    if STATE_CHANGE_TX.set(tx).is_err() {
+        return;
+    }
+
+    let spawn_result = std::thread::Builder::new()
+        .name("gateway-state-events".to_string())
+        .spawn(move || loop {
+            let change = match rx.recv_timeout(SHUTDOWN_POLL_INTERVAL) {
+                Ok(GatewayEvent::WorkerState(change)) => change,
+                Ok(GatewayEvent::PoolAlert(alert)) => {
+                    if let Err(e) = app.emit(GATEWAY_POOL_ALERT_EVENT, &alert) {
+                        log::warn!("发送 {} 事件失败: {}", GATEWAY_POOL_ALERT_EVENT, e);
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Timeout) if !super::shutting_down() => continue,
+                Err(_) => break,
+            };
+            log::debug!(
+                "[Gateway Pool] Worker-{} 状态 {:?} -> {:?}（{}）",
+                change.worker_id,
+                change.from,
+                change.to,
+                change.reason.as_deref().unwrap_or("-")
+            );
+            if let Err(e) = app.emit(WORKER_STATE_CHANGED_EVENT, &change) {
+                log::warn!("发送 {} 事件失败: {}", WORKER_STATE_CHANGED_EVENT, e);
+            }
+        });
+    match spawn_result {
+        Ok(handle) => super::register_background_thread("gateway-state-events", handle),
+        Err(e) => log::warn!("启动 Worker 状态事件线程失败: {}", e),
+    }
+}
@@ -0,0 +1,138 @@
+// 死锁监视器：定期尝试获取连接池锁与各 Worker 锁（try_lock + 退避，不阻塞），
+// 超过 STALL_THRESHOLD 仍无法获取时记录告警并发送 gateway-stall-detected 事件，
+// 尽早发现锁顺序等逻辑错误导致的“AI 功能卡死”
+// 转发请求（含流式）只在选择 Worker 时短暂持有连接池锁，上游往返期间不持有，
+// 因此正常的慢速补全不会触发告警；持有连接池锁的操作不应包含长时间的网络等待
+use super::pool::{GatewayPool, GatewayWorker};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, TryLockError};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// 卡死告警事件名
+pub const GATEWAY_STALL_EVENT: &str = "gateway-stall-detected";
+
+/// 两轮检查之间的间隔
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 超过该时间仍无法获取锁视为卡死
+const STALL_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// try_lock 重试的初始 / 最大退避间隔
+const MIN_BACKOFF: Duration = Duration::from_millis(50);
+const MAX_BACKOFF: Duration = Duration::from_secs(1);
+
+/// 卡死告警（事件负载）
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayStall {
+    /// 卡住的锁（GatewayPool 或 Worker-N）
+    pub lock: String,
+    /// 已等待的时间（毫秒）
+    pub waited_ms: u64,
+    /// 最近一次获取连接池锁的位置（best-effort，通常即当前持有者）
+    pub holder_hint: Option<String>,
+}
+
+/// 在 timeout 内尝试获取锁（被污染的锁视为可获取），成功返回 true
+fn try_lock_within<T>(mutex: &Mutex<T>, timeout: Duration) -> bool {
+    let start = Instant::now();
+    let mut backoff = MIN_BACKOFF;
+    loop {
+        match mutex.try_lock() {
+            Ok(_) | Err(TryLockError::Poisoned(_)) => return true,
+            Err(TryLockError::WouldBlock) => {}
+        }
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        thread::sleep(backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+fn holder_hint() -> Option<String> {
+    crate::utils::last_pool_lock_acquisition().map(|acquisition| {
+        format!(
+            "{}（线程 {:?}，{} 秒前获取）",
+            acquisition.location,
+            acquisition.thread_id,
+            acquisition.acquired_at.elapsed().as_secs()
+        )
+    })
+}
+
+/// 检查一把锁：卡死时首次告警并发送事件，恢复后记录日志（同一次卡死只告警一次）
+fn check_lock<T>(
+    app: &AppHandle,
+    stalled: &mut HashSet<String>,
+    name: String,
+    mutex: &Mutex<T>,
+) -> bool {
+    if try_lock_within(mutex, STALL_THRESHOLD) {
+        if stalled.remove(&name) {
+            log::warn!("[Gateway Pool] {} 锁已恢复可用", name);
+        }
+        return true;
+    }
+    if stalled.insert(name.clone()) {
+        let stall = GatewayStall {
+            lock: name,
+            waited_ms: STALL_THRESHOLD.as_millis() as u64,
+            holder_hint: holder_hint(),
+        };
+        log::error!(
+            "[Gateway Pool] !!! 检测到疑似死锁：{} 锁 {} 秒内无法获取，AI 功能可能已卡死；最近一次获取连接池锁: {}",
+            stall.lock,
+            STALL_THRESHOLD.as_secs(),
+            stall.holder_hint.as_deref().unwrap_or("未知")
+        );
+        if let Err(e) = app.emit(GATEWAY_STALL_EVENT, &stall) {
+            log::warn!("发送 {} 事件失败: {}", GATEWAY_STALL_EVENT, e);
+        }
+    }
+    false
+}
+
+/// 取出全部 Worker（短暂持有连接池锁，获取失败时返回空；Worker ID 即其下标）
+fn snapshot_workers(pool: &Mutex<GatewayPool>) -> Vec<Arc<Mutex<GatewayWorker>>> {
+    let pool_guard = match pool.try_lock() {
+        Ok(guard) => guard,
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        Err(TryLockError::WouldBlock) => return Vec::new(),
+    };
+    (0..)
+        .map_while(|worker_id| pool_guard.get_worker(worker_id))
+        .collect()
+}
+
+/// 启动死锁监视线程（重复调用时忽略；连接池未创建前不检查，也不会触发创建）
+pub fn start_stall_watchdog(app: AppHandle) {
+    static STARTED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+    if STARTED.swap(true, std::sync::atomic::Ordering::SeqCst) {
+        return;
+    }
+
+    let spawn_result = thread::Builder::new()
+        .name("gateway-stall-watchdog".to_string())
+        .spawn(move || {
+            let mut stalled = HashSet::new();
+            while crate::utils::sleep_unless_stopped(WATCHDOG_INTERVAL, super::shutting_down) {
+                let Some(pool) = super::GLOBAL_POOL.get() else {
+                    continue;
+                };
+                if !check_lock(&app, &mut stalled, "GatewayPool".to_string(), pool.as_ref()) {
+                    continue;
+                }
+                for (worker_id, worker) in snapshot_workers(pool.as_ref()).iter().enumerate() {
+                    let name = format!("Worker-{}", worker_id);
+                    check_lock(&app, &mut stalled, name, worker.as_ref());
+                }
+            }
+        });
+    match spawn_result {
+        Ok(handle) => super::register_background_thread("gateway-stall-watchdog", handle),
+        Err(e) => log::warn!("启动死锁监视线程失败: {}", e),
+    }
+}
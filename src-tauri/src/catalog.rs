@@ -0,0 +1,611 @@
+// 工具目录导入导出：将分类配置、工具数据、图标缓存和引用的上传文件打包为单个 zip
+use crate::utils::{get_config_dir, get_icons_dir, get_uploads_dir, hash_path, write_file_atomic};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+
+/// 打包格式版本（不兼容的结构变化时递增）
+const CATALOG_FORMAT_VERSION: u32 = 1;
+
+/// 包内清单文件名
+const MANIFEST_NAME: &str = "manifest.json";
+
+/// 随目录一起打包的配置文件
+const CATALOG_CONFIG_FILES: [&str; 2] = ["categories.json", "tools.json"];
+
+/// 工具中可能指向本地文件的字段
+const TOOL_PATH_FIELDS: [&str; 3] = ["exec_path", "working_dir", "jar_config.jar_path"];
+
+/// 包内清单
+#[derive(Debug, Serialize, Deserialize)]
+struct CatalogManifest {
+    format_version: u32,
+    app_version: String,
+    exported_at: u64,
+    /// 导出机器上的上传目录（导入时据此将路径映射到本机上传目录）
+    uploads_dir: String,
+    /// 导出机器上的图标目录（绝对路径形式的 icon_url 据此转换为相对路径）
+    icons_dir: String,
+}
+
+/// 指向包外的路径（需要在目标机器上手动调整）
+#[derive(Debug, Clone, Serialize)]
+pub struct UnresolvedPath {
+    pub tool_id: String,
+    pub tool_name: String,
+    /// 字段名（exec_path / working_dir / jar_config.jar_path）
+    pub field: String,
+    pub path: String,
+    /// 当前机器上该路径是否存在
+    pub exists: bool,
+}
+
+/// 导出结果
+#[derive(Debug, Serialize)]
+pub struct CatalogExportReport {
+    pub path: String,
+    pub tool_count: usize,
+    pub icon_count: usize,
+    pub upload_count: usize,
+    /// 未打包的外部路径
+    pub unresolved: Vec<UnresolvedPath>,
+}
+
+/// 导入结果
+#[derive(Debug, Serialize)]
+pub struct CatalogImportReport {
+    /// 导入方式（merge / replace）
+    pub mode: String,
+    pub categories_added: usize,
+    pub tools_added: usize,
+    /// 合并模式下因 ID 已存在而跳过的工具
+    pub tools_skipped: usize,
+    pub icon_count: usize,
+    pub upload_count: usize,
+    /// 包外路径（在本机不存在的需要手动修正）
+    pub unresolved: Vec<UnresolvedPath>,
+}
+
+fn is_url(s: &str) -> bool {
+    s.starts_with("http://") || s.starts_with("https://")
+}
+
+/// 按点分路径读取字段（如 jar_config.jar_path）
+fn get_field<'a>(tool: &'a Value, field: &str) -> Option<&'a str> {
+    field
+        .split('.')
+        .try_fold(tool, |v, key| v.get(key))
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+fn set_field(tool: &mut Value, field: &str, value: String) {
+    let mut current = tool;
+    let mut keys = field.split('.').peekable();
+    while let Some(key) = keys.next() {
+        if keys.peek().is_none() {
+            if let Some(obj) = current.as_object_mut() {
+                obj.insert(key.to_string(), Value::String(value));
+            }
+            return;
+        }
+        match current.get_mut(key) {
+            Some(next) => current = next,
+            None => return,
+        }
+    }
+}
+
+/// 遍历 tools.json（分类 → 子分类 → 工具）中的所有工具
+fn for_each_tool(data: &mut Value, mut f: impl FnMut(&mut Value)) {
+    let Some(pages) = data.as_array_mut() else {
+        return;
+    };
+    for page in pages {
+        let Some(subs) = page
+            .get_mut("sub_categories")
+            .and_then(|s| s.as_array_mut())
+        else {
+            continue;
+        };
+        for sub in subs {
+            let Some(tools) = sub.get_mut("tools").and_then(|t| t.as_array_mut()) else {
+                continue;
+            };
+            tools.iter_mut().for_each(&mut f);
+        }
+    }
+}
+
+fn tool_label(tool: &Value, key: &str) -> String {
+    tool.get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// 图标缓存键：与 extract_icon_from_file 一致（规范化绝对路径，去除 \\?\ 前缀）
+pub(crate) fn icon_cache_key(path: &Path) -> String {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let path_str = canonical.to_string_lossy().to_string();
+    hash_path(path_str.strip_prefix("\\\\?\\").unwrap_or(&path_str))
+}
+
+/// 统一使用 / 分隔的包内路径
+fn zip_entry_name(prefix: &str, relative: &Path) -> String {
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    format!("{}/{}", prefix, parts.join("/"))
+}
+
+/// 递归收集目录下的文件
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+}
+
+fn write_zip_file<W: Write + std::io::Seek>(
+    zip: &mut zip::ZipWriter<W>,
+    name: &str,
+    source: &Path,
+) -> Result<(), String> {
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file(name, options)
+        .map_err(|e| format!("写入 {} 失败: {}", name, e))?;
+    let mut file =
+        fs::File::open(source).map_err(|e| format!("打开 {} 失败: {}", source.display(), e))?;
+    std::io::copy(&mut file, zip).map_err(|e| format!("写入 {} 失败: {}", name, e))?;
+    Ok(())
+}
+
+/// 导出工具目录为 zip：categories.json、tools.json、icons/ 图标缓存及工具引用的 uploads/ 文件
+/// 指向上传目录之外的路径不会打包，作为 unresolved 返回
+#[tauri::command]
+pub fn export_catalog(path: String) -> Result<CatalogExportReport, String> {
+    let target = PathBuf::from(path.trim());
+    if target.as_os_str().is_empty() {
+        return Err("导出路径不能为空".to_string());
+    }
+    let config_dir = get_config_dir();
+    let icons_dir = get_icons_dir();
+    let uploads_dir = get_uploads_dir();
+    let uploads_root = uploads_dir.canonicalize().unwrap_or(uploads_dir.clone());
+
+    let mut tools_data: Value = fs::read_to_string(config_dir.join("tools.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or(Value::Array(Vec::new()));
+
+    // 工具引用的上传文件：位于 uploads/<工具ID>/ 子目录时打包整个子目录（同一工具的多个文件）
+    let mut upload_roots: BTreeSet<PathBuf> = BTreeSet::new();
+    let mut unresolved = Vec::new();
+    let mut tool_count = 0;
+    for_each_tool(&mut tools_data, |tool| {
+        tool_count += 1;
+        for field in TOOL_PATH_FIELDS {
+            let Some(value) = get_field(tool, field) else {
+                continue;
+            };
+            if is_url(value) {
+                continue;
+            }
+            let file_path = Path::new(value);
+            let canonical = file_path
+                .canonicalize()
+                .unwrap_or_else(|_| file_path.to_path_buf());
+            match canonical.strip_prefix(&uploads_root) {
+                Ok(relative) => {
+                    if let Some(first) = relative.components().next() {
+                        upload_roots.insert(uploads_root.join(first));
+                    }
+                }
+                Err(_) => unresolved.push(UnresolvedPath {
+                    tool_id: tool_label(tool, "id"),
+                    tool_name: tool_label(tool, "name"),
+                    field: field.to_string(),
+                    path: value.to_string(),
+                    exists: file_path.exists(),
+                }),
+            }
+        }
+    });
+
+    if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| format!("创建导出目录失败: {}", e))?;
+    }
+    let file = fs::File::create(&target)
+        .map_err(|e| format!("创建导出文件失败: {} ({})", target.display(), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+
+    let manifest = CatalogManifest {
+        format_version: CATALOG_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: crate::service::events::current_timestamp(),
+        uploads_dir: uploads_root.to_string_lossy().to_string(),
+        icons_dir: icons_dir
+            .canonicalize()
+            .unwrap_or(icons_dir.clone())
+            .to_string_lossy()
+            .to_string(),
+    };
+    let manifest_json =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| format!("序列化清单失败: {}", e))?;
+    zip.start_file(MANIFEST_NAME, SimpleFileOptions::default())
+        .and_then(|_| zip.write_all(&manifest_json).map_err(Into::into))
+        .map_err(|e| format!("写入清单失败: {}", e))?;
+
+    for name in CATALOG_CONFIG_FILES {
+        let source = config_dir.join(name);
+        if source.is_file() {
+            write_zip_file(&mut zip, name, &source)?;
+        }
+    }
+
+    let mut icon_files = Vec::new();
+    collect_files(&icons_dir, &mut icon_files);
+    for icon in &icon_files {
+        let relative = icon.strip_prefix(&icons_dir).unwrap_or(icon);
+        write_zip_file(&mut zip, &zip_entry_name("icons", relative), icon)?;
+    }
+
+    let mut upload_files = Vec::new();
+    for root in &upload_roots {
+        if root.is_dir() {
+            collect_files(root, &mut upload_files);
+        } else if root.is_file() {
+            upload_files.push(root.clone());
+        }
+    }
+    for upload in &upload_files {
+        let relative = upload.strip_prefix(&uploads_root).unwrap_or(upload);
+        write_zip_file(&mut zip, &zip_entry_name("uploads", relative), upload)?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("完成导出文件失败: {}", e))?;
+
+    log::info!(
+        "工具目录已导出: {}（{} 个工具，{} 个图标，{} 个上传文件，{} 个外部路径）",
+        target.display(),
+        tool_count,
+        icon_files.len(),
+        upload_files.len(),
+        unresolved.len()
+    );
+    Ok(CatalogExportReport {
+        path: target.to_string_lossy().to_string(),
+        tool_count,
+        icon_count: icon_files.len(),
+        upload_count: upload_files.len(),
+        unresolved,
+    })
+}
+
+/// 读取包内文本文件
+fn read_zip_text<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Result<Option<String>, String> {
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("读取 {} 失败: {}", name, e)),
+    };
+    let mut content = String::new();
+    file.read_to_string(&mut content)
+        .map_err(|e| format!("读取 {} 失败: {}", name, e))?;
+    Ok(Some(content))
+}
+
+/// 合并分类配置：保留现有分类，追加包中新增的分类
+fn merge_categories(existing: &mut Value, imported: Value) -> usize {
+    let (Some(current), Value::Array(incoming)) = (existing.as_array_mut(), imported) else {
+        return 0;
+    };
+    let ids: HashSet<String> = current
+        .iter()
+        .filter_map(|c| c.get("id").and_then(|v| v.as_str()).map(str::to_string))
+        .collect();
+    let mut added = 0;
+    for category in incoming {
+        let id = tool_label(&category, "id");
+        if !ids.contains(&id) {
+            current.push(category);
+            added += 1;
+        }
+    }
+    added
+}
+
+/// 合并工具数据：按分类 → 子分类逐级合并，已存在的工具 ID（全局）保留本机版本
+/// 返回（新增工具数, 跳过工具数）
+fn merge_tools(existing: &mut Value, imported: Value) -> (usize, usize) {
+    let mut existing_tool_ids = HashSet::new();
+    for_each_tool(existing, |tool| {
+        existing_tool_ids.insert(tool_label(tool, "id"));
+    });
+
+    let (Some(pages), Value::Array(incoming_pages)) = (existing.as_array_mut(), imported) else {
+        return (0, 0);
+    };
+    let (mut added, mut skipped) = (0, 0);
+    for mut incoming_page in incoming_pages {
+        // 先过滤掉已存在的工具
+        if let Some(subs) = incoming_page
+            .get_mut("sub_categories")
+            .and_then(|s| s.as_array_mut())
+        {
+            for sub in subs.iter_mut() {
+                if let Some(tools) = sub.get_mut("tools").and_then(|t| t.as_array_mut()) {
+                    tools.retain(|tool| {
+                        let keep = existing_tool_ids.insert(tool_label(tool, "id"));
+                        if keep {
+                            added += 1;
+                        } else {
+                            skipped += 1;
+                        }
+                        keep
+                    });
+                }
+            }
+        }
+
+        let page_id = tool_label(&incoming_page, "id");
+        let Some(target_page) = pages.iter_mut().find(|p| tool_label(p, "id") == page_id) else {
+            pages.push(incoming_page);
+            continue;
+        };
+        let Some(Value::Array(incoming_subs)) =
+            incoming_page.get_mut("sub_categories").map(Value::take)
+        else {
+            continue;
+        };
+        let Some(target_subs) = target_page
+            .get_mut("sub_categories")
+            .and_then(|s| s.as_array_mut())
+        else {
+            continue;
+        };
+        for mut incoming_sub in incoming_subs {
+            let sub_id = tool_label(&incoming_sub, "id");
+            match target_subs
+                .iter_mut()
+                .find(|s| tool_label(s, "id") == sub_id)
+            {
+                Some(target_sub) => {
+                    if let (Some(target_tools), Some(Value::Array(tools))) = (
+                        target_sub.get_mut("tools").and_then(|t| t.as_array_mut()),
+                        incoming_sub.get_mut("tools").map(Value::take),
+                    ) {
+                        target_tools.extend(tools);
+                    }
+                }
+                None => target_subs.push(incoming_sub),
+            }
+        }
+    }
+    (added, skipped)
+}
+
+/// 将包中的路径映射到本机：导出机器上传目录下的路径映射到本机上传目录，
+/// 图标目录下的绝对路径转换为 .config/icons/ 相对路径
+/// 返回（上传文件路径映射：旧路径 → 新路径, 未解析的外部路径）
+fn remap_tool_paths(
+    tools_data: &mut Value,
+    manifest: &CatalogManifest,
+    uploads_dir: &Path,
+) -> (HashMap<String, PathBuf>, Vec<UnresolvedPath>) {
+    let source_uploads = Path::new(&manifest.uploads_dir);
+    let source_icons = Path::new(&manifest.icons_dir);
+    let mut remapped = HashMap::new();
+    let mut unresolved = Vec::new();
+
+    for_each_tool(tools_data, |tool| {
+        for field in TOOL_PATH_FIELDS {
+            let Some(value) = get_field(tool, field).map(str::to_string) else {
+                continue;
+            };
+            if is_url(&value) {
+                continue;
+            }
+            match Path::new(&value).strip_prefix(source_uploads) {
+                Ok(relative) => {
+                    let new_path = uploads_dir.join(relative);
+                    set_field(tool, field, new_path.to_string_lossy().to_string());
+                    remapped.insert(value, new_path);
+                }
+                Err(_) => unresolved.push(UnresolvedPath {
+                    tool_id: tool_label(tool, "id"),
+                    tool_name: tool_label(tool, "name"),
+                    field: field.to_string(),
+                    exists: Path::new(&value).exists(),
+                    path: value,
+                }),
+            }
+        }
+
+        if let Some(icon_url) = get_field(tool, "icon_url").map(str::to_string) {
+            if let Ok(relative) = Path::new(&icon_url).strip_prefix(source_icons) {
+                let relative = zip_entry_name(".config/icons", relative);
+                set_field(tool, "icon_url", relative);
+            }
+        }
+    });
+
+    (remapped, unresolved)
+}
+
+/// 从 zip 导入工具目录
+/// replace 为 true 时覆盖现有分类与工具（原文件保留为 .bak），否则按 ID 合并（已有项保持不变）
+/// 上传文件恢复到本机上传目录并更新工具路径；按路径哈希命名的图标缓存随之重命名
+#[tauri::command]
+pub fn import_catalog(path: String, replace: Option<bool>) -> Result<CatalogImportReport, String> {
+    let replace = replace.unwrap_or(false);
+    let source = PathBuf::from(path.trim());
+    let file = fs::File::open(&source)
+        .map_err(|e| format!("打开导入文件失败: {} ({})", source.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("不是有效的目录包: {}", e))?;
+
+    let manifest: CatalogManifest = read_zip_text(&mut archive, MANIFEST_NAME)?
+        .ok_or("目录包缺少 manifest.json")
+        .and_then(|c| serde_json::from_str(&c).map_err(|_| "manifest.json 格式错误"))
+        .map_err(|e| e.to_string())?;
+    if manifest.format_version > CATALOG_FORMAT_VERSION {
+        return Err(format!(
+            "目录包格式版本 {} 高于当前支持的版本 {}，请升级应用后再导入",
+            manifest.format_version, CATALOG_FORMAT_VERSION
+        ));
+    }
+
+    let imported_categories = read_zip_text(&mut archive, "categories.json")?;
+    if let Some(content) = &imported_categories {
+        crate::config::validate_categories_content(content)
+            .map_err(|e| format!("目录包中的 categories.json 无效: {}", e))?;
+    }
+    let mut imported_tools: Option<Value> = read_zip_text(&mut archive, "tools.json")?
+        .map(|c| serde_json::from_str(&c))
+        .transpose()
+        .map_err(|e| format!("目录包中的 tools.json 无效: {}", e))?;
+    if imported_tools.as_ref().is_some_and(|t| !t.is_array()) {
+        return Err("目录包中的 tools.json 应为数组".to_string());
+    }
+
+    let config_dir = get_config_dir();
+    let icons_dir = get_icons_dir();
+    let uploads_dir = get_uploads_dir();
+    let uploads_dir = uploads_dir.canonicalize().unwrap_or(uploads_dir);
+
+    let (remapped, unresolved) = match imported_tools.as_mut() {
+        Some(tools) => remap_tool_paths(tools, &manifest, &uploads_dir),
+        None => (HashMap::new(), Vec::new()),
+    };
+
+    // 解压图标和上传文件（拒绝包含 .. 等越界路径的条目）
+    let mut icon_count = 0;
+    let mut upload_count = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("读取目录包失败: {}", e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(name) = entry.enclosed_name() else {
+            log::warn!("跳过不安全的目录包条目: {}", entry.name());
+            continue;
+        };
+        let (dest, is_icon) = if let Ok(relative) = name.strip_prefix("icons") {
+            (icons_dir.join(relative), true)
+        } else if let Ok(relative) = name.strip_prefix("uploads") {
+            (uploads_dir.join(relative), false)
+        } else {
+            continue;
+        };
+        // 合并模式下不覆盖本机已有的上传文件
+        if !is_icon && !replace && dest.exists() {
+            continue;
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+        let mut out = fs::File::create(&dest)
+            .map_err(|e| format!("写入文件失败: {} ({})", dest.display(), e))?;
+        std::io::copy(&mut entry, &mut out)
+            .map_err(|e| format!("写入文件失败: {} ({})", dest.display(), e))?;
+        if is_icon {
+            icon_count += 1;
+        } else {
+            upload_count += 1;
+        }
+    }
+
+    // 自动提取的图标缓存以文件路径哈希命名，路径变化后按新路径复制一份
+    for (old_path, new_path) in &remapped {
+        let old_icon = icons_dir.join(format!("{}.png", hash_path(old_path)));
+        let new_icon = icons_dir.join(format!("{}.png", icon_cache_key(new_path)));
+        if old_icon.is_file() && old_icon != new_icon {
+            if let Err(e) = fs::copy(&old_icon, &new_icon) {
+                log::warn!("复制图标缓存失败: {} ({})", old_icon.display(), e);
+            }
+        }
+    }
+
+    // 写入分类配置与工具数据
+    let mut categories_added = 0;
+    if let Some(content) = imported_categories {
+        let target = config_dir.join("categories.json");
+        let imported: Value =
+            serde_json::from_str(&content).map_err(|e| format!("解析分类配置失败: {}", e))?;
+        let merged = if replace {
+            categories_added = imported.as_array().map_or(0, Vec::len);
+            imported
+        } else {
+            let mut existing: Value = fs::read_to_string(&target)
+                .ok()
+                .and_then(|c| serde_json::from_str(&c).ok())
+                .filter(Value::is_array)
+                .unwrap_or(Value::Array(Vec::new()));
+            categories_added = merge_categories(&mut existing, imported);
+            existing
+        };
+        let json = serde_json::to_string_pretty(&merged)
+            .map_err(|e| format!("序列化分类配置失败: {}", e))?;
+        write_file_atomic(&target, json.as_bytes())?;
+    }
+
+    let (mut tools_added, mut tools_skipped) = (0, 0);
+    if let Some(mut imported) = imported_tools {
+        let target = config_dir.join("tools.json");
+        let merged = if replace {
+            for_each_tool(&mut imported, |_| tools_added += 1);
+            imported
+        } else {
+            let mut existing: Value = fs::read_to_string(&target)
+                .ok()
+                .and_then(|c| serde_json::from_str(&c).ok())
+                .filter(Value::is_array)
+                .unwrap_or(Value::Array(Vec::new()));
+            (tools_added, tools_skipped) = merge_tools(&mut existing, imported);
+            existing
+        };
+        let json = serde_json::to_string_pretty(&merged)
+            .map_err(|e| format!("序列化工具数据失败: {}", e))?;
+        write_file_atomic(&target, json.as_bytes())?;
+    }
+
+    let mode = if replace { "replace" } else { "merge" };
+    log::info!(
+        "工具目录已导入（{}）: {}，新增 {} 个分类、{} 个工具，跳过 {} 个工具，{} 个外部路径",
+        mode,
+        source.display(),
+        categories_added,
+        tools_added,
+        tools_skipped,
+        unresolved.len()
+    );
+    Ok(CatalogImportReport {
+        mode: mode.to_string(),
+        categories_added,
+        tools_added,
+        tools_skipped,
+        icon_count,
+        upload_count,
+        unresolved,
+    })
+}
@@ -1,13 +1,110 @@
+use crate::types::{CategoriesConfig, CategoriesData, CategoryConfig};
 use crate::utils;
+use serde::de::DeserializeOwned;
+use std::collections::HashSet;
 use std::fs;
 
+/// 按结构反序列化 JSON，失败时返回出错字段的路径（如 categories[2].sub_categories[0].tools[1].name）
+fn deserialize_with_path<T: DeserializeOwned>(value: &serde_json::Value) -> Result<T, String> {
+    serde_path_to_error::deserialize(value).map_err(|e| {
+        let path = e.path().to_string();
+        if path == "." {
+            e.into_inner().to_string()
+        } else {
+            format!("{}: {}", path, e.into_inner())
+        }
+    })
+}
+
+/// 记录 ID，重复或为空时追加错误
+fn check_unique_id(
+    ids: &mut HashSet<String>,
+    id: &str,
+    path: &str,
+    kind: &str,
+    errors: &mut Vec<String>,
+) {
+    if id.trim().is_empty() {
+        errors.push(format!("{}.id: {} ID 不能为空", path, kind));
+    } else if !ids.insert(id.to_string()) {
+        errors.push(format!("{}.id: {} ID \"{}\" 重复", path, kind, id));
+    }
+}
+
+/// 校验 categories.json 内容
+/// 支持三种格式：分类配置数组（当前格式）、{ categories: [...] } 分类配置、
+/// 含 sub_categories / tools 的分类页面数据；并要求分类 ID、工具 ID 唯一
+pub(crate) fn validate_categories_content(content: &str) -> Result<(), String> {
+    let value: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| format!("不是合法的 JSON: {}", e))?;
+
+    let mut errors = Vec::new();
+    let mut category_ids = HashSet::new();
+    let is_page_data = value
+        .get("categories")
+        .and_then(|c| c.as_array())
+        .is_some_and(|items| items.iter().any(|c| c.get("sub_categories").is_some()));
+
+    if value.is_array() {
+        let categories: Vec<CategoryConfig> = deserialize_with_path(&value)?;
+        for (i, category) in categories.iter().enumerate() {
+            let path = format!("[{}]", i);
+            check_unique_id(&mut category_ids, &category.id, &path, "分类", &mut errors);
+        }
+    } else if is_page_data {
+        let data: CategoriesData = deserialize_with_path(&value)?;
+        let mut tool_ids = HashSet::new();
+        for (i, page) in data.categories.iter().enumerate() {
+            let path = format!("categories[{}]", i);
+            check_unique_id(&mut category_ids, &page.id, &path, "分类", &mut errors);
+            for (j, sub) in page.sub_categories.iter().enumerate() {
+                for (k, tool) in sub.tools.iter().enumerate() {
+                    let tool_path = format!("{}.sub_categories[{}].tools[{}]", path, j, k);
+                    check_unique_id(&mut tool_ids, &tool.id, &tool_path, "工具", &mut errors);
+                }
+            }
+        }
+    } else {
+        let config: CategoriesConfig = deserialize_with_path(&value)?;
+        for (i, category) in config.categories.iter().enumerate() {
+            let path = format!("categories[{}]", i);
+            check_unique_id(&mut category_ids, &category.id, &path, "分类", &mut errors);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("；"))
+    }
+}
+
 /// 通用的读取配置文件命令
+/// JSON 配置损坏（如写入中途断电）时回退到 .bak 备份
 #[tauri::command]
 pub fn read_config_file(filename: String) -> Result<String, String> {
     let config_path = utils::get_config_dir().join(&filename);
     if config_path.exists() {
-        fs::read_to_string(&config_path)
-            .map_err(|e| format!("Failed to read config file {}: {}", filename, e))
+        let content = fs::read_to_string(&config_path)
+            .map_err(|e| format!("Failed to read config file {}: {}", filename, e))?;
+        if filename.ends_with(".json") {
+            if let Err(e) = serde_json::from_str::<serde_json::Value>(&content) {
+                let backup_path = utils::backup_path(&config_path);
+                if let Some(backup) = fs::read_to_string(&backup_path)
+                    .ok()
+                    .filter(|b| serde_json::from_str::<serde_json::Value>(b).is_ok())
+                {
+                    log::warn!(
+                        "配置文件 {} 已损坏（{}），使用备份: {}",
+                        filename,
+                        e,
+                        backup_path.display()
+                    );
+                    return Ok(backup);
+                }
+            }
+        }
+        Ok(content)
     } else {
         // 返回空JSON，前端会使用默认值
         Ok("{}".to_string())
@@ -15,10 +112,18 @@ pub fn read_config_file(filename: String) -> Result<String, String> {
 }
 
 /// 通用的写入配置文件命令
+/// categories.json 会先校验结构和 ID 唯一性，不合法时拒绝写入
+/// 先写入 <filename>.tmp 并刷盘，再重命名覆盖目标文件，上一版本保留为 <filename>.bak
 #[tauri::command]
 pub fn write_config_file(filename: String, content: String) -> Result<(), String> {
+    // 分类配置写入前校验结构，避免错误内容覆盖整个工具目录
+    if filename == "categories.json" {
+        validate_categories_content(&content)
+            .map_err(|e| format!("categories.json 校验失败，未保存: {}", e))?;
+    }
+
     let config_path = utils::get_config_dir().join(&filename);
-    fs::write(&config_path, content)
+    utils::write_file_atomic(&config_path, content.as_bytes())
         .map_err(|e| format!("Failed to write config file {}: {}", filename, e))
 }
 
@@ -0,0 +1,451 @@
+// 安装诊断：一次性检查运行环境并给出修复建议
+use crate::utils::{find_in_path, get_app_base_dir, get_config_dir};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// 检查结果级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    /// 正常
+    Ok,
+    /// 警告（部分功能可能不可用）
+    Warning,
+    /// 错误（核心功能不可用）
+    Error,
+}
+
+/// 单项检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticCheck {
+    /// 检查项标识
+    pub id: String,
+    /// 检查项名称
+    pub name: String,
+    pub severity: DiagnosticSeverity,
+    /// 检查结果说明
+    pub message: String,
+    /// 修复建议（仅未通过时提供）
+    pub suggestion: Option<String>,
+    /// 实际解析到的路径（自检项提供）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// 诊断报告
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsReport {
+    /// 是否没有错误级别的检查项
+    pub passed: bool,
+    pub error_count: usize,
+    pub warning_count: usize,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+impl DiagnosticCheck {
+    fn ok(id: &str, name: &str, message: impl Into<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            severity: DiagnosticSeverity::Ok,
+            message: message.into(),
+            suggestion: None,
+            path: None,
+        }
+    }
+
+    fn fail(
+        id: &str,
+        name: &str,
+        severity: DiagnosticSeverity,
+        message: impl Into<String>,
+        suggestion: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            severity,
+            message: message.into(),
+            suggestion: Some(suggestion.into()),
+            path: None,
+        }
+    }
+
+    fn with_path(mut self, path: &Path) -> Self {
+        self.path = Some(path.display().to_string());
+        self
+    }
+}
+
+/// 检查配置目录是否可写
+fn check_config_dir() -> DiagnosticCheck {
+    let config_dir = get_config_dir();
+    let probe = config_dir.join(".diagnostics_probe");
+    match std::fs::write(&probe, b"ok") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            DiagnosticCheck::ok(
+                "config_dir",
+                "配置目录可写",
+                format!("{}", config_dir.display()),
+            )
+        }
+        Err(e) => DiagnosticCheck::fail(
+            "config_dir",
+            "配置目录可写",
+            DiagnosticSeverity::Error,
+            format!("无法写入 {}: {}", config_dir.display(), e),
+            "检查目录权限，或将应用移动到当前用户有写权限的位置（避免放在 Program Files 下）",
+        ),
+    }
+}
+
+/// 检查目录能否创建并写入（不存在时创建）
+fn check_dir_writable(id: &str, name: &str, dir: &Path) -> DiagnosticCheck {
+    let probe = dir.join(".self_test_probe");
+    let result = std::fs::create_dir_all(dir).and_then(|()| std::fs::write(&probe, b"ok"));
+    match result {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            DiagnosticCheck::ok(id, name, "可创建并写入").with_path(dir)
+        }
+        Err(e) => DiagnosticCheck::fail(
+            id,
+            name,
+            DiagnosticSeverity::Error,
+            format!("无法创建或写入: {}", e),
+            "检查 .config 目录权限，或将应用移动到当前用户有写权限的位置",
+        )
+        .with_path(dir),
+    }
+}
+
+/// 执行程序的版本命令，返回输出的第一行（java -version 输出到 stderr，两者都检查）
+fn run_version_command(program: &Path, arg: &str) -> Result<String, String> {
+    let output = Command::new(program)
+        .arg(arg)
+        .output()
+        .map_err(|e| format!("无法执行: {}", e))?;
+    let first_line = |bytes: &[u8]| {
+        String::from_utf8_lossy(bytes)
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(str::to_string)
+    };
+    let version = first_line(&output.stdout).or_else(|| first_line(&output.stderr));
+    if !output.status.success() {
+        return Err(format!(
+            "退出码 {:?}: {}",
+            output.status.code(),
+            version.unwrap_or_default()
+        ));
+    }
+    Ok(version.unwrap_or_else(|| "（无版本输出）".to_string()))
+}
+
+/// 检查程序可运行：先解析路径（命令名在 PATH 中查找），再执行版本命令
+fn check_runnable(
+    id: &str,
+    name: &str,
+    program: &Path,
+    version_arg: &str,
+    severity: DiagnosticSeverity,
+    suggestion: &str,
+) -> DiagnosticCheck {
+    let resolved: Option<PathBuf> = if program.components().count() == 1 && !program.exists() {
+        program.to_str().and_then(find_in_path)
+    } else {
+        program.exists().then(|| program.to_path_buf())
+    };
+    let Some(resolved) = resolved else {
+        return DiagnosticCheck::fail(
+            id,
+            name,
+            severity,
+            format!("未找到: {}", program.display()),
+            suggestion,
+        )
+        .with_path(program);
+    };
+    match run_version_command(&resolved, version_arg) {
+        Ok(version) => DiagnosticCheck::ok(id, name, version).with_path(&resolved),
+        Err(e) => DiagnosticCheck::fail(id, name, severity, e, suggestion).with_path(&resolved),
+    }
+}
+
+/// 检查路径是否存在
+fn check_path(id: &str, name: &str, path: &Path, suggestion: &str) -> DiagnosticCheck {
+    if path.exists() {
+        DiagnosticCheck::ok(id, name, format!("{}", path.display()))
+    } else {
+        DiagnosticCheck::fail(
+            id,
+            name,
+            DiagnosticSeverity::Error,
+            format!("不存在: {}", path.display()),
+            suggestion,
+        )
+    }
+}
+
+/// 检查 models.json 是否为合法 JSON
+fn check_models_config() -> DiagnosticCheck {
+    let path = crate::ai_service::legacy::get_models_config_path();
+    if !path.exists() {
+        return DiagnosticCheck::fail(
+            "models_json",
+            "models.json 配置",
+            DiagnosticSeverity::Warning,
+            format!("不存在: {}", path.display()),
+            "在 AI 设置中保存一次模型配置，或从安装包中恢复 ai_service/config/models.json",
+        );
+    }
+    match std::fs::read_to_string(&path) {
+        Ok(content) => match serde_json::from_str::<serde_json::Value>(&content) {
+            Ok(_) => DiagnosticCheck::ok(
+                "models_json",
+                "models.json 配置",
+                format!("{}", path.display()),
+            ),
+            Err(e) => DiagnosticCheck::fail(
+                "models_json",
+                "models.json 配置",
+                DiagnosticSeverity::Error,
+                format!("JSON 格式错误: {}", e),
+                "修正 models.json 的语法错误（可用 JSON 校验工具定位第几行），或删除后重新配置",
+            ),
+        },
+        Err(e) => DiagnosticCheck::fail(
+            "models_json",
+            "models.json 配置",
+            DiagnosticSeverity::Error,
+            format!("读取失败: {}", e),
+            "检查文件权限",
+        ),
+    }
+}
+
+/// 检查连接池端口：由本应用 Worker 占用视为正常，被其他进程占用则告警
+fn check_ports() -> Vec<DiagnosticCheck> {
+    let pool = crate::ai_service::get_global_pool();
+    let pool_guard = crate::utils::lock_or_recover(pool.as_ref(), "GatewayPool");
+    let base_port = pool_guard.base_port();
+    let pool_size = pool_guard.pool_size();
+    let config = crate::ai_service::GatewayPoolConfig::load();
+
+    let mut checks = Vec::new();
+    match config.validate_pool_size(pool_size, base_port) {
+        Ok(()) => checks.push(DiagnosticCheck::ok(
+            "port_range",
+            "端口范围",
+            format!(
+                "{}-{}（{} 个 Worker）",
+                base_port,
+                base_port as usize + pool_size - 1,
+                pool_size
+            ),
+        )),
+        Err(e) => {
+            checks.push(DiagnosticCheck::fail(
+                "port_range",
+                "端口范围",
+                DiagnosticSeverity::Error,
+                e,
+                "调整 .config/gateway_pool.json 中的 max_pool_size，或减少 Worker 数量",
+            ));
+            return checks;
+        }
+    }
+
+    for worker in pool_guard.get_workers() {
+        let (worker_id, port, owned) = {
+            let wg = crate::utils::lock_or_recover(worker.as_ref(), "GatewayWorker");
+            (wg.id, wg.port, wg.process.is_some())
+        };
+        let id = format!("port_{}", port);
+        let name = format!("Worker-{} 端口 {}", worker_id, port);
+        if owned {
+            checks.push(DiagnosticCheck::ok(&id, &name, "由本应用的 Worker 使用"));
+        } else if std::net::TcpListener::bind(("127.0.0.1", port)).is_ok() {
+            checks.push(DiagnosticCheck::ok(&id, &name, "空闲"));
+        } else {
+            checks.push(DiagnosticCheck::fail(
+                &id,
+                &name,
+                DiagnosticSeverity::Warning,
+                "端口已被其他进程占用",
+                "结束占用该端口的进程（可能是上次异常退出残留的 Worker）后重启连接池",
+            ));
+        }
+    }
+    checks
+}
+
+/// 检查终端 / 系统工具是否可用（CLI、Python、JAR 工具需要在终端中运行）
+fn check_system_tools() -> Vec<DiagnosticCheck> {
+    let mut checks = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    {
+        for (program, purpose) in [
+            ("powershell", "打开终端运行 CLI 工具"),
+            ("taskkill", "终止 Worker 进程"),
+        ] {
+            let id = format!("tool_{}", program);
+            let name = format!("{}（{}）", program, purpose);
+            match find_in_path(program) {
+                Some(path) => checks.push(DiagnosticCheck::ok(
+                    &id,
+                    &name,
+                    format!("{}", path.display()),
+                )),
+                None => checks.push(DiagnosticCheck::fail(
+                    &id,
+                    &name,
+                    DiagnosticSeverity::Error,
+                    format!("未在 PATH 中找到 {}", program),
+                    "确认 C:\\Windows\\System32 及 WindowsPowerShell\\v1.0 目录在 PATH 环境变量中",
+                )),
+            }
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        match find_in_path("osascript") {
+            Some(path) => checks.push(DiagnosticCheck::ok(
+                "terminal",
+                "终端（osascript）",
+                format!("{}", path.display()),
+            )),
+            None => checks.push(DiagnosticCheck::fail(
+                "terminal",
+                "终端（osascript）",
+                DiagnosticSeverity::Error,
+                "未找到 osascript，无法打开 Terminal.app",
+                "确认 /usr/bin 在 PATH 环境变量中",
+            )),
+        }
+    }
+
+    #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+    {
+        // 与 launcher 中的终端候选列表保持一致
+        let candidates = ["gnome-terminal", "xterm", "konsole", "x-terminal-emulator"];
+        match candidates
+            .iter()
+            .find_map(|t| find_in_path(t).map(|p| (t, p)))
+        {
+            Some((terminal, path)) => checks.push(DiagnosticCheck::ok(
+                "terminal",
+                "终端模拟器",
+                format!("{} ({})", terminal, path.display()),
+            )),
+            None => checks.push(DiagnosticCheck::fail(
+                "terminal",
+                "终端模拟器",
+                DiagnosticSeverity::Warning,
+                "未找到可用的终端模拟器，CLI / Python / JAR 工具无法启动",
+                "安装 gnome-terminal、xterm、konsole 或 x-terminal-emulator 之一",
+            )),
+        }
+    }
+
+    checks
+}
+
+/// 汇总检查结果为报告
+fn build_report(kind: &str, checks: Vec<DiagnosticCheck>) -> DiagnosticsReport {
+    let error_count = checks
+        .iter()
+        .filter(|c| c.severity == DiagnosticSeverity::Error)
+        .count();
+    let warning_count = checks
+        .iter()
+        .filter(|c| c.severity == DiagnosticSeverity::Warning)
+        .count();
+    log::info!(
+        "{}完成: {} 项检查，{} 个错误，{} 个警告",
+        kind,
+        checks.len(),
+        error_count,
+        warning_count
+    );
+
+    DiagnosticsReport {
+        passed: error_count == 0,
+        error_count,
+        warning_count,
+        checks,
+    }
+}
+
+/// 启动链路自检（首次运行时展示）：配置目录可写、图标 / 上传目录可创建、
+/// java 与 Gateway Python 解释器可运行、Gateway 脚本存在；每项附带解析到的路径与实际错误
+#[tauri::command]
+pub fn run_self_test() -> Result<DiagnosticsReport, String> {
+    let config_dir = get_config_dir();
+    let pool_config = crate::ai_service::GatewayPoolConfig::load();
+    let script_path = pool_config.resolve_gateway_script_path();
+    let checks = vec![
+        check_config_dir().with_path(&config_dir),
+        check_dir_writable("icons_dir", "图标缓存目录", &config_dir.join("icons")),
+        check_dir_writable("uploads_dir", "上传文件目录", &config_dir.join("uploads")),
+        check_runnable(
+            "java",
+            "Java 运行时（java -version）",
+            Path::new("java"),
+            "-version",
+            DiagnosticSeverity::Warning,
+            "安装 JDK / JRE 并将 java 加入 PATH，或在 JAR 工具配置中指定 java_path",
+        ),
+        check_runnable(
+            "python",
+            "Gateway Python 解释器（--version）",
+            &pool_config.resolve_python_path(),
+            "--version",
+            DiagnosticSeverity::Error,
+            "确认安装包中的 python313 目录完整，或在 gateway_pool.json 中设置 python_path",
+        ),
+        check_path(
+            "gateway_script",
+            "AI Gateway 脚本",
+            &script_path,
+            "确认 ai_service/main_gateway.py 存在，或在 gateway_pool.json 中设置 gateway_script_path",
+        )
+        .with_path(&script_path),
+    ];
+    Ok(build_report("启动链路自检", checks))
+}
+
+/// 一站式安装诊断：配置目录、运行时路径、models.json、端口、终端与系统工具
+#[tauri::command]
+pub fn run_diagnostics() -> Result<DiagnosticsReport, String> {
+    let mut checks = vec![
+        check_config_dir(),
+        check_path(
+            "base_dir",
+            "应用根目录",
+            &get_app_base_dir(),
+            "重新安装应用，或确认从安装目录启动",
+        ),
+        check_path(
+            "python",
+            "Python 解释器",
+            &crate::ai_service::GatewayPool::get_python_path(),
+            "确认安装包中的 python313 目录完整，或在 gateway_pool.json 中设置 python_path",
+        ),
+        check_path(
+            "gateway_script",
+            "AI Gateway 脚本",
+            &crate::ai_service::GatewayPool::get_ai_service_path(),
+            "确认 ai_service/main_gateway.py 存在，或在 gateway_pool.json 中设置 gateway_script_path",
+        ),
+        check_models_config(),
+    ];
+    checks.extend(check_ports());
+    checks.extend(check_system_tools());
+    Ok(build_report("安装诊断", checks))
+}
@@ -1,8 +1,112 @@
-use crate::types::{ResolveFilePathParams, UploadFileParams};
+use crate::types::{ResolveFilePathParams, UploadFileBeginParams, UploadFileParams};
 use crate::utils::get_uploads_dir;
 use base64::{engine::general_purpose, Engine as _};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// 符号链接最多跟随的层数（与常见系统的 MAXSYMLINKS 一致）
+const MAX_SYMLINK_HOPS: usize = 40;
+
+/// 分块上传会话的空闲超时：超过该时间没有新数据块的会话视为已放弃（前端崩溃或刷新），
+/// 在下次开始上传或追加数据块时清理
+const UPLOAD_SESSION_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// 单个分块上传的大小上限（4 GB）：上传目录位于应用数据目录下，防止失控的前端写满磁盘
+const MAX_UPLOAD_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+/// 进行中的分块上传（上传 ID -> 会话）；写入数据块时只持有对应会话的锁，不阻塞其他上传
+static UPLOAD_SESSIONS: OnceLock<Mutex<HashMap<String, Arc<Mutex<UploadSession>>>>> =
+    OnceLock::new();
+
+/// 分块上传 ID 序号
+static UPLOAD_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// 分块上传会话：数据块追加写入上传目录下的临时文件，完成后重命名为最终文件
+struct UploadSession {
+    /// 目标目录（与单次上传相同：uploads 或 uploads/<tool_id>）
+    uploads_dir: PathBuf,
+    /// 已去除路径部分的文件名
+    file_name: String,
+    temp_path: PathBuf,
+    /// 临时文件（会话完成、取消或超时清理后为空）
+    file: Option<fs::File>,
+    bytes_written: u64,
+    /// 最近一次开始或写入数据块的时间
+    last_activity: Instant,
+}
+
+fn upload_sessions() -> &'static Mutex<HashMap<String, Arc<Mutex<UploadSession>>>> {
+    UPLOAD_SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 清理超过空闲超时的上传会话并删除其临时文件
+/// （正在写入数据块的会话持有锁，不视为空闲）
+fn reap_stale_upload_sessions(sessions: &mut HashMap<String, Arc<Mutex<UploadSession>>>) {
+    let stale: Vec<String> = sessions
+        .iter()
+        .filter(|(_, session)| {
+            session
+                .try_lock()
+                .is_ok_and(|session| session.last_activity.elapsed() > UPLOAD_SESSION_TTL)
+        })
+        .map(|(id, _)| id.clone())
+        .collect();
+    for upload_id in stale {
+        if let Some(session) = sessions.remove(&upload_id) {
+            let mut session = crate::utils::lock_or_recover(session.as_ref(), "UploadSession");
+            session.file = None;
+            let _ = fs::remove_file(&session.temp_path);
+            log::warn!(
+                "分块上传会话超时，已清理: {} ({} 字节)",
+                upload_id,
+                session.bytes_written
+            );
+        }
+    }
+}
+
+/// 是否为分块上传的临时文件（.<上传 ID>.part）
+fn is_upload_part_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.') && name.ends_with(".part"))
+}
+
+/// 删除上一次运行遗留的分块上传临时文件（上传目录及其下一级工具目录），应用启动时调用
+pub fn remove_orphan_upload_parts() {
+    let uploads_dir = get_uploads_dir();
+    let mut dirs = vec![uploads_dir.clone()];
+    if let Ok(entries) = fs::read_dir(&uploads_dir) {
+        dirs.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir()),
+        );
+    }
+    let mut removed = 0;
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_file() && is_upload_part_file(&path) {
+                match fs::remove_file(&path) {
+                    Ok(()) => removed += 1,
+                    Err(e) => log::warn!("删除遗留的上传临时文件失败 {}: {}", path.display(), e),
+                }
+            }
+        }
+    }
+    if removed > 0 {
+        log::info!("已删除 {} 个遗留的分块上传临时文件", removed);
+    }
+}
 
 /// 打开文件对话框的参数结构体
 #[derive(Debug, serde::Deserialize)]
@@ -116,42 +220,33 @@ if ($dialog.ShowDialog() -eq [System.Windows.Forms.DialogResult]::OK) {{
     }
 }
 
-/// 上传文件并保存到 uploads 目录
-/// 返回保存后的文件路径
-#[tauri::command]
-pub fn upload_file(params: UploadFileParams) -> Result<String, String> {
-    let file_name = params.file_name;
-    let file_data = params.file_data;
-    let tool_id = params.tool_id;
-
-    // 解码 base64 数据
-    let file_bytes = general_purpose::STANDARD
-        .decode(
-            file_data
-                .strip_prefix("data:")
-                .and_then(|s| s.split(',').nth(1))
-                .unwrap_or(&file_data),
-        )
-        .map_err(|e| format!("Base64 解码失败: {}", e))?;
-
-    // 确定保存目录
+/// 上传文件的保存目录（有工具 ID 时为 uploads/<tool_id>），不存在时创建
+/// 工具 ID 必须是单级普通目录名（拒绝 ..、绝对路径与多级路径，防止写到 uploads 之外）
+fn upload_target_dir(tool_id: Option<String>) -> Result<PathBuf, String> {
     let uploads_dir = if let Some(id) = tool_id {
-        // 如果有工具ID，创建子目录
-        get_uploads_dir().join(id)
+        let mut components = Path::new(&id).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(_)), None) => get_uploads_dir().join(id),
+            _ => return Err(format!("无效的工具 ID: {}", id)),
+        }
     } else {
         get_uploads_dir()
     };
-
-    // 确保目录存在
     std::fs::create_dir_all(&uploads_dir).map_err(|e| format!("创建上传目录失败: {}", e))?;
+    Ok(uploads_dir)
+}
 
-    // 生成安全的文件名（防止路径遍历攻击）
-    let safe_file_name = Path::new(&file_name)
+/// 生成安全的文件名（只保留最后一级，防止路径遍历攻击）
+fn safe_upload_name(file_name: &str) -> Result<String, String> {
+    Path::new(file_name)
         .file_name()
         .and_then(|n| n.to_str())
-        .ok_or("无效的文件名")?;
+        .map(|n| n.to_string())
+        .ok_or_else(|| "无效的文件名".to_string())
+}
 
-    // 如果文件已存在，添加时间戳后缀
+/// 上传文件的最终路径（文件已存在时添加时间戳后缀）
+fn unique_upload_path(uploads_dir: &Path, safe_file_name: &str) -> PathBuf {
     let mut final_path = uploads_dir.join(safe_file_name);
     if final_path.exists() {
         let stem = final_path
@@ -176,6 +271,30 @@ pub fn upload_file(params: UploadFileParams) -> Result<String, String> {
         };
         final_path = uploads_dir.join(new_name);
     }
+    final_path
+}
+
+/// 上传文件并保存到 uploads 目录（整个文件以 base64 传入，适合小文件；大文件使用分块上传）
+/// 返回保存后的文件路径
+#[tauri::command]
+pub fn upload_file(params: UploadFileParams) -> Result<String, String> {
+    let file_name = params.file_name;
+    let file_data = params.file_data;
+    let tool_id = params.tool_id;
+
+    // 解码 base64 数据
+    let file_bytes = general_purpose::STANDARD
+        .decode(
+            file_data
+                .strip_prefix("data:")
+                .and_then(|s| s.split(',').nth(1))
+                .unwrap_or(&file_data),
+        )
+        .map_err(|e| format!("Base64 解码失败: {}", e))?;
+
+    let uploads_dir = upload_target_dir(tool_id)?;
+    let safe_file_name = safe_upload_name(&file_name)?;
+    let final_path = unique_upload_path(&uploads_dir, &safe_file_name);
 
     // 保存文件
     fs::write(&final_path, &file_bytes).map_err(|e| format!("保存文件失败: {}", e))?;
@@ -187,6 +306,179 @@ pub fn upload_file(params: UploadFileParams) -> Result<String, String> {
     Ok(abs_path.to_string_lossy().to_string())
 }
 
+/// 开始分块上传：在上传目录创建临时文件，返回上传 ID
+#[tauri::command]
+pub fn upload_file_begin(params: UploadFileBeginParams) -> Result<String, String> {
+    let uploads_dir = upload_target_dir(params.tool_id)?;
+    let file_name = safe_upload_name(&params.file_name)?;
+
+    let upload_id = format!(
+        "{:x}-{}",
+        crate::service::events::current_timestamp(),
+        UPLOAD_SEQ.fetch_add(1, Ordering::SeqCst) + 1
+    );
+    // 临时文件与最终文件位于同一目录，完成时可以原子重命名
+    let temp_path = uploads_dir.join(format!(".{}.part", upload_id));
+    let file = fs::File::create(&temp_path).map_err(|e| format!("创建临时文件失败: {}", e))?;
+
+    let mut sessions = crate::utils::lock_or_recover(upload_sessions(), "UPLOAD_SESSIONS");
+    reap_stale_upload_sessions(&mut sessions);
+    sessions.insert(
+        upload_id.clone(),
+        Arc::new(Mutex::new(UploadSession {
+            uploads_dir,
+            file_name,
+            temp_path,
+            file: Some(file),
+            bytes_written: 0,
+            last_activity: Instant::now(),
+        })),
+    );
+    drop(sessions);
+    log::info!("开始分块上传: {} ({})", params.file_name, upload_id);
+    Ok(upload_id)
+}
+
+/// 追加一个数据块（base64 编码，除最后一块外长度须为 4 的倍数），返回已写入的总字节数
+#[tauri::command]
+pub fn upload_file_chunk(upload_id: String, chunk: String) -> Result<u64, String> {
+    let bytes = general_purpose::STANDARD
+        .decode(chunk.trim())
+        .map_err(|e| format!("Base64 解码失败: {}", e))?;
+
+    let session = {
+        let mut sessions = crate::utils::lock_or_recover(upload_sessions(), "UPLOAD_SESSIONS");
+        reap_stale_upload_sessions(&mut sessions);
+        sessions
+            .get(&upload_id)
+            .cloned()
+            .ok_or_else(|| format!("上传会话不存在: {}", upload_id))?
+    };
+    let mut session = crate::utils::lock_or_recover(session.as_ref(), "UploadSession");
+    if session.bytes_written + bytes.len() as u64 > MAX_UPLOAD_BYTES {
+        return Err(format!(
+            "上传文件超过 {} GB 上限",
+            MAX_UPLOAD_BYTES / 1024 / 1024 / 1024
+        ));
+    }
+    session
+        .file
+        .as_mut()
+        .ok_or_else(|| format!("上传会话已结束: {}", upload_id))?
+        .write_all(&bytes)
+        .map_err(|e| format!("写入临时文件失败: {}", e))?;
+    session.bytes_written += bytes.len() as u64;
+    session.last_activity = Instant::now();
+    Ok(session.bytes_written)
+}
+
+/// 完成分块上传：将临时文件重命名到上传目录（文件已存在时添加时间戳后缀），返回最终路径
+#[tauri::command]
+pub fn upload_file_finish(upload_id: String) -> Result<String, String> {
+    let session = crate::utils::lock_or_recover(upload_sessions(), "UPLOAD_SESSIONS")
+        .remove(&upload_id)
+        .ok_or_else(|| format!("上传会话不存在: {}", upload_id))?;
+    // 等待进行中的数据块写入完成
+    let mut session = crate::utils::lock_or_recover(session.as_ref(), "UploadSession");
+    let file = session
+        .file
+        .take()
+        .ok_or_else(|| format!("上传会话已结束: {}", upload_id))?;
+
+    if let Err(e) = file.sync_all() {
+        let _ = fs::remove_file(&session.temp_path);
+        return Err(format!("写入临时文件失败: {}", e));
+    }
+    drop(file);
+
+    let final_path = unique_upload_path(&session.uploads_dir, &session.file_name);
+    if let Err(e) = fs::rename(&session.temp_path, &final_path) {
+        let _ = fs::remove_file(&session.temp_path);
+        return Err(format!("保存文件失败: {}", e));
+    }
+    log::info!(
+        "分块上传完成: {} ({} 字节)",
+        final_path.display(),
+        session.bytes_written
+    );
+
+    let abs_path = final_path.canonicalize().unwrap_or(final_path);
+    Ok(abs_path.to_string_lossy().to_string())
+}
+
+/// 取消分块上传并删除临时文件，返回上传会话是否存在
+#[tauri::command]
+pub fn upload_file_abort(upload_id: String) -> Result<bool, String> {
+    let Some(session) =
+        crate::utils::lock_or_recover(upload_sessions(), "UPLOAD_SESSIONS").remove(&upload_id)
+    else {
+        return Ok(false);
+    };
+    let mut session = crate::utils::lock_or_recover(session.as_ref(), "UploadSession");
+    session.file = None;
+    fs::remove_file(&session.temp_path).map_err(|e| format!("删除临时文件失败: {}", e))?;
+    log::info!("已取消分块上传: {}", upload_id);
+    Ok(true)
+}
+
+/// 检查路径末端的符号链接：失效（目标不存在）或循环时返回说明，其他情况返回 None
+fn diagnose_symlink(path: &Path) -> Option<String> {
+    let mut current = path.to_path_buf();
+    let mut seen = HashSet::new();
+    for _ in 0..MAX_SYMLINK_HOPS {
+        let meta = fs::symlink_metadata(&current).ok()?;
+        if !meta.file_type().is_symlink() {
+            return None;
+        }
+        if !seen.insert(current.clone()) {
+            return Some("路径存在符号链接循环".to_string());
+        }
+        let target = fs::read_link(&current).ok()?;
+        current = match current.parent() {
+            Some(parent) if target.is_relative() => parent.join(target),
+            _ => target,
+        };
+        if fs::symlink_metadata(&current).is_err() {
+            return Some(format!(
+                "路径是失效的符号链接（目标不存在: {}）",
+                current.display()
+            ));
+        }
+    }
+    Some("符号链接层级过深或存在循环".to_string())
+}
+
+/// 是否为网络路径（UNC：\\server\share 或 //server/share）
+fn is_network_path(path: &str) -> bool {
+    (path.starts_with("\\\\") && !path.starts_with("\\\\?\\")) || path.starts_with("//")
+}
+
+/// 规范化路径，失败时区分失效符号链接、符号链接循环与网络路径不可达
+fn canonicalize_path(path: &Path, original: &str) -> Result<PathBuf, String> {
+    path.canonicalize().map_err(|e| {
+        if let Some(reason) = diagnose_symlink(path) {
+            format!("无法解析路径 {}: {}", original, reason)
+        } else if is_network_path(original) {
+            format!("无法解析路径 {}: 网络路径不可访问: {}", original, e)
+        } else {
+            format!("无法解析路径 {}: {}", original, e)
+        }
+    })
+}
+
+/// 将规范化后的路径转换为常规格式：\\?\UNC\server\share 还原为 \\server\share，
+/// 其他路径移除 Windows 长路径前缀 (\\?\)
+fn display_path(path: &Path) -> String {
+    let path_str = path.to_string_lossy();
+    if let Some(unc) = path_str.strip_prefix("\\\\?\\UNC\\") {
+        format!("\\\\{}", unc)
+    } else if let Some(local) = path_str.strip_prefix("\\\\?\\") {
+        local.to_string()
+    } else {
+        path_str.to_string()
+    }
+}
+
 /// 解析文件路径为绝对路径
 /// 支持相对路径和绝对路径，总是返回规范化的绝对路径
 /// 如果是相对路径且在当前目录找不到，会在 PATH 环境变量中查找
@@ -199,8 +491,7 @@ pub fn resolve_file_path(params: ResolveFilePathParams) -> Result<String, String
 
     // 如果已经是绝对路径，直接规范化
     let abs_path = if path.is_absolute() {
-        path.canonicalize()
-            .map_err(|e| format!("无法解析路径 {}: {}", file_path, e))?
+        canonicalize_path(path, &file_path)?
     } else {
         // 如果是相对路径，尝试从当前工作目录解析
         let current_dir =
@@ -208,6 +499,11 @@ pub fn resolve_file_path(params: ResolveFilePathParams) -> Result<String, String
 
         let joined_path = current_dir.join(path);
 
+        // exists() 对失效的符号链接返回 false，此时直接报告具体原因
+        if let Some(reason) = diagnose_symlink(&joined_path) {
+            return Err(format!("无法解析路径 {}: {}", file_path, reason));
+        }
+
         // 如果文件不存在，尝试在 PATH 环境变量中查找
         if !joined_path.exists() {
             // 尝试在 PATH 中查找可执行文件
@@ -219,16 +515,8 @@ pub fn resolve_file_path(params: ResolveFilePathParams) -> Result<String, String
                         for path_dir in path_env.split(';') {
                             let test_path = Path::new(path_dir).join(file_name);
                             if test_path.exists() {
-                                let canonical = test_path
-                                    .canonicalize()
-                                    .map_err(|e| format!("无法解析路径 {}: {}", file_path, e))?;
-                                let final_path_str = canonical.to_string_lossy().to_string();
-                                let final_path_str = if final_path_str.starts_with("\\\\?\\") {
-                                    &final_path_str[4..]
-                                } else {
-                                    &final_path_str
-                                }
-                                .to_string();
+                                let canonical = canonicalize_path(&test_path, &file_path)?;
+                                let final_path_str = display_path(&canonical);
                                 log::info!("解析文件路径: 在 PATH 中找到='{}'", final_path_str);
                                 return Ok(final_path_str);
                             }
@@ -243,10 +531,8 @@ pub fn resolve_file_path(params: ResolveFilePathParams) -> Result<String, String
                         for path_dir in path_env.split(':') {
                             let test_path = Path::new(path_dir).join(file_name);
                             if test_path.exists() {
-                                let canonical = test_path
-                                    .canonicalize()
-                                    .map_err(|e| format!("无法解析路径 {}: {}", file_path, e))?;
-                                let final_path_str = canonical.to_string_lossy().to_string();
+                                let canonical = canonicalize_path(&test_path, &file_path)?;
+                                let final_path_str = display_path(&canonical);
                                 log::info!("解析文件路径: 在 PATH 中找到='{}'", final_path_str);
                                 return Ok(final_path_str);
                             }
@@ -259,17 +545,97 @@ pub fn resolve_file_path(params: ResolveFilePathParams) -> Result<String, String
             return Err(format!("无法解析路径 {}: 文件不存在", file_path));
         }
 
-        joined_path
-            .canonicalize()
-            .map_err(|e| format!("无法解析路径 {}: {}", file_path, e))?
+        canonicalize_path(&joined_path, &file_path)?
     };
 
-    // 移除 Windows 长路径前缀 (\\?\)，返回标准路径格式
-    let mut final_path_str = abs_path.to_string_lossy().to_string();
-    if final_path_str.starts_with("\\\\?\\") {
-        final_path_str = final_path_str[4..].to_string();
-    }
+    // 移除 Windows 长路径前缀 (\\?\)，返回标准路径格式（UNC 路径保留 \\server\share 形式）
+    let final_path_str = display_path(&abs_path);
 
     log::info!("解析文件路径: 绝对='{}'", final_path_str);
     Ok(final_path_str)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 临时目录夹具（测试结束时删除）
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "netsec-toolbox-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir.canonicalize().unwrap())
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn resolve(path: &Path) -> Result<String, String> {
+        resolve_file_path(ResolveFilePathParams {
+            file_path: path.to_string_lossy().to_string(),
+        })
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dangling_symlink_is_reported() {
+        let dir = TempDir::new("dangling-symlink");
+        let link = dir.0.join("tool");
+        std::os::unix::fs::symlink(dir.0.join("missing-target"), &link).unwrap();
+
+        let err = resolve(&link).unwrap_err();
+        assert!(err.contains("失效的符号链接"), "{}", err);
+        assert!(err.contains("missing-target"), "{}", err);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_loop_is_reported() {
+        let dir = TempDir::new("symlink-loop");
+        let a = dir.0.join("a");
+        let b = dir.0.join("b");
+        std::os::unix::fs::symlink(&b, &a).unwrap();
+        std::os::unix::fs::symlink(&a, &b).unwrap();
+
+        let err = resolve(&a).unwrap_err();
+        assert!(err.contains("循环"), "{}", err);
+    }
+
+    #[test]
+    fn parent_components_escaping_the_root_are_normalized() {
+        let dir = TempDir::new("parent-escape");
+        let root = dir.0.join("root");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        let outside = dir.0.join("outside.txt");
+        fs::write(&outside, b"x").unwrap();
+
+        // root/sub/../../outside.txt 越出 root：返回规范化后的真实路径，不保留 ..
+        let resolved =
+            resolve(&root.join("sub").join("..").join("..").join("outside.txt")).unwrap();
+        assert_eq!(resolved, display_path(&outside));
+        assert!(!resolved.contains(".."));
+
+        // 越出后目标不存在时报错
+        let err = resolve(&root.join("..").join("..").join("no-such-file")).unwrap_err();
+        assert!(err.contains("无法解析路径"), "{}", err);
+    }
+
+    #[test]
+    fn upload_tool_id_must_be_a_single_directory_name() {
+        for tool_id in ["", "..", ".", "a/b", "../escape", "/etc", "a/../b"] {
+            let err = upload_target_dir(Some(tool_id.to_string())).unwrap_err();
+            assert!(err.contains("无效的工具 ID"), "{}: {}", tool_id, err);
+        }
+    }
+}
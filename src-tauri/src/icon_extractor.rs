@@ -1,12 +1,229 @@
-use crate::types::ExtractIconParams;
-use crate::utils::{get_icons_dir, hash_path};
+use crate::types::{ExtractIconParams, FetchFaviconParams, ToolItem};
+use crate::utils::{get_config_dir, get_icons_dir, hash_path};
 use base64::{engine::general_purpose, Engine as _};
 use image::{DynamicImage, GenericImageView};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use sha2::Digest;
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// 图标下载的超时时间（连接与整体请求）
+const ICON_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 图标下载最多跟随的重定向次数
+const ICON_FETCH_MAX_REDIRECTS: usize = 5;
+
+/// 图标下载响应体大小上限（2 MB，页面 HTML 同样适用）
+const ICON_FETCH_MAX_BYTES: u64 = 2 * 1024 * 1024;
+
+/// 默认图标尺寸（缓存文件同样按此尺寸保存）
+const DEFAULT_ICON_SIZE: u32 = 160;
+
+/// 扫描目录导入工具时的最大递归深度
+const TOOL_SCAN_MAX_DEPTH: usize = 4;
+
+/// 扫描目录导入工具时最多返回的工具数（避免误选大目录时长时间提取图标）
+const TOOL_SCAN_MAX_TOOLS: usize = 200;
+
+/// 扫描目录时跳过的目录名（依赖与缓存目录中不会有需要导入的工具）
+const TOOL_SCAN_SKIP_DIRS: [&str; 4] = ["node_modules", "__pycache__", "site-packages", "venv"];
+
+/// 调用方可请求的图标尺寸范围
+const ICON_SIZE_RANGE: std::ops::RangeInclusive<u32> = 16..=256;
+
+static ICON_HTTP_CLIENT: OnceLock<Result<reqwest::blocking::Client, String>> = OnceLock::new();
+
+/// 图标下载共用的 HTTP 客户端（超时、重定向次数上限、User-Agent）
+fn icon_http_client() -> Result<&'static reqwest::blocking::Client, String> {
+    ICON_HTTP_CLIENT
+        .get_or_init(|| {
+            reqwest::blocking::Client::builder()
+                .connect_timeout(ICON_FETCH_TIMEOUT)
+                .timeout(ICON_FETCH_TIMEOUT)
+                .redirect(reqwest::redirect::Policy::limited(ICON_FETCH_MAX_REDIRECTS))
+                .user_agent(concat!("NetSecToolbox/", env!("CARGO_PKG_VERSION")))
+                .build()
+                .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))
+        })
+        .as_ref()
+        .map_err(|e| e.clone())
+}
+
+/// 读取响应体，超过大小上限时报错（避免恶意服务器返回超大响应占满内存）
+fn read_limited_body(response: reqwest::blocking::Response) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+
+    if response
+        .content_length()
+        .is_some_and(|len| len > ICON_FETCH_MAX_BYTES)
+    {
+        return Err(format!("响应体过大（超过 {} 字节）", ICON_FETCH_MAX_BYTES));
+    }
+    let mut body = Vec::new();
+    response
+        .take(ICON_FETCH_MAX_BYTES + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| format!("读取响应失败: {}", e))?;
+    if body.len() as u64 > ICON_FETCH_MAX_BYTES {
+        return Err(format!("响应体过大（超过 {} 字节）", ICON_FETCH_MAX_BYTES));
+    }
+    Ok(body)
+}
+
+/// favicon 缓存的上游校验信息（与缓存 PNG 同名的 .meta.json 旁路文件）
+#[derive(Debug, Serialize, Deserialize)]
+struct FaviconValidators {
+    /// 实际下载到图标的地址（条件请求发往该地址）
+    icon_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+}
+
+impl FaviconValidators {
+    /// 从响应头提取 ETag / Last-Modified（两者都没有时返回 None）
+    fn from_response(icon_url: &url::Url, response: &reqwest::blocking::Response) -> Option<Self> {
+        let header = |name: reqwest::header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        let etag = header(reqwest::header::ETAG);
+        let last_modified = header(reqwest::header::LAST_MODIFIED);
+        if etag.is_none() && last_modified.is_none() {
+            return None;
+        }
+        Some(Self {
+            icon_url: icon_url.to_string(),
+            etag,
+            last_modified,
+        })
+    }
+
+    fn sidecar_path(cache_path: &Path) -> PathBuf {
+        cache_path.with_extension("meta.json")
+    }
+
+    fn load(cache_path: &Path) -> Option<Self> {
+        let data = fs::read(Self::sidecar_path(cache_path)).ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    /// 保存或删除缓存的校验信息（服务器未提供校验信息时删除旧的旁路文件）
+    fn store(cache_path: &Path, validators: Option<&Self>) {
+        let sidecar = Self::sidecar_path(cache_path);
+        let Some(validators) = validators else {
+            let _ = fs::remove_file(&sidecar);
+            return;
+        };
+        let result = serde_json::to_vec_pretty(validators)
+            .map_err(|e| e.to_string())
+            .and_then(|data| fs::write(&sidecar, data).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            log::warn!("保存 favicon 校验信息失败: {} ({})", sidecar.display(), e);
+        }
+    }
+}
+
+/// 按缓存的校验信息发起条件请求：上游返回新图标时更新缓存并返回新图标；
+/// 304、请求失败或新图标无法解析时返回 None（继续使用缓存）
+fn revalidate_favicon(
+    cache_path: &Path,
+    validators: &FaviconValidators,
+    size: u32,
+) -> Option<DynamicImage> {
+    let client = icon_http_client().ok()?;
+    let mut request = client.get(&validators.icon_url);
+    if let Some(etag) = &validators.etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+    }
+    let response = match request.send() {
+        Ok(response) => response,
+        Err(e) => {
+            log::debug!(
+                "favicon 重新验证失败，使用缓存: {} ({})",
+                validators.icon_url,
+                e
+            );
+            return None;
+        }
+    };
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED || !response.status().is_success() {
+        return None;
+    }
+
+    let icon_url = url::Url::parse(&validators.icon_url).ok()?;
+    let fresh_validators = FaviconValidators::from_response(&icon_url, &response);
+    let bytes = read_limited_body(response).ok()?;
+    let img = load_icon_image(&bytes, DEFAULT_ICON_SIZE.max(size)).ok()?;
+    log::debug!("favicon 已更新: {}", validators.icon_url);
+    match write_icon_cache(cache_path, &img) {
+        Ok(()) => FaviconValidators::store(cache_path, fresh_validators.as_ref()),
+        Err(e) => log::warn!("Favicon 缓存失败: {}", e),
+    }
+    Some(img)
+}
+
+/// 调用方请求的图标尺寸（未指定时使用默认尺寸，超出范围时截断）
+fn requested_icon_size(size: Option<u32>) -> u32 {
+    size.unwrap_or(DEFAULT_ICON_SIZE)
+        .clamp(*ICON_SIZE_RANGE.start(), *ICON_SIZE_RANGE.end())
+}
+
+/// 解码图标数据：多帧 ICO 选择不小于目标尺寸的最小帧（都小于目标尺寸时取最大帧），
+/// 避免选中低分辨率帧后放大导致模糊；其他格式交给 image 库
+fn load_icon_image(bytes: &[u8], size: u32) -> image::ImageResult<DynamicImage> {
+    if bytes.starts_with(&[0, 0, 1, 0]) {
+        if let Some(img) = select_ico_frame(bytes, size) {
+            return Ok(img);
+        }
+    }
+    image::load_from_memory(bytes)
+}
+
+/// 从 ICO 中选择最合适的帧并解码（解析失败时返回 None）
+fn select_ico_frame(bytes: &[u8], size: u32) -> Option<DynamicImage> {
+    let dir = ico::IconDir::read(std::io::Cursor::new(bytes)).ok()?;
+    let edge = |entry: &ico::IconDirEntry| entry.width().max(entry.height());
+    // 排序键：足够大的帧优先，其中越接近目标越好；都不够大时越大越好；同尺寸取色深更高的帧
+    let entry = dir.entries().iter().min_by_key(|entry| {
+        let edge = edge(entry);
+        let fits = edge >= size;
+        let distance = if fits { edge - size } else { size - edge };
+        (!fits, distance, std::cmp::Reverse(entry.bits_per_pixel()))
+    })?;
+    let decoded = entry.decode().ok()?;
+    log::debug!(
+        "ICO 包含 {} 帧，选择 {}x{} 帧（目标 {}）",
+        dir.entries().len(),
+        decoded.width(),
+        decoded.height(),
+        size
+    );
+    image::RgbaImage::from_raw(
+        decoded.width(),
+        decoded.height(),
+        decoded.rgba_data().to_vec(),
+    )
+    .map(DynamicImage::ImageRgba8)
+}
+
+/// 读取图标文件（ICO 按目标尺寸选择帧）
+fn open_icon_file(path: &Path, size: u32) -> Result<DynamicImage, String> {
+    let bytes = fs::read(path).map_err(|e| format!("读取图标失败: {} ({})", path.display(), e))?;
+    load_icon_image(&bytes, size).map_err(|e| format!("加载图标失败: {} ({})", path.display(), e))
+}
 
 /// 将图标转换为统一尺寸的 PNG base64
 /// 容错处理：不假设输入图像的尺寸，总是调整到目标尺寸
@@ -176,7 +393,7 @@ try {
 
 /// 提取 LNK 图标（Windows）
 /// 使用 PowerShell/.NET 解析快捷方式并提取图标
-/// 优先使用快捷方式的 IconLocation，如果没有则使用目标路径的图标
+/// 优先使用快捷方式的 IconLocation（按索引提取），其次目标路径的图标，最后回退到目标文件类型的默认图标
 /// 参考代码：resolve_lnk_icon_base64
 #[cfg(target_os = "windows")]
 fn extract_lnk_icon(lnk_path: &str) -> Result<DynamicImage, String> {
@@ -197,6 +414,8 @@ fn extract_lnk_icon(lnk_path: &str) -> Result<DynamicImage, String> {
 
     // 使用环境变量传递路径，避免 PowerShell 脚本中的路径转义问题
     // 这样可以正确处理包含中文、空格、特殊字符的路径
+    // IconLocation 形如 "shell32.dll,23"，通过 ExtractIconEx 按索引提取；
+    // 目标本身是 .lnk 时再解析一层；都提取不到时回退到目标文件类型的默认图标（SHGetFileInfo）
     let script = r#"
 $ErrorActionPreference = 'Stop'
 [Console]::OutputEncoding = [System.Text.Encoding]::UTF8
@@ -206,32 +425,104 @@ try {
     Write-Error "环境变量 ICON_LNK_PATH 未设置"
     exit 1
   }
-  $s = (New-Object -ComObject WScript.Shell).CreateShortcut($lnkPath)
-  $icon = $s.IconLocation
-  if ([string]::IsNullOrEmpty($icon)) {
-    $icon = $s.TargetPath
-  }
-  if ([string]::IsNullOrEmpty($icon)) {
-    Write-Error "无法获取图标路径"
-    exit 1
-  }
-  $parts = $icon -split ','
-  $iconFile = $parts[0].Trim()
-  if (-not (Test-Path $iconFile)) {
-    Write-Error "图标文件不存在: $iconFile"
-    exit 1
-  }
   Add-Type -AssemblyName System.Drawing
-  $ic = [System.Drawing.Icon]::ExtractAssociatedIcon($iconFile)
-  if ($ic -ne $null) {
-    $bmp = $ic.ToBitmap()
+  Add-Type -Namespace NetsecIcon -Name Native -MemberDefinition @'
+[DllImport("shell32.dll", CharSet = CharSet.Unicode)]
+public static extern uint ExtractIconEx(string file, int index, IntPtr[] large, IntPtr[] small, uint count);
+[DllImport("shell32.dll", CharSet = CharSet.Unicode)]
+public static extern IntPtr SHGetFileInfo(string path, uint attrs, ref SHFILEINFO info, uint size, uint flags);
+[DllImport("user32.dll")]
+public static extern bool DestroyIcon(IntPtr handle);
+[StructLayout(LayoutKind.Sequential, CharSet = CharSet.Unicode)]
+public struct SHFILEINFO {
+  public IntPtr hIcon;
+  public int iIcon;
+  public uint dwAttributes;
+  [MarshalAs(UnmanagedType.ByValTStr, SizeConst = 260)] public string szDisplayName;
+  [MarshalAs(UnmanagedType.ByValTStr, SizeConst = 80)] public string szTypeName;
+}
+'@
+
+  function ConvertTo-PngBase64($icon) {
+    $bmp = $icon.ToBitmap()
     $ms = New-Object System.IO.MemoryStream
     $bmp.Save($ms, [System.Drawing.Imaging.ImageFormat]::Png)
-    [Convert]::ToBase64String($ms.ToArray())
-  } else {
+    return [Convert]::ToBase64String($ms.ToArray())
+  }
+
+  function Convert-IconHandle($handle) {
+    if ($handle -eq [IntPtr]::Zero) { return $null }
+    try {
+      return ConvertTo-PngBase64 ([System.Drawing.Icon]::FromHandle($handle))
+    } finally {
+      [void][NetsecIcon.Native]::DestroyIcon($handle)
+    }
+  }
+
+  # 按索引提取图标（负数索引表示资源 ID）
+  function Get-IndexedIcon($file, [int]$index) {
+    $large = New-Object IntPtr[] 1
+    $small = New-Object IntPtr[] 1
+    [void][NetsecIcon.Native]::ExtractIconEx($file, $index, $large, $small, 1)
+    if ($small[0] -ne [IntPtr]::Zero) { [void][NetsecIcon.Native]::DestroyIcon($small[0]) }
+    return Convert-IconHandle $large[0]
+  }
+
+  function Get-AssociatedIcon($file) {
+    if ([string]::IsNullOrEmpty($file) -or -not (Test-Path -LiteralPath $file)) { return $null }
+    $ic = [System.Drawing.Icon]::ExtractAssociatedIcon($file)
+    if ($ic -eq $null) { return $null }
+    return ConvertTo-PngBase64 $ic
+  }
+
+  # 文件类型默认图标（SHGFI_ICON | SHGFI_USEFILEATTRIBUTES，文件不存在时同样可用）
+  function Get-FileTypeIcon($file) {
+    $info = New-Object NetsecIcon.Native+SHFILEINFO
+    $size = [System.Runtime.InteropServices.Marshal]::SizeOf($info)
+    [void][NetsecIcon.Native]::SHGetFileInfo($file, 0x80, [ref]$info, [uint32]$size, 0x110)
+    return Convert-IconHandle $info.hIcon
+  }
+
+  # 解析 IconLocation（"文件,索引"），未设置图标文件时返回 $null
+  function Split-IconLocation($location) {
+    if ([string]::IsNullOrEmpty($location)) { return $null }
+    $file = $location
+    $index = 0
+    $sep = $location.LastIndexOf(',')
+    if ($sep -ge 0) {
+      $file = $location.Substring(0, $sep)
+      [void][int]::TryParse($location.Substring($sep + 1).Trim(), [ref]$index)
+    }
+    $file = [Environment]::ExpandEnvironmentVariables($file.Trim().Trim('"'))
+    if ([string]::IsNullOrEmpty($file)) { return $null }
+    return @($file, $index)
+  }
+
+  $shell = New-Object -ComObject WScript.Shell
+  $s = $shell.CreateShortcut($lnkPath)
+  $iconLocation = Split-IconLocation $s.IconLocation
+  $target = $s.TargetPath
+  if ($target -like '*.lnk' -and (Test-Path -LiteralPath $target)) {
+    $inner = $shell.CreateShortcut($target)
+    if ($iconLocation -eq $null) { $iconLocation = Split-IconLocation $inner.IconLocation }
+    $target = $inner.TargetPath
+  }
+
+  $result = $null
+  if ($iconLocation -ne $null -and (Test-Path -LiteralPath $iconLocation[0])) {
+    $result = Get-IndexedIcon $iconLocation[0] $iconLocation[1]
+    if (-not $result) { $result = Get-AssociatedIcon $iconLocation[0] }
+  }
+  if (-not $result) { $result = Get-AssociatedIcon $target }
+  if (-not $result) {
+    $typeSource = if ([string]::IsNullOrEmpty($target)) { $lnkPath } else { $target }
+    $result = Get-FileTypeIcon $typeSource
+  }
+  if (-not $result) {
     Write-Error "无法提取图标"
     exit 1
   }
+  $result
 } catch {
   Write-Error $_.Exception.Message
   exit 1
@@ -266,47 +557,363 @@ try {
     }
 }
 
-/// 提取 HTML 文件的图标
-fn extract_html_icon(html_path: &str) -> Result<DynamicImage, String> {
-    // 读取 HTML 文件内容
-    let content =
-        fs::read_to_string(html_path).map_err(|e| format!("读取 HTML 文件失败: {}", e))?;
+/// 默认图标（灰色占位图），找不到图标时使用
+fn placeholder_icon() -> DynamicImage {
+    DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
+        160,
+        160,
+        image::Rgba([200, 200, 200, 255]),
+    ))
+}
 
-    // 查找 favicon 链接
-    let favicon_re = Regex::new(
-        r#"(?i)<link[^>]+rel=["'](?:icon|shortcut\s+icon)["'][^>]*href=["']([^"']+)["']"#,
-    )
-    .map_err(|e| format!("正则表达式错误: {}", e))?;
+/// 提取可执行文件图标（macOS）
+/// 定位所在的 .app 应用包，读取 Info.plist 中的 CFBundleIconFile，
+/// 再用系统自带的 sips 将 .icns 转换为 PNG（image 库不支持 ICNS）
+#[cfg(target_os = "macos")]
+fn extract_exe_icon(file_path: &str) -> Result<DynamicImage, String> {
+    // 可执行文件通常位于 Foo.app/Contents/MacOS/ 下，也可能直接选择 .app 目录
+    let bundle = Path::new(file_path)
+        .ancestors()
+        .find(|p| {
+            p.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("app"))
+        })
+        .ok_or_else(|| format!("文件不在 .app 应用包内: {}", file_path))?;
+    let contents = bundle.join("Contents");
+    let resources = contents.join("Resources");
+    let plist_path = contents.join("Info.plist");
 
-    if let Some(cap) = favicon_re.captures(&content) {
-        if let Some(favicon_path) = cap.get(1) {
-            let favicon_url = favicon_path.as_str();
+    // XML 格式的 plist 直接解析，二进制格式交给 plutil
+    let icon_name = fs::read_to_string(&plist_path)
+        .ok()
+        .and_then(|plist| {
+            Regex::new(r"<key>CFBundleIconFile</key>\s*<string>([^<]+)</string>")
+                .ok()?
+                .captures(&plist)
+                .map(|c| c[1].trim().to_string())
+        })
+        .or_else(|| {
+            let output = Command::new("plutil")
+                .args(["-extract", "CFBundleIconFile", "raw", "-o", "-"])
+                .arg(&plist_path)
+                .output()
+                .ok()?;
+            let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (output.status.success() && !name.is_empty()).then_some(name)
+        });
 
-            // 如果是相对路径，转换为绝对路径
-            let favicon_abs_path = if favicon_url.starts_with("http://")
-                || favicon_url.starts_with("https://")
-            {
-                return Err("HTML 文件中的绝对 URL favicon 需要使用 fetch_favicon 命令".to_string());
+    // CFBundleIconFile 可以省略 .icns 扩展名；未声明时取 Resources 下的第一个 .icns
+    let icns_path = icon_name
+        .map(|name| {
+            if name.to_lowercase().ends_with(".icns") {
+                resources.join(name)
+            } else {
+                resources.join(format!("{}.icns", name))
+            }
+        })
+        .filter(|p| p.is_file())
+        .or_else(|| {
+            fs::read_dir(&resources)
+                .ok()?
+                .flatten()
+                .map(|entry| entry.path())
+                .find(|p| p.extension().and_then(|e| e.to_str()) == Some("icns"))
+        })
+        .ok_or_else(|| format!("应用包中未找到 .icns 图标: {}", bundle.display()))?;
+
+    let png_path = std::env::temp_dir().join(format!(
+        "netsec_icns_{}.png",
+        hash_path(&icns_path.to_string_lossy())
+    ));
+    let output = Command::new("sips")
+        .args(["-s", "format", "png"])
+        .arg(&icns_path)
+        .arg("--out")
+        .arg(&png_path)
+        .output()
+        .map_err(|e| format!("执行 sips 命令失败: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("sips 转换 ICNS 失败: {}", stderr));
+    }
+
+    let img = image::open(&png_path).map_err(|e| format!("加载图片失败: {}", e));
+    let _ = fs::remove_file(&png_path);
+    img
+}
+
+/// XDG 数据目录（XDG_DATA_HOME 在前，随后是 XDG_DATA_DIRS）
+#[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+fn xdg_data_dirs() -> Vec<std::path::PathBuf> {
+    let mut dirs = Vec::new();
+    match std::env::var_os("XDG_DATA_HOME").filter(|d| !d.is_empty()) {
+        Some(dir) => dirs.push(std::path::PathBuf::from(dir)),
+        None => {
+            if let Some(home) = std::env::var_os("HOME") {
+                dirs.push(std::path::PathBuf::from(home).join(".local/share"));
+            }
+        }
+    }
+    let system = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(
+        system
+            .split(':')
+            .filter(|d| !d.is_empty())
+            .map(std::path::PathBuf::from),
+    );
+    dirs
+}
+
+/// 读取 .desktop 文件中 [Desktop Entry] 段的键值
+#[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+fn desktop_entry_value(content: &str, key: &str) -> Option<String> {
+    let mut in_entry = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        if let Some((k, v)) = line.split_once('=') {
+            if k.trim() == key {
+                return Some(v.trim().to_string());
+            }
+        }
+    }
+    None
+}
+
+/// 查找 Exec 指向该可执行文件的 .desktop 条目，返回其 Icon 值
+/// Exec 为绝对路径时要求完全一致，为命令名时与文件名比较
+#[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+fn find_desktop_icon(exec_path: &Path) -> Option<String> {
+    let file_name = exec_path.file_name()?.to_str()?;
+    for dir in xdg_data_dirs() {
+        let Ok(entries) = fs::read_dir(dir.join("applications")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("desktop") {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let Some(exec) = desktop_entry_value(&content, "Exec") else {
+                continue;
+            };
+            let program = exec
+                .split_whitespace()
+                .next()
+                .unwrap_or("")
+                .trim_matches('"');
+            let matched = if program.contains('/') {
+                Path::new(program) == exec_path
             } else {
-                let html_dir = Path::new(html_path)
-                    .parent()
-                    .ok_or("无法获取 HTML 文件目录")?;
-                html_dir.join(favicon_url)
+                program == file_name
             };
+            if matched {
+                if let Some(icon) = desktop_entry_value(&content, "Icon") {
+                    log::debug!("匹配到 .desktop 条目: {}", path.display());
+                    return Some(icon);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// 解析 .desktop 中的 Icon：绝对路径直接使用，
+/// 图标名在 hicolor 主题中按尺寸从大到小查找 PNG，最后查找 pixmaps
+#[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+fn resolve_xdg_icon(icon: &str) -> Option<std::path::PathBuf> {
+    const ICON_SIZES: [&str; 8] = [
+        "512x512", "256x256", "192x192", "128x128", "96x96", "64x64", "48x48", "32x32",
+    ];
+
+    let icon_path = Path::new(icon);
+    if icon_path.is_absolute() {
+        return icon_path.is_file().then(|| icon_path.to_path_buf());
+    }
+
+    let file_name = if icon.to_lowercase().ends_with(".png") {
+        icon.to_string()
+    } else {
+        format!("{}.png", icon)
+    };
+    let data_dirs = xdg_data_dirs();
+    let mut theme_dirs: Vec<std::path::PathBuf> = Vec::new();
+    if let Some(home) = std::env::var_os("HOME") {
+        theme_dirs.push(std::path::PathBuf::from(home).join(".icons"));
+    }
+    theme_dirs.extend(data_dirs.iter().map(|d| d.join("icons")));
 
-            if favicon_abs_path.exists() {
-                return image::open(&favicon_abs_path)
-                    .map_err(|e| format!("加载 favicon 图片失败: {}", e));
+    for size in ICON_SIZES {
+        for theme_dir in &theme_dirs {
+            let candidate = theme_dir
+                .join("hicolor")
+                .join(size)
+                .join("apps")
+                .join(&file_name);
+            if candidate.is_file() {
+                return Some(candidate);
             }
         }
     }
+    data_dirs
+        .iter()
+        .map(|d| d.join("pixmaps").join(&file_name))
+        .find(|p| p.is_file())
+}
+
+/// 可执行文件同目录下的图标（便携版工具、解压后的 AppImage 常见布局）
+#[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+fn sibling_icon(exec_path: &Path) -> Option<std::path::PathBuf> {
+    let dir = exec_path.parent()?;
+    let stem = exec_path.file_stem()?.to_str()?;
+    [
+        dir.join(format!("{}.png", stem)),
+        dir.join(".DirIcon"),
+        dir.join("icon.png"),
+    ]
+    .into_iter()
+    .find(|p| p.is_file())
+}
+
+/// 提取可执行文件图标（Linux）
+/// 依次尝试：.desktop 条目的 Icon（XDG 图标主题）、同目录下的图标文件
+#[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+fn extract_exe_icon(file_path: &str) -> Result<DynamicImage, String> {
+    let path = Path::new(file_path);
+    // 直接选择 .desktop 文件时读取其自身的 Icon
+    let icon_name = if path.extension().and_then(|e| e.to_str()) == Some("desktop") {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| desktop_entry_value(&content, "Icon"))
+    } else {
+        find_desktop_icon(path)
+    };
+
+    let icon_path = icon_name
+        .as_deref()
+        .and_then(resolve_xdg_icon)
+        .or_else(|| sibling_icon(path))
+        .ok_or_else(|| {
+            format!(
+                "未找到图标（.desktop 条目 / 图标主题 / 同目录）: {}",
+                file_path
+            )
+        })?;
+    open_icon_file(&icon_path, DEFAULT_ICON_SIZE)
+}
+
+/// 页面中声明的图标链接
+#[derive(Debug, Clone)]
+struct IconLink {
+    href: String,
+    /// 声明的最大边长（sizes 属性，未声明时为 None）
+    size: Option<u32>,
+}
+
+/// 读取标签中的属性值（支持单引号、双引号和无引号写法，属性顺序任意）
+fn html_attr(tag: &str, name: &str) -> Option<String> {
+    let re = Regex::new(&format!(
+        r#"(?i)\s{}\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#,
+        regex::escape(name)
+    ))
+    .ok()?;
+    let cap = re.captures(tag)?;
+    cap.get(1)
+        .or_else(|| cap.get(2))
+        .or_else(|| cap.get(3))
+        .map(|m| m.as_str().trim().to_string())
+}
+
+/// 解析页面中所有图标链接（icon / shortcut icon / apple-touch-icon），按声明尺寸从大到小排序
+/// 未声明尺寸的链接保持文档顺序排在后面；apple-touch-icon 未声明尺寸时按默认的 180 计
+fn parse_icon_links(html: &str) -> Vec<IconLink> {
+    let Ok(link_re) = Regex::new(r"(?is)<link\b[^>]*>") else {
+        return Vec::new();
+    };
+    let mut links: Vec<IconLink> = link_re
+        .find_iter(html)
+        .filter_map(|m| {
+            let tag = m.as_str();
+            let rel = html_attr(tag, "rel")?.to_ascii_lowercase();
+            let rel_tokens: Vec<&str> = rel.split_whitespace().collect();
+            let is_apple = rel_tokens.iter().any(|t| t.starts_with("apple-touch-icon"));
+            if !is_apple && !rel_tokens.contains(&"icon") {
+                return None;
+            }
+            let href = html_attr(tag, "href").filter(|h| !h.is_empty())?;
+            // image 库无法解码 SVG
+            let is_svg = href.to_ascii_lowercase().ends_with(".svg")
+                || html_attr(tag, "type").is_some_and(|t| t.eq_ignore_ascii_case("image/svg+xml"));
+            if is_svg {
+                return None;
+            }
+            let size = html_attr(tag, "sizes")
+                .and_then(|sizes| {
+                    sizes
+                        .split_whitespace()
+                        .filter_map(|s| {
+                            let (w, h) = s
+                                .to_ascii_lowercase()
+                                .split_once('x')
+                                .map(|(w, h)| (w.parse::<u32>().ok(), h.parse::<u32>().ok()))?;
+                            Some(w?.max(h?))
+                        })
+                        .max()
+                })
+                .or(if is_apple { Some(180) } else { None });
+            Some(IconLink { href, size })
+        })
+        .collect();
+    // 稳定排序：有尺寸的按从大到小，未声明尺寸的保持原顺序
+    links.sort_by_key(|link| std::cmp::Reverse(link.size.map(|s| s as u64 + 1).unwrap_or(0)));
+    links
+}
+
+/// 提取 HTML 文件的图标
+fn extract_html_icon(html_path: &str) -> Result<DynamicImage, String> {
+    // 读取 HTML 文件内容
+    let content =
+        fs::read_to_string(html_path).map_err(|e| format!("读取 HTML 文件失败: {}", e))?;
+
+    // 按声明尺寸从大到小尝试页面中的图标链接
+    let html_dir = Path::new(html_path)
+        .parent()
+        .ok_or("无法获取 HTML 文件目录")?;
+    let mut has_remote_icon = false;
+    for link in parse_icon_links(&content) {
+        if link.href.starts_with("http://")
+            || link.href.starts_with("https://")
+            || link.href.starts_with("//")
+        {
+            has_remote_icon = true;
+            continue;
+        }
+        let favicon_abs_path = html_dir.join(&link.href);
+        if favicon_abs_path.exists() {
+            match open_icon_file(&favicon_abs_path, DEFAULT_ICON_SIZE) {
+                Ok(img) => return Ok(img),
+                Err(e) => log::debug!("加载 favicon 图片失败: {} ({})", link.href, e),
+            }
+        }
+    }
+    if has_remote_icon {
+        return Err("HTML 文件中的绝对 URL favicon 需要使用 fetch_favicon 命令".to_string());
+    }
 
     // 如果没有找到 favicon，返回默认图标
-    Ok(DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
-        160,
-        160,
-        image::Rgba([200, 200, 200, 255]),
-    )))
+    Ok(placeholder_icon())
 }
 
 /// 从文件提取图标（EXE/LNK/HTML）
@@ -314,6 +921,12 @@ fn extract_html_icon(html_path: &str) -> Result<DynamicImage, String> {
 pub fn extract_icon_from_file(params: ExtractIconParams) -> Result<String, String> {
     let file_path = params.file_path;
     let tool_type = params.tool_type;
+    let force = params.force.unwrap_or(false);
+    let size = requested_icon_size(params.size);
+
+    if force && params.icon_locked.unwrap_or(false) {
+        return Err("该工具的自定义图标已锁定，已跳过强制刷新".to_string());
+    }
 
     // 规范化路径（转换为绝对路径）
     let path = Path::new(&file_path);
@@ -354,14 +967,16 @@ pub fn extract_icon_from_file(params: ExtractIconParams) -> Result<String, Strin
     // 检查缓存（使用绝对路径作为缓存键）
     let cache_key = hash_path(&file_path_str);
     let cache_path = get_icons_dir().join(format!("{}.png", cache_key));
-    if cache_path.exists() {
+    if force {
+        log::info!("强制刷新图标，忽略缓存: {}", file_path_str);
+    } else if cache_path.exists() {
         // 从缓存读取
         match fs::read(&cache_path) {
             Ok(data) => {
                 match image::load_from_memory(&data) {
                     Ok(img) => {
                         log::info!("从缓存加载图标: {}", file_path_str);
-                        return process_icon_to_base64(img, 160);
+                        return process_icon_to_base64(img, size);
                     }
                     Err(_) => {
                         // 缓存文件损坏，删除并重新提取
@@ -405,7 +1020,11 @@ pub fn extract_icon_from_file(params: ExtractIconParams) -> Result<String, Strin
             }
             #[cfg(not(target_os = "windows"))]
             {
-                return Err("可执行文件图标提取仅在 Windows 上支持".to_string());
+                log::info!("提取可执行文件图标: {}", file_path_str);
+                extract_exe_icon(&file_path_str).unwrap_or_else(|e| {
+                    log::info!("{}，使用默认图标", e);
+                    placeholder_icon()
+                })
             }
         }
         "网页" => {
@@ -421,15 +1040,19 @@ pub fn extract_icon_from_file(params: ExtractIconParams) -> Result<String, Strin
             }
             #[cfg(not(target_os = "windows"))]
             {
-                return Err(format!("不支持的文件类型: {}", tool_type));
+                log::info!("未知类型，尝试作为可执行文件提取图标: {}", file_path_str);
+                extract_exe_icon(&file_path_str).unwrap_or_else(|e| {
+                    log::info!("{}，使用默认图标", e);
+                    placeholder_icon()
+                })
             }
         }
     };
 
-    // 处理图标尺寸（先调整尺寸，确保是 160x160）
+    // 缓存统一保存为 160x160，返回值按请求尺寸直接从原图缩放（避免二次缩放）
     // 不要假设图标是特定尺寸，Windows 可能返回任意尺寸的图标
     let resized_icon = icon_data.resize_exact(160, 160, image::imageops::FilterType::Lanczos3);
-    let base64 = process_icon_to_base64(resized_icon.clone(), 160)?;
+    let base64 = process_icon_to_base64(icon_data, size)?;
 
     log::info!(
         "图标提取成功: file_path={}, base64_length={}",
@@ -527,6 +1150,7 @@ pub fn save_icon_to_cache(icon_data: String) -> Result<String, String> {
 #[tauri::command]
 pub fn fetch_favicon(params: crate::types::FetchFaviconParams) -> Result<String, String> {
     let url_str = params.url_str;
+    let size = requested_icon_size(params.size);
     // 解析 URL
     let url = url::Url::parse(&url_str).map_err(|e| format!("无效的 URL: {}", e))?;
 
@@ -537,7 +1161,10 @@ pub fn fetch_favicon(params: crate::types::FetchFaviconParams) -> Result<String,
         match fs::read(&cache_path) {
             Ok(data) => match image::load_from_memory(&data) {
                 Ok(img) => {
-                    return process_icon_to_base64(img, 160);
+                    // 有上游校验信息时发起条件请求，图标变化后更新缓存；否则直接使用缓存
+                    let fresh = FaviconValidators::load(&cache_path)
+                        .and_then(|validators| revalidate_favicon(&cache_path, &validators, size));
+                    return process_icon_to_base64(fresh.unwrap_or(img), size);
                 }
                 Err(_) => {
                     let _ = fs::remove_file(&cache_path);
@@ -547,79 +1174,566 @@ pub fn fetch_favicon(params: crate::types::FetchFaviconParams) -> Result<String,
         }
     }
 
-    // 尝试多个常见的 favicon 路径
-    let base_url = format!("{}://{}", url.scheme(), url.host_str().unwrap_or(""));
-    let favicon_paths = vec![
-        format!("{}/favicon.ico", base_url),
-        format!("{}/favicon.png", base_url),
-        format!("{}/apple-touch-icon.png", base_url),
-    ];
+    let client = icon_http_client()?;
+
+    // 优先使用页面声明的图标（按尺寸从大到小），再回退到常见的固定路径
+    let mut candidates: Vec<url::Url> = Vec::new();
+    match client.get(url.as_str()).send() {
+        Ok(response) if response.status().is_success() => {
+            // 以重定向后的最终地址为基准解析相对 / 协议相对路径
+            let page_url = response.url().clone();
+            if let Ok(html) = read_limited_body(response) {
+                let html = String::from_utf8_lossy(&html);
+                candidates.extend(
+                    parse_icon_links(&html)
+                        .iter()
+                        .filter_map(|link| page_url.join(&link.href).ok()),
+                );
+            }
+        }
+        Ok(response) => log::debug!("获取页面失败: {} ({})", url, response.status()),
+        Err(e) => log::debug!("获取页面失败: {} ({})", url, e),
+    }
+    for path in ["/favicon.ico", "/favicon.png", "/apple-touch-icon.png"] {
+        if let Ok(fallback) = url.join(path) {
+            if !candidates.contains(&fallback) {
+                candidates.push(fallback);
+            }
+        }
+    }
 
     // 尝试下载 favicon
-    for favicon_url in favicon_paths {
-        match reqwest::blocking::get(&favicon_url) {
-            Ok(response) => {
-                if response.status().is_success() {
-                    match response.bytes() {
-                        Ok(bytes) => {
-                            match image::load_from_memory(&bytes) {
-                                Ok(img) => {
-                                    // 处理图标尺寸（确保是 160x160）
-                                    let resized_img = img.resize_exact(
-                                        160,
-                                        160,
-                                        image::imageops::FilterType::Lanczos3,
-                                    );
-                                    let base64 = process_icon_to_base64(resized_img.clone(), 160)?;
-
-                                    // 保存到缓存（使用调整后的图像）
-                                    let rgba = resized_img.to_rgba8();
-                                    let (rgba_width, rgba_height) = rgba.dimensions();
-                                    if rgba_width == 160 && rgba_height == 160 {
-                                        let mut png_data = Vec::new();
-                                        {
-                                            use image::codecs::png::PngEncoder;
-                                            use image::ImageEncoder;
-                                            let encoder = PngEncoder::new(&mut png_data);
-                                            encoder
-                                                .write_image(
-                                                    &rgba,
-                                                    160,
-                                                    160,
-                                                    image::ColorType::Rgba8.into(),
-                                                )
-                                                .map_err(|e| format!("PNG 编码失败: {}", e))?;
-                                        }
-                                        fs::write(&cache_path, &png_data)
-                                            .map_err(|e| format!("保存图标缓存失败: {}", e))?;
-                                    } else {
-                                        log::warn!(
-                      "Favicon 尺寸不匹配: 期望 160x160，实际 {}x{}，跳过缓存",
-                      rgba_width,
-                      rgba_height
-                    );
-                                    }
-
-                                    return Ok(base64);
-                                }
-                                Err(_) => continue,
-                            }
-                        }
-                        Err(_) => continue,
-                    }
-                }
+    for favicon_url in candidates {
+        let Ok(response) = client.get(favicon_url.as_str()).send() else {
+            continue;
+        };
+        if !response.status().is_success() {
+            continue;
+        }
+        let validators = FaviconValidators::from_response(response.url(), &response);
+        let bytes = match read_limited_body(response) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                log::debug!("下载 favicon 失败: {} ({})", favicon_url, e);
+                continue;
             }
-            Err(_) => continue,
+        };
+        let Ok(img) = load_icon_image(&bytes, DEFAULT_ICON_SIZE.max(size)) else {
+            continue;
+        };
+        log::debug!(
+            "使用 favicon: {} ({}x{})",
+            favicon_url,
+            img.width(),
+            img.height()
+        );
+        match write_icon_cache(&cache_path, &img) {
+            Ok(()) => FaviconValidators::store(&cache_path, validators.as_ref()),
+            Err(e) => log::warn!("Favicon 缓存失败: {}", e),
         }
+        return process_icon_to_base64(img, size);
     }
 
     // 如果所有路径都失败，返回默认图标
-    Ok(process_icon_to_base64(
-        DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
-            160,
-            160,
-            image::Rgba([200, 200, 200, 255]),
-        )),
-        160,
-    )?)
+    Ok(process_icon_to_base64(placeholder_icon(), size)?)
+}
+
+/// 工具图标解析结果
+#[derive(Debug, Serialize)]
+pub struct ResolvedToolIcon {
+    /// 图标来源：icon_url / custom / extracted / initials
+    pub source: String,
+    /// base64 数据 URL
+    pub data_url: String,
+}
+
+/// 从缓存文件读取图标（缓存损坏时删除并返回 None）
+fn read_cached_icon(cache_path: &Path) -> Option<String> {
+    let data = fs::read(cache_path).ok()?;
+    match image::load_from_memory(&data) {
+        Ok(img) => process_icon_to_base64(img, 160).ok(),
+        Err(_) => {
+            let _ = fs::remove_file(cache_path);
+            None
+        }
+    }
+}
+
+/// 将图标编码为 160x160 PNG 写入缓存
+fn write_icon_cache(cache_path: &Path, img: &DynamicImage) -> Result<(), String> {
+    use image::codecs::png::PngEncoder;
+    use image::ImageEncoder;
+
+    let rgba = img
+        .resize_exact(160, 160, image::imageops::FilterType::Lanczos3)
+        .to_rgba8();
+    let mut png_data = Vec::new();
+    PngEncoder::new(&mut png_data)
+        .write_image(&rgba, 160, 160, image::ColorType::Rgba8.into())
+        .map_err(|e| format!("PNG 编码失败: {}", e))?;
+    fs::write(cache_path, &png_data).map_err(|e| format!("保存图标缓存失败: {}", e))
+}
+
+/// 下载显式配置的图标 URL 并缓存
+fn fetch_icon_url(icon_url: &str) -> Result<String, String> {
+    let cache_key = hash_path(&format!("icon_url:{}", icon_url));
+    let cache_path = get_icons_dir().join(format!("{}.png", cache_key));
+    if let Some(cached) = read_cached_icon(&cache_path) {
+        return Ok(cached);
+    }
+
+    let response = icon_http_client()?
+        .get(icon_url)
+        .send()
+        .map_err(|e| format!("下载图标失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("下载图标失败，HTTP 状态码: {}", response.status()));
+    }
+    let bytes = read_limited_body(response).map_err(|e| format!("读取图标数据失败: {}", e))?;
+    let img = image::load_from_memory(&bytes).map_err(|e| format!("无效的图片数据: {}", e))?;
+
+    if let Err(e) = write_icon_cache(&cache_path, &img) {
+        log::warn!("图标 URL 缓存失败: {}", e);
+    }
+    process_icon_to_base64(img, 160)
+}
+
+/// 读取用户保存的自定义图标（data URL、.config/icons/ 相对路径或本地图片路径）
+fn load_custom_icon(icon_url: &str) -> Result<String, String> {
+    if icon_url.starts_with("data:image") {
+        return Ok(icon_url.to_string());
+    }
+    crate::config::read_icon_file(icon_url.to_string())
+}
+
+/// 根据工具名称生成首字母占位图标（SVG 数据 URL）
+fn initials_icon(name: &str) -> String {
+    const PALETTE: [&str; 6] = [
+        "#4DA3FF", "#7C5CFF", "#FF6B8B", "#2EC4B6", "#FF9F1C", "#6C757D",
+    ];
+
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let initials: String = if words.len() >= 2 {
+        words
+            .iter()
+            .take(2)
+            .filter_map(|w| w.chars().next())
+            .collect()
+    } else {
+        name.trim().chars().take(2).collect()
+    };
+    let initials = if initials.is_empty() {
+        "?".to_string()
+    } else {
+        initials
+            .to_uppercase()
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+
+    let color_index = name.bytes().map(|b| b as usize).sum::<usize>() % PALETTE.len();
+    let svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="160" height="160" viewBox="0 0 160 160"><rect width="160" height="160" rx="32" fill="{}"/><text x="50%" y="50%" dy=".35em" text-anchor="middle" font-family="sans-serif" font-size="64" font-weight="bold" fill="#FFFFFF">{}</text></svg>"##,
+        PALETTE[color_index], initials
+    );
+    format!(
+        "data:image/svg+xml;base64,{}",
+        general_purpose::STANDARD.encode(svg)
+    )
+}
+
+/// 解析工具图标（前端统一入口），优先级固定为：
+/// 1. 显式 icon_url（http/https，下载后缓存）
+/// 2. 用户保存的自定义图标（data URL、.config/icons/ 路径或本地图片）
+/// 3. 从 exec_path 自动提取（网页类型抓取 favicon）
+/// 4. 名称首字母占位图标
+/// 前一级失败时记录日志并回退到下一级，最终总能返回图标
+#[tauri::command]
+pub fn resolve_tool_icon(tool: ToolItem) -> Result<ResolvedToolIcon, String> {
+    let icon_url = tool
+        .icon_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    if let Some(icon_url) = icon_url {
+        if icon_url.starts_with("http://") || icon_url.starts_with("https://") {
+            match fetch_icon_url(icon_url) {
+                Ok(data_url) => {
+                    return Ok(ResolvedToolIcon {
+                        source: "icon_url".to_string(),
+                        data_url,
+                    })
+                }
+                Err(e) => log::warn!("工具 {} 的图标 URL 不可用: {}", tool.id, e),
+            }
+        } else {
+            match load_custom_icon(icon_url) {
+                Ok(data_url) => {
+                    return Ok(ResolvedToolIcon {
+                        source: "custom".to_string(),
+                        data_url,
+                    })
+                }
+                Err(e) => log::warn!("工具 {} 的自定义图标不可用: {}", tool.id, e),
+            }
+        }
+    }
+
+    let exec_path = tool
+        .exec_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    if let Some(exec_path) = exec_path {
+        let is_web = tool.tool_type.as_deref() == Some("网页")
+            || exec_path.starts_with("http://")
+            || exec_path.starts_with("https://");
+        let extracted = if is_web {
+            fetch_favicon(FetchFaviconParams {
+                url_str: exec_path.to_string(),
+                size: None,
+            })
+        } else {
+            extract_icon_from_file(ExtractIconParams {
+                file_path: exec_path.to_string(),
+                tool_type: tool.tool_type.clone(),
+                force: None,
+                icon_locked: None,
+                size: None,
+            })
+        };
+        match extracted {
+            Ok(data_url) => {
+                return Ok(ResolvedToolIcon {
+                    source: "extracted".to_string(),
+                    data_url,
+                })
+            }
+            Err(e) => log::warn!("工具 {} 的图标自动提取失败: {}", tool.id, e),
+        }
+    }
+
+    Ok(ResolvedToolIcon {
+        source: "initials".to_string(),
+        data_url: initials_icon(&tool.name),
+    })
+}
+
+/// 根据拖入的文件推断工具配置（供用户确认/编辑后保存）
+/// - tool_type：沿用 detect_file_type_from_path 的扩展名判断（.ps1 等脚本归为 CLI）
+/// - name：文件名（不含扩展名）
+/// - icon_url：提取的文件图标，提取失败时使用首字母图标
+#[tauri::command]
+pub fn infer_tool_from_path(path: String) -> Result<ToolItem, String> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err("文件路径不能为空".to_string());
+    }
+
+    let file_path = Path::new(trimmed);
+    if !file_path.exists() {
+        return Err(format!("文件不存在: {}", trimmed));
+    }
+    if !file_path.is_file() {
+        return Err(format!("路径不是文件: {}", trimmed));
+    }
+
+    let name = file_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or(trimmed)
+        .to_string();
+
+    let detected = detect_file_type_from_path(trimmed);
+    // 无法识别的类型不预设，由用户手动选择
+    let tool_type = if detected == "其他" {
+        log::info!("无法根据扩展名推断工具类型: {}", trimmed);
+        None
+    } else {
+        Some(detected)
+    };
+
+    let icon_url = match extract_icon_from_file(ExtractIconParams {
+        file_path: trimmed.to_string(),
+        tool_type: tool_type.clone(),
+        force: None,
+        icon_locked: None,
+        size: None,
+    }) {
+        Ok(data_url) => data_url,
+        Err(e) => {
+            log::warn!("推断工具时提取图标失败，使用首字母图标: {}", e);
+            initials_icon(&name)
+        }
+    };
+
+    let working_dir = file_path
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .filter(|s| !s.is_empty());
+
+    Ok(ToolItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        description: None,
+        icon_url: Some(icon_url),
+        wiki_url: None,
+        tool_type,
+        exec_path: Some(trimmed.to_string()),
+        args: None,
+        working_dir,
+    })
+}
+
+/// 目录扫描结果（供用户确认/编辑后保存到指定分类）
+#[derive(Debug, Serialize)]
+pub struct ToolScanResult {
+    /// 目标分类 ID（原样返回，由前端保存时使用）
+    pub category_id: String,
+    /// 推断出的工具草稿
+    pub tools: Vec<ToolItem>,
+    /// 是否因达到数量上限而提前结束扫描
+    pub truncated: bool,
+}
+
+/// 目录扫描只导入的文件类型（按扩展名，其他文件如 .dll、.sh 不作为工具导入）
+fn scan_tool_type(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    match ext.as_str() {
+        "exe" => Some("GUI"),
+        "py" | "pyw" => Some("Python"),
+        "jar" => Some("JAR"),
+        "html" | "htm" => Some("HTML"),
+        "lnk" => Some("LNK"),
+        _ => None,
+    }
+}
+
+/// 递归收集可导入的文件（跳过隐藏目录、依赖目录与符号链接目录），达到上限时返回 true
+fn collect_tool_files(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        log::debug!("读取目录失败，跳过: {}", dir.display());
+        return false;
+    };
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if file_type.is_dir() {
+            if depth < TOOL_SCAN_MAX_DEPTH
+                && !name.starts_with('.')
+                && !TOOL_SCAN_SKIP_DIRS.contains(&name.as_str())
+                && collect_tool_files(&path, depth + 1, files)
+            {
+                return true;
+            }
+        } else if scan_tool_type(&path).is_some() {
+            if files.len() >= TOOL_SCAN_MAX_TOOLS {
+                return true;
+            }
+            files.push(path);
+        }
+    }
+    false
+}
+
+/// 扫描目录，按扩展名识别工具（.exe→GUI、.py→Python、.jar→JAR、.html→HTML、.lnk→LNK）并提取图标
+/// 只返回草稿列表，不写入 categories.json，由前端让用户编辑名称、描述后保存到 category_id 分类
+#[tauri::command]
+pub fn scan_directory_for_tools(
+    root: String,
+    category_id: String,
+) -> Result<ToolScanResult, String> {
+    let root_path = Path::new(root.trim());
+    if root.trim().is_empty() {
+        return Err("目录路径不能为空".to_string());
+    }
+    if !root_path.is_dir() {
+        return Err(format!("目录不存在: {}", root));
+    }
+    if category_id.trim().is_empty() {
+        return Err("分类 ID 不能为空".to_string());
+    }
+
+    let mut files = Vec::new();
+    let truncated = collect_tool_files(root_path, 0, &mut files);
+
+    let tools: Vec<ToolItem> = files
+        .iter()
+        .filter_map(|file| {
+            let path = file.to_string_lossy().to_string();
+            let mut tool = match infer_tool_from_path(path.clone()) {
+                Ok(tool) => tool,
+                Err(e) => {
+                    log::warn!("推断工具失败，跳过: {} ({})", path, e);
+                    return None;
+                }
+            };
+            tool.tool_type = scan_tool_type(file).map(str::to_string);
+            Some(tool)
+        })
+        .collect();
+
+    log::info!(
+        "扫描目录 {} 得到 {} 个工具{}",
+        root,
+        tools.len(),
+        if truncated {
+            format!("（已达上限 {}，其余文件未扫描）", TOOL_SCAN_MAX_TOOLS)
+        } else {
+            String::new()
+        }
+    );
+    Ok(ToolScanResult {
+        category_id,
+        tools,
+        truncated,
+    })
+}
+
+/// 图标缓存清理结果
+#[derive(Debug, Serialize)]
+pub struct IconCacheCleanup {
+    /// 删除的缓存文件数
+    pub removed: usize,
+    /// 释放的字节数
+    pub freed_bytes: u64,
+    /// 因被工具配置引用（自定义图标）而保留的文件数
+    pub kept: usize,
+}
+
+/// 工具配置（tools.json / categories.json）引用的图标文件名，清理缓存时保留
+fn referenced_icon_files() -> HashSet<String> {
+    static ICON_REF: OnceLock<Regex> = OnceLock::new();
+    let re = ICON_REF
+        .get_or_init(|| Regex::new(r"icons[/\\]+([0-9a-f]{32}\.png)").expect("图标引用正则无效"));
+    let config_dir = get_config_dir();
+    ["tools.json", "categories.json"]
+        .iter()
+        .filter_map(|name| fs::read_to_string(config_dir.join(name)).ok())
+        .flat_map(|content| {
+            re.captures_iter(&content)
+                .map(|c| c[1].to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// 删除图标目录中满足条件的 PNG 缓存（跳过被工具配置引用的文件）
+fn remove_icon_cache_files(
+    mut should_remove: impl FnMut(&fs::Metadata) -> bool,
+) -> Result<IconCacheCleanup, String> {
+    let icons_dir = get_icons_dir();
+    let referenced = referenced_icon_files();
+    let entries = fs::read_dir(&icons_dir).map_err(|e| format!("读取图标目录失败: {}", e))?;
+
+    let mut cleanup = IconCacheCleanup {
+        removed: 0,
+        freed_bytes: 0,
+        kept: 0,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("png") {
+            continue;
+        }
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if !meta.is_file() || !should_remove(&meta) {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if referenced.contains(&name) {
+            cleanup.kept += 1;
+            continue;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                cleanup.removed += 1;
+                cleanup.freed_bytes += meta.len();
+                FaviconValidators::store(&path, None);
+            }
+            Err(e) => log::warn!("删除图标缓存失败: {} ({})", path.display(), e),
+        }
+    }
+    Ok(cleanup)
+}
+
+/// 清空图标缓存（工具配置引用的自定义图标除外）
+#[tauri::command]
+pub fn clear_icon_cache() -> Result<IconCacheCleanup, String> {
+    let cleanup = remove_icon_cache_files(|_| true)?;
+    log::info!(
+        "已清空图标缓存: 删除 {} 个文件（{} 字节），保留 {} 个自定义图标",
+        cleanup.removed,
+        cleanup.freed_bytes,
+        cleanup.kept
+    );
+    Ok(cleanup)
+}
+
+/// 删除修改时间早于 max_age_days 天前的图标缓存（工具配置引用的自定义图标除外）
+#[tauri::command]
+pub fn prune_icon_cache(max_age_days: u64) -> Result<IconCacheCleanup, String> {
+    let max_age = Duration::from_secs(max_age_days.saturating_mul(24 * 60 * 60));
+    let now = std::time::SystemTime::now();
+    let cleanup = remove_icon_cache_files(|meta| {
+        meta.modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > max_age)
+    })?;
+    log::info!(
+        "已清理 {} 天前的图标缓存: 删除 {} 个文件（{} 字节）",
+        max_age_days,
+        cleanup.removed,
+        cleanup.freed_bytes
+    );
+    Ok(cleanup)
+}
+
+/// 删除单个文件路径或 URL 对应的图标缓存，下次加载时重新提取；返回是否删除了缓存文件
+#[tauri::command]
+pub fn invalidate_icon(path_or_url: String) -> Result<bool, String> {
+    let target = path_or_url.trim();
+    if target.is_empty() {
+        return Err("路径或 URL 不能为空".to_string());
+    }
+
+    // 与各缓存写入处的键保持一致：文件路径（规范化）、favicon URL、显式图标 URL
+    let keys = if target.starts_with("http://") || target.starts_with("https://") {
+        vec![
+            hash_path(target),
+            hash_path(&format!("icon_url:{}", target)),
+        ]
+    } else {
+        vec![
+            crate::catalog::icon_cache_key(Path::new(target)),
+            hash_path(target),
+        ]
+    };
+
+    let icons_dir = get_icons_dir();
+    let mut removed = false;
+    for key in keys {
+        let cache_path = icons_dir.join(format!("{}.png", key));
+        if cache_path.is_file() {
+            fs::remove_file(&cache_path).map_err(|e| format!("删除图标缓存失败: {}", e))?;
+            FaviconValidators::store(&cache_path, None);
+            removed = true;
+        }
+    }
+    log::info!("图标缓存失效: {} (已删除: {})", target, removed);
+    Ok(removed)
 }
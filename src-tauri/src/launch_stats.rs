@@ -0,0 +1,100 @@
+// 工具启动统计：记录每个工具的启动次数、失败次数和最近使用时间，
+// 持久化到 .config/launch_stats.json，供前端按“最近使用 / 常用”排序
+use crate::utils::{get_config_dir, read_json_with_backup, write_file_atomic};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// 启动统计文件名（位于 .config 目录下）
+const LAUNCH_STATS_FILE: &str = "launch_stats.json";
+
+/// 错误信息最多保留的字符数
+const MAX_ERROR_CHARS: usize = 500;
+
+/// 串行化统计文件的读-改-写（多个后台写入线程之间）
+static LAUNCH_STATS_LOCK: Mutex<()> = Mutex::new(());
+
+/// 单个工具的启动统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchRecord {
+    /// 成功启动次数
+    pub launch_count: u64,
+    /// 启动失败次数
+    pub failure_count: u64,
+    /// 最近一次成功启动的时间（Unix 毫秒）
+    pub last_launched_at: Option<u64>,
+    /// 最近一次启动失败的时间（Unix 毫秒）
+    pub last_failure_at: Option<u64>,
+    /// 最近一次失败的错误信息
+    pub last_error: Option<String>,
+    /// 工具类型
+    pub tool_type: String,
+}
+
+/// 带键的启动统计（get_launch_stats 返回，键为工具 ID 或“类型:解析后的路径”）
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchStatsEntry {
+    pub key: String,
+    #[serde(flatten)]
+    pub record: LaunchRecord,
+}
+
+fn stats_path() -> std::path::PathBuf {
+    get_config_dir().join(LAUNCH_STATS_FILE)
+}
+
+fn load_stats() -> HashMap<String, LaunchRecord> {
+    read_json_with_backup(&stats_path()).unwrap_or_default()
+}
+
+fn save_stats(stats: &HashMap<String, LaunchRecord>) -> Result<(), String> {
+    let content =
+        serde_json::to_string_pretty(stats).map_err(|e| format!("序列化启动统计失败: {}", e))?;
+    write_file_atomic(&stats_path(), content.as_bytes())
+}
+
+/// 记录一次启动结果（后台线程写入，不阻塞启动流程；写入失败只记录日志）
+pub fn record_launch(key: String, tool_type: String, result: Result<(), String>) {
+    std::thread::spawn(move || {
+        let _guard = crate::utils::lock_or_recover(&LAUNCH_STATS_LOCK, "LAUNCH_STATS_LOCK");
+        let mut stats = load_stats();
+        let record = stats.entry(key).or_default();
+        let now = crate::service::events::current_timestamp();
+        record.tool_type = tool_type;
+        match result {
+            Ok(()) => {
+                record.launch_count += 1;
+                record.last_launched_at = Some(now);
+            }
+            Err(e) => {
+                record.failure_count += 1;
+                record.last_failure_at = Some(now);
+                record.last_error = Some(e.chars().take(MAX_ERROR_CHARS).collect());
+            }
+        }
+        if let Err(e) = save_stats(&stats) {
+            log::warn!("保存启动统计失败: {}", e);
+        }
+    });
+}
+
+/// 获取全部工具的启动统计（按最近使用时间倒序，从未成功启动的排在最后）
+#[tauri::command]
+pub fn get_launch_stats() -> Vec<LaunchStatsEntry> {
+    let stats = {
+        let _guard = crate::utils::lock_or_recover(&LAUNCH_STATS_LOCK, "LAUNCH_STATS_LOCK");
+        load_stats()
+    };
+    let mut entries: Vec<LaunchStatsEntry> = stats
+        .into_iter()
+        .map(|(key, record)| LaunchStatsEntry { key, record })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.record
+            .last_launched_at
+            .cmp(&a.record.last_launched_at)
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    entries
+}
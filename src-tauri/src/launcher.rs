@@ -1,18 +1,424 @@
 use crate::types::{JarConfig, LaunchToolParams};
-use crate::utils::get_file_dir;
-use std::path::Path;
+use crate::utils::{get_config_dir, get_file_dir, read_json_with_backup, write_file_atomic};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// 启动后打开地址的默认等待时间（毫秒）
+const DEFAULT_POST_LAUNCH_DELAY_MS: u64 = 2000;
+
+/// 启动后地址中的端口占位符
+const PORT_PLACEHOLDER: &str = "{{port}}";
+
+/// 启动策略配置文件名（位于 .config 目录下）
+const LAUNCH_POLICY_FILE: &str = "launch_policy.json";
+
+/// 同一工具的启动防抖窗口：窗口内的重复启动请求会被跳过（避免连点打开多个窗口）
+const LAUNCH_DEBOUNCE: Duration = Duration::from_millis(1500);
+
+/// 各启动目标最近一次启动的时间（键为解析后的路径）
+static RECENT_LAUNCHES: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+
+/// 启动结果（序列化为 "launched" / "recently_launched"）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchOutcome {
+    /// 已启动
+    Launched,
+    /// 同一工具刚刚启动过，本次请求被跳过
+    RecentlyLaunched,
+}
+
+/// 启动错误（序列化为 { kind, message } 返回前端，便于按类型区分处理）
+#[derive(Debug, Clone)]
+pub enum LaunchError {
+    /// 文件不存在
+    FileNotFound(String),
+    /// 路径存在但不是可执行的文件（如目录）
+    NotExecutable(String),
+    /// 不支持的工具类型（或当前平台不支持）
+    UnsupportedType(String),
+    /// 启动进程失败（操作说明, 错误信息）
+    SpawnFailed(String, String),
+    /// 找不到可用的终端
+    TerminalNotFound(String),
+    /// 被启动策略拦截
+    PolicyBlocked(String),
+    /// 参数缺失或无效
+    InvalidArgument(String),
+    /// 文件完整性校验失败（哈希不匹配或无法读取）
+    IntegrityCheckFailed(String),
+}
+
+impl LaunchError {
+    /// 错误类型标识（供前端判断）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LaunchError::FileNotFound(_) => "FileNotFound",
+            LaunchError::NotExecutable(_) => "NotExecutable",
+            LaunchError::UnsupportedType(_) => "UnsupportedType",
+            LaunchError::SpawnFailed(_, _) => "SpawnFailed",
+            LaunchError::TerminalNotFound(_) => "TerminalNotFound",
+            LaunchError::PolicyBlocked(_) => "PolicyBlocked",
+            LaunchError::InvalidArgument(_) => "InvalidArgument",
+            LaunchError::IntegrityCheckFailed(_) => "IntegrityCheckFailed",
+        }
+    }
+
+    fn spawn(action: &str, e: impl fmt::Display) -> Self {
+        LaunchError::SpawnFailed(action.to_string(), e.to_string())
+    }
+}
+
+impl fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaunchError::FileNotFound(path) => write!(f, "文件不存在: {}", path),
+            LaunchError::NotExecutable(path) => write!(f, "路径不是可执行文件: {}", path),
+            LaunchError::UnsupportedType(msg) => write!(f, "不支持的工具类型: {}", msg),
+            LaunchError::SpawnFailed(action, e) => write!(f, "{}失败: {}", action, e),
+            LaunchError::TerminalNotFound(msg) => write!(f, "{}", msg),
+            LaunchError::PolicyBlocked(reason) => write!(f, "启动被策略拦截: {}", reason),
+            LaunchError::InvalidArgument(msg) => write!(f, "{}", msg),
+            LaunchError::IntegrityCheckFailed(msg) => write!(f, "文件完整性校验失败: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for LaunchError {}
+
+impl Serialize for LaunchError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("LaunchError", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+/// 启动策略（.config/launch_policy.json）
+/// allowed_roots 为空时允许启动任意路径（默认，保持兼容）；非空时只允许启动位于这些目录下的文件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchPolicy {
+    /// 允许启动的根目录（绝对路径，或相对应用根目录的路径）
+    #[serde(alias = "allowedRoots")]
+    pub allowed_roots: Vec<String>,
+}
+
+impl LaunchPolicy {
+    fn path() -> PathBuf {
+        get_config_dir().join(LAUNCH_POLICY_FILE)
+    }
+
+    /// 读取启动策略（文件不存在或损坏时使用默认策略，即不限制）
+    pub fn load() -> Self {
+        read_json_with_backup::<Self>(&Self::path()).unwrap_or_default()
+    }
+
+    /// 保存启动策略（原子写入，并保留上一版本为 .bak）
+    pub fn save(&self) -> Result<(), String> {
+        let content =
+            serde_json::to_string_pretty(self).map_err(|e| format!("序列化启动策略失败: {}", e))?;
+        write_file_atomic(&Self::path(), content.as_bytes())
+    }
+
+    /// 解析允许的根目录（相对路径基于应用根目录，并解析符号链接）
+    fn resolve_root(root: &str) -> Result<PathBuf, String> {
+        let path = PathBuf::from(root.trim());
+        let path = if path.is_absolute() {
+            path
+        } else {
+            crate::utils::get_app_base_dir().join(path)
+        };
+        let resolved = path
+            .canonicalize()
+            .map_err(|e| format!("无法解析允许的目录 {}: {}", root, e))?;
+        if !resolved.is_dir() {
+            return Err(format!("允许的路径不是目录: {}", root));
+        }
+        Ok(resolved)
+    }
+
+    /// 校验各根目录均存在且为目录
+    pub fn validate(&self) -> Result<(), String> {
+        for root in &self.allowed_roots {
+            if root.trim().is_empty() {
+                return Err("允许的目录不能为空".to_string());
+            }
+            Self::resolve_root(root)?;
+        }
+        Ok(())
+    }
+
+    /// 检查启动目标是否位于允许的目录下（按解析符号链接后的真实路径判断，防止 ../ 与链接绕过）
+    /// 设置了允许的目录时，无法解析的目标一律拒绝（无法确认其位置）
+    fn check(&self, target: &str) -> Result<(), LaunchError> {
+        if self.allowed_roots.is_empty() {
+            return Ok(());
+        }
+        let path = Path::new(target);
+        let resolved = match path.canonicalize() {
+            Ok(resolved) => Some(resolved),
+            Err(_) if path.components().count() == 1 => {
+                crate::utils::find_in_path(target).and_then(|p| p.canonicalize().ok())
+            }
+            Err(_) => None,
+        };
+        let Some(resolved) = resolved else {
+            log::warn!("无法解析启动目标，启动策略限制了目录，拒绝启动: {}", target);
+            return Err(LaunchError::PolicyBlocked(format!(
+                "无法解析 {} 的真实路径，已设置允许启动的目录时拒绝启动（{}）",
+                target,
+                self.allowed_roots.join(", ")
+            )));
+        };
+
+        let allowed = self
+            .allowed_roots
+            .iter()
+            .any(|root| match Self::resolve_root(root) {
+                Ok(root) => resolved.starts_with(root),
+                Err(e) => {
+                    log::warn!("启动策略中的目录无效，已忽略: {}", e);
+                    false
+                }
+            });
+        if allowed {
+            return Ok(());
+        }
+        log::warn!("启动目标不在允许的目录内，拒绝启动: {}", resolved.display());
+        Err(LaunchError::PolicyBlocked(format!(
+            "{} 不在允许启动的目录内（{}）",
+            resolved.display(),
+            self.allowed_roots.join(", ")
+        )))
+    }
+
+    /// 按工具类型检查所有会被执行的本地文件（网页工具不涉及本地文件）
+    fn check_tool(
+        &self,
+        tool_type: &str,
+        exec_path: Option<&str>,
+        jar_config: Option<&JarConfig>,
+    ) -> Result<(), LaunchError> {
+        match tool_type {
+            "网页" => Ok(()),
+            "JAR" => {
+                let Some(jar_config) = jar_config else {
+                    return Ok(());
+                };
+                self.check(&jar_config.jar_path)?;
+                // 仅检查显式配置的 Java 路径（默认使用 PATH 中的 java）
+                match jar_config.java_path.as_deref().map(str::trim) {
+                    Some(java_path) if !java_path.is_empty() => self.check(java_path),
+                    _ => Ok(()),
+                }
+            }
+            // 快捷方式本身与其指向的程序都须位于允许的目录内
+            "LNK" => {
+                let Some(path) = exec_path else {
+                    return Ok(());
+                };
+                self.check(path)?;
+                if self.allowed_roots.is_empty() {
+                    return Ok(());
+                }
+                let target = resolve_lnk_target(path).map_err(|e| {
+                    LaunchError::PolicyBlocked(format!(
+                        "无法解析快捷方式 {} 的目标，已设置允许启动的目录时拒绝启动: {}",
+                        path, e
+                    ))
+                })?;
+                self.check(&target)
+            }
+            _ => exec_path.map_or(Ok(()), |path| self.check(path)),
+        }
+    }
+}
+
+/// 获取启动策略
+#[tauri::command]
+pub fn get_launch_policy() -> LaunchPolicy {
+    LaunchPolicy::load()
+}
+
+/// 设置启动策略并保存到 .config/launch_policy.json（allowed_roots 为空表示不限制）
+#[tauri::command]
+pub fn set_launch_policy(policy: LaunchPolicy) -> Result<(), String> {
+    policy.validate()?;
+    policy.save()?;
+    log::info!("启动策略已更新: 允许的目录 {:?}", policy.allowed_roots);
+    Ok(())
+}
+
+/// 检查待启动的文件：不存在返回 FileNotFound，不是文件返回 NotExecutable
+fn ensure_launchable_file(path: &str) -> Result<(), LaunchError> {
+    let p = Path::new(path);
+    if !p.exists() {
+        return Err(LaunchError::FileNotFound(path.to_string()));
+    }
+    if !p.is_file() {
+        return Err(LaunchError::NotExecutable(path.to_string()));
+    }
+    Ok(())
+}
+
+/// 检查工作目录：不存在返回 FileNotFound，不是目录返回 InvalidArgument
+fn ensure_working_dir(dir: &str) -> Result<(), LaunchError> {
+    let p = Path::new(dir);
+    if !p.exists() {
+        return Err(LaunchError::FileNotFound(dir.to_string()));
+    }
+    if !p.is_dir() {
+        return Err(LaunchError::InvalidArgument(format!(
+            "工作目录不是目录: {}",
+            dir
+        )));
+    }
+    Ok(())
+}
+
+/// 检查命令可解析：包含路径分隔符时按文件检查，否则在 PATH 中查找
+fn ensure_command_resolvable(cmd: &str) -> Result<(), LaunchError> {
+    if cmd.contains('/') || cmd.contains('\\') {
+        return ensure_launchable_file(cmd);
+    }
+    if crate::utils::find_in_path(cmd).is_none() {
+        return Err(LaunchError::FileNotFound(format!(
+            "{}（未在 PATH 中找到）",
+            cmd
+        )));
+    }
+    Ok(())
+}
+
+/// 检查 URL 格式（仅允许 http / https）
+fn ensure_http_url(url: &str) -> Result<(), LaunchError> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(LaunchError::InvalidArgument(format!(
+            "无效的 URL 格式: {}",
+            url
+        )));
+    }
+    Ok(())
+}
+
+/// Python 命令（Windows 使用 python，Linux/macOS 使用 python3）
+fn python_command() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "python"
+    } else {
+        "python3"
+    }
+}
+
+/// Java 命令（未配置 java_path 时使用 PATH 中的 java）
+fn java_command(jar_config: &JarConfig) -> String {
+    jar_config
+        .java_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or("java")
+        .to_string()
+}
+
+/// 校验文件 SHA-256，不匹配时拒绝启动（用于发现被篡改的工具）
+fn verify_sha256(path: &str, expected: &str) -> Result<(), LaunchError> {
+    let expected = expected.trim().to_ascii_lowercase();
+    if expected.len() != 64 || !expected.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(LaunchError::InvalidArgument(format!(
+            "无效的 SHA-256: {}（应为 64 位十六进制）",
+            expected
+        )));
+    }
+    ensure_launchable_file(path)?;
+
+    let actual =
+        crate::utils::sha256_file(Path::new(path)).map_err(LaunchError::IntegrityCheckFailed)?;
+    if actual != expected {
+        log::warn!(
+            "文件哈希不匹配，拒绝启动: {} (期望 {}, 实际 {})",
+            path,
+            expected,
+            actual
+        );
+        return Err(LaunchError::IntegrityCheckFailed(format!(
+            "{} 的 SHA-256 为 {}，与期望值 {} 不一致，文件可能已被替换或篡改",
+            path, actual, expected
+        )));
+    }
+    Ok(())
+}
+
+/// 工具环境变量（变量名, 值）
+type ToolEnv = [(String, String)];
+
+/// 校验环境变量名：仅允许字母、数字和下划线且不以数字开头（需要安全地写入 shell 命令）
+fn validate_env(env: &ToolEnv) -> Result<(), LaunchError> {
+    for (key, value) in env {
+        let valid_key = key
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_key {
+            return Err(LaunchError::InvalidArgument(format!(
+                "无效的环境变量名: {:?}",
+                key
+            )));
+        }
+        if value.contains('\0') || value.contains('\n') || value.contains('\r') {
+            return Err(LaunchError::InvalidArgument(format!(
+                "环境变量 {} 的值不能包含换行或空字符",
+                key
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// 生成在终端会话中设置环境变量的命令前缀
+/// 终端模拟器（如 Terminal.app、gnome-terminal）不一定继承启动进程的环境，因此直接写入命令
+fn shell_env_prefix(env: &ToolEnv) -> String {
+    env.iter()
+        .map(|(key, value)| {
+            if cfg!(target_os = "windows") {
+                format!("$env:{}='{}'; ", key, value.replace('\'', "''"))
+            } else {
+                format!("export {}='{}'; ", key, value.replace('\'', "'\\''"))
+            }
+        })
+        .collect()
+}
+
+/// 生成在 Unix 终端中执行的 shell 脚本：切换目录、设置环境变量后执行命令
+/// 只有工作目录在此加引号；环境变量前缀已由 shell_env_prefix 引用，命令按原样拼接，不能再次转义
+#[cfg(not(target_os = "windows"))]
+fn unix_terminal_script(working_dir: &Path, env: &ToolEnv, command: &str) -> String {
+    format!(
+        "cd '{}' && {}{}",
+        working_dir.to_string_lossy().replace('\'', "'\\''"),
+        shell_env_prefix(env),
+        command
+    )
+}
 
 /// 启动 GUI 工具（直接启动，不打开终端）
 fn launch_gui_tool(
     exec_path: &str,
     args: Option<Vec<String>>,
     working_dir: Option<String>,
-) -> Result<(), String> {
+    env: &ToolEnv,
+) -> Result<(), LaunchError> {
+    ensure_launchable_file(exec_path)?;
     let path = Path::new(exec_path);
-    if !path.exists() {
-        return Err(format!("文件不存在: {}", exec_path));
-    }
 
     let mut cmd = Command::new(exec_path);
 
@@ -28,53 +434,94 @@ fn launch_gui_tool(
         cmd.args(args_vec);
     }
 
-    // 启动进程（不等待）
-    cmd.spawn().map_err(|e| format!("启动工具失败: {}", e))?;
+    // 设置环境变量（在继承的环境上追加 / 覆盖）
+    cmd.envs(env.iter().map(|(k, v)| (k, v)));
+
+    // 启动进程（不等待），登记到进程注册表
+    let child = cmd.spawn().map_err(|e| LaunchError::spawn("启动工具", e))?;
+    crate::process_registry::track_child(child, tool_label(exec_path));
 
     Ok(())
 }
 
+/// 进程注册表中显示的工具名（文件名，无法取得时为完整路径）
+fn tool_label(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
 /// 在 Windows 上打开 PowerShell 并执行命令
+/// 登记的是 Start-Process 所在的 PowerShell 进程，它打开新窗口后立即退出，新窗口中的工具不在进程注册表中
 #[cfg(target_os = "windows")]
-fn launch_in_terminal_windows(working_dir: &Path, command: &str) -> Result<(), String> {
+fn launch_in_terminal_windows(
+    working_dir: &Path,
+    command: &str,
+    env: &ToolEnv,
+    label: &str,
+) -> Result<(), LaunchError> {
     // 构建 PowerShell 命令
     // 使用 Start-Process 启动新的 PowerShell 窗口，并执行命令
+    let command = format!("{}{}", shell_env_prefix(env), command);
     let ps_command = format!(
         "Start-Process powershell -ArgumentList '-NoExit', '-Command', 'Set-Location ''{}''; {}'",
         working_dir.to_string_lossy().replace('\'', "''"),
         command.replace('\'', "''")
     );
 
-    Command::new("powershell")
+    let child = Command::new("powershell")
         .args(&["-Command", &ps_command])
+        .envs(env.iter().map(|(k, v)| (k, v)))
         .spawn()
-        .map_err(|e| format!("启动终端失败: {}", e))?;
+        .map_err(|e| LaunchError::spawn("启动终端", e))?;
+    crate::process_registry::track_child(child, format!("{}（终端）", label));
 
     Ok(())
 }
 
 /// 在 macOS 上打开终端并执行命令
+/// 登记的是 osascript 进程，它通知 Terminal.app 后立即退出，终端中的工具不在进程注册表中
 #[cfg(target_os = "macos")]
-fn launch_in_terminal_unix(working_dir: &Path, command: &str) -> Result<(), String> {
-    // macOS 使用 AppleScript 打开 Terminal.app
-    let working_dir_str = working_dir.to_string_lossy();
+fn launch_in_terminal_unix(
+    working_dir: &Path,
+    command: &str,
+    env: &ToolEnv,
+    label: &str,
+) -> Result<(), LaunchError> {
+    // macOS 使用 AppleScript 打开 Terminal.app（新窗口不继承本进程环境，变量写入命令）
+    // do script 的参数是 AppleScript 字符串，只需转义反斜杠和双引号
+    let shell_script = unix_terminal_script(working_dir, env, command);
     let script = format!(
-        "tell application \"Terminal\"\n  activate\n  do script \"cd '{}' && {}\"\nend tell",
-        working_dir_str.replace('\'', "'\\''"),
-        command.replace('\'', "'\\''").replace('"', "\\\"")
+        "tell application \"Terminal\"\n  activate\n  do script \"{}\"\nend tell",
+        shell_script.replace('\\', "\\\\").replace('"', "\\\"")
     );
 
-    Command::new("osascript")
+    let child = Command::new("osascript")
         .args(&["-e", &script])
         .spawn()
-        .map_err(|e| format!("启动终端失败: {}", e))?;
+        .map_err(|e| LaunchError::spawn("启动终端", e))?;
+    crate::process_registry::track_child(child, format!("{}（终端）", label));
 
     Ok(())
 }
 
 /// 在 Linux 上打开终端并执行命令
+/// 登记终端进程（终止它会关闭终端及其中的工具）；gnome-terminal 等由服务进程创建窗口的终端会立即退出，
+/// 此时工具不在进程注册表中
 #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
-fn launch_in_terminal_unix(working_dir: &Path, command: &str) -> Result<(), String> {
+fn launch_in_terminal_unix(
+    working_dir: &Path,
+    command: &str,
+    env: &ToolEnv,
+    label: &str,
+) -> Result<(), LaunchError> {
+    // gnome-terminal / konsole 自行切换目录，xterm 等需要在脚本中 cd
+    let command_with_env = format!("{}{}", shell_env_prefix(env), command);
+    let script = format!(
+        "{}; exec bash",
+        unix_terminal_script(working_dir, env, command)
+    );
     // 尝试使用常见的终端模拟器，按优先级排序
     let terminal_commands = vec![
         (
@@ -85,22 +532,10 @@ fn launch_in_terminal_unix(working_dir: &Path, command: &str) -> Result<(), Stri
                 "--",
                 "bash",
                 "-c",
-                &format!("{}; exec bash", command),
-            ],
-        ),
-        (
-            "xterm",
-            vec![
-                "-e",
-                "bash",
-                "-c",
-                &format!(
-                    "cd '{}' && {}; exec bash",
-                    working_dir.to_string_lossy().replace('\'', "'\\''"),
-                    command.replace('\'', "'\\''")
-                ),
+                &format!("{}; exec bash", command_with_env),
             ],
         ),
+        ("xterm", vec!["-e", "bash", "-c", &script]),
         (
             "konsole",
             vec![
@@ -109,44 +544,37 @@ fn launch_in_terminal_unix(working_dir: &Path, command: &str) -> Result<(), Stri
                 "-e",
                 "bash",
                 "-c",
-                &format!("{}; exec bash", command),
-            ],
-        ),
-        (
-            "x-terminal-emulator",
-            vec![
-                "-e",
-                "bash",
-                "-c",
-                &format!(
-                    "cd '{}' && {}; exec bash",
-                    working_dir.to_string_lossy().replace('\'', "'\\''"),
-                    command.replace('\'', "'\\''")
-                ),
+                &format!("{}; exec bash", command_with_env),
             ],
         ),
+        ("x-terminal-emulator", vec!["-e", "bash", "-c", &script]),
     ];
 
     for (terminal, args) in terminal_commands {
-        if let Ok(mut child) = Command::new(terminal).args(&args).spawn() {
-            // 不等待子进程，让它独立运行
-            let _ = child.wait();
+        if let Ok(child) = Command::new(terminal)
+            .args(&args)
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .spawn()
+        {
+            // 不等待子进程，让它独立运行（由进程注册表回收）
+            crate::process_registry::track_child(child, format!("{}（{}）", label, terminal));
             return Ok(());
         }
     }
 
-    Err(
+    Err(LaunchError::TerminalNotFound(
         "无法找到可用的终端模拟器（请安装 gnome-terminal、xterm、konsole 或 x-terminal-emulator）"
             .to_string(),
-    )
+    ))
 }
 
 /// 启动 CLI 工具（在对应目录打开终端执行）
-fn launch_cli_tool(exec_path: &str, args: Option<Vec<String>>) -> Result<(), String> {
-    let path = Path::new(exec_path);
-    if !path.exists() {
-        return Err(format!("文件不存在: {}", exec_path));
-    }
+fn launch_cli_tool(
+    exec_path: &str,
+    args: Option<Vec<String>>,
+    env: &ToolEnv,
+) -> Result<(), LaunchError> {
+    ensure_launchable_file(exec_path)?;
 
     let working_dir = get_file_dir(exec_path);
 
@@ -165,29 +593,25 @@ fn launch_cli_tool(exec_path: &str, args: Option<Vec<String>>) -> Result<(), Str
 
     // 在终端中执行
     #[cfg(target_os = "windows")]
-    launch_in_terminal_windows(&working_dir, &command)?;
+    launch_in_terminal_windows(&working_dir, &command, env, &tool_label(exec_path))?;
 
     #[cfg(not(target_os = "windows"))]
-    launch_in_terminal_unix(&working_dir, &command)?;
+    launch_in_terminal_unix(&working_dir, &command, env, &tool_label(exec_path))?;
 
     Ok(())
 }
 
 /// 启动 Python 脚本（在对应目录打开终端执行）
-fn launch_python_tool(exec_path: &str, args: Option<Vec<String>>) -> Result<(), String> {
-    let path = Path::new(exec_path);
-    if !path.exists() {
-        return Err(format!("文件不存在: {}", exec_path));
-    }
+fn launch_python_tool(
+    exec_path: &str,
+    args: Option<Vec<String>>,
+    env: &ToolEnv,
+) -> Result<(), LaunchError> {
+    ensure_launchable_file(exec_path)?;
 
     let working_dir = get_file_dir(exec_path);
 
-    // 确定 Python 命令（Windows 使用 python，Linux/macOS 使用 python3）
-    let python_cmd = if cfg!(target_os = "windows") {
-        "python"
-    } else {
-        "python3"
-    };
+    let python_cmd = python_command();
 
     // 构建命令
     let mut command = format!("{} \"{}\"", python_cmd, exec_path.replace('"', "\\\""));
@@ -204,29 +628,21 @@ fn launch_python_tool(exec_path: &str, args: Option<Vec<String>>) -> Result<(),
 
     // 在终端中执行
     #[cfg(target_os = "windows")]
-    launch_in_terminal_windows(&working_dir, &command)?;
+    launch_in_terminal_windows(&working_dir, &command, env, &tool_label(exec_path))?;
 
     #[cfg(not(target_os = "windows"))]
-    launch_in_terminal_unix(&working_dir, &command)?;
+    launch_in_terminal_unix(&working_dir, &command, env, &tool_label(exec_path))?;
 
     Ok(())
 }
 
 /// 启动 JAR 工具（在对应目录打开终端执行）
-fn launch_jar_tool(jar_config: &JarConfig) -> Result<(), String> {
-    let jar_path = Path::new(&jar_config.jar_path);
-    if !jar_path.exists() {
-        return Err(format!("JAR 文件不存在: {}", jar_config.jar_path));
-    }
+fn launch_jar_tool(jar_config: &JarConfig, env: &ToolEnv) -> Result<(), LaunchError> {
+    ensure_launchable_file(&jar_config.jar_path)?;
 
     let working_dir = get_file_dir(&jar_config.jar_path);
 
-    // 确定 Java 命令
-    let java_cmd = if let Some(ref java_path) = jar_config.java_path {
-        java_path.clone()
-    } else {
-        "java".to_string()
-    };
+    let java_cmd = java_command(jar_config);
 
     // 构建 Java 命令
     let mut command = format!("{}", java_cmd);
@@ -258,44 +674,93 @@ fn launch_jar_tool(jar_config: &JarConfig) -> Result<(), String> {
 
     // 在终端中执行
     #[cfg(target_os = "windows")]
-    launch_in_terminal_windows(&working_dir, &command)?;
+    launch_in_terminal_windows(
+        &working_dir,
+        &command,
+        env,
+        &tool_label(&jar_config.jar_path),
+    )?;
 
     #[cfg(not(target_os = "windows"))]
-    launch_in_terminal_unix(&working_dir, &command)?;
+    launch_in_terminal_unix(
+        &working_dir,
+        &command,
+        env,
+        &tool_label(&jar_config.jar_path),
+    )?;
 
     Ok(())
 }
 
-/// 启动 LNK 工具（Windows 快捷方式）
+/// 解析快捷方式指向的目标（目标本身是 .lnk 时再解析一层，与图标提取一致）
 #[cfg(target_os = "windows")]
-fn launch_lnk_tool(exec_path: &str) -> Result<(), String> {
-    let path = Path::new(exec_path);
-    if !path.exists() {
-        return Err(format!("快捷方式文件不存在: {}", exec_path));
+fn resolve_lnk_target(lnk_path: &str) -> Result<String, String> {
+    let script = r#"
+  $shell = New-Object -ComObject WScript.Shell
+  $target = $shell.CreateShortcut($env:LAUNCH_LNK_PATH).TargetPath
+  if ($target -like '*.lnk' -and (Test-Path -LiteralPath $target)) {
+    $target = $shell.CreateShortcut($target).TargetPath
+  }
+  [Console]::OutputEncoding = [System.Text.Encoding]::UTF8
+  Write-Output $target
+"#;
+    // 使用环境变量传递路径，避免编码和转义问题
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-ExecutionPolicy",
+            "Bypass",
+            "-Command",
+            script,
+        ])
+        .env("LAUNCH_LNK_PATH", lnk_path)
+        .output()
+        .map_err(|e| format!("执行 PowerShell 命令失败: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
     }
+    let target = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if target.is_empty() {
+        // 指向“此电脑”等虚拟位置或 URL 的快捷方式没有文件目标
+        return Err("快捷方式没有指向本地文件".to_string());
+    }
+    Ok(target)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn resolve_lnk_target(_lnk_path: &str) -> Result<String, String> {
+    Err("LNK 工具仅在 Windows 系统上支持".to_string())
+}
+
+/// 启动 LNK 工具（Windows 快捷方式）
+/// 登记的是 cmd 进程，start 启动目标程序后立即退出，目标程序不在进程注册表中
+#[cfg(target_os = "windows")]
+fn launch_lnk_tool(exec_path: &str) -> Result<(), LaunchError> {
+    ensure_launchable_file(exec_path)?;
 
     // Windows 上使用 start 命令打开快捷方式
     // start 命令会自动处理快捷方式并启动目标程序
-    Command::new("cmd")
+    let child = Command::new("cmd")
         .args(&["/C", "start", "", exec_path])
         .spawn()
-        .map_err(|e| format!("启动快捷方式失败: {}", e))?;
+        .map_err(|e| LaunchError::spawn("启动快捷方式", e))?;
+    crate::process_registry::track_child(child, tool_label(exec_path));
 
     Ok(())
 }
 
 #[cfg(not(target_os = "windows"))]
-fn launch_lnk_tool(_exec_path: &str) -> Result<(), String> {
-    Err("LNK 工具仅在 Windows 系统上支持".to_string())
+fn launch_lnk_tool(_exec_path: &str) -> Result<(), LaunchError> {
+    Err(LaunchError::UnsupportedType(
+        "LNK 工具仅在 Windows 系统上支持".to_string(),
+    ))
 }
 
 /// 打开 URL 在默认浏览器中（用于网页工具）
 #[tauri::command]
-pub fn open_url_in_browser(url: String) -> Result<(), String> {
+pub fn open_url_in_browser(url: String) -> Result<(), LaunchError> {
     // 验证 URL 格式
-    if !url.starts_with("http://") && !url.starts_with("https://") {
-        return Err(format!("无效的 URL 格式: {}", url));
-    }
+    ensure_http_url(&url)?;
 
     // 在默认浏览器中打开
     #[cfg(target_os = "windows")]
@@ -304,7 +769,7 @@ pub fn open_url_in_browser(url: String) -> Result<(), String> {
         Command::new("cmd")
             .args(&["/C", "start", "", url.as_str()])
             .spawn()
-            .map_err(|e| format!("打开浏览器失败: {}", e))?;
+            .map_err(|e| LaunchError::spawn("打开浏览器", e))?;
     }
 
     #[cfg(target_os = "macos")]
@@ -313,7 +778,7 @@ pub fn open_url_in_browser(url: String) -> Result<(), String> {
         Command::new("open")
             .arg(url.as_str())
             .spawn()
-            .map_err(|e| format!("打开浏览器失败: {}", e))?;
+            .map_err(|e| LaunchError::spawn("打开浏览器", e))?;
     }
 
     #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
@@ -322,29 +787,27 @@ pub fn open_url_in_browser(url: String) -> Result<(), String> {
         Command::new("xdg-open")
             .arg(url)
             .spawn()
-            .map_err(|e| format!("打开浏览器失败: {}", e))?;
+            .map_err(|e| LaunchError::spawn("打开浏览器", e))?;
     }
 
     Ok(())
 }
 
 /// 启动 HTML 工具（本地 HTML 文件在浏览器中打开）
-fn launch_html_tool(exec_path: &str) -> Result<(), String> {
+fn launch_html_tool(exec_path: &str) -> Result<(), LaunchError> {
+    ensure_launchable_file(exec_path)?;
     let path = Path::new(exec_path);
-    if !path.exists() {
-        return Err(format!("HTML 文件不存在: {}", exec_path));
-    }
 
     // 将路径转换为绝对路径并规范化
     let abs_path = if path.is_absolute() {
         path.canonicalize()
-            .map_err(|e| format!("无法解析路径: {}", e))?
+            .map_err(|_| LaunchError::FileNotFound(exec_path.to_string()))?
     } else {
         std::env::current_dir()
-            .map_err(|e| format!("获取当前目录失败: {}", e))?
+            .map_err(|e| LaunchError::InvalidArgument(format!("获取当前目录失败: {}", e)))?
             .join(path)
             .canonicalize()
-            .map_err(|e| format!("无法解析路径: {}", e))?
+            .map_err(|_| LaunchError::FileNotFound(exec_path.to_string()))?
     };
 
     // 转换为 file:// URL
@@ -365,7 +828,7 @@ fn launch_html_tool(exec_path: &str) -> Result<(), String> {
         Command::new("cmd")
             .args(&["/C", "start", "", &file_url])
             .spawn()
-            .map_err(|e| format!("打开浏览器失败: {}", e))?;
+            .map_err(|e| LaunchError::spawn("打开浏览器", e))?;
     }
 
     #[cfg(target_os = "macos")]
@@ -374,7 +837,7 @@ fn launch_html_tool(exec_path: &str) -> Result<(), String> {
         Command::new("open")
             .arg(&file_url)
             .spawn()
-            .map_err(|e| format!("打开浏览器失败: {}", e))?;
+            .map_err(|e| LaunchError::spawn("打开浏览器", e))?;
     }
 
     #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
@@ -383,21 +846,72 @@ fn launch_html_tool(exec_path: &str) -> Result<(), String> {
         Command::new("xdg-open")
             .arg(&file_url)
             .spawn()
-            .map_err(|e| format!("打开浏览器失败: {}", e))?;
+            .map_err(|e| LaunchError::spawn("打开浏览器", e))?;
     }
 
     Ok(())
 }
 
+/// 启动防抖的键：工具类型 + 解析后的目标路径（JAR 使用 jar_path，网页使用 URL）
+fn launch_key(
+    tool_type: &str,
+    exec_path: Option<&str>,
+    jar_config: Option<&JarConfig>,
+) -> Option<String> {
+    let target = match tool_type {
+        "JAR" => jar_config.map(|c| c.jar_path.as_str()),
+        _ => exec_path,
+    }?;
+    let resolved = if tool_type == "网页" {
+        target.to_string()
+    } else {
+        std::fs::canonicalize(target)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| target.to_string())
+    };
+    Some(format!("{}:{}", tool_type, resolved))
+}
+
+/// 登记一次启动：防抖窗口内已启动过时返回 false（同时清理过期记录）
+fn claim_launch(key: &str) -> bool {
+    let recent = RECENT_LAUNCHES.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut recent = crate::utils::lock_or_recover(recent, "RECENT_LAUNCHES");
+    let now = Instant::now();
+    recent.retain(|_, launched_at| now.duration_since(*launched_at) < LAUNCH_DEBOUNCE);
+    if recent.contains_key(key) {
+        return false;
+    }
+    recent.insert(key.to_string(), now);
+    true
+}
+
+/// 启动失败时撤销登记，允许立即重试
+fn release_launch(key: &str) {
+    if let Some(recent) = RECENT_LAUNCHES.get() {
+        crate::utils::lock_or_recover(recent, "RECENT_LAUNCHES").remove(key);
+    }
+}
+
 /// 启动工具的主函数
 /// 参数支持 camelCase（前端）和 snake_case（Rust）两种命名方式
 #[tauri::command]
-pub fn launch_tool(params: LaunchToolParams) -> Result<(), String> {
+pub fn launch_tool(params: LaunchToolParams) -> Result<LaunchOutcome, LaunchError> {
     let tool_type = params.tool_type.as_deref().unwrap_or("GUI");
     let exec_path = params.exec_path;
     let args = params.args;
     let working_dir = params.working_dir;
     let jar_config = params.jar_config;
+    let env = params.env.unwrap_or_default();
+    validate_env(&env)?;
+
+    // 先解析启动后地址，占位符无法替换时直接报错，避免启动后才发现
+    let post_launch_url = params
+        .post_launch_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|url| resolve_post_launch_url(url, params.port))
+        .transpose()?;
 
     // 调试日志（开发环境）
     #[cfg(debug_assertions)]
@@ -413,35 +927,258 @@ pub fn launch_tool(params: LaunchToolParams) -> Result<(), String> {
     );
     }
 
-    match tool_type {
+    let missing = |msg: &str| LaunchError::InvalidArgument(msg.to_string());
+
+    // 启动策略：配置了允许的目录时，拒绝启动目录外的文件
+    LaunchPolicy::load().check_tool(tool_type, exec_path.as_deref(), jar_config.as_ref())?;
+
+    // 启动前校验哈希（路径缺失时由下方各类型分支报错）
+    if let Some(expected) = params
+        .expected_sha256
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+    {
+        let target = match tool_type {
+            "JAR" => jar_config.as_ref().map(|c| c.jar_path.as_str()),
+            "网页" => return Err(missing("网页工具不支持 SHA-256 校验")),
+            _ => exec_path.as_deref(),
+        };
+        if let Some(target) = target {
+            verify_sha256(target, expected)?;
+        }
+    }
+
+    // 防抖：同一工具在短时间内重复启动时直接跳过
+    let launch_key = launch_key(tool_type, exec_path.as_deref(), jar_config.as_ref());
+    if let Some(ref key) = launch_key {
+        if !claim_launch(key) {
+            log::info!("工具刚刚启动过，跳过重复启动: {}", key);
+            return Ok(LaunchOutcome::RecentlyLaunched);
+        }
+    }
+
+    let result = match tool_type {
         "GUI" => {
-            let exec_path = exec_path.ok_or("GUI 工具需要 exec_path")?;
-            launch_gui_tool(&exec_path, args, working_dir)
+            let exec_path = exec_path.ok_or_else(|| missing("GUI 工具需要 exec_path"))?;
+            launch_gui_tool(&exec_path, args, working_dir, &env)
         }
         "CLI" => {
-            let exec_path = exec_path.ok_or("CLI 工具需要 exec_path")?;
-            launch_cli_tool(&exec_path, args)
+            let exec_path = exec_path.ok_or_else(|| missing("CLI 工具需要 exec_path"))?;
+            launch_cli_tool(&exec_path, args, &env)
         }
         "Python" => {
-            let exec_path = exec_path.ok_or("Python 工具需要 exec_path")?;
-            launch_python_tool(&exec_path, args)
+            let exec_path = exec_path.ok_or_else(|| missing("Python 工具需要 exec_path"))?;
+            launch_python_tool(&exec_path, args, &env)
         }
         "JAR" => {
-            let jar_config = jar_config.ok_or("JAR 工具需要 jar_config")?;
-            launch_jar_tool(&jar_config)
+            let jar_config = jar_config.ok_or_else(|| missing("JAR 工具需要 jar_config"))?;
+            launch_jar_tool(&jar_config, &env)
         }
         "LNK" => {
-            let exec_path = exec_path.ok_or("LNK 工具需要 exec_path")?;
+            let exec_path = exec_path.ok_or_else(|| missing("LNK 工具需要 exec_path"))?;
             launch_lnk_tool(&exec_path)
         }
         "HTML" => {
-            let exec_path = exec_path.ok_or("HTML 工具需要 exec_path")?;
+            let exec_path = exec_path.ok_or_else(|| missing("HTML 工具需要 exec_path"))?;
             launch_html_tool(&exec_path)
         }
         "网页" => {
-            let exec_path = exec_path.ok_or("网页工具需要 URL 地址")?;
+            let exec_path = exec_path.ok_or_else(|| missing("网页工具需要 URL 地址"))?;
             open_url_in_browser(exec_path)
         }
-        _ => Err(format!("不支持的工具类型: {}", tool_type)),
+        _ => Err(LaunchError::UnsupportedType(tool_type.to_string())),
+    };
+
+    if let (Err(_), Some(key)) = (&result, &launch_key) {
+        release_launch(key);
+    }
+
+    // 启动统计：优先按工具 ID 记录
+    let stats_key = params
+        .tool_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .or(launch_key);
+    if let Some(key) = stats_key {
+        let outcome = result.as_ref().map(|_| ()).map_err(|e| e.to_string());
+        crate::launch_stats::record_launch(key, tool_type.to_string(), outcome);
+    }
+
+    // 仅在启动成功后打开
+    if let (Ok(()), Some(url)) = (&result, post_launch_url) {
+        let delay = Duration::from_millis(
+            params
+                .post_launch_delay_ms
+                .unwrap_or(DEFAULT_POST_LAUNCH_DELAY_MS),
+        );
+        std::thread::spawn(move || {
+            std::thread::sleep(delay);
+            if let Err(e) = open_url_in_browser(url.clone()) {
+                log::warn!("打开启动后地址失败: {} ({})", url, e);
+            }
+        });
+    }
+
+    result.map(|()| LaunchOutcome::Launched)
+}
+
+/// 预检工具配置：执行各类型启动前的路径 / 命令检查但不启动进程，返回发现的全部问题（为空表示可以启动）
+/// 参数与 launch_tool 相同，便于导入其他机器的工具目录后批量检查路径
+#[tauri::command]
+pub fn validate_tool(params: LaunchToolParams) -> Vec<LaunchError> {
+    let tool_type = params.tool_type.as_deref().unwrap_or("GUI");
+    let exec_path = params.exec_path.as_deref();
+    let env = params.env.unwrap_or_default();
+    let missing = |msg: &str| LaunchError::InvalidArgument(msg.to_string());
+
+    let mut checks = vec![validate_env(&env)];
+    if let Some(url) = params
+        .post_launch_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        checks.push(resolve_post_launch_url(url, params.port).map(|_| ()));
+    }
+
+    // 需要 exec_path 的类型：缺失时记录问题，否则检查文件
+    let require_file = |msg: &str| match exec_path {
+        Some(path) => ensure_launchable_file(path),
+        None => Err(missing(msg)),
+    };
+
+    match tool_type {
+        "GUI" => {
+            checks.push(require_file("GUI 工具需要 exec_path"));
+            if let Some(wd) = params.working_dir.as_deref() {
+                checks.push(ensure_working_dir(wd));
+            }
+        }
+        "CLI" => checks.push(require_file("CLI 工具需要 exec_path")),
+        "Python" => {
+            checks.push(require_file("Python 工具需要 exec_path"));
+            checks.push(ensure_command_resolvable(python_command()));
+        }
+        "JAR" => match params.jar_config.as_ref() {
+            Some(jar_config) => {
+                checks.push(ensure_launchable_file(&jar_config.jar_path));
+                checks.push(ensure_command_resolvable(&java_command(jar_config)));
+            }
+            None => checks.push(Err(missing("JAR 工具需要 jar_config"))),
+        },
+        "LNK" => {
+            checks.push(require_file("LNK 工具需要 exec_path"));
+            if !cfg!(target_os = "windows") {
+                checks.push(Err(LaunchError::UnsupportedType(
+                    "LNK 工具仅在 Windows 系统上支持".to_string(),
+                )));
+            }
+        }
+        "HTML" => checks.push(require_file("HTML 工具需要 exec_path")),
+        "网页" => match exec_path {
+            Some(url) => checks.push(ensure_http_url(url)),
+            None => checks.push(Err(missing("网页工具需要 URL 地址"))),
+        },
+        _ => checks.push(Err(LaunchError::UnsupportedType(tool_type.to_string()))),
+    }
+
+    checks.push(LaunchPolicy::load().check_tool(tool_type, exec_path, params.jar_config.as_ref()));
+
+    // 哈希校验（文件不存在已在上方记录，此处不再重复）
+    if let Some(expected) = params
+        .expected_sha256
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+    {
+        let target = match tool_type {
+            "JAR" => params.jar_config.as_ref().map(|c| c.jar_path.as_str()),
+            "网页" => {
+                checks.push(Err(missing("网页工具不支持 SHA-256 校验")));
+                None
+            }
+            _ => exec_path,
+        };
+        if let Some(target) = target.filter(|t| Path::new(t).is_file()) {
+            checks.push(verify_sha256(target, expected));
+        }
+    }
+
+    checks.into_iter().filter_map(Result::err).collect()
+}
+
+/// 替换启动后地址中的 {{port}} 占位符
+fn resolve_post_launch_url(url: &str, port: Option<u16>) -> Result<String, LaunchError> {
+    if !url.contains(PORT_PLACEHOLDER) {
+        return Ok(url.to_string());
+    }
+    let port = port.ok_or_else(|| {
+        LaunchError::InvalidArgument(format!(
+            "启动后地址包含 {} 占位符，但未提供端口: {}",
+            PORT_PLACEHOLDER, url
+        ))
+    })?;
+    Ok(url.replace(PORT_PLACEHOLDER, &port.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(target_os = "windows"))]
+    fn env(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    /// 用 bash -n 检查脚本语法（不执行）
+    #[cfg(not(target_os = "windows"))]
+    fn bash_syntax_ok(script: &str) -> bool {
+        Command::new("bash")
+            .args(["-n", "-c", script])
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn unix_terminal_script_quotes_env_exactly_once() {
+        let cases = [
+            (env(&[]), "/tmp", "ls -la"),
+            (env(&[("K", "v")]), "/tmp", "ls"),
+            (
+                env(&[("K", "it's"), ("P", "a b")]),
+                "/tmp/o'dir",
+                "tool \"x y\"",
+            ),
+        ];
+        for (vars, dir, command) in &cases {
+            let script = format!(
+                "{}; exec bash",
+                unix_terminal_script(Path::new(dir), vars, command)
+            );
+            assert!(bash_syntax_ok(&script), "语法错误: {}", script);
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn unix_terminal_script_exports_values_verbatim() {
+        let vars = env(&[("NETSEC_TEST_VAR", "it's \"quoted\" $HOME")]);
+        let script = unix_terminal_script(
+            &std::env::temp_dir(),
+            &vars,
+            "printf '%s' \"$NETSEC_TEST_VAR\"",
+        );
+        let output = Command::new("bash").args(["-c", &script]).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "it's \"quoted\" $HOME"
+        );
     }
 }
@@ -1,9 +1,14 @@
 // 模块声明
 mod ai_service;
+mod catalog;
 mod config;
+mod diagnostics;
 mod file_ops;
 mod icon_extractor;
+mod launch_stats;
 mod launcher;
+mod logging;
+mod process_registry;
 mod service;
 mod types;
 mod utils;
@@ -15,10 +20,15 @@ mod wiki_commands {
 }
 
 // 重新导出公共类型和函数
+pub use catalog::*;
 pub use config::*;
+pub use diagnostics::*;
 pub use file_ops::*;
 pub use icon_extractor::*;
+pub use launch_stats::*;
 pub use launcher::*;
+pub use logging::set_log_level;
+pub use process_registry::*;
 pub use types::*;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -43,7 +53,7 @@ pub fn run() {
             ai_service::GatewayPoolService::new("ai-gateway".to_string(), "AI Gateway".to_string()),
         ));
 
-        if let Err(e) = manager.register(ai_gateway_service) {
+        if let Err(e) = manager.register(ai_gateway_service, None) {
             log::warn!("注册 AI Gateway 服务失败: {}", e);
         } else {
             log::info!("AI Gateway 服务已注册到 ServiceManager");
@@ -55,13 +65,23 @@ pub fn run() {
 
     tauri::Builder::default()
         .setup(|app| {
-            if cfg!(debug_assertions) {
-                app.handle().plugin(
-                    tauri_plugin_log::Builder::default()
-                        .level(log::LevelFilter::Info)
-                        .build(),
-                )?;
-            }
+            // 开发与发布版本均启用日志（控制台 + .config/logs 滚动文件）
+            app.handle().plugin(logging::build_log_plugin())?;
+            log::set_max_level(logging::initial_log_level());
+            logging::warn_invalid_log_level();
+
+            // 删除上一次运行中断的分块上传留下的临时文件
+            file_ops::remove_orphan_upload_parts();
+
+            // 监听 Wiki 目录，文件增删改时刷新文件列表缓存并通知前端
+            let wiki_dir = wiki::server::WikiServer::new().get_wiki_dir().to_path_buf();
+            wiki::watcher::start_wiki_watcher(app.handle().clone(), wiki_dir);
+
+            // Worker 状态变化时通知前端（gateway-worker-state-changed）
+            ai_service::start_worker_state_emitter(app.handle().clone());
+
+            // 连接池 / Worker 锁长时间无法获取时告警（gateway-stall-detected）
+            ai_service::start_stall_watchdog(app.handle().clone());
 
             // 窗口全屏设置已在 tauri.conf.json 中配置
             // fullscreen: true 和 resizable: true 允许用户自行调整窗口大小
@@ -77,21 +97,48 @@ pub fn run() {
             get_config_file_path,
             config_file_exists,
             read_icon_file,
+            // 工具目录导入导出
+            export_catalog,
+            import_catalog,
+            // 安装诊断
+            run_diagnostics,
+            run_self_test,
+            set_log_level,
             // 工具启动
             launch_tool,
+            validate_tool,
             open_url_in_browser,
+            get_launch_policy,
+            set_launch_policy,
+            get_launch_stats,
+            list_spawned_processes,
+            kill_spawned_process,
             // 图标提取
             extract_icon_from_file,
             fetch_favicon,
             save_icon_to_cache,
+            resolve_tool_icon,
+            clear_icon_cache,
+            prune_icon_cache,
+            invalidate_icon,
+            infer_tool_from_path,
+            scan_directory_for_tools,
             // 文件操作
             upload_file,
+            upload_file_begin,
+            upload_file_chunk,
+            upload_file_finish,
+            upload_file_abort,
             resolve_file_path,
             open_file_dialog,
             // Wiki 功能
             wiki_commands::get_wiki_files,
             wiki_commands::read_wiki_file,
+            wiki_commands::download_wiki_file,
+            wiki_commands::read_wiki_image,
+            wiki_commands::read_wiki_section,
             wiki_commands::search_wiki,
+            wiki_commands::rebuild_wiki_index,
             wiki_commands::get_wiki_dir,
             wiki_commands::find_wiki_for_tool,
             // AI Gateway 服务（旧版，保持兼容）
@@ -104,22 +151,80 @@ pub fn run() {
             ai_service::init_gateway_pool,
             ai_service::start_gateway_pool,
             ai_service::stop_gateway_pool,
+            ai_service::cleanup_orphan_workers,
+            ai_service::configure_gateway_pool,
+            ai_service::get_health_check_config,
+            ai_service::set_health_check_config,
+            ai_service::get_circuit_breaker_config,
+            ai_service::set_circuit_breaker_config,
+            ai_service::refresh_models,
+            ai_service::get_model_cache_config,
+            ai_service::set_model_cache_config,
+            ai_service::get_startup_timeout_config,
+            ai_service::set_startup_timeout_config,
+            ai_service::get_request_policy,
+            ai_service::set_request_policy,
+            ai_service::get_model_aliases,
+            ai_service::set_model_aliases,
+            ai_service::reload_model_aliases,
             ai_service::forward_ai_request,
+            ai_service::forward_ai_request_legacy,
+            ai_service::forward_ai_request_stream,
             ai_service::get_gateway_pool_status,
+            ai_service::get_gateway_error_stats,
+            ai_service::get_gateway_snapshot,
+            ai_service::drain_gateway_worker,
+            ai_service::restart_gateway_worker,
+            ai_service::reenable_gateway_worker,
+            ai_service::set_worker_maintenance,
+            ai_service::reset_worker_stats,
+            ai_service::reset_gateway_pool_stats,
+            ai_service::warmup_worker,
+            ai_service::gateway_smoke_test,
+            ai_service::check_worker_health,
             ai_service::diagnose_worker,
+            ai_service::probe_worker_http,
+            ai_service::get_worker_logs,
+            ai_service::explain_selection,
+            ai_service::query_worker_direct,
+            ai_service::get_worker_endpoints,
+            ai_service::get_gateway_pool_alert,
             // 统一服务管理（新架构）
             service::get_all_services,
             service::get_service_status,
             service::start_service,
             service::stop_service,
             service::restart_service,
+            service::pause_service,
+            service::resume_service,
+            service::get_service_restart_policy,
+            service::set_service_restart_policy,
+            service::register_external_service,
+            service::get_service_events,
+            service::subscribe_service_events,
+            service::unsubscribe_service_events,
             service::get_prometheus_metrics,
             service::get_service_metrics,
+            service::export_services_state,
+            service::import_services_state,
+            service::get_monitor_drift,
         ])
         .manage(ai_service::legacy::AIServiceState::default())
         .manage(ai_service::AIServicePoolState::default())
         .manage(service_manager)
-        .run(tauri::generate_context!())
+        .build(tauri::generate_context!())
+        .map(|app| {
+            app.run(|app_handle, event| {
+                if let tauri::RunEvent::Exit = event {
+                    // 退出时统一停机：停止监控循环与所有服务、注销前端事件订阅，
+                    // 再停止连接池（健康检查线程退出，Worker 进程终止），避免残留进程占用端口
+                    use tauri::Manager;
+                    let manager = app_handle.state::<std::sync::Mutex<service::ServiceManager>>();
+                    utils::lock_or_recover(&*manager, "ServiceManager").shutdown_all();
+                    ai_service::shutdown_gateway_pool();
+                }
+            })
+        })
         .unwrap_or_else(|e| {
             log::error!("error while running tauri application: {}", e);
         });
@@ -0,0 +1,251 @@
+// 日志配置：开发与发布版本均写入滚动日志文件，并支持运行时调整级别
+use crate::utils::get_config_dir;
+use log::LevelFilter;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind, TimezoneStrategy};
+
+/// 默认日志级别
+pub const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Info;
+
+/// 单个日志文件最大大小（5 MB）
+const MAX_LOG_FILE_SIZE: u128 = 5 * 1024 * 1024;
+
+/// 保留的历史日志文件数
+const MAX_LOG_FILES: usize = 5;
+
+/// 日志文件名（不含扩展名）
+const LOG_FILE_NAME: &str = "netsec-toolbox";
+
+/// 启动日志级别环境变量（如 NETSEC_LOG=debug），未设置或无效时使用默认级别
+const LOG_LEVEL_ENV: &str = "NETSEC_LOG";
+
+/// 日志格式环境变量（NETSEC_LOG_FORMAT=json 时每行输出一个 JSON 对象，便于采集）
+const LOG_FORMAT_ENV: &str = "NETSEC_LOG_FORMAT";
+
+/// 临时调整级别的代次（新的调整会使旧的定时恢复失效）
+static LEVEL_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 敏感信息匹配规则（API Key、Authorization 头等）
+static SECRET_PATTERNS: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    [
+        (
+            r#"(?i)("?(?:authorization|x-api-key|api[_-]?key)"?\s*[:=]\s*"?)(?:bearer\s+)?[^\s",}]+"#,
+            "${1}***",
+        ),
+        (r"(?i)\bbearer\s+[A-Za-z0-9._~+/=-]+", "Bearer ***"),
+        (r"\bsk-[A-Za-z0-9_-]{8,}", "sk-***"),
+    ]
+    .into_iter()
+    .map(|(pattern, replacement)| (Regex::new(pattern).expect("日志脱敏规则无效"), replacement))
+    .collect()
+});
+
+/// 日志中的组件标签（如 [Gateway Pool]）、Worker 编号与服务 ID
+static COMPONENT_PATTERN: Lazy<Option<Regex>> = Lazy::new(|| Regex::new(r"^\[([^\]]+)\]").ok());
+static WORKER_ID_PATTERN: Lazy<Option<Regex>> = Lazy::new(|| Regex::new(r"Worker-(\d+)").ok());
+static SERVICE_ID_PATTERN: Lazy<Option<Regex>> =
+    Lazy::new(|| Regex::new(r"服务:?\s+([A-Za-z0-9_.:-]+)").ok());
+
+/// 启动日志级别及 NETSEC_LOG 无效时的提示（只读取、校验一次，临时调整级别到期恢复时复用）
+static INITIAL_LOG_LEVEL: Lazy<(LevelFilter, Option<String>)> =
+    Lazy::new(|| match std::env::var(LOG_LEVEL_ENV) {
+        Ok(value) if !value.trim().is_empty() => match value.trim().parse() {
+            Ok(level) => (level, None),
+            Err(_) => (
+                DEFAULT_LOG_LEVEL,
+                Some(format!(
+                    "{} 的值无效: {}（可选 off/error/warn/info/debug/trace），使用默认级别 {}",
+                    LOG_LEVEL_ENV, value, DEFAULT_LOG_LEVEL
+                )),
+            ),
+        },
+        _ => (DEFAULT_LOG_LEVEL, None),
+    });
+
+/// 启动时的日志级别（NETSEC_LOG 环境变量，未设置或无效时为默认级别）
+pub fn initial_log_level() -> LevelFilter {
+    INITIAL_LOG_LEVEL.0
+}
+
+/// NETSEC_LOG 无效时记录警告（需在日志插件安装后调用，启动时调用一次）
+pub fn warn_invalid_log_level() {
+    if let Some(warning) = INITIAL_LOG_LEVEL.1.as_deref() {
+        log::warn!("{}", warning);
+    }
+}
+
+/// 是否输出 JSON 格式日志（NETSEC_LOG_FORMAT=json）
+fn json_format_enabled() -> bool {
+    std::env::var(LOG_FORMAT_ENV).is_ok_and(|value| value.trim().eq_ignore_ascii_case("json"))
+}
+
+/// 按正则提取第一个捕获组
+fn capture_first(pattern: &Option<Regex>, text: &str) -> Option<String> {
+    pattern
+        .as_ref()?
+        .captures(text)?
+        .get(1)
+        .map(|m| m.as_str().to_string())
+}
+
+/// 构建一行 JSON 日志：时间、级别、模块、组件标签、Worker / 服务 ID（从消息中提取，没有时为 null）与脱敏后的消息
+fn json_log_line(timestamp: String, record: &log::Record, message: &str) -> serde_json::Value {
+    let worker_id =
+        capture_first(&WORKER_ID_PATTERN, message).and_then(|id| id.parse::<u64>().ok());
+    serde_json::json!({
+        "ts": timestamp,
+        "level": record.level().as_str(),
+        "module": record.target(),
+        "component": capture_first(&COMPONENT_PATTERN, message),
+        "worker_id": worker_id,
+        "service_id": capture_first(&SERVICE_ID_PATTERN, message),
+        "message": message,
+    })
+}
+
+/// 脱敏日志内容（隐藏 API Key / Authorization 等）
+pub fn redact_secrets(message: &str) -> String {
+    let mut redacted = message.to_string();
+    for (re, replacement) in SECRET_PATTERNS.iter() {
+        if re.is_match(&redacted) {
+            redacted = re.replace_all(&redacted, *replacement).into_owned();
+        }
+    }
+    redacted
+}
+
+/// 日志目录（.config/logs）
+pub fn get_log_dir() -> std::path::PathBuf {
+    get_config_dir().join("logs")
+}
+
+/// 构建日志插件：输出到控制台和 .config/logs 下的滚动日志文件
+/// 插件本身放行所有级别，实际级别由 log::set_max_level 控制（见 initial_log_level / set_log_level）
+/// NETSEC_LOG_FORMAT=json 时控制台与文件均改为每行一个 JSON 对象
+pub fn build_log_plugin<R: tauri::Runtime>() -> tauri::plugin::TauriPlugin<R> {
+    let json_format = json_format_enabled();
+    tauri_plugin_log::Builder::default()
+        .clear_targets()
+        .target(Target::new(TargetKind::Stdout))
+        .target(Target::new(TargetKind::Folder {
+            path: get_log_dir(),
+            file_name: Some(LOG_FILE_NAME.to_string()),
+        }))
+        .level(LevelFilter::Trace)
+        // 第三方库的调试日志过多，单独限制
+        .level_for("hyper", LevelFilter::Info)
+        .level_for("hyper_util", LevelFilter::Info)
+        .level_for("reqwest", LevelFilter::Info)
+        .max_file_size(MAX_LOG_FILE_SIZE)
+        .rotation_strategy(RotationStrategy::KeepSome(MAX_LOG_FILES))
+        .format(move |out, message, record| {
+            let now = TimezoneStrategy::UseUtc.get_now();
+            if json_format {
+                let timestamp = format!(
+                    "{}T{:02}:{:02}:{:02}.{:03}Z",
+                    now.date(),
+                    now.hour(),
+                    now.minute(),
+                    now.second(),
+                    now.millisecond()
+                );
+                let message = redact_secrets(&message.to_string());
+                return out.finish(format_args!(
+                    "{}",
+                    json_log_line(timestamp, record, &message)
+                ));
+            }
+            out.finish(format_args!(
+                "[{}][{:02}:{:02}:{:02}][{}][{}] {}",
+                now.date(),
+                now.hour(),
+                now.minute(),
+                now.second(),
+                record.target(),
+                record.level(),
+                redact_secrets(&message.to_string())
+            ))
+        })
+        .build()
+}
+
+/// 运行时调整日志级别
+/// duration_secs 指定时，到期后自动恢复为启动级别（用于临时提高详细程度排查问题）
+#[tauri::command]
+pub fn set_log_level(level: String, duration_secs: Option<u64>) -> Result<String, String> {
+    let filter: LevelFilter = level.trim().parse().map_err(|_| {
+        format!(
+            "无效的日志级别: {}（可选 off/error/warn/info/debug/trace）",
+            level
+        )
+    })?;
+
+    log::set_max_level(filter);
+    let generation = LEVEL_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    log::info!("日志级别已调整为 {}", filter);
+
+    match duration_secs.filter(|secs| *secs > 0) {
+        Some(secs) => {
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_secs(secs));
+                // 期间又有新的调整时不再恢复
+                if LEVEL_GENERATION.load(Ordering::SeqCst) == generation {
+                    let restored = initial_log_level();
+                    log::set_max_level(restored);
+                    log::info!("日志级别已恢复为 {}", restored);
+                }
+            });
+            Ok(format!(
+                "日志级别已调整为 {}，{} 秒后恢复为 {}",
+                filter,
+                secs,
+                initial_log_level()
+            ))
+        }
+        None => Ok(format!("日志级别已调整为 {}", filter)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_patterns_compile() {
+        assert_eq!(SECRET_PATTERNS.len(), 3);
+    }
+
+    #[test]
+    fn redacts_authorization_header() {
+        assert_eq!(
+            redact_secrets("请求头 Authorization: Bearer eyJhbGciOi.abc-123"),
+            "请求头 Authorization: ***"
+        );
+        assert_eq!(
+            redact_secrets("token=Bearer abc.def_ghi 已过期"),
+            "token=Bearer *** 已过期"
+        );
+    }
+
+    #[test]
+    fn redacts_api_key_fields() {
+        assert_eq!(
+            redact_secrets(r#"{"api_key": "my-secret-value", "model": "gpt"}"#),
+            r#"{"api_key": "***", "model": "gpt"}"#
+        );
+        assert_eq!(redact_secrets("x-api-key=abcdef"), "x-api-key=***");
+    }
+
+    #[test]
+    fn redacts_sk_tokens() {
+        assert_eq!(
+            redact_secrets("使用密钥 sk-proj_AbCdEf123456 调用"),
+            "使用密钥 sk-*** 调用"
+        );
+        // 过短的 sk- 前缀不是密钥
+        assert_eq!(redact_secrets("task sk-1"), "task sk-1");
+    }
+}
@@ -0,0 +1,219 @@
+// 进程注册表：记录本应用直接启动的工具进程、Worker 进程与外部进程服务，供前端查看并终止残留进程
+// 只管理本应用启动的进程，不扫描系统进程列表；CLI / Python / JAR / LNK 工具登记的是直接启动的
+// 终端或中间进程（Start-Process、osascript、cmd start 等），中间进程打开窗口后立即退出，
+// 窗口中实际运行的工具不在此列；通过 xdg-open 等打开的浏览器同样不在此列
+use serde::Serialize;
+use std::collections::HashMap;
+use std::process::Child;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// 进程类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SpawnedProcessKind {
+    /// 工具进程（launch_tool 启动的 GUI 工具）
+    Tool,
+    /// AI Gateway Worker 进程
+    Worker,
+    /// 外部进程服务（register_external_service 注册的服务进程）
+    Service,
+}
+
+/// 进程句柄：工具进程由注册表持有 Child（句柄未释放前 PID 不会被系统回收复用），
+/// 外部进程服务与服务共享 Child，Worker 进程由连接池持有，按 Worker ID 核对当前进程
+enum ProcessHandle {
+    Owned(Child),
+    Shared(Arc<Mutex<Child>>),
+    Worker(usize),
+}
+
+impl ProcessHandle {
+    /// 持有 Child 的进程是否仍在运行（Worker 返回 None，需向连接池核对）
+    fn child_alive(&mut self) -> Option<bool> {
+        match self {
+            ProcessHandle::Owned(child) => Some(matches!(child.try_wait(), Ok(None))),
+            ProcessHandle::Shared(child) => Some(matches!(
+                crate::utils::lock_or_recover(child.as_ref(), "SpawnedProcess.child").try_wait(),
+                Ok(None)
+            )),
+            ProcessHandle::Worker(_) => None,
+        }
+    }
+}
+
+struct TrackedProcess {
+    kind: SpawnedProcessKind,
+    label: String,
+    handle: ProcessHandle,
+}
+
+/// 本应用启动的进程（list_spawned_processes 返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct SpawnedProcess {
+    pub pid: u32,
+    pub kind: SpawnedProcessKind,
+    pub label: String,
+    pub alive: bool,
+}
+
+static PROCESS_REGISTRY: OnceLock<Mutex<HashMap<u32, TrackedProcess>>> = OnceLock::new();
+
+fn registry() -> &'static Mutex<HashMap<u32, TrackedProcess>> {
+    PROCESS_REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 移除并回收已退出的工具进程（登记新进程时调用，避免已退出的子进程长期未回收）
+fn prune_exited_tools(processes: &mut HashMap<u32, TrackedProcess>) {
+    processes.retain(|_, process| process.handle.child_alive().unwrap_or(true));
+}
+
+/// 登记本应用启动的工具进程（注册表接管 Child 句柄）
+pub fn track_child(child: Child, label: String) {
+    let mut processes = crate::utils::lock_or_recover(registry(), "PROCESS_REGISTRY");
+    prune_exited_tools(&mut processes);
+    processes.insert(
+        child.id(),
+        TrackedProcess {
+            kind: SpawnedProcessKind::Tool,
+            label,
+            handle: ProcessHandle::Owned(child),
+        },
+    );
+}
+
+/// 登记外部进程服务的进程（服务与注册表共享 Child 句柄；在此终止后由 ServiceManager 按重启策略处理）
+pub fn track_service(child: Arc<Mutex<Child>>, label: String) {
+    let pid = crate::utils::lock_or_recover(child.as_ref(), "SpawnedProcess.child").id();
+    let mut processes = crate::utils::lock_or_recover(registry(), "PROCESS_REGISTRY");
+    prune_exited_tools(&mut processes);
+    processes.insert(
+        pid,
+        TrackedProcess {
+            kind: SpawnedProcessKind::Service,
+            label,
+            handle: ProcessHandle::Shared(child),
+        },
+    );
+}
+
+/// 登记 Worker 进程（Child 句柄仍由连接池持有）
+pub fn track_worker(pid: u32, worker_id: usize, port: u16) {
+    let mut processes = crate::utils::lock_or_recover(registry(), "PROCESS_REGISTRY");
+    prune_exited_tools(&mut processes);
+    processes.insert(
+        pid,
+        TrackedProcess {
+            kind: SpawnedProcessKind::Worker,
+            label: format!("Worker-{}（端口 {}）", worker_id, port),
+            handle: ProcessHandle::Worker(worker_id),
+        },
+    );
+}
+
+/// 列出本应用启动的进程（按 PID 排序）；已退出的进程在本次结果中标记 alive = false 后从注册表移除
+/// 在终端中运行的工具（CLI / Python / JAR）与 LNK 快捷方式只登记直接启动的终端或中间进程，
+/// 中间进程退出后工具本身不会出现在列表中，需在其窗口中关闭
+#[tauri::command]
+pub fn list_spawned_processes() -> Vec<SpawnedProcess> {
+    let mut result = Vec::new();
+    let mut workers = Vec::new();
+    {
+        let mut processes = crate::utils::lock_or_recover(registry(), "PROCESS_REGISTRY");
+        processes.retain(|pid, process| match process.handle.child_alive() {
+            Some(alive) => {
+                result.push(SpawnedProcess {
+                    pid: *pid,
+                    kind: process.kind,
+                    label: process.label.clone(),
+                    alive,
+                });
+                alive
+            }
+            None => {
+                if let ProcessHandle::Worker(worker_id) = process.handle {
+                    workers.push((*pid, worker_id, process.label.clone()));
+                }
+                true
+            }
+        });
+    }
+
+    // 检查 Worker 进程需要获取连接池与 Worker 锁，不持有注册表锁
+    let mut exited_workers = Vec::new();
+    for (pid, worker_id, label) in workers {
+        let alive = crate::ai_service::worker_process_alive(worker_id, pid);
+        if !alive {
+            exited_workers.push((pid, worker_id));
+        }
+        result.push(SpawnedProcess {
+            pid,
+            kind: SpawnedProcessKind::Worker,
+            label,
+            alive,
+        });
+    }
+    if !exited_workers.is_empty() {
+        let mut processes = crate::utils::lock_or_recover(registry(), "PROCESS_REGISTRY");
+        for (pid, worker_id) in exited_workers {
+            remove_worker_entry(&mut processes, pid, worker_id);
+        }
+    }
+
+    result.sort_by_key(|process| process.pid);
+    result
+}
+
+/// 移除 Worker 进程的登记（释放注册表锁期间同一 PID 被重新登记为其他进程时保留新条目）
+fn remove_worker_entry(processes: &mut HashMap<u32, TrackedProcess>, pid: u32, worker_id: usize) {
+    if matches!(processes.get(&pid), Some(p) if matches!(p.handle, ProcessHandle::Worker(id) if id == worker_id))
+    {
+        processes.remove(&pid);
+    }
+}
+
+/// 终止本应用启动的进程：只接受注册表中的 PID，并通过持有的句柄（或 Worker 当前进程）核对，
+/// 不会终止已被系统回收复用的 PID；Worker 进程与外部进程服务终止后按各自的重启策略处理
+#[tauri::command]
+pub fn kill_spawned_process(pid: u32) -> Result<(), String> {
+    let mut processes = crate::utils::lock_or_recover(registry(), "PROCESS_REGISTRY");
+    let process = processes
+        .get_mut(&pid)
+        .ok_or_else(|| format!("进程 {} 不是本应用启动的进程或已退出", pid))?;
+    let label = process.label.clone();
+
+    match process.handle {
+        ProcessHandle::Owned(ref mut child) => {
+            if !matches!(child.try_wait(), Ok(None)) {
+                processes.remove(&pid);
+                return Err(format!("进程 {}（{}）已退出", pid, label));
+            }
+            child
+                .kill()
+                .map_err(|e| format!("终止进程 {}（{}）失败: {}", pid, label, e))?;
+            let _ = child.wait();
+            processes.remove(&pid);
+        }
+        ProcessHandle::Shared(ref child) => {
+            // 服务检测到进程退出后按重启策略处理，重启后的新进程重新登记
+            let child = Arc::clone(child);
+            let mut child = crate::utils::lock_or_recover(child.as_ref(), "SpawnedProcess.child");
+            if !matches!(child.try_wait(), Ok(None)) {
+                processes.remove(&pid);
+                return Err(format!("进程 {}（{}）已退出", pid, label));
+            }
+            child
+                .kill()
+                .map_err(|e| format!("终止进程 {}（{}）失败: {}", pid, label, e))?;
+            let _ = child.wait();
+            processes.remove(&pid);
+        }
+        ProcessHandle::Worker(worker_id) => {
+            // 终止 Worker 需要获取 Worker 锁，先释放注册表锁；终止成功后再移除登记
+            drop(processes);
+            crate::ai_service::kill_worker_process(worker_id, pid)?;
+            let mut processes = crate::utils::lock_or_recover(registry(), "PROCESS_REGISTRY");
+            remove_worker_entry(&mut processes, pid, worker_id);
+        }
+    }
+    log::info!("已终止进程 {}（{}）", pid, label);
+    Ok(())
+}
@@ -1,4 +1,5 @@
 /// 统一熔断与限流策略
+use serde::Serialize;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
@@ -27,6 +28,8 @@ pub struct CircuitBreakerConfig {
     /// 最小请求数（时间窗口内最少请求数才统计失败率）
     #[allow(dead_code)]
     pub min_requests: u32,
+    /// 半开状态下同时允许的试探请求数
+    pub half_open_max_trials: u32,
 }
 
 impl Default for CircuitBreakerConfig {
@@ -37,19 +40,34 @@ impl Default for CircuitBreakerConfig {
             timeout: Duration::from_secs(30),
             time_window: Duration::from_secs(60),
             min_requests: 10,
+            half_open_max_trials: 1,
         }
     }
 }
 
-/// 熔断器
-#[derive(Debug)]
+/// 当前半开窗口内的试探请求统计（进入半开时清零）
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct HalfOpenTrials {
+    /// 允许同时进行的试探请求数
+    pub max_trials: u32,
+    /// 进行中的试探请求数
+    pub in_flight: u32,
+    /// 已成功的试探请求数
+    pub succeeded: u32,
+    /// 已失败的试探请求数（失败后熔断器重新打开）
+    pub failed: u32,
+}
+
+/// 熔断器（克隆得到的实例与原实例共享同一状态，可用于只读查询）
+#[derive(Debug, Clone)]
 pub struct CircuitBreaker {
     state: Arc<Mutex<CircuitBreakerState>>,
-    config: CircuitBreakerConfig,
+    /// 配置（运行中可调整，克隆实例共享同一配置）
+    config: Arc<Mutex<CircuitBreakerConfig>>,
     /// 连续失败次数
     consecutive_failures: Arc<Mutex<u32>>,
-    /// 半开状态下的成功次数
-    half_open_successes: Arc<Mutex<u32>>,
+    /// 半开状态下的试探请求统计
+    half_open_trials: Arc<Mutex<HalfOpenTrials>>,
     /// 熔断器打开时间
     opened_at: Arc<Mutex<Option<Instant>>>,
     /// 请求历史（用于统计失败率）
@@ -60,9 +78,9 @@ impl CircuitBreaker {
     pub fn new(config: CircuitBreakerConfig) -> Self {
         Self {
             state: Arc::new(Mutex::new(CircuitBreakerState::Closed)),
-            config,
+            config: Arc::new(Mutex::new(config)),
             consecutive_failures: Arc::new(Mutex::new(0)),
-            half_open_successes: Arc::new(Mutex::new(0)),
+            half_open_trials: Arc::new(Mutex::new(HalfOpenTrials::default())),
             opened_at: Arc::new(Mutex::new(None)),
             request_history: Arc::new(Mutex::new(Vec::new())),
         }
@@ -74,6 +92,25 @@ impl CircuitBreaker {
         opened_at.map(|t| t.elapsed())
     }
 
+    /// 距离下一次尝试半开恢复的剩余时间（仅打开状态返回；已到期但尚未有请求触发时为 0）
+    pub fn time_until_half_open(&self) -> Option<Duration> {
+        if self.state() != CircuitBreakerState::Open {
+            return None;
+        }
+        self.opened_elapsed()
+            .map(|elapsed| self.config().timeout.saturating_sub(elapsed))
+    }
+
+    /// 熔断器配置
+    pub fn config(&self) -> CircuitBreakerConfig {
+        crate::utils::lock_or_recover(self.config.as_ref(), "CircuitBreaker.config").clone()
+    }
+
+    /// 调整熔断器配置（对之后的失败统计生效，不改变当前打开 / 关闭状态）
+    pub fn set_config(&self, config: CircuitBreakerConfig) {
+        *crate::utils::lock_or_recover(self.config.as_ref(), "CircuitBreaker.config") = config;
+    }
+
     pub fn reset(&self) {
         let mut state_guard =
             crate::utils::lock_or_recover(self.state.as_ref(), "CircuitBreaker.state");
@@ -87,12 +124,10 @@ impl CircuitBreaker {
         *failures = 0;
         drop(failures);
 
-        let mut successes = crate::utils::lock_or_recover(
-            self.half_open_successes.as_ref(),
-            "CircuitBreaker.half_open_successes",
-        );
-        *successes = 0;
-        drop(successes);
+        *crate::utils::lock_or_recover(
+            self.half_open_trials.as_ref(),
+            "CircuitBreaker.half_open_trials",
+        ) = HalfOpenTrials::default();
 
         let mut opened_at =
             crate::utils::lock_or_recover(self.opened_at.as_ref(), "CircuitBreaker.opened_at");
@@ -109,15 +144,13 @@ impl CircuitBreaker {
             self.consecutive_failures.as_ref(),
             "CircuitBreaker.consecutive_failures",
         );
-        *failures = self.config.failure_threshold;
+        *failures = self.config().failure_threshold;
         drop(failures);
 
-        let mut successes = crate::utils::lock_or_recover(
-            self.half_open_successes.as_ref(),
-            "CircuitBreaker.half_open_successes",
-        );
-        *successes = 0;
-        drop(successes);
+        *crate::utils::lock_or_recover(
+            self.half_open_trials.as_ref(),
+            "CircuitBreaker.half_open_trials",
+        ) = HalfOpenTrials::default();
 
         let mut opened_at =
             crate::utils::lock_or_recover(self.opened_at.as_ref(), "CircuitBreaker.opened_at");
@@ -136,18 +169,17 @@ impl CircuitBreaker {
                     self.opened_at.as_ref(),
                     "CircuitBreaker.opened_at",
                 ) {
-                    if opened_at.elapsed() >= self.config.timeout {
+                    if opened_at.elapsed() >= self.config().timeout {
                         let mut state_guard = crate::utils::lock_or_recover(
                             self.state.as_ref(),
                             "CircuitBreaker.state",
                         );
                         *state_guard = CircuitBreakerState::HalfOpen;
-                        let mut successes = crate::utils::lock_or_recover(
-                            self.half_open_successes.as_ref(),
-                            "CircuitBreaker.half_open_successes",
-                        );
-                        *successes = 0;
-                        return true; // 半开状态允许一个请求测试
+                        *crate::utils::lock_or_recover(
+                            self.half_open_trials.as_ref(),
+                            "CircuitBreaker.half_open_trials",
+                        ) = HalfOpenTrials::default();
+                        return true; // 半开状态允许有限个请求测试
                     }
                 }
                 false
@@ -180,14 +212,15 @@ impl CircuitBreaker {
             }
             CircuitBreakerState::HalfOpen => {
                 // 半开状态下成功，增加成功计数
-                let mut successes = crate::utils::lock_or_recover(
-                    self.half_open_successes.as_ref(),
-                    "CircuitBreaker.half_open_successes",
+                let mut trials = crate::utils::lock_or_recover(
+                    self.half_open_trials.as_ref(),
+                    "CircuitBreaker.half_open_trials",
                 );
-                *successes += 1;
+                trials.in_flight = trials.in_flight.saturating_sub(1);
+                trials.succeeded += 1;
 
                 // 如果成功次数达到阈值，关闭熔断器
-                if *successes >= self.config.success_threshold {
+                if trials.succeeded >= self.config().success_threshold {
                     *state_guard = CircuitBreakerState::Closed;
                     *failures = 0;
                     let mut opened_at = crate::utils::lock_or_recover(
@@ -225,7 +258,7 @@ impl CircuitBreaker {
                 *failures += 1;
 
                 // 检查是否达到失败阈值
-                if *failures >= self.config.failure_threshold {
+                if *failures >= self.config().failure_threshold {
                     // 打开熔断器
                     *state_guard = CircuitBreakerState::Open;
                     let mut opened_at = crate::utils::lock_or_recover(
@@ -236,14 +269,22 @@ impl CircuitBreaker {
                 }
             }
             CircuitBreakerState::HalfOpen => {
-                // 半开状态下失败，立即打开熔断器
+                // 半开状态下失败，立即打开熔断器（保留试探统计，便于查看失败原因）
+                {
+                    let mut trials = crate::utils::lock_or_recover(
+                        self.half_open_trials.as_ref(),
+                        "CircuitBreaker.half_open_trials",
+                    );
+                    trials.in_flight = trials.in_flight.saturating_sub(1);
+                    trials.failed += 1;
+                }
                 *state_guard = CircuitBreakerState::Open;
                 let mut opened_at = crate::utils::lock_or_recover(
                     self.opened_at.as_ref(),
                     "CircuitBreaker.opened_at",
                 );
                 *opened_at = Some(Instant::now());
-                *failures = self.config.failure_threshold;
+                *failures = self.config().failure_threshold;
             }
             CircuitBreakerState::Open => {
                 // 已经打开，更新打开时间
@@ -261,6 +302,43 @@ impl CircuitBreaker {
         *crate::utils::lock_or_recover(self.state.as_ref(), "CircuitBreaker.state")
     }
 
+    /// 半开状态下是否还有空余的试探名额（非半开状态时恒为 true）
+    pub fn half_open_trial_available(&self) -> bool {
+        if self.state() != CircuitBreakerState::HalfOpen {
+            return true;
+        }
+        crate::utils::lock_or_recover(
+            self.half_open_trials.as_ref(),
+            "CircuitBreaker.half_open_trials",
+        )
+        .in_flight
+            < self.config().half_open_max_trials.max(1)
+    }
+
+    /// 占用一个半开试探名额（非半开状态时忽略）
+    pub fn begin_half_open_trial(&self) {
+        if self.state() != CircuitBreakerState::HalfOpen {
+            return;
+        }
+        crate::utils::lock_or_recover(
+            self.half_open_trials.as_ref(),
+            "CircuitBreaker.half_open_trials",
+        )
+        .in_flight += 1;
+    }
+
+    /// 获取当前半开窗口内的试探统计
+    pub fn half_open_trials(&self) -> HalfOpenTrials {
+        let trials = *crate::utils::lock_or_recover(
+            self.half_open_trials.as_ref(),
+            "CircuitBreaker.half_open_trials",
+        );
+        HalfOpenTrials {
+            max_trials: self.config().half_open_max_trials.max(1),
+            ..trials
+        }
+    }
+
     /// 获取失败率（时间窗口内）
     #[allow(dead_code)]
     pub fn failure_rate(&self) -> f64 {
@@ -269,14 +347,14 @@ impl CircuitBreaker {
             "CircuitBreaker.request_history",
         );
         let now = Instant::now();
-        let window_start = now - self.config.time_window;
+        let window_start = now - self.config().time_window;
 
         let recent_requests: Vec<_> = history
             .iter()
             .filter(|(time, _)| *time >= window_start)
             .collect();
 
-        if recent_requests.len() < self.config.min_requests as usize {
+        if recent_requests.len() < self.config().min_requests as usize {
             return 0.0; // 请求数不足，返回 0
         }
 
@@ -290,7 +368,7 @@ impl CircuitBreaker {
     /// 清理过期历史记录
     fn cleanup_old_history(&self, history: &mut Vec<(Instant, bool)>) {
         let now = Instant::now();
-        let cutoff = now - self.config.time_window * 2; // 保留 2 倍时间窗口的数据
+        let cutoff = now - self.config().time_window * 2; // 保留 2 倍时间窗口的数据
         history.retain(|(time, _)| *time >= cutoff);
     }
 }
@@ -1,9 +1,18 @@
-use crate::service::dto::{ServiceStatusDTO, ServiceStatusListDTO};
+use crate::service::drift::LoopDriftStats;
+use crate::service::dto::{
+    ServiceStatusDTO, ServiceStatusListDTO, ServicesImportReport, ServicesStateSnapshot,
+    SERVICES_SNAPSHOT_VERSION,
+};
+use crate::service::events::{EventLevel, ServiceEvent};
+use crate::service::external_process::{ExternalProcessConfig, ExternalProcessService};
 use crate::service::manager::ServiceManager;
+use crate::service::restart_policy::RestartPolicy;
+use crate::service::websocket::WebSocketEventListener;
 use serde::Serialize;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex};
 /// ServiceManager 的 Tauri 命令
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 
 /// 获取所有服务状态
 #[tauri::command]
@@ -63,11 +72,206 @@ pub fn restart_service(
         .map_err(|e| format!("重启失败: {}", e))
 }
 
-/// 获取 Prometheus 格式的指标
+/// 暂停服务的自动恢复（排查问题时避免监控循环反复重启服务）
 #[tauri::command]
-pub fn get_prometheus_metrics(manager: State<'_, Mutex<ServiceManager>>) -> Result<String, String> {
+pub fn pause_service(
+    manager: State<'_, Mutex<ServiceManager>>,
+    id: String,
+) -> Result<String, String> {
+    let manager_guard = crate::utils::lock_or_recover(&*manager, "ServiceManager");
+    manager_guard
+        .pause_service(&id)
+        .map(|_| format!("服务 {} 已暂停自动恢复", id))
+}
+
+/// 恢复服务的自动恢复
+#[tauri::command]
+pub fn resume_service(
+    manager: State<'_, Mutex<ServiceManager>>,
+    id: String,
+) -> Result<String, String> {
+    let manager_guard = crate::utils::lock_or_recover(&*manager, "ServiceManager");
+    manager_guard
+        .resume_service(&id)
+        .map(|_| format!("服务 {} 已恢复自动恢复", id))
+}
+
+/// 注册外部进程服务（由 ServiceManager 监控，进程退出或健康检查失败时按策略自动重启）
+#[tauri::command]
+pub fn register_external_service(
+    manager: State<'_, Mutex<ServiceManager>>,
+    config: ExternalProcessConfig,
+) -> Result<String, String> {
+    config.validate()?;
+    let id = config.id.clone();
+    let auto_start = config.auto_start;
+    let restart_policy = config.restart_policy.clone();
+    let service = std::sync::Arc::new(Mutex::new(ExternalProcessService::new(config)));
+
+    let manager_guard = crate::utils::lock_or_recover(&*manager, "ServiceManager");
+    manager_guard.register(service, restart_policy)?;
+    if auto_start {
+        manager_guard
+            .start_service(&id)
+            .map_err(|e| format!("服务 {} 已注册，但启动失败: {}", id, e))?;
+        return Ok(format!("服务 {} 已注册并启动", id));
+    }
+    Ok(format!("服务 {} 已注册", id))
+}
+
+/// 单次查询事件历史的默认条数
+const DEFAULT_SERVICE_EVENTS_LIMIT: usize = 50;
+
+/// 获取服务最近的事件（用于服务卡片的日志面板）
+/// min_level 为 debug / info / warn / error（默认 info），limit 默认 50 条
+#[tauri::command]
+pub fn get_service_events(
+    manager: State<'_, Mutex<ServiceManager>>,
+    id: String,
+    min_level: Option<EventLevel>,
+    limit: Option<usize>,
+) -> Result<Vec<ServiceEvent>, String> {
+    let manager_guard = crate::utils::lock_or_recover(&*manager, "ServiceManager");
+    manager_guard.recent_events(
+        &id,
+        min_level.unwrap_or(EventLevel::Info),
+        limit.unwrap_or(DEFAULT_SERVICE_EVENTS_LIMIT),
+    )
+}
+
+/// 事件订阅序号
+static EVENT_SUBSCRIPTION_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// 订阅服务事件（状态变化、健康检查、错误等），返回本次订阅专用的 Tauri 事件名（同时作为订阅 ID）
+/// 前端监听该事件即可实时接收推送；不再需要时调用 unsubscribe_service_events 注销
+#[tauri::command]
+pub fn subscribe_service_events(
+    app: AppHandle,
+    manager: State<'_, Mutex<ServiceManager>>,
+) -> Result<String, String> {
+    let id = format!(
+        "service-event-{}",
+        EVENT_SUBSCRIPTION_SEQ.fetch_add(1, Ordering::Relaxed) + 1
+    );
+
+    // 事件总线回调只写入通道，由独立线程发送 Tauri 事件，避免持有服务锁时调用 emit；
+    // 注销订阅后发送端被释放，线程随之退出
+    let (tx, rx) = mpsc::channel::<ServiceEvent>();
+    let event_name = id.clone();
+    std::thread::Builder::new()
+        .name(format!("{}-emitter", id))
+        .spawn(move || {
+            while let Ok(event) = rx.recv() {
+                if let Err(e) = app.emit(&event_name, &event) {
+                    log::warn!("发送 {} 事件失败: {}", event_name, e);
+                }
+            }
+            log::debug!("[ServiceManager] 事件订阅 {} 的发送线程已退出", event_name);
+        })
+        .map_err(|e| format!("启动事件发送线程失败: {}", e))?;
+
+    let listener = WebSocketEventListener::new(id.clone());
+    listener.set_sender(Box::new(move |event| tx.send(event.clone()).is_ok()));
+
     let manager_guard = crate::utils::lock_or_recover(&*manager, "ServiceManager");
-    Ok(manager_guard.get_prometheus_metrics())
+    manager_guard.subscribe_events(Box::new(listener));
+    log::info!("[ServiceManager] 新增事件订阅: {}", id);
+    Ok(id)
+}
+
+/// 注销服务事件订阅
+#[tauri::command]
+pub fn unsubscribe_service_events(
+    manager: State<'_, Mutex<ServiceManager>>,
+    id: String,
+) -> Result<bool, String> {
+    let manager_guard = crate::utils::lock_or_recover(&*manager, "ServiceManager");
+    Ok(manager_guard.unsubscribe_events(&id))
+}
+
+/// 获取服务当前使用的自动重启策略
+#[tauri::command]
+pub fn get_service_restart_policy(
+    manager: State<'_, Mutex<ServiceManager>>,
+    id: String,
+) -> Result<RestartPolicy, String> {
+    let manager_guard = crate::utils::lock_or_recover(&*manager, "ServiceManager");
+    if manager_guard.get_service(&id).is_none() {
+        return Err(format!("服务 {} 不存在", id));
+    }
+    Ok(manager_guard.restart_policy_for(&id))
+}
+
+/// 更新服务的自动重启策略（超出范围的取值会被限制，返回实际生效的策略）
+#[tauri::command]
+pub fn set_service_restart_policy(
+    manager: State<'_, Mutex<ServiceManager>>,
+    id: String,
+    policy: RestartPolicy,
+) -> Result<RestartPolicy, String> {
+    let manager_guard = crate::utils::lock_or_recover(&*manager, "ServiceManager");
+    manager_guard.set_restart_policy(&id, policy)
+}
+
+/// 导出服务拓扑快照（含连接池配置），用于保存已知可用的配置
+#[tauri::command]
+pub fn export_services_state(
+    manager: State<'_, Mutex<ServiceManager>>,
+) -> Result<ServicesStateSnapshot, String> {
+    let manager_guard = crate::utils::lock_or_recover(&*manager, "ServiceManager");
+    let mut snapshot = manager_guard.export_state();
+    snapshot.gateway_pool = Some(crate::ai_service::GatewayPoolConfig::load());
+    Ok(snapshot)
+}
+
+/// 按快照恢复服务拓扑（启动/停止/重启服务以匹配期望状态）
+#[tauri::command]
+pub fn import_services_state(
+    manager: State<'_, Mutex<ServiceManager>>,
+    snapshot: ServicesStateSnapshot,
+) -> Result<ServicesImportReport, String> {
+    if snapshot.version > SERVICES_SNAPSHOT_VERSION {
+        return Err(format!(
+            "不支持的快照版本: {}（当前支持 {}）",
+            snapshot.version, SERVICES_SNAPSHOT_VERSION
+        ));
+    }
+
+    // 先写入连接池配置，使随后启动的 Gateway 服务使用快照中的配置
+    let gateway_pool_applied = match &snapshot.gateway_pool {
+        Some(config) => {
+            config
+                .save()
+                .map_err(|e| format!("恢复连接池配置失败: {}", e))?;
+            true
+        }
+        None => false,
+    };
+
+    // 启动 / 停止服务耗时较长，取得句柄后释放 ServiceManager 锁再执行
+    let handle = crate::utils::lock_or_recover(&*manager, "ServiceManager").handle();
+    let mut report = handle.import_state(&snapshot);
+    report.gateway_pool_applied = gateway_pool_applied;
+    Ok(report)
+}
+
+/// 获取后台循环（服务监控、Gateway 健康检查）的实际间隔与漂移统计
+/// 实际间隔超过预期 2 倍时 overloaded 为 true，说明应用过载或存在锁竞争
+#[tauri::command]
+pub fn get_monitor_drift() -> Result<Vec<LoopDriftStats>, String> {
+    Ok(crate::service::drift::loop_drift_snapshot())
+}
+
+/// 获取 Prometheus 格式的指标（服务级指标 + AI Gateway 连接池的 Worker 级指标）
+#[tauri::command]
+pub fn get_prometheus_metrics(manager: State<'_, Mutex<ServiceManager>>) -> Result<String, String> {
+    // 先释放 ServiceManager 锁再读取连接池，避免与连接池相关操作形成锁顺序依赖
+    let mut output = {
+        let manager_guard = crate::utils::lock_or_recover(&*manager, "ServiceManager");
+        manager_guard.get_prometheus_metrics()
+    };
+    output.push_str(&crate::ai_service::gateway_prometheus_metrics());
+    Ok(output)
 }
 
 /// 获取服务指标
@@ -0,0 +1,131 @@
+/// 后台循环的调度漂移统计（监控线程 / 健康检查线程）
+///
+/// 循环按固定间隔 sleep，但负载过高或锁被长时间占用时，实际间隔会明显变长，
+/// 导致故障发现延迟。这里记录每轮实际间隔，供诊断命令查看。
+use log::warn;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// 保留的最近间隔样本数
+const MAX_SAMPLES: usize = 30;
+
+/// 实际间隔超过预期间隔的倍数时告警
+const OVERRUN_FACTOR: u32 = 2;
+
+static LOOP_DRIFT: OnceLock<Mutex<HashMap<&'static str, LoopDriftTracker>>> = OnceLock::new();
+
+/// 单个循环的漂移统计（诊断命令返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct LoopDriftStats {
+    /// 循环名称
+    pub name: String,
+    /// 预期间隔（毫秒）
+    pub expected_interval_ms: u64,
+    /// 样本数
+    pub samples: usize,
+    /// 最近一次实际间隔（毫秒）
+    pub last_interval_ms: u64,
+    /// 最近样本的平均间隔（毫秒）
+    pub avg_interval_ms: u64,
+    /// 最近样本的最大间隔（毫秒）
+    pub max_interval_ms: u64,
+    /// 累计超过 2 倍预期间隔的次数
+    pub overrun_count: u64,
+    /// 最近一次间隔是否超过 2 倍预期（过载或锁竞争）
+    pub overloaded: bool,
+}
+
+#[derive(Debug)]
+struct LoopDriftTracker {
+    expected: Duration,
+    last_tick: Option<Instant>,
+    recent_ms: VecDeque<u64>,
+    overrun_count: u64,
+}
+
+impl LoopDriftTracker {
+    fn new(expected: Duration) -> Self {
+        Self {
+            expected,
+            last_tick: None,
+            recent_ms: VecDeque::with_capacity(MAX_SAMPLES),
+            overrun_count: 0,
+        }
+    }
+
+    fn stats(&self, name: &str) -> LoopDriftStats {
+        let expected_ms = self.expected.as_millis() as u64;
+        let last = self.recent_ms.back().copied().unwrap_or(0);
+        let avg = if self.recent_ms.is_empty() {
+            0
+        } else {
+            self.recent_ms.iter().sum::<u64>() / self.recent_ms.len() as u64
+        };
+        LoopDriftStats {
+            name: name.to_string(),
+            expected_interval_ms: expected_ms,
+            samples: self.recent_ms.len(),
+            last_interval_ms: last,
+            avg_interval_ms: avg,
+            max_interval_ms: self.recent_ms.iter().copied().max().unwrap_or(0),
+            overrun_count: self.overrun_count,
+            overloaded: last > expected_ms * OVERRUN_FACTOR as u64,
+        }
+    }
+}
+
+/// 在循环每轮开始时调用，记录与上一轮之间的实际间隔
+pub fn record_loop_tick(name: &'static str, expected: Duration) {
+    let registry = LOOP_DRIFT.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut registry = crate::utils::lock_or_recover(registry, "LOOP_DRIFT");
+    let tracker = registry
+        .entry(name)
+        .or_insert_with(|| LoopDriftTracker::new(expected));
+    tracker.expected = expected;
+
+    let now = Instant::now();
+    if let Some(last) = tracker.last_tick.replace(now) {
+        let interval = now.duration_since(last);
+        if tracker.recent_ms.len() >= MAX_SAMPLES {
+            tracker.recent_ms.pop_front();
+        }
+        tracker.recent_ms.push_back(interval.as_millis() as u64);
+
+        if interval > expected * OVERRUN_FACTOR {
+            tracker.overrun_count += 1;
+            warn!(
+                "[{}] 循环间隔 {}ms 超过预期 {}ms 的 {} 倍，可能负载过高或锁占用过久",
+                name,
+                interval.as_millis(),
+                expected.as_millis(),
+                OVERRUN_FACTOR
+            );
+        }
+    }
+}
+
+/// 循环（重新）启动时调用，避免把停止期间计入间隔
+pub fn reset_loop_tick(name: &'static str) {
+    if let Some(registry) = LOOP_DRIFT.get() {
+        let mut registry = crate::utils::lock_or_recover(registry, "LOOP_DRIFT");
+        if let Some(tracker) = registry.get_mut(name) {
+            tracker.last_tick = None;
+        }
+    }
+}
+
+/// 获取所有循环的漂移统计（按名称排序）
+pub fn loop_drift_snapshot() -> Vec<LoopDriftStats> {
+    let Some(registry) = LOOP_DRIFT.get() else {
+        return Vec::new();
+    };
+    let registry = crate::utils::lock_or_recover(registry, "LOOP_DRIFT");
+    let mut stats: Vec<LoopDriftStats> = registry
+        .iter()
+        .map(|(name, tracker)| tracker.stats(name))
+        .collect();
+    stats.sort_by(|a, b| a.name.cmp(&b.name));
+    stats
+}
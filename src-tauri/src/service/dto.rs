@@ -1,3 +1,5 @@
+use crate::service::external_process::ExternalProcessConfig;
+use crate::service::restart_policy::RestartPolicy;
 use crate::service::state::ServiceState;
 /// 统一的服务状态 DTO（供前端使用）
 use serde::{Deserialize, Serialize};
@@ -28,6 +30,28 @@ pub struct ServiceStatusDTO {
     /// 预计剩余时间（秒，可选）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub eta_seconds: Option<u64>,
+    /// 自动恢复状态（由 ServiceManager 填充）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery: Option<RecoveryStatusDTO>,
+}
+
+/// 服务的自动恢复状态（重启次数、下次重启时间、是否暂停）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryStatusDTO {
+    /// 统计窗口内已执行的自动重启次数
+    pub restarts_in_window: usize,
+    /// 统计窗口内允许的最多重启次数
+    pub max_restarts: usize,
+    /// 重启次数的统计窗口（秒）
+    pub window_secs: u64,
+    /// 距下次自动重启的秒数（未安排重启时为空）
+    pub next_restart_in_secs: Option<u64>,
+    /// 已持续异常（Degraded / Unhealthy）的秒数
+    pub degraded_for_secs: Option<u64>,
+    /// 自动恢复是否已暂停（手动暂停或重启熔断触发）
+    pub paused: bool,
+    /// 是否因窗口内重启次数耗尽（重启熔断）而暂停
+    pub paused_by_restart_circuit: bool,
 }
 
 impl ServiceStatusDTO {
@@ -51,6 +75,7 @@ impl ServiceStatusDTO {
             }),
             progress: None,
             eta_seconds: None,
+            recovery: None,
         }
     }
 
@@ -80,3 +105,73 @@ impl ServiceStatusDTO {
 pub struct ServiceStatusListDTO {
     pub services: Vec<ServiceStatusDTO>,
 }
+
+/// 服务拓扑快照格式版本（不兼容变更时递增）
+pub const SERVICES_SNAPSHOT_VERSION: u32 = 1;
+
+/// 单个服务的快照（注册信息 + 期望运行状态）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceSnapshotDTO {
+    /// 服务 ID
+    pub id: String,
+    /// 服务名称（仅供展示，恢复时不使用）
+    #[serde(default)]
+    pub name: String,
+    /// 导出时的状态（仅供参考）
+    #[serde(default)]
+    pub state: ServiceState,
+    /// 期望运行状态（恢复时据此启动/停止服务）
+    #[serde(alias = "desiredRunning")]
+    pub desired_running: bool,
+    /// 单独配置的重启策略（为空时使用默认策略）
+    #[serde(
+        default,
+        alias = "restartPolicy",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub restart_policy: Option<RestartPolicy>,
+    /// 外部进程服务的配置（恢复时据此重新注册已删除的服务；内置服务为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external: Option<ExternalProcessConfig>,
+}
+
+/// 服务拓扑快照（导出/恢复用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServicesStateSnapshot {
+    /// 快照格式版本
+    #[serde(default)]
+    pub version: u32,
+    /// 导出时间（Unix 秒）
+    #[serde(default, alias = "exportedAt")]
+    pub exported_at: u64,
+    /// 服务列表
+    pub services: Vec<ServiceSnapshotDTO>,
+    /// AI Gateway 连接池配置（可选，恢复后在下次初始化连接池时生效）
+    #[serde(
+        default,
+        alias = "gatewayPool",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub gateway_pool: Option<crate::ai_service::GatewayPoolConfig>,
+}
+
+/// 快照恢复结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServicesImportReport {
+    /// 已启动的服务
+    pub started: Vec<String>,
+    /// 已停止的服务
+    pub stopped: Vec<String>,
+    /// 已重启的服务（期望运行但当前不健康）
+    pub restarted: Vec<String>,
+    /// 已符合期望状态、无需操作的服务
+    pub unchanged: Vec<String>,
+    /// 快照中存在但当前未注册、已按快照配置重新注册的外部进程服务
+    pub registered: Vec<String>,
+    /// 快照中存在但当前未注册且没有外部进程配置的服务（无法重建，已跳过）
+    pub skipped: Vec<String>,
+    /// 操作失败的服务（"id: 错误信息"）
+    pub failed: Vec<String>,
+    /// 是否已写入连接池配置
+    pub gateway_pool_applied: bool,
+}
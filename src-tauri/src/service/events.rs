@@ -1,9 +1,15 @@
 use crate::service::state::ServiceState;
 /// 事件驱动架构（替代轮询）
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 
-/// 服务事件类型
+/// 每个服务保留的最近事件数
+const EVENT_HISTORY_CAPACITY: usize = 200;
+
+/// 服务事件类型（序列化为带 type 字段的扁平对象，与前端 ServiceEvent 一致）
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
 pub enum ServiceEvent {
     /// 状态变化事件
     StateChanged {
@@ -32,8 +38,48 @@ pub enum ServiceEvent {
     Restarted { service_id: String, timestamp: u64 },
 }
 
+/// 事件严重程度（用于按级别过滤事件历史）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl ServiceEvent {
+    /// 事件所属服务
+    pub fn service_id(&self) -> &str {
+        match self {
+            ServiceEvent::StateChanged { service_id, .. }
+            | ServiceEvent::HealthCheck { service_id, .. }
+            | ServiceEvent::Error { service_id, .. }
+            | ServiceEvent::Started { service_id, .. }
+            | ServiceEvent::Stopped { service_id, .. }
+            | ServiceEvent::Restarted { service_id, .. } => service_id,
+        }
+    }
+
+    /// 事件严重程度：错误为 error，异常的健康检查与进入异常状态为 warn，健康检查通过为 debug
+    pub fn level(&self) -> EventLevel {
+        match self {
+            ServiceEvent::Error { .. } => EventLevel::Error,
+            ServiceEvent::HealthCheck { status, .. } => match status {
+                HealthCheckResult::Healthy => EventLevel::Debug,
+                HealthCheckResult::Degraded | HealthCheckResult::Unhealthy => EventLevel::Warn,
+            },
+            ServiceEvent::StateChanged {
+                to: ServiceState::Degraded | ServiceState::Unhealthy,
+                ..
+            } => EventLevel::Warn,
+            _ => EventLevel::Info,
+        }
+    }
+}
+
 /// 健康检查结果
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum HealthCheckResult {
     Healthy,
     Degraded,
@@ -43,6 +89,16 @@ pub enum HealthCheckResult {
 /// 事件监听器 trait
 pub trait EventListener: Send + Sync {
     fn on_event(&self, event: &ServiceEvent);
+
+    /// 订阅 ID（可注销的监听器返回 Some）
+    fn listener_id(&self) -> Option<&str> {
+        None
+    }
+
+    /// 监听器是否已失效（如前端已关闭通道），失效后在下次订阅时移除
+    fn is_closed(&self) -> bool {
+        false
+    }
 }
 
 /// 事件总线（简化版，使用回调）
@@ -62,6 +118,27 @@ impl EventBus {
         self.listeners.push(listener);
     }
 
+    /// 注销指定订阅 ID 的监听器，返回是否找到
+    pub fn unsubscribe(&mut self, id: &str) -> bool {
+        let before = self.listeners.len();
+        self.listeners
+            .retain(|listener| listener.listener_id() != Some(id));
+        self.listeners.len() != before
+    }
+
+    /// 移除已失效的监听器
+    pub fn prune_closed(&mut self) {
+        self.listeners.retain(|listener| !listener.is_closed());
+    }
+
+    /// 注销所有可注销的监听器（应用退出时调用）
+    pub fn clear_subscriptions(&mut self) -> usize {
+        let before = self.listeners.len();
+        self.listeners
+            .retain(|listener| listener.listener_id().is_none());
+        before - self.listeners.len()
+    }
+
     pub fn emit(&self, event: &ServiceEvent) {
         for listener in &self.listeners {
             listener.on_event(event);
@@ -75,6 +152,60 @@ impl Default for EventBus {
     }
 }
 
+/// 按服务保存最近事件的环形缓冲区（作为事件总线监听器，收到所有已发送的事件）
+#[derive(Clone, Default)]
+pub struct EventHistory {
+    events: Arc<Mutex<HashMap<String, VecDeque<ServiceEvent>>>>,
+}
+
+impl EventHistory {
+    /// 指定服务最近的事件（按时间顺序，只保留级别不低于 min_level 的最后 limit 条）
+    pub fn recent(
+        &self,
+        service_id: &str,
+        min_level: EventLevel,
+        limit: usize,
+    ) -> Vec<ServiceEvent> {
+        let events = crate::utils::lock_or_recover(self.events.as_ref(), "EventHistory.events");
+        let Some(history) = events.get(service_id) else {
+            return Vec::new();
+        };
+        let mut recent: Vec<ServiceEvent> = history
+            .iter()
+            .rev()
+            .filter(|event| event.level() >= min_level)
+            .take(limit)
+            .cloned()
+            .collect();
+        recent.reverse();
+        recent
+    }
+}
+
+impl EventListener for EventHistory {
+    fn on_event(&self, event: &ServiceEvent) {
+        let mut events = crate::utils::lock_or_recover(self.events.as_ref(), "EventHistory.events");
+        let history = events.entry(event.service_id().to_string()).or_default();
+        // 连续相同结果的健康检查只保留最新一条，避免周期性检查挤掉有用的事件
+        if let (
+            ServiceEvent::HealthCheck { status, .. },
+            Some(ServiceEvent::HealthCheck {
+                status: last_status,
+                ..
+            }),
+        ) = (event, history.back())
+        {
+            if status == last_status {
+                history.pop_back();
+            }
+        }
+        if history.len() >= EVENT_HISTORY_CAPACITY {
+            history.pop_front();
+        }
+        history.push_back(event.clone());
+    }
+}
+
 /// 获取当前时间戳（毫秒）
 pub fn current_timestamp() -> u64 {
     std::time::SystemTime::now()
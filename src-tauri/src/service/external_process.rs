@@ -0,0 +1,259 @@
+use anyhow::Result;
+use log::{info, warn};
+/// 外部进程服务：把长期运行的辅助工具（本地代理、DNS 记录器等）交给 ServiceManager 监控
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::service::restart_policy::RestartPolicy;
+use crate::service::state::ServiceState;
+use crate::service::trait_def::{HealthStatus, Service};
+
+/// HTTP 健康检查超时
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 外部进程服务配置（register_external_service 的参数）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalProcessConfig {
+    /// 服务 ID（唯一）
+    pub id: String,
+    /// 显示名称（为空时使用 ID）
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 可执行文件路径或 PATH 中的命令名
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default, alias = "workingDir")]
+    pub working_dir: Option<String>,
+    /// HTTP 健康检查地址（为空时只检查进程是否存活）
+    #[serde(default, alias = "healthUrl")]
+    pub health_url: Option<String>,
+    /// 注册后立即启动
+    #[serde(default, alias = "autoStart")]
+    pub auto_start: bool,
+    /// 自动重启策略（为空时使用默认策略）
+    #[serde(default, alias = "restartPolicy")]
+    pub restart_policy: Option<RestartPolicy>,
+}
+
+impl ExternalProcessConfig {
+    /// 校验配置（ID、命令非空，工作目录存在，健康检查地址为 http/https）
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("服务 ID 不能为空".to_string());
+        }
+        if self.command.trim().is_empty() {
+            return Err("启动命令不能为空".to_string());
+        }
+        if let Some(dir) = self.working_dir.as_deref() {
+            if !PathBuf::from(dir).is_dir() {
+                return Err(format!("工作目录不存在: {}", dir));
+            }
+        }
+        if let Some(url) = self.health_url.as_deref() {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(format!(
+                    "健康检查地址必须以 http:// 或 https:// 开头: {}",
+                    url
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 外部进程服务（start 启动进程，stop 结束进程，健康检查探测 URL 或进程存活）
+pub struct ExternalProcessService {
+    config: ExternalProcessConfig,
+    name: String,
+    state: ServiceState,
+    /// 与进程注册表共享（可在进程列表中终止，之后按重启策略处理）
+    child: Option<Arc<Mutex<Child>>>,
+    /// 最近一次异常说明（进程退出、健康检查失败等）
+    last_error: Option<String>,
+}
+
+impl ExternalProcessService {
+    pub fn new(config: ExternalProcessConfig) -> Self {
+        let name = config
+            .name
+            .clone()
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| config.id.clone());
+        Self {
+            config,
+            name,
+            state: ServiceState::Stopped,
+            child: None,
+            last_error: None,
+        }
+    }
+
+    /// 进程是否仍在运行（已退出时回收进程并记录退出码）
+    fn process_alive(&mut self) -> bool {
+        let Some(child) = self.child.as_ref() else {
+            return false;
+        };
+        let status = crate::utils::lock_or_recover(child.as_ref(), "ExternalProcessService.child")
+            .try_wait();
+        match status {
+            Ok(None) => true,
+            Ok(Some(status)) => {
+                warn!(
+                    "[ExternalProcessService] 服务 {} 的进程已退出: {}",
+                    self.config.id, status
+                );
+                self.last_error = Some(format!("进程已退出: {}", status));
+                self.child = None;
+                false
+            }
+            Err(e) => {
+                self.last_error = Some(format!("查询进程状态失败: {}", e));
+                false
+            }
+        }
+    }
+
+    /// 探测健康检查地址：2xx 健康，其他状态码降级，请求失败不健康
+    fn probe_health_url(&mut self, url: &str) -> HealthStatus {
+        let result = reqwest::blocking::Client::builder()
+            .timeout(HEALTH_CHECK_TIMEOUT)
+            .build()
+            .and_then(|client| client.get(url).send());
+        match result {
+            Ok(response) if response.status().is_success() => {
+                self.last_error = None;
+                HealthStatus::Healthy
+            }
+            Ok(response) => {
+                self.last_error = Some(format!("健康检查返回 {}", response.status()));
+                HealthStatus::Degraded
+            }
+            Err(e) => {
+                self.last_error = Some(format!("健康检查失败: {}", e));
+                HealthStatus::Unhealthy
+            }
+        }
+    }
+
+    fn kill_child(&mut self) -> Result<()> {
+        let Some(child) = self.child.take() else {
+            return Ok(());
+        };
+        let mut child =
+            crate::utils::lock_or_recover(child.as_ref(), "ExternalProcessService.child");
+        if matches!(child.try_wait(), Ok(Some(_))) {
+            return Ok(());
+        }
+        child
+            .kill()
+            .map_err(|e| anyhow::anyhow!("结束进程 {} 失败: {}", child.id(), e))?;
+        let _ = child.wait();
+        Ok(())
+    }
+}
+
+impl Service for ExternalProcessService {
+    fn id(&self) -> &str {
+        &self.config.id
+    }
+
+    fn group(&self) -> Option<&str> {
+        Some("external")
+    }
+
+    fn state(&self) -> ServiceState {
+        self.state
+    }
+
+    fn set_state_unchecked(&mut self, new_state: ServiceState) {
+        self.state = new_state;
+    }
+
+    fn start(&mut self) -> Result<()> {
+        if self.process_alive() {
+            info!(
+                "[ExternalProcessService] 服务 {} 已在运行，跳过重复启动",
+                self.config.id
+            );
+            return Ok(());
+        }
+
+        let mut cmd = Command::new(&self.config.command);
+        cmd.args(&self.config.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        if let Some(dir) = self.config.working_dir.as_deref() {
+            cmd.current_dir(dir);
+        }
+        let child = cmd
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("启动进程失败: {}（命令 {}）", e, self.config.command))?;
+        info!(
+            "[ExternalProcessService] 服务 {} 已启动，PID {}",
+            self.config.id,
+            child.id()
+        );
+        let child = Arc::new(Mutex::new(child));
+        crate::process_registry::track_service(
+            Arc::clone(&child),
+            format!("{}（服务 {}）", self.name, self.config.id),
+        );
+        self.child = Some(child);
+        self.last_error = None;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        info!("[ExternalProcessService] 停止服务: {}", self.config.id);
+        self.kill_child()
+    }
+
+    fn health_check(&mut self) -> HealthStatus {
+        if !self.process_alive() {
+            return HealthStatus::Unhealthy;
+        }
+        match self.config.health_url.clone() {
+            Some(url) => self.probe_health_url(&url),
+            None => HealthStatus::Healthy,
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> Option<&str> {
+        Some("外部进程服务")
+    }
+
+    fn external_config(&self) -> Option<&ExternalProcessConfig> {
+        Some(&self.config)
+    }
+
+    fn message(&self) -> Option<String> {
+        if let Some(error) = &self.last_error {
+            return Some(error.clone());
+        }
+        self.child.as_ref().map(|child| {
+            let pid =
+                crate::utils::lock_or_recover(child.as_ref(), "ExternalProcessService.child").id();
+            format!("运行中，PID {}", pid)
+        })
+    }
+}
+
+impl Drop for ExternalProcessService {
+    fn drop(&mut self) {
+        if let Err(e) = self.kill_child() {
+            warn!(
+                "[ExternalProcessService] 服务 {} 清理进程失败: {}",
+                self.config.id, e
+            );
+        }
+    }
+}
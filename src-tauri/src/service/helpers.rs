@@ -16,6 +16,8 @@ pub fn worker_state_to_service_state(worker_state: WorkerState) -> ServiceState
         | WorkerState::FailedPermanent
         | WorkerState::Disabled => ServiceState::Unhealthy,
         WorkerState::Restarting => ServiceState::Restarting,
+        WorkerState::Draining => ServiceState::Stopping,
+        WorkerState::Warmup => ServiceState::Warmup,
     }
 }
 
@@ -6,48 +6,27 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use crate::service::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
-use crate::service::dto::{ServiceStatusDTO, ServiceStatusListDTO};
-use crate::service::events::{current_timestamp, EventBus, ServiceEvent};
+use crate::service::dto::{
+    RecoveryStatusDTO, ServiceSnapshotDTO, ServiceStatusDTO, ServiceStatusListDTO,
+    ServicesImportReport, ServicesStateSnapshot, SERVICES_SNAPSHOT_VERSION,
+};
+use crate::service::events::{
+    current_timestamp, EventBus, EventHistory, EventLevel, EventListener, ServiceEvent,
+};
+use crate::service::external_process::ExternalProcessService;
 use crate::service::metrics::MetricsCollector;
+use crate::service::restart_policy::RestartPolicy;
 use crate::service::state::ServiceState;
 use crate::service::trait_def::{HealthStatus, ServiceHandle};
 
-#[derive(Clone)]
-struct RestartPolicy {
-    max_restarts: usize,
-    window: Duration,
-    base_backoff: Duration,
-    max_backoff: Duration,
-    grace_period: Duration,
-    degraded_to_dead: Duration,
-}
+/// 监控循环间隔
+const MONITOR_INTERVAL: Duration = Duration::from_secs(10);
 
-impl Default for RestartPolicy {
-    fn default() -> Self {
-        Self {
-            max_restarts: 3,
-            window: Duration::from_secs(300),
-            base_backoff: Duration::from_secs(5),
-            max_backoff: Duration::from_secs(120),
-            grace_period: Duration::from_secs(30),
-            degraded_to_dead: Duration::from_secs(60),
-        }
-    }
-}
+/// 退出时等待监控线程结束的最长时间
+const MONITOR_JOIN_TIMEOUT: Duration = Duration::from_secs(2);
 
-impl RestartPolicy {
-    fn can_restart(&self, history: &mut Vec<Instant>, now: Instant) -> Option<Duration> {
-        history.retain(|t| now.duration_since(*t) < self.window);
-        if history.len() >= self.max_restarts {
-            return None;
-        }
-        let exp = history.len() as u32;
-        let multiplier = 1u32.checked_shl(exp).unwrap_or(u32::MAX);
-        let backoff = self.base_backoff.saturating_mul(multiplier);
-        history.push(now);
-        Some(backoff.min(self.max_backoff))
-    }
-}
+/// 监控循环名称（用于漂移统计）
+const MONITOR_LOOP_NAME: &str = "ServiceManager 监控";
 
 #[derive(Default, Clone)]
 struct RecoveryState {
@@ -58,6 +37,8 @@ struct RecoveryState {
     restart_history: Vec<Instant>,
     restart_in_progress: bool,
     paused: bool,
+    /// 因窗口内重启次数耗尽而暂停（区别于手动暂停）
+    restart_circuit_tripped: bool,
 }
 
 /// 服务管理器（统一管理所有服务）
@@ -66,27 +47,40 @@ pub struct ServiceManager {
     services: Arc<Mutex<HashMap<String, ServiceHandle>>>,
     /// 监控线程是否运行
     monitoring: Arc<Mutex<bool>>,
+    /// 监控线程句柄（退出时在限定时间内等待其结束）
+    monitor_thread: Mutex<Option<thread::JoinHandle<()>>>,
     /// 事件总线
     event_bus: Arc<Mutex<EventBus>>,
     /// 服务熔断器（按服务 ID）
     circuit_breakers: Arc<Mutex<HashMap<String, CircuitBreaker>>>,
     /// 指标收集器
     metrics: Arc<Mutex<MetricsCollector>>,
+    /// 默认重启策略
     restart_policy: RestartPolicy,
+    /// 按服务覆盖的重启策略（未配置的服务使用默认策略）
+    restart_policies: Arc<Mutex<HashMap<String, RestartPolicy>>>,
     recovery: Arc<Mutex<HashMap<String, RecoveryState>>>,
+    /// 各服务最近的事件（get_service_events 查询）
+    event_history: EventHistory,
 }
 
 impl ServiceManager {
     /// 创建新的服务管理器
     pub fn new() -> Self {
+        let event_history = EventHistory::default();
+        let mut event_bus = EventBus::new();
+        event_bus.subscribe(Box::new(event_history.clone()));
         Self {
             services: Arc::new(Mutex::new(HashMap::new())),
             monitoring: Arc::new(Mutex::new(false)),
-            event_bus: Arc::new(Mutex::new(EventBus::new())),
+            monitor_thread: Mutex::new(None),
+            event_bus: Arc::new(Mutex::new(event_bus)),
             circuit_breakers: Arc::new(Mutex::new(HashMap::new())),
             metrics: Arc::new(Mutex::new(MetricsCollector::new())),
             restart_policy: RestartPolicy::default(),
+            restart_policies: Arc::new(Mutex::new(HashMap::new())),
             recovery: Arc::new(Mutex::new(HashMap::new())),
+            event_history,
         }
     }
 
@@ -108,6 +102,43 @@ impl ServiceManager {
         Arc::clone(&self.event_bus)
     }
 
+    /// 指定服务最近的事件（级别不低于 min_level，最多 limit 条，按时间顺序）
+    pub fn recent_events(
+        &self,
+        id: &str,
+        min_level: EventLevel,
+        limit: usize,
+    ) -> Result<Vec<ServiceEvent>, String> {
+        if self.get_service(id).is_none() {
+            return Err(format!("服务 {} 不存在", id));
+        }
+        Ok(self.event_history.recent(id, min_level, limit))
+    }
+
+    /// 订阅服务事件（同时移除已失效的订阅）
+    pub fn subscribe_events(&self, listener: Box<dyn EventListener>) {
+        let mut bus =
+            crate::utils::lock_or_recover(self.event_bus.as_ref(), "ServiceManager.event_bus");
+        bus.prune_closed();
+        bus.subscribe(listener);
+    }
+
+    /// 注销服务事件订阅，返回是否找到
+    pub fn unsubscribe_events(&self, id: &str) -> bool {
+        crate::utils::lock_or_recover(self.event_bus.as_ref(), "ServiceManager.event_bus")
+            .unsubscribe(id)
+    }
+
+    /// 注销所有前端事件订阅（应用退出时调用）
+    pub fn clear_event_subscriptions(&self) {
+        let removed =
+            crate::utils::lock_or_recover(self.event_bus.as_ref(), "ServiceManager.event_bus")
+                .clear_subscriptions();
+        if removed > 0 {
+            info!("[ServiceManager] 已注销 {} 个事件订阅", removed);
+        }
+    }
+
     /// 发送事件
     fn emit_event(&self, event: ServiceEvent) {
         let bus =
@@ -115,8 +146,12 @@ impl ServiceManager {
         bus.emit(&event);
     }
 
-    /// 注册服务
-    pub fn register(&self, service: ServiceHandle) -> Result<(), String> {
+    /// 注册服务（restart_policy 为空时使用默认重启策略）
+    pub fn register(
+        &self,
+        service: ServiceHandle,
+        restart_policy: Option<RestartPolicy>,
+    ) -> Result<(), String> {
         let mut services =
             crate::utils::lock_or_recover(self.services.as_ref(), "ServiceManager.services");
         let id = {
@@ -142,6 +177,13 @@ impl ServiceManager {
         drop(breakers);
 
         services.insert(id.clone(), service);
+        if let Some(policy) = restart_policy {
+            crate::utils::lock_or_recover(
+                self.restart_policies.as_ref(),
+                "ServiceManager.restart_policies",
+            )
+            .insert(id.clone(), policy.clamped());
+        }
         {
             let mut recovery =
                 crate::utils::lock_or_recover(self.recovery.as_ref(), "ServiceManager.recovery");
@@ -176,6 +218,11 @@ impl ServiceManager {
                 );
                 recovery.remove(id);
             }
+            crate::utils::lock_or_recover(
+                self.restart_policies.as_ref(),
+                "ServiceManager.restart_policies",
+            )
+            .remove(id);
             Ok(())
         } else {
             Err(format!("服务 {} 不存在", id))
@@ -183,7 +230,6 @@ impl ServiceManager {
     }
 
     /// 获取服务
-    #[allow(dead_code)]
     pub fn get_service(&self, id: &str) -> Option<ServiceHandle> {
         let services =
             crate::utils::lock_or_recover(self.services.as_ref(), "ServiceManager.services");
@@ -198,7 +244,8 @@ impl ServiceManager {
 
         for (_, service) in services.iter() {
             let service_guard = crate::utils::lock_or_recover(service.as_ref(), "ServiceHandle");
-            let dto = ServiceStatusDTO::from_service(&*service_guard);
+            let mut dto = ServiceStatusDTO::from_service(&*service_guard);
+            self.annotate_recovery(&mut dto);
             status_list.push(dto);
         }
 
@@ -232,7 +279,9 @@ impl ServiceManager {
             crate::utils::lock_or_recover(self.services.as_ref(), "ServiceManager.services");
         services.get(id).map(|service| {
             let service_guard = crate::utils::lock_or_recover(service.as_ref(), "ServiceHandle");
-            ServiceStatusDTO::from_service(&*service_guard)
+            let mut dto = ServiceStatusDTO::from_service(&*service_guard);
+            self.annotate_recovery(&mut dto);
+            dto
         })
     }
 
@@ -289,6 +338,7 @@ impl ServiceManager {
             entry.backoff_until = None;
             entry.restart_in_progress = false;
             entry.paused = false;
+            entry.restart_circuit_tripped = false;
             entry.restart_history.clear();
         }
 
@@ -411,6 +461,7 @@ impl ServiceManager {
                 entry.backoff_until = None;
                 entry.restart_in_progress = false;
                 entry.paused = false;
+                entry.restart_circuit_tripped = false;
                 entry.restart_history.clear();
             }
         }
@@ -441,6 +492,230 @@ impl ServiceManager {
         self.start_service(id)
     }
 
+    /// 暂停服务的自动恢复（监控循环继续做健康检查并更新状态，但不再自动重启）
+    /// 手动启动 / 停止服务或调用 resume_service 后恢复
+    pub fn pause_service(&self, id: &str) -> Result<(), String> {
+        if self.get_service(id).is_none() {
+            return Err(format!("服务 {} 不存在", id));
+        }
+        let mut recovery =
+            crate::utils::lock_or_recover(self.recovery.as_ref(), "ServiceManager.recovery");
+        let entry = recovery.entry(id.to_string()).or_default();
+        entry.paused = true;
+        entry.backoff_until = None;
+        info!("[ServiceManager] 已暂停服务 {} 的自动恢复", id);
+        Ok(())
+    }
+
+    /// 恢复服务的自动恢复（清空重启历史，重新计算重启次数）
+    pub fn resume_service(&self, id: &str) -> Result<(), String> {
+        if self.get_service(id).is_none() {
+            return Err(format!("服务 {} 不存在", id));
+        }
+        let mut recovery =
+            crate::utils::lock_or_recover(self.recovery.as_ref(), "ServiceManager.recovery");
+        let entry = recovery.entry(id.to_string()).or_default();
+        entry.paused = false;
+        entry.restart_circuit_tripped = false;
+        entry.backoff_until = None;
+        entry.restart_history.clear();
+        info!("[ServiceManager] 已恢复服务 {} 的自动恢复", id);
+        Ok(())
+    }
+
+    /// 服务当前使用的重启策略
+    pub fn restart_policy_for(&self, id: &str) -> RestartPolicy {
+        resolve_restart_policy(&self.restart_policies, &self.restart_policy, id)
+    }
+
+    /// 更新服务的重启策略（超出范围的取值会被限制），下一轮监控生效，返回实际生效的策略
+    pub fn set_restart_policy(
+        &self,
+        id: &str,
+        policy: RestartPolicy,
+    ) -> Result<RestartPolicy, String> {
+        if self.get_service(id).is_none() {
+            return Err(format!("服务 {} 不存在", id));
+        }
+        let policy = policy.clamped();
+        crate::utils::lock_or_recover(
+            self.restart_policies.as_ref(),
+            "ServiceManager.restart_policies",
+        )
+        .insert(id.to_string(), policy.clone());
+        info!(
+            "[ServiceManager] 服务 {} 的重启策略已更新: {:?}",
+            id, policy
+        );
+        Ok(policy)
+    }
+
+    /// 在状态 DTO 中填充自动恢复状态（重启次数、下次重启倒计时、是否暂停）
+    fn annotate_recovery(&self, dto: &mut ServiceStatusDTO) {
+        let recovery =
+            crate::utils::lock_or_recover(self.recovery.as_ref(), "ServiceManager.recovery");
+        let Some(entry) = recovery.get(&dto.id) else {
+            return;
+        };
+        let now = Instant::now();
+        let policy = &self.restart_policy_for(&dto.id);
+        dto.recovery = Some(RecoveryStatusDTO {
+            restarts_in_window: entry
+                .restart_history
+                .iter()
+                .filter(|t| now.duration_since(**t) < policy.window())
+                .count(),
+            max_restarts: policy.max_restarts,
+            window_secs: policy.window_secs,
+            next_restart_in_secs: entry
+                .backoff_until
+                .map(|until| until.saturating_duration_since(now).as_secs()),
+            degraded_for_secs: entry
+                .degraded_since
+                .map(|since| now.duration_since(since).as_secs()),
+            paused: entry.paused,
+            paused_by_restart_circuit: entry.restart_circuit_tripped,
+        });
+    }
+
+    /// 共享同一组服务与状态的句柄（不含监控线程句柄）
+    /// 命令先取得句柄、释放 ServiceManager 锁，再执行耗时的启动 / 停止，避免阻塞其他命令
+    pub fn handle(&self) -> ServiceManager {
+        ServiceManager {
+            services: Arc::clone(&self.services),
+            monitoring: Arc::clone(&self.monitoring),
+            monitor_thread: Mutex::new(None),
+            event_bus: Arc::clone(&self.event_bus),
+            circuit_breakers: Arc::clone(&self.circuit_breakers),
+            metrics: Arc::clone(&self.metrics),
+            restart_policy: self.restart_policy.clone(),
+            restart_policies: Arc::clone(&self.restart_policies),
+            recovery: Arc::clone(&self.recovery),
+            event_history: self.event_history.clone(),
+        }
+    }
+
+    /// 导出服务拓扑快照（已注册服务、单独配置的重启策略、外部进程配置及期望运行状态）
+    pub fn export_state(&self) -> ServicesStateSnapshot {
+        let services =
+            crate::utils::lock_or_recover(self.services.as_ref(), "ServiceManager.services");
+        let policies = crate::utils::lock_or_recover(
+            self.restart_policies.as_ref(),
+            "ServiceManager.restart_policies",
+        );
+        let mut snapshot: Vec<ServiceSnapshotDTO> = services
+            .iter()
+            .map(|(id, service)| {
+                let service_guard =
+                    crate::utils::lock_or_recover(service.as_ref(), "ServiceHandle");
+                let state = service_guard.state();
+                ServiceSnapshotDTO {
+                    id: id.clone(),
+                    name: service_guard.name().to_string(),
+                    state,
+                    desired_running: !matches!(
+                        state,
+                        ServiceState::Stopped | ServiceState::Stopping
+                    ),
+                    restart_policy: policies.get(id).cloned(),
+                    external: service_guard.external_config().cloned(),
+                }
+            })
+            .collect();
+        drop(policies);
+        snapshot.sort_by(|a, b| a.id.cmp(&b.id));
+
+        ServicesStateSnapshot {
+            version: SERVICES_SNAPSHOT_VERSION,
+            exported_at: current_timestamp(),
+            services: snapshot,
+            gateway_pool: None,
+        }
+    }
+
+    /// 按快照恢复服务：重新注册已删除的外部进程服务、恢复重启策略，再启动/停止/重启以匹配期望状态
+    /// （未注册且没有外部进程配置的服务无法重建，仅跳过）
+    pub fn import_state(&self, snapshot: &ServicesStateSnapshot) -> ServicesImportReport {
+        let mut report = ServicesImportReport::default();
+
+        for entry in &snapshot.services {
+            if self.get_service(&entry.id).is_none() {
+                let Some(config) = entry.external.clone() else {
+                    warn!("[ServiceManager] 快照中的服务 {} 未注册，跳过", entry.id);
+                    report.skipped.push(entry.id.clone());
+                    continue;
+                };
+                let registered = config.validate().and_then(|()| {
+                    if config.id != entry.id {
+                        return Err(format!("外部进程配置的 ID 为 {}", config.id));
+                    }
+                    let service = Arc::new(Mutex::new(ExternalProcessService::new(config)));
+                    self.register(service, entry.restart_policy.clone())
+                });
+                match registered {
+                    Ok(()) => report.registered.push(entry.id.clone()),
+                    Err(e) => {
+                        error!("[ServiceManager] 重新注册服务 {} 失败: {}", entry.id, e);
+                        report.failed.push(format!("{}: {}", entry.id, e));
+                        continue;
+                    }
+                }
+            } else if let Some(policy) = entry.restart_policy.clone() {
+                if let Err(e) = self.set_restart_policy(&entry.id, policy) {
+                    report.failed.push(format!("{}: {}", entry.id, e));
+                }
+            }
+
+            // 不持有 services 锁调用 start/stop，避免重入死锁
+            let Some(service) = self.get_service(&entry.id) else {
+                continue;
+            };
+            let current = crate::utils::lock_or_recover(service.as_ref(), "ServiceHandle").state();
+            let running = !matches!(current, ServiceState::Stopped | ServiceState::Stopping);
+
+            let result = match (entry.desired_running, running) {
+                (true, false) => self
+                    .start_service(&entry.id)
+                    .map(|_| report.started.push(entry.id.clone())),
+                (true, true) if current == ServiceState::Unhealthy => self
+                    .restart_service(&entry.id)
+                    .map(|_| report.restarted.push(entry.id.clone())),
+                (false, true) => self
+                    .stop_service(&entry.id)
+                    .map(|_| report.stopped.push(entry.id.clone())),
+                _ => {
+                    report.unchanged.push(entry.id.clone());
+                    Ok(())
+                }
+            };
+            if let Err(e) = result {
+                error!("[ServiceManager] 恢复服务 {} 状态失败: {}", entry.id, e);
+                report.failed.push(format!("{}: {}", entry.id, e));
+            }
+        }
+
+        {
+            let services =
+                crate::utils::lock_or_recover(self.services.as_ref(), "ServiceManager.services");
+            for id in services.keys() {
+                if !snapshot.services.iter().any(|s| &s.id == id) {
+                    info!("[ServiceManager] 服务 {} 不在快照中，保持当前状态", id);
+                }
+            }
+        }
+
+        info!(
+            "[ServiceManager] 快照恢复完成: 注册 {}，启动 {}，停止 {}，重启 {}，跳过 {}，失败 {}",
+            report.registered.len(),
+            report.started.len(),
+            report.stopped.len(),
+            report.restarted.len(),
+            report.skipped.len(),
+            report.failed.len()
+        );
+        report
+    }
+
     /// 启动监控循环（后台线程）
     pub fn start_monitoring(&self) {
         let mut monitoring =
@@ -457,12 +732,16 @@ impl ServiceManager {
         let metrics = Arc::clone(&self.metrics);
         let event_bus = Arc::clone(&self.event_bus);
         let recovery = Arc::clone(&self.recovery);
-        let restart_policy = self.restart_policy.clone();
+        let default_policy = self.restart_policy.clone();
+        let restart_policies = Arc::clone(&self.restart_policies);
 
-        thread::spawn(move || {
+        let handle = thread::spawn(move || {
             info!("[ServiceManager] 监控线程已启动");
+            crate::service::drift::reset_loop_tick(MONITOR_LOOP_NAME);
 
             loop {
+                crate::service::drift::record_loop_tick(MONITOR_LOOP_NAME, MONITOR_INTERVAL);
+
                 // 检查是否应该停止监控
                 {
                     let flag = crate::utils::lock_or_recover(
@@ -499,9 +778,6 @@ impl ServiceManager {
                                 "ServiceManager.recovery",
                             );
                             let entry = recovery_guard.entry(id.clone()).or_default();
-                            if entry.paused {
-                                continue;
-                            }
                             if entry.restart_in_progress {
                                 continue;
                             }
@@ -510,7 +786,10 @@ impl ServiceManager {
                             {
                                 entry.starting_since = Some(now);
                             }
-                            if let Some(until) = entry.backoff_until {
+                            // 已暂停自动恢复：仍做健康检查以反映真实状态，但不执行到期的重启
+                            if entry.paused {
+                                entry.backoff_until = None;
+                            } else if let Some(until) = entry.backoff_until {
                                 if now >= until && entry.dead_since.is_some() {
                                     entry.restart_in_progress = true;
                                     entry.backoff_until = None;
@@ -562,6 +841,8 @@ impl ServiceManager {
                             });
                         }
 
+                        let restart_policy =
+                            resolve_restart_policy(&restart_policies, &default_policy, id);
                         let mut state_change: Option<(ServiceState, ServiceState)> = None;
                         let mut schedule_restart: Option<Duration> = None;
                         let mut pause_service = false;
@@ -572,14 +853,11 @@ impl ServiceManager {
                                 "ServiceManager.recovery",
                             );
                             let entry = recovery_guard.entry(id.clone()).or_default();
-                            if entry.paused {
-                                continue;
-                            }
 
                             let in_grace = entry
                                 .starting_since
                                 .map(|since| {
-                                    now.duration_since(since) < restart_policy.grace_period
+                                    now.duration_since(since) < restart_policy.grace_period()
                                 })
                                 .unwrap_or(false);
 
@@ -620,7 +898,7 @@ impl ServiceManager {
                                             .degraded_since
                                             .map(|since| {
                                                 now.duration_since(since)
-                                                    >= restart_policy.degraded_to_dead
+                                                    >= restart_policy.degraded_to_dead()
                                             })
                                             .unwrap_or(false)
                                         {
@@ -634,7 +912,7 @@ impl ServiceManager {
                                 }
                             }
 
-                            if entry.dead_since.is_some() {
+                            if entry.dead_since.is_some() && !entry.paused {
                                 match restart_policy.can_restart(&mut entry.restart_history, now) {
                                     Some(delay) => {
                                         schedule_restart = Some(delay);
@@ -642,6 +920,7 @@ impl ServiceManager {
                                     None => {
                                         pause_service = true;
                                         entry.paused = true;
+                                        entry.restart_circuit_tripped = true;
                                     }
                                 }
                             }
@@ -840,10 +1119,53 @@ impl ServiceManager {
                     });
                 }
 
-                // 每 10 秒检查一次（优化：减少检查频率，降低资源占用）
-                thread::sleep(Duration::from_secs(10));
+                // 每 10 秒检查一次（优化：减少检查频率，降低资源占用）；停止监控时提前结束等待
+                crate::utils::sleep_unless_stopped(MONITOR_INTERVAL, || {
+                    !*crate::utils::lock_or_recover(
+                        monitoring_flag.as_ref(),
+                        "ServiceManager.monitoring",
+                    )
+                });
             }
         });
+        *crate::utils::lock_or_recover(&self.monitor_thread, "ServiceManager.monitor_thread") =
+            Some(handle);
+    }
+
+    /// 应用退出时停止所有服务：先停止监控循环（避免停机过程中触发自动重启），
+    /// 再逐个停止仍在运行的服务，最后注销前端事件订阅
+    pub fn shutdown_all(&self) {
+        self.stop_monitoring();
+        let running: Vec<String> = {
+            let services =
+                crate::utils::lock_or_recover(self.services.as_ref(), "ServiceManager.services");
+            services
+                .iter()
+                .filter(|(_, service)| {
+                    let state =
+                        crate::utils::lock_or_recover(service.as_ref(), "ServiceHandle").state();
+                    !matches!(state, ServiceState::Stopped | ServiceState::Stopping)
+                })
+                .map(|(id, _)| id.clone())
+                .collect()
+        };
+        for id in running {
+            if let Err(e) = self.stop_service(&id) {
+                warn!("[ServiceManager] 退出时停止服务 {} 失败: {}", id, e);
+            }
+        }
+        self.clear_event_subscriptions();
+        let monitor_thread =
+            crate::utils::lock_or_recover(&self.monitor_thread, "ServiceManager.monitor_thread")
+                .take();
+        if let Some(handle) = monitor_thread {
+            crate::utils::join_until(
+                "service-monitor",
+                handle,
+                Instant::now() + MONITOR_JOIN_TIMEOUT,
+            );
+        }
+        info!("[ServiceManager] 所有服务已停止");
     }
 
     /// 停止监控
@@ -860,3 +1182,15 @@ impl Default for ServiceManager {
         Self::new()
     }
 }
+
+/// 查找服务的重启策略（未单独配置时使用默认策略）
+fn resolve_restart_policy(
+    policies: &Mutex<HashMap<String, RestartPolicy>>,
+    default_policy: &RestartPolicy,
+    id: &str,
+) -> RestartPolicy {
+    crate::utils::lock_or_recover(policies, "ServiceManager.restart_policies")
+        .get(id)
+        .cloned()
+        .unwrap_or_else(|| default_policy.clone())
+}
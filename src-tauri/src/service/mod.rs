@@ -1,12 +1,15 @@
 pub mod circuit_breaker;
 pub mod commands;
+pub mod drift;
 pub mod dto;
 pub mod events;
+pub mod external_process;
 pub mod health_check;
 pub mod helpers;
 pub mod lifecycle;
 pub mod manager;
 pub mod metrics;
+pub mod restart_policy;
 pub mod sse_handler;
 /// 统一的服务管理架构
 ///
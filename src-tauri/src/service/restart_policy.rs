@@ -0,0 +1,90 @@
+/// 服务自动重启策略（可按服务单独配置）
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// 统计窗口内最多自动重启次数的上限（防止反复重启依赖已失效的服务）
+const MAX_RESTARTS_LIMIT: usize = 10;
+
+/// 各时间参数的上限（秒）
+const MAX_WINDOW_SECS: u64 = 24 * 3600;
+const MAX_BACKOFF_SECS: u64 = 3600;
+const MAX_GRACE_PERIOD_SECS: u64 = 600;
+
+/// 服务自动重启策略（时间单位为秒）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestartPolicy {
+    /// 统计窗口内最多自动重启次数，超过后触发重启熔断（服务停机）
+    #[serde(alias = "maxRestarts")]
+    pub max_restarts: usize,
+    /// 重启次数的统计窗口
+    #[serde(alias = "windowSecs")]
+    pub window_secs: u64,
+    /// 首次重启前的退避时间，之后每次翻倍
+    #[serde(alias = "baseBackoffSecs")]
+    pub base_backoff_secs: u64,
+    /// 退避时间上限
+    #[serde(alias = "maxBackoffSecs")]
+    pub max_backoff_secs: u64,
+    /// 启动宽限期：期间健康检查异常不计入退化
+    #[serde(alias = "gracePeriodSecs")]
+    pub grace_period_secs: u64,
+    /// 持续退化多久后视为失效并安排重启
+    #[serde(alias = "degradedToDeadSecs")]
+    pub degraded_to_dead_secs: u64,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 3,
+            window_secs: 300,
+            base_backoff_secs: 5,
+            max_backoff_secs: 120,
+            grace_period_secs: 30,
+            degraded_to_dead_secs: 60,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// 将各项限制在合理范围内（重启次数 1-10，时间参数至少 1 秒，退避上限不小于基础退避）
+    pub fn clamped(self) -> Self {
+        let base_backoff_secs = self.base_backoff_secs.clamp(1, MAX_BACKOFF_SECS);
+        Self {
+            max_restarts: self.max_restarts.clamp(1, MAX_RESTARTS_LIMIT),
+            window_secs: self.window_secs.clamp(1, MAX_WINDOW_SECS),
+            base_backoff_secs,
+            max_backoff_secs: self
+                .max_backoff_secs
+                .clamp(base_backoff_secs, MAX_BACKOFF_SECS),
+            grace_period_secs: self.grace_period_secs.clamp(1, MAX_GRACE_PERIOD_SECS),
+            degraded_to_dead_secs: self.degraded_to_dead_secs.clamp(1, MAX_WINDOW_SECS),
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(self.window_secs)
+    }
+
+    pub fn grace_period(&self) -> Duration {
+        Duration::from_secs(self.grace_period_secs)
+    }
+
+    pub fn degraded_to_dead(&self) -> Duration {
+        Duration::from_secs(self.degraded_to_dead_secs)
+    }
+
+    /// 窗口内重启次数未耗尽时记录本次重启并返回退避时间，否则返回 None
+    pub(crate) fn can_restart(&self, history: &mut Vec<Instant>, now: Instant) -> Option<Duration> {
+        history.retain(|t| now.duration_since(*t) < self.window());
+        if history.len() >= self.max_restarts {
+            return None;
+        }
+        let exp = history.len() as u32;
+        let multiplier = 1u32.checked_shl(exp).unwrap_or(u32::MAX);
+        let backoff = Duration::from_secs(self.base_backoff_secs).saturating_mul(multiplier);
+        history.push(now);
+        Some(backoff.min(Duration::from_secs(self.max_backoff_secs)))
+    }
+}
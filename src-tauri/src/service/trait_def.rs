@@ -1,4 +1,5 @@
 /// Service trait 定义（统一接口）
+use crate::service::external_process::ExternalProcessConfig;
 use crate::service::state::ServiceState;
 use std::sync::{Arc, Mutex};
 
@@ -73,6 +74,11 @@ pub trait Service: Send + Sync {
     fn message(&self) -> Option<String> {
         None
     }
+
+    /// 外部进程服务的注册配置（导出服务快照时保存，恢复时据此重新注册）
+    fn external_config(&self) -> Option<&ExternalProcessConfig> {
+        None
+    }
 }
 
 /// Service 的 Arc<Mutex<dyn Service>> 类型别名
@@ -1,14 +1,19 @@
 use crate::service::events::{EventListener, ServiceEvent};
 /// WebSocket/SSE 前端事件推送
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
+/// 事件发送函数（返回 false 表示客户端已断开）
+pub type EventSender = Box<dyn Fn(&ServiceEvent) -> bool + Send + Sync>;
+
 /// WebSocket 事件监听器（用于向前端推送事件）
-#[allow(dead_code)]
 pub struct WebSocketEventListener {
-    /// 连接的客户端 ID
+    /// 连接的客户端 ID（即订阅 ID）
     client_id: String,
-    /// 事件发送器（实际实现中应该使用 WebSocket 发送）
-    sender: Arc<Mutex<Option<Box<dyn Fn(&ServiceEvent) + Send + Sync>>>>,
+    /// 事件发送器（Tauri Channel 等）
+    sender: Arc<Mutex<Option<EventSender>>>,
+    /// 客户端已断开（发送失败），等待从事件总线移除
+    closed: AtomicBool,
 }
 
 impl WebSocketEventListener {
@@ -16,11 +21,11 @@ impl WebSocketEventListener {
         Self {
             client_id,
             sender: Arc::new(Mutex::new(None)),
+            closed: AtomicBool::new(false),
         }
     }
 
-    #[allow(dead_code)]
-    pub fn set_sender(&self, sender: Box<dyn Fn(&ServiceEvent) + Send + Sync>) {
+    pub fn set_sender(&self, sender: EventSender) {
         let mut s =
             crate::utils::lock_or_recover(self.sender.as_ref(), "WebSocketEventListener.sender");
         *s = Some(sender);
@@ -29,12 +34,26 @@ impl WebSocketEventListener {
 
 impl EventListener for WebSocketEventListener {
     fn on_event(&self, event: &ServiceEvent) {
+        if self.closed.load(Ordering::Relaxed) {
+            return;
+        }
         let sender =
             crate::utils::lock_or_recover(self.sender.as_ref(), "WebSocketEventListener.sender");
         if let Some(ref send_fn) = *sender {
-            send_fn(event);
+            if !send_fn(event) {
+                log::debug!("[ServiceEvents] 客户端 {} 已断开，停止推送", self.client_id);
+                self.closed.store(true, Ordering::Relaxed);
+            }
         }
     }
+
+    fn listener_id(&self) -> Option<&str> {
+        Some(&self.client_id)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
 }
 
 /// SSE 事件流管理器
@@ -88,6 +88,24 @@ pub struct LaunchToolParams {
     pub working_dir: Option<String>,
     #[serde(alias = "jarConfig", alias = "jar_config")]
     pub jar_config: Option<JarConfig>,
+    /// 启动成功后在浏览器中打开的地址（支持 {{port}} 占位符）
+    #[serde(default, alias = "postLaunchUrl", alias = "post_launch_url")]
+    pub post_launch_url: Option<String>,
+    /// 打开 post_launch_url 前的等待时间（毫秒，默认 2000，留给本地服务启动）
+    #[serde(default, alias = "postLaunchDelayMs", alias = "post_launch_delay_ms")]
+    pub post_launch_delay_ms: Option<u64>,
+    /// 替换 post_launch_url 中 {{port}} 占位符的端口
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// 额外的环境变量（如 JAVA_OPTS、代理设置），在继承的环境上追加 / 覆盖
+    #[serde(default)]
+    pub env: Option<Vec<(String, String)>>,
+    /// 期望的 SHA-256（十六进制），提供时启动前校验可执行文件 / JAR，不匹配则拒绝启动
+    #[serde(default, alias = "expectedSha256", alias = "expected_sha256")]
+    pub expected_sha256: Option<String>,
+    /// 工具 ID（用于记录启动统计；未提供时按工具类型 + 解析后的路径记录）
+    #[serde(default, alias = "toolId", alias = "tool_id")]
+    pub tool_id: Option<String>,
 }
 
 /// 提取图标的参数结构体（支持 camelCase 和 snake_case）
@@ -97,6 +115,15 @@ pub struct ExtractIconParams {
     pub file_path: String,
     #[serde(alias = "toolType", alias = "tool_type")]
     pub tool_type: Option<String>,
+    /// 强制重新提取：跳过缓存读取并覆盖缓存（用于"刷新图标"）
+    #[serde(default)]
+    pub force: Option<bool>,
+    /// 用户已锁定自定义图标：此时拒绝强制刷新，避免覆盖用户的选择
+    #[serde(default, alias = "iconLocked", alias = "icon_locked")]
+    pub icon_locked: Option<bool>,
+    /// 返回图标的边长（像素，默认 160，可按界面密度请求 32 / 64 等）
+    #[serde(default)]
+    pub size: Option<u32>,
 }
 
 /// 获取 favicon 的参数结构体（支持 camelCase 和 snake_case）
@@ -104,6 +131,9 @@ pub struct ExtractIconParams {
 pub struct FetchFaviconParams {
     #[serde(alias = "urlStr", alias = "url_str")]
     pub url_str: String,
+    /// 返回图标的边长（像素，默认 160）
+    #[serde(default)]
+    pub size: Option<u32>,
 }
 
 /// 上传文件的参数结构体（支持 camelCase 和 snake_case）
@@ -117,6 +147,15 @@ pub struct UploadFileParams {
     pub tool_id: Option<String>, // 可选的工具ID，用于组织文件
 }
 
+/// 分块上传开始时的参数结构体（支持 camelCase 和 snake_case）
+#[derive(Debug, Deserialize)]
+pub struct UploadFileBeginParams {
+    #[serde(alias = "fileName", alias = "file_name")]
+    pub file_name: String,
+    #[serde(alias = "toolId", alias = "tool_id")]
+    pub tool_id: Option<String>, // 可选的工具ID，用于组织文件
+}
+
 /// 解析文件路径的参数结构体
 #[derive(Debug, Deserialize)]
 pub struct ResolveFilePathParams {
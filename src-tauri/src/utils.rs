@@ -1,21 +1,90 @@
+use serde::de::DeserializeOwned;
 use sha2::{Digest, Sha256};
-use std::path::PathBuf;
+use std::io::Write;
+use std::panic::Location;
+use std::path::{Path, PathBuf};
 use std::sync::{Mutex, MutexGuard, Once};
+use std::time::Instant;
 
 // 缓存应用程序基础目录，避免重复计算和日志输出
 static APP_BASE_DIR: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
 static INIT_LOG: Once = Once::new();
 
+/// 记录获取位置的锁名（死锁监视器告警时作为持有者提示）
+const TRACKED_LOCK: &str = "GatewayPool";
+
+/// 最近一次获取被记录锁的位置与线程
+#[derive(Debug, Clone, Copy)]
+pub struct LockAcquisition {
+    pub location: &'static Location<'static>,
+    pub thread_id: std::thread::ThreadId,
+    pub acquired_at: Instant,
+}
+
+static LAST_TRACKED_ACQUISITION: Mutex<Option<LockAcquisition>> = Mutex::new(None);
+
+#[track_caller]
 pub fn lock_or_recover<'a, T: ?Sized>(mutex: &'a Mutex<T>, name: &str) -> MutexGuard<'a, T> {
-    match mutex.lock() {
+    let guard = match mutex.lock() {
         Ok(guard) => guard,
         Err(poisoned) => {
             log::error!("{} Mutex 被污染，尝试恢复", name);
             poisoned.into_inner()
         }
+    };
+    if name == TRACKED_LOCK {
+        let acquisition = LockAcquisition {
+            location: Location::caller(),
+            thread_id: std::thread::current().id(),
+            acquired_at: Instant::now(),
+        };
+        *LAST_TRACKED_ACQUISITION
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(acquisition);
+    }
+    guard
+}
+
+/// 最近一次获取连接池锁的位置（锁长时间未释放时，通常就是当前持有者）
+pub fn last_pool_lock_acquisition() -> Option<LockAcquisition> {
+    *LAST_TRACKED_ACQUISITION
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// 后台线程分段睡眠的粒度（停止请求最多延迟这么久被发现）
+const STOPPABLE_SLEEP_SLICE: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// 分段睡眠，stop 返回 true 时提前结束；返回是否睡满（未被停止）
+pub fn sleep_unless_stopped(duration: std::time::Duration, stop: impl Fn() -> bool) -> bool {
+    let deadline = Instant::now() + duration;
+    loop {
+        if stop() {
+            return false;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return true;
+        }
+        std::thread::sleep(STOPPABLE_SLEEP_SLICE.min(deadline - now));
     }
 }
 
+/// 在 deadline 前等待后台线程结束；超时则不再等待（线程随进程退出），返回线程是否已结束
+pub fn join_until(name: &str, handle: std::thread::JoinHandle<()>, deadline: Instant) -> bool {
+    while !handle.is_finished() {
+        if Instant::now() >= deadline {
+            log::warn!("等待后台线程 {} 退出超时，不再等待", name);
+            return false;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    if handle.join().is_err() {
+        log::warn!("后台线程 {} 异常退出", name);
+    }
+    true
+}
+
 /// 获取应用程序基础目录（项目根目录，src-tauri 的父目录）
 /// 用户可自定义的文件都放在这个目录下
 /// 结果会被缓存，避免重复计算和日志输出
@@ -135,6 +204,84 @@ pub fn get_uploads_dir() -> PathBuf {
     uploads_dir
 }
 
+/// 获取文件的 .bak 备份路径（如 gateway_pool.json -> gateway_pool.json.bak）
+pub fn backup_path(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!("{}.bak", file_name))
+}
+
+/// 原子写入文件：先写入同目录临时文件并刷盘，再重命名覆盖目标文件
+/// 目标文件已存在时先轮转为 .bak 备份，写入中途崩溃不会留下半截文件
+pub fn write_file_atomic(path: &Path, content: &[u8]) -> Result<(), String> {
+    let parent = path
+        .parent()
+        .ok_or_else(|| format!("无效的文件路径: {}", path.display()))?;
+    std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("无效的文件路径: {}", path.display()))?;
+    let tmp_path = path.with_file_name(format!("{}.tmp", file_name));
+
+    let write_result = std::fs::File::create(&tmp_path).and_then(|mut file| {
+        file.write_all(content)?;
+        file.sync_all()
+    });
+    if let Err(e) = write_result {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(format!("写入临时文件失败: {} ({})", e, tmp_path.display()));
+    }
+
+    if path.exists() {
+        if let Err(e) = std::fs::copy(path, backup_path(path)) {
+            log::warn!("备份文件失败: {} ({})", e, path.display());
+        }
+    }
+
+    std::fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp_path);
+        format!("替换文件失败: {} ({})", e, path.display())
+    })
+}
+
+/// 读取 JSON 文件，文件损坏或不完整时回退到 .bak 备份
+/// 均不可用时返回 None 并记录警告（不会 panic）
+pub fn read_json_with_backup<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let parse = |p: &Path| -> Result<T, String> {
+        let content = std::fs::read_to_string(p).map_err(|e| format!("读取失败: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("解析失败: {}", e))
+    };
+
+    if !path.exists() {
+        return None;
+    }
+
+    match parse(path) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            let bak = backup_path(path);
+            log::warn!("文件已损坏，尝试使用备份: {} ({})", e, path.display());
+            if !bak.exists() {
+                return None;
+            }
+            match parse(&bak) {
+                Ok(value) => {
+                    log::warn!("已从备份恢复: {}", bak.display());
+                    Some(value)
+                }
+                Err(e) => {
+                    log::warn!("备份文件同样不可用: {} ({})", e, bak.display());
+                    None
+                }
+            }
+        }
+    }
+}
+
 /// 生成文件路径的哈希值（用于缓存文件名）
 pub fn hash_path(path: &str) -> String {
     let mut hasher = Sha256::new();
@@ -143,6 +290,26 @@ pub fn hash_path(path: &str) -> String {
     hex::encode(&hash[..16]) // 使用前16字节，32个十六进制字符
 }
 
+/// 计算文件的 SHA-256（分块读取，不整体载入内存），返回小写十六进制
+pub fn sha256_file(path: &std::path::Path) -> Result<String, String> {
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| format!("打开文件失败: {} ({})", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| format!("读取文件失败: {} ({})", path.display(), e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
 /// 从文件路径提取所在目录
 pub fn get_file_dir(file_path: &str) -> PathBuf {
     let path = std::path::Path::new(file_path);
@@ -205,3 +372,21 @@ pub fn get_theme_dir() -> PathBuf {
 
     theme_dir
 }
+
+/// 在 PATH 中查找可执行文件
+pub fn find_in_path(program: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths).find_map(|dir| {
+        let candidate = dir.join(program);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if cfg!(target_os = "windows") {
+            let exe = dir.join(format!("{}.exe", program));
+            if exe.is_file() {
+                return Some(exe);
+            }
+        }
+        None
+    })
+}
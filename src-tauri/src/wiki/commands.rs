@@ -1,5 +1,6 @@
 // Wiki 相关的 Tauri 命令
 use crate::wiki::server::WikiServer;
+use crate::wiki::types::{WikiContentType, WikiDocument};
 
 /// 获取 Wiki 文件列表
 #[tauri::command]
@@ -9,53 +10,122 @@ pub fn get_wiki_files() -> Result<Vec<crate::wiki::types::WikiFileInfo>, String>
     server.list_files()
 }
 
-/// 读取 Wiki 文件内容（不渲染，返回原始文本）
-/// 支持读取 Markdown 文件和主题 CSS 文件
-#[tauri::command]
-pub fn read_wiki_file(file_path: String) -> Result<String, String> {
+/// 解析 Wiki 文件路径：themes/ 前缀从主题目录读取，其余从 docs 目录读取；
+/// 只接受不含 .. 的相对路径，且解析符号链接后仍须位于对应目录内
+fn resolve_wiki_file(file_path: &str) -> Result<std::path::PathBuf, String> {
     use crate::utils::{get_docs_dir, get_theme_dir};
-    use std::fs;
-
-    // 如果是主题文件，从 themes 目录读取
-    if file_path.starts_with("themes/") {
-        let theme_dir = get_theme_dir();
-        let theme_name = file_path.strip_prefix("themes/").unwrap_or(&file_path);
-        let full_path = theme_dir.join(theme_name);
+    use std::path::{Component, Path};
+
+    let (root, relative, label) = match file_path.strip_prefix("themes/") {
+        Some(theme_name) => (get_theme_dir(), theme_name, "主题文件"),
+        None => (get_docs_dir(), file_path, "Wiki 文件"),
+    };
+
+    let relative = Path::new(relative);
+    if relative.as_os_str().is_empty()
+        || !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!("文件路径无效或超出 Wiki 目录: {}", file_path));
+    }
 
-        if !full_path.exists() {
-            return Err(format!("主题文件不存在: {}", file_path));
-        }
+    let full_path = root.join(relative);
+    if !full_path.exists() {
+        return Err(format!("{}不存在: {}", label, file_path));
+    }
 
-        if !full_path.is_file() {
-            return Err(format!("路径不是文件: {}", file_path));
-        }
+    let root = root
+        .canonicalize()
+        .map_err(|e| format!("无法访问 Wiki 目录: {}", e))?;
+    let full_path = full_path
+        .canonicalize()
+        .map_err(|e| format!("无法解析文件路径 {}: {}", file_path, e))?;
+    if !full_path.starts_with(&root) {
+        return Err(format!("文件路径超出 Wiki 目录: {}", file_path));
+    }
 
-        return fs::read_to_string(&full_path).map_err(|e| format!("读取主题文件失败: {}", e));
+    if !full_path.is_file() {
+        return Err(format!("路径不是文件: {}", file_path));
     }
 
-    // 否则从 docs 目录读取
-    let docs_dir = get_docs_dir();
-    let full_path = docs_dir.join(&file_path);
+    Ok(full_path)
+}
 
-    if !full_path.exists() {
-        return Err(format!("Wiki 文件不存在: {}", file_path));
+/// 读取 Wiki 文件内容（不渲染）
+/// Markdown、纯文本与主题 CSS 原样返回，PDF / DOCX 返回提取的纯文本；
+/// 其他二进制文件返回 content_type = unsupported 的结果，可通过 download_wiki_file 下载
+/// rewrite_images 为 true 时，Markdown 中的相对图片链接改写为 wiki-image:// 链接（可通过 read_wiki_image 读取）
+#[tauri::command]
+pub fn read_wiki_file(
+    file_path: String,
+    rewrite_images: Option<bool>,
+) -> Result<WikiDocument, String> {
+    let full_path = resolve_wiki_file(&file_path)?;
+    let mut document = crate::wiki::document::read_document(&full_path)?;
+    if rewrite_images.unwrap_or(false)
+        && document.content_type == WikiContentType::Markdown
+        && !file_path.starts_with("themes/")
+    {
+        document.content = crate::wiki::image::rewrite_image_links(&document.content, &file_path);
     }
+    Ok(document)
+}
 
-    if !full_path.is_file() {
-        return Err(format!("路径不是文件: {}", file_path));
+/// 读取 Wiki 中的图片（路径相对 Wiki 文档目录，即 wiki-image:// 之后 URL 解码的部分），
+/// 返回 base64 data URI；拒绝超出 Wiki 目录的路径
+#[tauri::command]
+pub fn read_wiki_image(wiki_relative_path: String) -> Result<String, String> {
+    let full_path =
+        crate::wiki::image::resolve_wiki_image(&crate::utils::get_docs_dir(), &wiki_relative_path)?;
+    crate::wiki::image::read_image_data_uri(&full_path)
+}
+
+/// 下载 Wiki 文件原始内容（用于不支持预览的格式）
+#[tauri::command]
+pub fn download_wiki_file(file_path: String) -> Result<tauri::ipc::Response, String> {
+    let full_path = resolve_wiki_file(&file_path)?;
+    let size = std::fs::metadata(&full_path)
+        .map_err(|e| format!("读取文件信息失败: {}", e))?
+        .len();
+    if size > crate::wiki::document::MAX_WIKI_DOCUMENT_BYTES {
+        return Err(format!(
+            "文件过大（{} MB），请直接在 Wiki 目录中打开: {}",
+            size / 1024 / 1024,
+            full_path.display()
+        ));
     }
+    let bytes = std::fs::read(&full_path).map_err(|e| format!("读取文件失败: {}", e))?;
+    Ok(tauri::ipc::Response::new(bytes))
+}
 
-    fs::read_to_string(&full_path).map_err(|e| format!("读取文件失败: {}", e))
+/// 读取 Wiki 文件中指定标题锚点的章节（用于从工具页跳转到某一章节）
+/// 返回从该标题到下一个同级或更高级标题之前的内容
+#[tauri::command]
+pub fn read_wiki_section(path: String, anchor: String) -> Result<String, String> {
+    let document = read_wiki_file(path.clone(), None)?;
+    if document.content_type != WikiContentType::Markdown {
+        return Err(format!("只支持读取 Markdown 文件的章节（文件: {}）", path));
+    }
+    crate::wiki::server::extract_markdown_section(&document.content, &anchor)
+        .map_err(|e| format!("{}（文件: {}）", e, path))
 }
 
-/// 搜索 Wiki
+/// 搜索 Wiki（按相关度排序，附带匹配片段和匹配总数）
 #[tauri::command]
-pub fn search_wiki(query: String) -> Result<Vec<crate::wiki::types::SearchResult>, String> {
+pub fn search_wiki(query: String) -> Result<crate::wiki::types::SearchResponse, String> {
     // 直接创建实例搜索，不依赖服务器
     let server = WikiServer::new();
     server.search(&query)
 }
 
+/// 重建 Wiki 搜索索引（目录监听正常时索引会自动增量更新，通常无需手动调用）
+#[tauri::command]
+pub fn rebuild_wiki_index() -> Result<crate::wiki::index::WikiIndexStats, String> {
+    let server = WikiServer::new();
+    crate::wiki::index::rebuild(server.get_wiki_dir())
+}
+
 /// 获取 Wiki 目录路径
 #[tauri::command]
 pub fn get_wiki_dir() -> Result<String, String> {
@@ -107,70 +177,21 @@ pub fn get_wiki_themes() -> Result<Vec<String>, String> {
 }
 
 /// 根据工具 ID 或名称查找对应的 Wiki 文件
+/// 返回按得分排序的候选列表（模糊匹配文件名、标题与路径）；文件名与工具 ID / 名称完全一致时得分为 1.0 并排在最前
 #[tauri::command]
 pub fn find_wiki_for_tool(
     tool_id: String,
     tool_name: Option<String>,
-) -> Result<Option<String>, String> {
+) -> Result<Vec<crate::wiki::types::WikiMatch>, String> {
     let server = WikiServer::new();
     let files = server.list_files()?;
-
-    // 尝试多种匹配方式：
-    // 1. 精确匹配工具 ID
-    // 2. 匹配工具名称（不区分大小写）
-    // 3. 匹配文件名包含工具 ID 或名称
-
-    let tool_name_lower = tool_name
-        .as_ref()
-        .map(|n| n.to_lowercase())
-        .unwrap_or_default();
-    let tool_id_lower = tool_id.to_lowercase();
-
-    // 递归搜索文件
-    fn search_files(
-        files: &[crate::wiki::types::WikiFileInfo],
-        tool_id: &str,
-        tool_name: &str,
-    ) -> Option<String> {
-        for file in files {
-            if file.is_dir {
-                if let Some(children) = &file.children {
-                    if let Some(found) = search_files(children, tool_id, tool_name) {
-                        return Some(found);
-                    }
-                }
-            } else {
-                // 检查文件名或路径是否匹配
-                let name_lower = file.name.to_lowercase();
-                let path_lower = file.path.to_lowercase();
-                let title_lower = file.title.to_lowercase();
-
-                // 移除 .md 扩展名进行比较
-                let name_without_ext = name_lower.trim_end_matches(".md");
-                let path_without_ext = path_lower.trim_end_matches(".md");
-
-                // 匹配逻辑：
-                // 1. 文件名完全匹配工具 ID 或名称
-                // 2. 文件名包含工具 ID 或名称
-                // 3. 路径包含工具 ID 或名称
-                // 4. 标题包含工具 ID 或名称
-                if name_without_ext == tool_id
-                    || name_without_ext == tool_name
-                    || name_without_ext.contains(tool_id)
-                    || name_without_ext.contains(tool_name)
-                    || path_without_ext.contains(tool_id)
-                    || path_without_ext.contains(tool_name)
-                    || title_lower.contains(tool_id)
-                    || title_lower.contains(tool_name)
-                {
-                    return Some(file.path.clone());
-                }
-            }
-        }
-        None
+    let mut queries = vec![tool_id.as_str()];
+    if let Some(name) = tool_name.as_deref() {
+        queries.push(name);
     }
-
-    Ok(search_files(&files, &tool_id_lower, &tool_name_lower))
+    Ok(crate::wiki::server::rank_tool_wiki_matches(
+        &files, &queries,
+    ))
 }
 
 /// 设置当前主题
@@ -0,0 +1,403 @@
+// Wiki 文档读取：按扩展名识别类型，Markdown / 纯文本原样返回，
+// PDF / DOCX 提取纯文本，其他二进制文件返回“不支持预览”结果（可通过 download_wiki_file 下载）
+use crate::wiki::types::{WikiContentType, WikiDocument};
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+use std::path::Path;
+
+/// 文件列表中显示的扩展名
+pub const WIKI_FILE_EXTENSIONS: &[&str] = &["md", "markdown", "txt", "pdf", "docx"];
+
+/// 按纯文本读取的扩展名
+const TEXT_EXTENSIONS: &[&str] = &[
+    "txt", "log", "csv", "json", "yaml", "yml", "xml", "ini", "conf", "toml",
+];
+
+/// 读取 / 下载的文件大小上限（50 MB）
+pub const MAX_WIKI_DOCUMENT_BYTES: u64 = 50 * 1024 * 1024;
+
+/// PDF 内容流 / DOCX 正文解压后的大小上限（防止压缩炸弹）
+const MAX_DECODED_BYTES: u64 = MAX_WIKI_DOCUMENT_BYTES;
+
+/// 判断二进制内容时检查的前缀长度
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+/// 文件扩展名（小写）
+fn extension_of(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default()
+}
+
+/// 是否为文件列表中显示的 Wiki 文档
+pub fn is_wiki_document(path: &Path) -> bool {
+    WIKI_FILE_EXTENSIONS.contains(&extension_of(path).as_str())
+}
+
+/// 读取 Wiki 文档并按类型返回内容
+pub fn read_document(path: &Path) -> Result<WikiDocument, String> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| format!("读取文件信息失败: {}", e))?
+        .len();
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if size > MAX_WIKI_DOCUMENT_BYTES {
+        return Ok(unsupported(
+            file_name,
+            size,
+            format!("文件过大（{} MB），请下载后查看", size / 1024 / 1024),
+        ));
+    }
+
+    let bytes = std::fs::read(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let ext = extension_of(path);
+    let extracted = match ext.as_str() {
+        "md" | "markdown" => text_content(bytes).map(|c| (c, WikiContentType::Markdown)),
+        "css" => text_content(bytes).map(|c| (c, WikiContentType::Css)),
+        "pdf" => extract_pdf_text(&bytes, MAX_DECODED_BYTES).map(|c| (c, WikiContentType::Pdf)),
+        "docx" => extract_docx_text(&bytes, MAX_DECODED_BYTES).map(|c| (c, WikiContentType::Docx)),
+        ext if TEXT_EXTENSIONS.contains(&ext) => {
+            text_content(bytes).map(|c| (c, WikiContentType::Text))
+        }
+        // 未知扩展名：内容是 UTF-8 文本时按纯文本返回
+        _ => text_content(bytes)
+            .map(|c| (c, WikiContentType::Text))
+            .map_err(|_| "不支持预览该格式".to_string()),
+    };
+
+    Ok(match extracted {
+        Ok((content, content_type)) => WikiDocument {
+            content,
+            content_type,
+            file_name,
+            size,
+            message: None,
+        },
+        Err(reason) => unsupported(file_name, size, format!("{}，请下载后查看", reason)),
+    })
+}
+
+fn unsupported(file_name: String, size: u64, message: String) -> WikiDocument {
+    WikiDocument {
+        content: String::new(),
+        content_type: WikiContentType::Unsupported,
+        file_name,
+        size,
+        message: Some(message),
+    }
+}
+
+/// 按 UTF-8 文本读取（包含 NUL 字节或不是合法 UTF-8 时视为二进制）
+fn text_content(bytes: Vec<u8>) -> Result<String, String> {
+    let sniff = &bytes[..bytes.len().min(BINARY_SNIFF_BYTES)];
+    if sniff.contains(&0) {
+        return Err("文件是二进制格式".to_string());
+    }
+    String::from_utf8(bytes).map_err(|_| "文件不是 UTF-8 文本".to_string())
+}
+
+/// 解压内容超过上限时的错误
+fn decoded_too_large() -> String {
+    format!("解压后的内容超过 {} MB", MAX_DECODED_BYTES / 1024 / 1024)
+}
+
+/// 最多读取 limit 字节追加到 out，超过上限时返回 Ok(false)
+fn read_limited(reader: impl Read, limit: u64, out: &mut Vec<u8>) -> std::io::Result<bool> {
+    let read = reader.take(limit + 1).read_to_end(out)?;
+    Ok(read as u64 <= limit)
+}
+
+/// 提取 DOCX 正文（word/document.xml 中的 w:t 文本，按段落换行）
+/// 正文解压后超过 limit 字节时视为不支持
+fn extract_docx_text(bytes: &[u8], limit: u64) -> Result<String, String> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))
+        .map_err(|e| format!("DOCX 文件损坏: {}", e))?;
+    let entry = archive
+        .by_name("word/document.xml")
+        .map_err(|e| format!("DOCX 缺少正文: {}", e))?;
+    let mut raw = Vec::new();
+    if !read_limited(entry, limit, &mut raw).map_err(|e| format!("读取 DOCX 正文失败: {}", e))?
+    {
+        return Err(decoded_too_large());
+    }
+    let xml = String::from_utf8(raw).map_err(|_| "DOCX 正文不是 UTF-8 文本".to_string())?;
+
+    let mut text = String::new();
+    let mut rest = xml.as_str();
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        let after = &rest[start + end + 1..];
+        let name = tag.split_whitespace().next().unwrap_or("");
+        match name {
+            "w:t" => {
+                let close = after.find("</w:t>").unwrap_or(after.len());
+                text.push_str(&unescape_xml(&after[..close]));
+                rest = &after[close..];
+                continue;
+            }
+            "w:tab/" => text.push('\t'),
+            "w:br/" | "w:cr/" | "/w:p" => text.push('\n'),
+            _ => {}
+        }
+        rest = after;
+    }
+    non_empty(text, "DOCX 中没有文本内容")
+}
+
+/// 还原 XML 实体
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 提取 PDF 文本：解码内容流（无压缩或 FlateDecode），收集文本操作符中的字面字符串
+/// 只支持使用标准编码字体的 PDF；扫描件或使用 CID 字体（常见于中文文档）时无法提取
+/// 所有内容流解压后合计超过 limit 字节时视为不支持
+fn extract_pdf_text(bytes: &[u8], limit: u64) -> Result<String, String> {
+    if !bytes.starts_with(b"%PDF") {
+        return Err("不是有效的 PDF 文件".to_string());
+    }
+    let mut text = String::new();
+    let mut decoded_total = 0u64;
+    let mut pos = 0;
+    while let Some(offset) = find_bytes(&bytes[pos..], b"stream") {
+        let keyword = pos + offset;
+        pos = keyword + b"stream".len();
+        // 跳过 endstream 中的 stream
+        if keyword >= 3 && &bytes[keyword - 3..keyword] == b"end" {
+            continue;
+        }
+        let mut data_start = pos;
+        if bytes.get(data_start) == Some(&b'\r') {
+            data_start += 1;
+        }
+        if bytes.get(data_start) == Some(&b'\n') {
+            data_start += 1;
+        }
+        let Some(len) = find_bytes(&bytes[data_start..], b"endstream") else {
+            break;
+        };
+        let data = &bytes[data_start..data_start + len];
+        pos = data_start + len + b"endstream".len();
+
+        // 流字典位于 stream 关键字之前的最后一个 << 处
+        let dict_start = rfind_bytes(&bytes[..keyword], b"<<").unwrap_or(0);
+        let dict = &bytes[dict_start..keyword];
+        let decoded = if find_bytes(dict, b"/FlateDecode").is_some() {
+            let mut out = Vec::new();
+            match read_limited(ZlibDecoder::new(data), limit - decoded_total, &mut out) {
+                Ok(true) => out,
+                Ok(false) => return Err(decoded_too_large()),
+                Err(_) => continue,
+            }
+        } else if find_bytes(dict, b"/Filter").is_some() {
+            // 图片等其他编码的流
+            continue;
+        } else {
+            data.to_vec()
+        };
+        decoded_total += decoded.len() as u64;
+        if decoded_total > limit {
+            return Err(decoded_too_large());
+        }
+        if find_bytes(&decoded, b"BT").is_some() {
+            collect_pdf_strings(&decoded, &mut text);
+        }
+    }
+    let text = text
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    non_empty(
+        text,
+        "未能从 PDF 中提取文本（可能是扫描件或使用了嵌入字体编码）",
+    )
+}
+
+/// 从内容流中收集 (…) 字面字符串，遇到换行类文本操作符时换行
+fn collect_pdf_strings(content: &[u8], out: &mut String) {
+    let mut i = 0;
+    while i < content.len() {
+        match content[i] {
+            b'(' => {
+                let (literal, next) = read_pdf_literal(content, i + 1);
+                out.extend(
+                    literal
+                        .into_iter()
+                        .map(char::from)
+                        .filter(|c| !c.is_control() || *c == '\n'),
+                );
+                i = next;
+            }
+            b'T' if matches!(content.get(i + 1), Some(b'd' | b'D' | b'*')) => {
+                out.push('\n');
+                i += 2;
+            }
+            b'E' if content.get(i + 1) == Some(&b'T') => {
+                out.push('\n');
+                i += 2;
+            }
+            _ => i += 1,
+        }
+    }
+}
+
+/// 读取字面字符串（处理转义与嵌套括号），返回内容与结束后的位置
+fn read_pdf_literal(content: &[u8], mut i: usize) -> (Vec<u8>, usize) {
+    let mut literal = Vec::new();
+    let mut depth = 1;
+    while i < content.len() {
+        let byte = content[i];
+        i += 1;
+        match byte {
+            b'\\' => {
+                let Some(&escaped) = content.get(i) else {
+                    break;
+                };
+                i += 1;
+                match escaped {
+                    b'n' => literal.push(b'\n'),
+                    b'r' | b't' => literal.push(b' '),
+                    b'0'..=b'7' => {
+                        let mut value = u32::from(escaped - b'0');
+                        for _ in 0..2 {
+                            match content.get(i) {
+                                Some(&d @ b'0'..=b'7') => {
+                                    value = value * 8 + u32::from(d - b'0');
+                                    i += 1;
+                                }
+                                _ => break,
+                            }
+                        }
+                        literal.push(value as u8);
+                    }
+                    b'\r' | b'\n' => {}
+                    other => literal.push(other),
+                }
+            }
+            b'(' => {
+                depth += 1;
+                literal.push(byte);
+            }
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+                literal.push(byte);
+            }
+            _ => literal.push(byte),
+        }
+    }
+    (literal, i)
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn rfind_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|w| w == needle)
+}
+
+fn non_empty(text: String, empty_reason: &str) -> Result<String, String> {
+    if text.trim().is_empty() {
+        Err(empty_reason.to_string())
+    } else {
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    /// 构造只含一个内容流的最小 PDF
+    fn pdf_with_stream(dict: &str, data: &[u8]) -> Vec<u8> {
+        let mut pdf = b"%PDF-1.4\n1 0 obj\n".to_vec();
+        pdf.extend_from_slice(
+            format!("<< /Length {} {} >>\nstream\n", data.len(), dict).as_bytes(),
+        );
+        pdf.extend_from_slice(data);
+        pdf.extend_from_slice(b"\nendstream\nendobj\n%%EOF\n");
+        pdf
+    }
+
+    fn zlib(data: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn docx_with_body(xml: &[u8]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer
+            .start_file(
+                "word/document.xml",
+                zip::write::SimpleFileOptions::default()
+                    .compression_method(zip::CompressionMethod::Deflated),
+            )
+            .unwrap();
+        writer.write_all(xml).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn pdf_flate_stream_text_is_extracted() {
+        let content = b"BT /F1 12 Tf (Hello \\(PDF\\)) Tj T* (second line) Tj ET";
+        let pdf = pdf_with_stream("/Filter /FlateDecode", &zlib(content));
+        assert_eq!(
+            extract_pdf_text(&pdf, MAX_DECODED_BYTES).unwrap(),
+            "Hello (PDF)\nsecond line"
+        );
+    }
+
+    #[test]
+    fn pdf_stream_over_decoded_limit_is_rejected() {
+        let mut content = b"BT (x) Tj ET ".to_vec();
+        content.resize(64 * 1024, b' ');
+        let pdf = pdf_with_stream("/Filter /FlateDecode", &zlib(&content));
+        assert!(pdf.len() < 1024, "高压缩比样本");
+        assert_eq!(extract_pdf_text(&pdf, 4096), Err(decoded_too_large()));
+        assert!(extract_pdf_text(&pdf, MAX_DECODED_BYTES).is_ok());
+    }
+
+    #[test]
+    fn docx_text_keeps_tabs_and_paragraphs() {
+        let xml = br#"<w:document><w:body><w:p><w:r><w:t>a &amp; b</w:t><w:tab/><w:t xml:space="preserve">c</w:t></w:r></w:p><w:p><w:r><w:t>next</w:t></w:r></w:p></w:body></w:document>"#;
+        assert_eq!(
+            extract_docx_text(&docx_with_body(xml), MAX_DECODED_BYTES).unwrap(),
+            "a & b\tc\nnext\n"
+        );
+    }
+
+    #[test]
+    fn docx_body_over_decoded_limit_is_rejected() {
+        let mut xml = b"<w:p><w:t>x</w:t></w:p>".to_vec();
+        xml.resize(64 * 1024, b' ');
+        let docx = docx_with_body(&xml);
+        assert_eq!(extract_docx_text(&docx, 4096), Err(decoded_too_large()));
+        assert!(extract_docx_text(&docx, MAX_DECODED_BYTES).is_ok());
+    }
+
+    #[test]
+    fn text_content_rejects_binary() {
+        assert_eq!(text_content(b"plain text".to_vec()).unwrap(), "plain text");
+        assert!(text_content(b"MZ\x00\x01binary".to_vec()).is_err());
+        assert!(text_content(vec![0xff, 0xfe, b'a']).is_err());
+    }
+}
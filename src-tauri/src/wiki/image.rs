@@ -0,0 +1,154 @@
+// Wiki 图片：Markdown 中引用的本地图片由前端通过 read_wiki_image 读取（前端无法直接访问 Wiki 目录），
+// read_wiki_file 可将相对图片链接改写为 wiki-image:// 链接，前端据此请求图片
+use base64::{engine::general_purpose, Engine as _};
+use std::path::{Component, Path, PathBuf};
+
+/// 改写后的图片链接前缀（其后为相对 Wiki 文档目录的路径，按路径段 URL 编码）
+pub const WIKI_IMAGE_SCHEME: &str = "wiki-image://";
+
+/// 图片大小上限（10 MB，data URI 会整体经 IPC 传给前端）
+const MAX_WIKI_IMAGE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// 按扩展名识别图片 MIME 类型
+fn image_mime(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        _ => return None,
+    })
+}
+
+/// 按路径段规范化相对路径（处理 . 与 ..），越出根目录、绝对路径或包含盘符时返回 None
+fn normalize_relative(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!normalized.as_os_str().is_empty()).then_some(normalized)
+}
+
+/// 解析 Wiki 图片路径：只接受 Wiki 目录内的相对路径（规范化后仍在目录内，且符号链接不能指向目录外）
+pub fn resolve_wiki_image(root: &Path, relative_path: &str) -> Result<PathBuf, String> {
+    let relative = normalize_relative(Path::new(relative_path.trim()))
+        .ok_or_else(|| format!("图片路径无效或超出 Wiki 目录: {}", relative_path))?;
+    if image_mime(&relative).is_none() {
+        return Err(format!("不支持的图片格式: {}", relative_path));
+    }
+    let root = root
+        .canonicalize()
+        .map_err(|e| format!("无法访问 Wiki 目录: {}", e))?;
+    let full_path = root
+        .join(&relative)
+        .canonicalize()
+        .map_err(|_| format!("图片不存在: {}", relative_path))?;
+    if !full_path.starts_with(&root) {
+        return Err(format!("图片路径超出 Wiki 目录: {}", relative_path));
+    }
+    if !full_path.is_file() {
+        return Err(format!("路径不是文件: {}", relative_path));
+    }
+    Ok(full_path)
+}
+
+/// 读取图片并编码为 base64 data URI
+pub fn read_image_data_uri(path: &Path) -> Result<String, String> {
+    let mime = image_mime(path).ok_or_else(|| format!("不支持的图片格式: {}", path.display()))?;
+    let size = std::fs::metadata(path)
+        .map_err(|e| format!("读取图片信息失败: {}", e))?
+        .len();
+    if size > MAX_WIKI_IMAGE_BYTES {
+        return Err(format!("图片过大（{} MB）", size / 1024 / 1024));
+    }
+    let bytes = std::fs::read(path).map_err(|e| format!("读取图片失败: {}", e))?;
+    Ok(format!(
+        "data:{};base64,{}",
+        mime,
+        general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// 将图片链接目标改写为 wiki-image:// 链接；外部链接、data URI、锚点及超出 Wiki 目录的路径保持不变
+fn rewrite_image_target(target: &str, document_dir: &Path) -> Option<String> {
+    let lower = target.to_ascii_lowercase();
+    if target.is_empty()
+        || target.starts_with('/')
+        || target.starts_with('#')
+        || lower.starts_with("data:")
+        || target.contains("://")
+    {
+        return None;
+    }
+    let decoded = urlencoding::decode(target).ok()?;
+    let resolved = normalize_relative(&document_dir.join(decoded.as_ref()))?;
+    let encoded = resolved
+        .components()
+        .map(|part| urlencoding::encode(&part.as_os_str().to_string_lossy()).into_owned())
+        .collect::<Vec<_>>()
+        .join("/");
+    Some(format!("{}{}", WIKI_IMAGE_SCHEME, encoded))
+}
+
+/// 改写 Markdown 中的相对图片链接（![alt](path "title") 与 ![alt](<path>)），
+/// 路径按文档所在目录解析为相对 Wiki 目录的路径
+pub fn rewrite_image_links(markdown: &str, document_path: &str) -> String {
+    let document_dir = Path::new(document_path)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let mut out = String::with_capacity(markdown.len());
+    let mut rest = markdown;
+    while let Some(start) = rest.find("![") {
+        // 替代文本不含 ]，其后紧跟 ( 才是图片链接
+        let Some(close) = rest[start..].find(']') else {
+            break;
+        };
+        let target_start = start + close + 2;
+        if !rest[start + close..].starts_with("](") {
+            out.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            continue;
+        }
+        let Some(target_len) = rest[target_start..].find(')') else {
+            break;
+        };
+        let inner = &rest[target_start..target_start + target_len];
+        // 链接目标后可带标题；<...> 形式的目标可包含空格
+        let (target, suffix) = match inner.strip_prefix('<') {
+            Some(bracketed) => match bracketed.find('>') {
+                Some(end) => (&bracketed[..end], &bracketed[end + 1..]),
+                None => (inner, ""),
+            },
+            None => match inner.find(char::is_whitespace) {
+                Some(space) => (&inner[..space], &inner[space..]),
+                None => (inner, ""),
+            },
+        };
+        out.push_str(&rest[..target_start]);
+        match rewrite_image_target(target.trim(), &document_dir) {
+            Some(rewritten) => {
+                out.push_str(&rewritten);
+                out.push_str(suffix);
+            }
+            None => out.push_str(inner),
+        }
+        out.push(')');
+        rest = &rest[target_start + target_len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,321 @@
+// Wiki 搜索索引：常驻内存的倒排索引（字符 / 相邻字符对 → 文档），搜索时不再逐个读取全部文件
+// 搜索词按子串匹配（中文没有空格分词），索引只负责筛选候选文档，候选文档仍按原有规则校验与打分；
+// 监听线程按变化的路径增量更新索引，索引未建立时搜索回退为全量扫描
+use crate::utils::lock_or_recover;
+use crate::wiki::server::{
+    collect_markdown_files, fold_text, is_searchable_file, rank_search_results, SearchDocument,
+};
+use crate::wiki::types::SearchResponse;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// 索引键：单字符搜索词按字符查找，其余搜索词按相邻字符对查找（均为归一化后的字符）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Gram {
+    Char(char),
+    Pair(char, char),
+}
+
+/// 提取文本的索引键（跳过空白，搜索词不含空白）
+fn text_grams(text: &str, grams: &mut HashSet<Gram>) {
+    let folded = fold_text(text);
+    for (i, &c) in folded.iter().enumerate() {
+        if c.is_whitespace() {
+            continue;
+        }
+        grams.insert(Gram::Char(c));
+        if let Some(&next) = folded.get(i + 1) {
+            if !next.is_whitespace() {
+                grams.insert(Gram::Pair(c, next));
+            }
+        }
+    }
+}
+
+/// 文档的索引键（正文、标题、文件名分别提取，搜索词在三者之一中命中即可）
+fn document_grams(doc: &SearchDocument) -> HashSet<Gram> {
+    let mut grams = HashSet::new();
+    text_grams(&doc.content, &mut grams);
+    text_grams(&doc.title, &mut grams);
+    text_grams(&doc.file_name, &mut grams);
+    grams
+}
+
+/// 搜索词的索引键
+fn term_grams(term: &[char]) -> Vec<Gram> {
+    match term {
+        [c] => vec![Gram::Char(*c)],
+        _ => term.windows(2).map(|w| Gram::Pair(w[0], w[1])).collect(),
+    }
+}
+
+/// 规范化路径（解析符号链接，macOS 上 /var 与 /private/var 等）；路径已被删除时规范化其父目录
+fn canonical_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => parent
+            .canonicalize()
+            .map(|parent| parent.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// 倒排索引
+struct WikiIndex {
+    root: PathBuf,
+    docs: HashMap<usize, SearchDocument>,
+    ids_by_path: HashMap<String, usize>,
+    postings: HashMap<Gram, HashSet<usize>>,
+    next_id: usize,
+    /// 全部文档正文字符数之和（用于计算平均长度）
+    total_length: usize,
+}
+
+impl WikiIndex {
+    fn build(root: &Path) -> Result<Self, String> {
+        // 根目录与监听事件中的路径均规范化后再比较前缀
+        let root = canonical_path(root);
+        let root = root.as_path();
+        let mut files = Vec::new();
+        collect_markdown_files(root, &mut files)?;
+        let mut index = Self {
+            root: root.to_path_buf(),
+            docs: HashMap::new(),
+            ids_by_path: HashMap::new(),
+            postings: HashMap::new(),
+            next_id: 0,
+            total_length: 0,
+        };
+        for path in files {
+            if let Some(doc) = SearchDocument::load(root, &path) {
+                index.insert(doc);
+            }
+        }
+        Ok(index)
+    }
+
+    fn insert(&mut self, doc: SearchDocument) {
+        self.remove(&doc.relative_path.clone());
+        let id = self.next_id;
+        self.next_id += 1;
+        for gram in document_grams(&doc) {
+            self.postings.entry(gram).or_default().insert(id);
+        }
+        self.total_length += doc.length;
+        self.ids_by_path.insert(doc.relative_path.clone(), id);
+        self.docs.insert(id, doc);
+    }
+
+    fn remove(&mut self, relative_path: &str) {
+        let Some(id) = self.ids_by_path.remove(relative_path) else {
+            return;
+        };
+        let Some(doc) = self.docs.remove(&id) else {
+            return;
+        };
+        for gram in document_grams(&doc) {
+            if let Some(ids) = self.postings.get_mut(&gram) {
+                ids.remove(&id);
+                if ids.is_empty() {
+                    self.postings.remove(&gram);
+                }
+            }
+        }
+        self.total_length -= doc.length;
+    }
+
+    /// 移除路径本身及其下的全部文档（目录被删除或移走时）
+    fn remove_under(&mut self, relative: &Path) {
+        let paths: Vec<String> = self
+            .ids_by_path
+            .keys()
+            .filter(|path| Path::new(path).starts_with(relative))
+            .cloned()
+            .collect();
+        for path in paths {
+            self.remove(&path);
+        }
+    }
+
+    /// 重新读取文件；已不是参与搜索的文件或无法读取时从索引移除
+    fn refresh_file(&mut self, path: &Path) {
+        let doc = is_searchable_file(&self.root, path)
+            .then(|| SearchDocument::load(&self.root, path))
+            .flatten();
+        match doc {
+            Some(doc) => self.insert(doc),
+            None => {
+                if let Ok(relative) = path.strip_prefix(&self.root) {
+                    self.remove(&relative.to_string_lossy());
+                }
+            }
+        }
+    }
+
+    /// 按变化的路径更新索引
+    fn apply_change(&mut self, path: &Path) {
+        let path = canonical_path(path);
+        let path = path.as_path();
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return;
+        };
+        let relative = relative.to_path_buf();
+        if path.is_dir() {
+            // 目录被创建或移入：重新读取其下的文件
+            let mut files = Vec::new();
+            if collect_markdown_files(path, &mut files).is_ok() {
+                for file in files {
+                    self.refresh_file(&file);
+                }
+            }
+        } else if path.is_file() {
+            self.refresh_file(path);
+        } else {
+            // 文件或目录被删除或移走
+            self.remove_under(&relative);
+        }
+    }
+
+    /// 所有搜索词的索引键都出现的文档（候选集合，需进一步校验子串匹配）
+    fn candidates(&self, terms: &[Vec<char>]) -> Vec<usize> {
+        let mut posting_sets = Vec::new();
+        for gram in terms.iter().flat_map(|term| term_grams(term)) {
+            match self.postings.get(&gram) {
+                Some(ids) => posting_sets.push(ids),
+                None => return Vec::new(),
+            }
+        }
+        posting_sets.sort_by_key(|ids| ids.len());
+        let Some((smallest, rest)) = posting_sets.split_first() else {
+            return Vec::new();
+        };
+        smallest
+            .iter()
+            .filter(|id| rest.iter().all(|ids| ids.contains(id)))
+            .copied()
+            .collect()
+    }
+
+    fn search(&self, terms: &[Vec<char>]) -> SearchResponse {
+        let average_length = if self.docs.is_empty() {
+            0.0
+        } else {
+            self.total_length as f64 / self.docs.len() as f64
+        };
+        let results = self
+            .candidates(terms)
+            .into_iter()
+            .filter_map(|id| self.docs.get(&id))
+            .filter_map(|doc| doc.score(terms, average_length))
+            .collect();
+        rank_search_results(results)
+    }
+}
+
+/// 索引状态
+#[derive(Default)]
+struct IndexState {
+    index: Option<WikiIndex>,
+    /// 正在进行的重建次数
+    building: usize,
+    /// 重建期间发生变化的路径（重建完成后补充应用，避免新索引遗漏这些变化）
+    pending_changes: HashSet<PathBuf>,
+}
+
+static WIKI_INDEX: Lazy<Mutex<IndexState>> = Lazy::new(|| Mutex::new(IndexState::default()));
+
+/// 索引重建结果
+#[derive(Debug, Serialize)]
+pub struct WikiIndexStats {
+    pub files: usize,    // 已索引的文件数
+    pub grams: usize,    // 索引键数量
+    pub elapsed_ms: u64, // 重建耗时（毫秒）
+}
+
+/// 使用索引搜索；索引尚未建立时返回 None（调用方回退为全量扫描）
+pub fn search(terms: &[Vec<char>]) -> Option<SearchResponse> {
+    let state = lock_or_recover(&WIKI_INDEX, "WikiIndex");
+    state.index.as_ref().map(|index| index.search(terms))
+}
+
+/// 重建索引（读取文件期间不持有锁，搜索继续使用旧索引或全量扫描）；
+/// 目录监听未工作时索引无法保持最新，只返回统计信息、不替换索引
+pub fn rebuild(root: &Path) -> Result<WikiIndexStats, String> {
+    let started = Instant::now();
+    lock_or_recover(&WIKI_INDEX, "WikiIndex").building += 1;
+    let built = WikiIndex::build(root);
+
+    let mut state = lock_or_recover(&WIKI_INDEX, "WikiIndex");
+    state.building -= 1;
+    let mut index = built?;
+    let pending: Vec<PathBuf> = if state.building == 0 {
+        state.pending_changes.drain().collect()
+    } else {
+        state.pending_changes.iter().cloned().collect()
+    };
+    for path in &pending {
+        index.apply_change(path);
+    }
+    let stats = WikiIndexStats {
+        files: index.docs.len(),
+        grams: index.postings.len(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    };
+    // 与 clear 在同一把锁下检查，监听停止后不会再装入索引
+    if !crate::wiki::watcher::watcher_active() {
+        log::info!(
+            "Wiki 目录监听未工作，索引未启用（{} 个文件，耗时 {} ms），搜索将全量扫描",
+            stats.files,
+            stats.elapsed_ms
+        );
+        return Ok(stats);
+    }
+    state.index = Some(index);
+    log::info!(
+        "Wiki 搜索索引已重建: {} 个文件，{} 个索引键，耗时 {} ms",
+        stats.files,
+        stats.grams,
+        stats.elapsed_ms
+    );
+    Ok(stats)
+}
+
+/// 在后台线程重建索引
+pub fn rebuild_in_background(root: PathBuf) {
+    let spawn_result = std::thread::Builder::new()
+        .name("wiki-index".to_string())
+        .spawn(move || {
+            if let Err(e) = rebuild(&root) {
+                log::warn!("建立 Wiki 搜索索引失败，搜索将全量扫描: {}", e);
+            }
+        });
+    if let Err(e) = spawn_result {
+        log::warn!("启动 Wiki 索引线程失败，搜索将全量扫描: {}", e);
+    }
+}
+
+/// 按变化的路径增量更新索引（由目录监听线程调用）
+pub fn apply_changes(paths: impl IntoIterator<Item = PathBuf>) {
+    let mut state = lock_or_recover(&WIKI_INDEX, "WikiIndex");
+    let paths: Vec<PathBuf> = paths.into_iter().collect();
+    if state.building > 0 {
+        state.pending_changes.extend(paths.iter().cloned());
+    }
+    if let Some(index) = state.index.as_mut() {
+        for path in &paths {
+            index.apply_change(path);
+        }
+    }
+}
+
+/// 丢弃索引（目录监听停止后索引无法保持最新，搜索回退为全量扫描）
+pub fn clear() {
+    lock_or_recover(&WIKI_INDEX, "WikiIndex").index = None;
+}
@@ -1,4 +1,8 @@
 // Wiki 模块
 pub mod commands;
+pub mod document;
+pub mod image;
+pub mod index;
 pub mod server;
 pub mod types;
+pub mod watcher;
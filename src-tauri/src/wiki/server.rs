@@ -1,5 +1,6 @@
 use crate::utils::{get_docs_dir, get_wiki_dir};
 use crate::wiki::types::*;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -40,13 +41,13 @@ impl WikiServer {
         &self.wiki_dir
     }
 
-    /// 获取文件列表
+    /// 获取文件列表（目录监听正常时使用缓存，目录变化后重新扫描）
     pub fn list_files(&self) -> Result<Vec<WikiFileInfo>, String> {
-        list_wiki_files(&self.wiki_dir, &self.wiki_dir)
+        crate::wiki::watcher::cached_file_tree(|| list_wiki_files(&self.wiki_dir, &self.wiki_dir))
     }
 
     /// 搜索 Wiki
-    pub fn search(&self, query: &str) -> Result<Vec<SearchResult>, String> {
+    pub fn search(&self, query: &str) -> Result<SearchResponse, String> {
         search_wiki_files(&self.wiki_dir, query)
     }
 }
@@ -80,11 +81,9 @@ pub fn list_wiki_files(root: &Path, current: &Path) -> Result<Vec<WikiFileInfo>,
                 dirs.push(path);
             }
         } else if metadata.is_file() {
-            // 只处理 Markdown 文件
-            if let Some(ext) = path.extension() {
-                if ext == "md" || ext == "markdown" {
-                    md_files.push(path);
-                }
+            // 只处理支持的文档（Markdown、纯文本、PDF、DOCX）
+            if crate::wiki::document::is_wiki_document(&path) {
+                md_files.push(path);
             }
         }
     }
@@ -125,12 +124,19 @@ pub fn list_wiki_files(root: &Path, current: &Path) -> Result<Vec<WikiFileInfo>,
                 .to_string_lossy()
                 .to_string();
 
-            // 从文件内容提取标题
-            let title = extract_title_from_file(&file_path).unwrap_or_else(|| {
-                name.trim_end_matches(".md")
-                    .trim_end_matches(".markdown")
-                    .to_string()
-            });
+            // 从 Markdown 内容提取标题，其他文档使用文件名（不含扩展名）
+            let is_markdown = file_path
+                .extension()
+                .is_some_and(|ext| ext == "md" || ext == "markdown");
+            let title = is_markdown
+                .then(|| extract_title_from_file(&file_path))
+                .flatten()
+                .unwrap_or_else(|| {
+                    file_path
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                        .unwrap_or_else(|| name.to_string())
+                });
 
             files.push(WikiFileInfo {
                 name: name.to_string(),
@@ -165,79 +171,559 @@ fn extract_title_from_file(file_path: &Path) -> Option<String> {
     None
 }
 
-/// 搜索 Wiki 文件
-pub fn search_wiki_files(root: &Path, query: &str) -> Result<Vec<SearchResult>, String> {
-    let query_lower = query.to_lowercase();
-    let mut results = Vec::new();
-
-    fn search_recursive(
-        root: &Path,
-        current: &Path,
-        query: &str,
-        results: &mut Vec<SearchResult>,
-    ) -> Result<(), String> {
-        if !current.exists() {
-            return Ok(());
-        }
-
-        let entries = fs::read_dir(current).map_err(|e| format!("读取目录失败: {}", e))?;
-
-        for entry in entries {
-            let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
-            let path = entry.path();
-            let metadata = entry
-                .metadata()
-                .map_err(|e| format!("获取文件元数据失败: {}", e))?;
-
-            if metadata.is_dir() {
-                // 递归搜索子目录
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    if !name.starts_with('.') && name != "node_modules" && name != "target" {
-                        search_recursive(root, &path, query, results)?;
-                    }
+/// 搜索最多返回的结果数
+const MAX_SEARCH_RESULTS: usize = 50;
+
+/// 匹配片段中命中位置前后保留的字符数
+const SNIPPET_CONTEXT_CHARS: usize = 60;
+
+/// 标题命中加分
+const TITLE_MATCH_BONUS: f64 = 5.0;
+
+/// 文件名命中加分
+const FILE_NAME_MATCH_BONUS: f64 = 3.0;
+
+/// 章节标题（#、## 等）命中加分（每个命中的标题行）
+const HEADING_MATCH_BONUS: f64 = 2.0;
+
+/// BM25 词频饱和参数
+const BM25_K1: f64 = 1.2;
+
+/// BM25 文档长度归一化强度（0 不归一化，1 完全按长度归一化）
+const BM25_B: f64 = 0.5;
+
+/// 归一化字符用于匹配：转小写并去除常见拉丁字母的重音符号
+/// 保持一对一映射，便于根据匹配位置截取原文片段
+fn fold_char(c: char) -> char {
+    let lower = c.to_lowercase().next().unwrap_or(c);
+    match lower {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+        'ď' | 'đ' => 'd',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+        'ĥ' | 'ħ' => 'h',
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => 'i',
+        'ĵ' => 'j',
+        'ķ' => 'k',
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => 'l',
+        'ñ' | 'ń' | 'ņ' | 'ň' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => 'o',
+        'ŕ' | 'ŗ' | 'ř' => 'r',
+        'ś' | 'ŝ' | 'ş' | 'š' => 's',
+        'ţ' | 'ť' | 'ŧ' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+        'ŵ' => 'w',
+        'ý' | 'ÿ' | 'ŷ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        other => other,
+    }
+}
+
+/// 归一化文本（大小写、重音不敏感）
+pub(crate) fn fold_text(text: &str) -> Vec<char> {
+    text.chars().map(fold_char).collect()
+}
+
+/// 查找所有不重叠的出现位置（字符下标）
+fn find_all(haystack: &[char], needle: &[char]) -> Vec<usize> {
+    let mut positions = Vec::new();
+    if needle.is_empty() {
+        return positions;
+    }
+    let mut i = 0;
+    while i + needle.len() <= haystack.len() {
+        if haystack[i..i + needle.len()] == *needle {
+            positions.push(i);
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+    positions
+}
+
+fn contains_term(haystack: &[char], needle: &[char]) -> bool {
+    !needle.is_empty() && haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+/// 生成匹配片段：以第一个命中位置为中心截取上下文
+/// 片段已做 HTML 转义，命中词以 <mark></mark> 包裹，前端可直接渲染并设置样式
+fn build_snippet(original: &[char], folded: &[char], terms: &[Vec<char>]) -> String {
+    let Some(first) = terms
+        .iter()
+        .filter_map(|term| find_all(folded, term).first().copied())
+        .min()
+    else {
+        return String::new();
+    };
+    let start = first.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let end = (first + SNIPPET_CONTEXT_CHARS * 2).min(original.len());
+
+    let mut marked = vec![false; end - start];
+    for term in terms {
+        for pos in find_all(&folded[start..end], term) {
+            marked[pos..pos + term.len()].fill(true);
+        }
+    }
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    let mut in_mark = false;
+    for (&c, &is_marked) in original[start..end].iter().zip(&marked) {
+        if is_marked != in_mark {
+            snippet.push_str(if is_marked { "<mark>" } else { "</mark>" });
+            in_mark = is_marked;
+        }
+        match c {
+            '\r' | '\n' | '\t' => snippet.push(' '),
+            '<' => snippet.push_str("&lt;"),
+            '>' => snippet.push_str("&gt;"),
+            '&' => snippet.push_str("&amp;"),
+            '"' => snippet.push_str("&quot;"),
+            c => snippet.push(c),
+        }
+    }
+    if in_mark {
+        snippet.push_str("</mark>");
+    }
+    if end < original.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// 搜索时跳过的目录（隐藏目录和特殊目录）
+fn is_skipped_dir(name: &str) -> bool {
+    name.starts_with('.') || name == "node_modules" || name == "target"
+}
+
+/// 是否为参与搜索的 Markdown 文件（扩展名为 md / markdown，且不在跳过的目录中）
+pub(crate) fn is_searchable_file(root: &Path, path: &Path) -> bool {
+    let is_markdown = path
+        .extension()
+        .is_some_and(|ext| ext == "md" || ext == "markdown");
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+    is_markdown
+        && relative
+            .parent()
+            .into_iter()
+            .flat_map(Path::components)
+            .all(|part| !is_skipped_dir(&part.as_os_str().to_string_lossy()))
+}
+
+/// 递归收集 Markdown 文件（跳过隐藏目录和特殊目录）
+pub(crate) fn collect_markdown_files(
+    current: &Path,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    if !current.exists() {
+        return Ok(());
+    }
+
+    let entries = fs::read_dir(current).map_err(|e| format!("读取目录失败: {}", e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+        let path = entry.path();
+        let metadata = entry
+            .metadata()
+            .map_err(|e| format!("获取文件元数据失败: {}", e))?;
+
+        if metadata.is_dir() {
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                if !is_skipped_dir(name) {
+                    collect_markdown_files(&path, files)?;
+                }
+            }
+        } else if metadata.is_file() {
+            if let Some(ext) = path.extension() {
+                if ext == "md" || ext == "markdown" {
+                    files.push(path);
                 }
-            } else if metadata.is_file() {
-                // 搜索 Markdown 文件
-                if let Some(ext) = path.extension() {
-                    if ext == "md" || ext == "markdown" {
-                        if let Ok(content) = fs::read_to_string(&path) {
-                            let content_lower = content.to_lowercase();
-
-                            // 检查文件名或内容是否包含查询
-                            let file_name = path
-                                .file_name()
-                                .and_then(|n| n.to_str())
-                                .unwrap_or("")
-                                .to_lowercase();
-
-                            if file_name.contains(query) || content_lower.contains(query) {
-                                let relative_path = path
-                                    .strip_prefix(root)
-                                    .unwrap_or(&path)
-                                    .to_string_lossy()
-                                    .to_string();
-
-                                let title = extract_title_from_file(&path).unwrap_or_else(|| {
-                                    file_name
-                                        .trim_end_matches(".md")
-                                        .trim_end_matches(".markdown")
-                                        .to_string()
-                                });
-
-                                results.push(SearchResult {
-                                    file_path: relative_path,
-                                    title,
-                                });
-                            }
-                        }
-                    }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 工具 Wiki 匹配最多返回的候选数
+const MAX_TOOL_WIKI_MATCHES: usize = 10;
+
+/// 工具 Wiki 候选的最低得分
+const MIN_TOOL_WIKI_SCORE: f64 = 0.5;
+
+/// 文件名中不参与匹配的通用词（如 nmap-guide.md 视为 nmap）
+const WIKI_NAME_STOPWORDS: &[&str] = &[
+    "guide", "wiki", "readme", "doc", "docs", "manual", "usage", "notes", "tutorial", "使用",
+    "教程", "手册", "指南", "笔记",
+];
+
+/// 拆分为归一化的词（按非字母数字字符分隔，去掉通用词）
+fn name_tokens(text: &str) -> Vec<String> {
+    text.chars()
+        .map(fold_char)
+        .collect::<String>()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty() && !WIKI_NAME_STOPWORDS.contains(token))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 编辑距离相似度（0-1）
+fn levenshtein_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            current[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+    1.0 - prev[b.len()] as f64 / longest as f64
+}
+
+/// 查询词与候选名称的相似度（0-1）：
+/// 归一化后完全一致为 1.0；去掉通用词后一致为 0.95；
+/// 否则取词重合度（近似词按编辑距离相似度 >= 0.8 计为重合）、包含关系与整体编辑距离相似度中的较高者
+fn name_similarity(query: &str, candidate: &str) -> f64 {
+    let query_tokens = name_tokens(query);
+    let candidate_tokens = name_tokens(candidate);
+    if query_tokens.is_empty() || candidate_tokens.is_empty() {
+        return 0.0;
+    }
+    if fold_text(query.trim()) == fold_text(candidate.trim()) {
+        return 1.0;
+    }
+    let query_joined = query_tokens.concat();
+    let candidate_joined = candidate_tokens.concat();
+    if query_joined == candidate_joined {
+        return 0.95;
+    }
+
+    let matched = query_tokens
+        .iter()
+        .filter(|q| {
+            candidate_tokens
+                .iter()
+                .any(|c| c == *q || levenshtein_similarity(q, c) >= 0.8)
+        })
+        .count();
+    let overlap = matched as f64 / query_tokens.len().max(candidate_tokens.len()) as f64 * 0.9;
+    let contains = if query_joined.chars().count() >= 3 && candidate_joined.contains(&query_joined)
+    {
+        0.8
+    } else {
+        0.0
+    };
+    let edit = levenshtein_similarity(&query_joined, &candidate_joined) * 0.85;
+    overlap.max(contains).max(edit)
+}
+
+/// 为工具排序候选 Wiki 文件：分别按文件名、标题、路径与工具 ID / 名称比较，取最高分
+/// （标题、路径略微降权），返回得分不低于 MIN_TOOL_WIKI_SCORE 的前 MAX_TOOL_WIKI_MATCHES 个
+pub fn rank_tool_wiki_matches(files: &[WikiFileInfo], queries: &[&str]) -> Vec<WikiMatch> {
+    fn collect<'a>(files: &'a [WikiFileInfo], out: &mut Vec<&'a WikiFileInfo>) {
+        for file in files {
+            if file.is_dir {
+                if let Some(children) = &file.children {
+                    collect(children, out);
                 }
+            } else {
+                out.push(file);
+            }
+        }
+    }
+    let mut all_files = Vec::new();
+    collect(files, &mut all_files);
+
+    let queries: Vec<&str> = queries
+        .iter()
+        .map(|q| q.trim())
+        .filter(|q| !q.is_empty())
+        .collect();
+    let mut matches: Vec<WikiMatch> = all_files
+        .into_iter()
+        .filter_map(|file| {
+            let stem = Path::new(&file.name)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| file.name.clone());
+            let path_stem = Path::new(&file.path)
+                .with_extension("")
+                .to_string_lossy()
+                .to_string();
+            let score = queries
+                .iter()
+                .map(|query| {
+                    name_similarity(query, &stem)
+                        .max(name_similarity(query, &file.title) * 0.95)
+                        .max(name_similarity(query, &path_stem) * 0.9)
+                })
+                .fold(0.0, f64::max);
+            (score >= MIN_TOOL_WIKI_SCORE).then(|| WikiMatch {
+                path: file.path.clone(),
+                title: file.title.clone(),
+                score: (score * 1000.0).round() / 1000.0,
+            })
+        })
+        .collect();
+    matches.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    matches.truncate(MAX_TOOL_WIKI_MATCHES);
+    matches
+}
+
+/// 参与搜索的 Markdown 文档（全量扫描时临时读取，建立索引后常驻内存）
+pub(crate) struct SearchDocument {
+    pub relative_path: String,
+    pub title: String,
+    pub file_name: String,
+    pub content: String,
+    /// 正文字符数（用于文档长度归一化）
+    pub length: usize,
+}
+
+impl SearchDocument {
+    /// 读取文档；读取失败（如非 UTF-8 文本）时返回 None
+    pub fn load(root: &Path, path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string();
+        let title = extract_title_from_file(path).unwrap_or_else(|| {
+            file_name
+                .trim_end_matches(".md")
+                .trim_end_matches(".markdown")
+                .to_string()
+        });
+        let relative_path = path
+            .strip_prefix(root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string();
+        Some(Self {
+            relative_path,
+            title,
+            file_name,
+            length: content.chars().count(),
+            content,
+        })
+    }
+
+    /// 按搜索词校验并打分；任一搜索词未出现在正文、标题或文件名中时返回 None
+    /// 词频按 BM25 做长度归一化：长文件不会仅因篇幅长而累积高分，b 取较小值，
+    /// 避免短文件（如只有几行的笔记）仅因篇幅短排到内容更相关的长文件前面
+    pub fn score(&self, terms: &[Vec<char>], average_length: f64) -> Option<SearchResult> {
+        let original: Vec<char> = self.content.chars().collect();
+        let folded: Vec<char> = original.iter().copied().map(fold_char).collect();
+        let folded_title = fold_text(&self.title);
+        let folded_name = fold_text(&self.file_name);
+        let folded_headings: Vec<Vec<char>> = self
+            .content
+            .lines()
+            .filter_map(parse_heading)
+            .map(|(_, text)| fold_text(text))
+            .collect();
+        let length_norm = 1.0 - BM25_B + BM25_B * self.length as f64 / average_length.max(1.0);
+
+        let mut score = 0.0;
+        for term in terms {
+            let tf = find_all(&folded, term).len();
+            let in_title = contains_term(&folded_title, term);
+            let in_name = contains_term(&folded_name, term);
+            if tf == 0 && !in_title && !in_name {
+                return None;
+            }
+            if tf > 0 {
+                let tf = tf as f64;
+                score += tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * length_norm);
+            }
+            if in_title {
+                score += TITLE_MATCH_BONUS;
+            }
+            if in_name {
+                score += FILE_NAME_MATCH_BONUS;
+            }
+            let heading_hits = folded_headings
+                .iter()
+                .filter(|heading| contains_term(heading, term))
+                .count();
+            score += HEADING_MATCH_BONUS * heading_hits as f64;
+        }
+
+        Some(SearchResult {
+            file_path: self.relative_path.clone(),
+            title: self.title.clone(),
+            score,
+            snippet: build_snippet(&original, &folded, terms),
+        })
+    }
+}
+
+/// 解析搜索词：按空白分隔、归一化并去重
+pub(crate) fn parse_search_terms(query: &str) -> Vec<Vec<char>> {
+    let mut terms: Vec<Vec<char>> = Vec::new();
+    for term in query.split_whitespace().map(fold_text) {
+        if !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms
+}
+
+/// 按得分排序并截取前 MAX_SEARCH_RESULTS 条
+pub(crate) fn rank_search_results(mut results: Vec<SearchResult>) -> SearchResponse {
+    results.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    let total = results.len();
+    results.truncate(MAX_SEARCH_RESULTS);
+    SearchResponse { total, results }
+}
+
+/// 搜索 Wiki 文件
+/// 多个关键词（空白分隔）需全部出现（AND），匹配不区分大小写和重音；
+/// 按词频打分，标题、文件名、章节标题命中额外加分，最多返回 MAX_SEARCH_RESULTS 条
+/// 索引已建立时使用索引筛选候选文档，否则全量扫描
+pub fn search_wiki_files(root: &Path, query: &str) -> Result<SearchResponse, String> {
+    let terms = parse_search_terms(query);
+    if terms.is_empty() {
+        return Ok(rank_search_results(Vec::new()));
+    }
+    if let Some(response) = crate::wiki::index::search(&terms) {
+        return Ok(response);
+    }
+
+    let mut files = Vec::new();
+    collect_markdown_files(root, &mut files)?;
+    let documents: Vec<SearchDocument> = files
+        .iter()
+        .filter_map(|path| SearchDocument::load(root, path))
+        .collect();
+    let average_length = if documents.is_empty() {
+        0.0
+    } else {
+        documents.iter().map(|doc| doc.length).sum::<usize>() as f64 / documents.len() as f64
+    };
+
+    let results = documents
+        .iter()
+        .filter_map(|doc| doc.score(&terms, average_length))
+        .collect();
+    Ok(rank_search_results(results))
+}
+
+/// 将标题文本转换为锚点（GitHub 风格：小写、空格转 -、去除标点，保留中文等字符）
+pub fn slugify_heading(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                Some(c)
+            } else if c.is_whitespace() {
+                Some('-')
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// 解析 ATX 标题行，返回（级别, 标题文本）
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let trimmed = line.trim_start();
+    // 缩进 4 个空格以上为代码块
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &trimmed[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') && !rest.starts_with('\t') {
+        return None;
+    }
+    // 去除可选的结尾 #
+    let text = rest.trim().trim_end_matches('#').trim_end();
+    Some((level, text))
+}
+
+/// 提取 Markdown 中指定锚点对应的章节（含标题行，直到下一个同级或更高级标题）
+pub fn extract_markdown_section(content: &str, anchor: &str) -> Result<String, String> {
+    let anchor = anchor.trim().trim_start_matches('#');
+    if anchor.is_empty() {
+        return Err("锚点不能为空".to_string());
+    }
+    // 兼容直接传入标题原文
+    let target = slugify_heading(anchor);
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut in_fence = false;
+    let mut slug_counts: HashMap<String, usize> = HashMap::new();
+    let mut section: Option<(usize, usize)> = None; // (起始行, 标题级别)
+    let mut end = lines.len();
+
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let Some((level, text)) = parse_heading(line) else {
+            continue;
+        };
+
+        if let Some((_, section_level)) = section {
+            if level <= section_level {
+                end = i;
+                break;
             }
+            continue;
         }
 
-        Ok(())
+        // 重复标题按 GitHub 规则追加 -1、-2 后缀
+        let base = slugify_heading(text);
+        let count = slug_counts.entry(base.clone()).or_insert(0);
+        let slug = if *count == 0 {
+            base
+        } else {
+            format!("{}-{}", base, count)
+        };
+        *count += 1;
+
+        if slug == target {
+            section = Some((i, level));
+        }
     }
 
-    search_recursive(root, root, &query_lower, &mut results)?;
-    Ok(results)
+    match section {
+        Some((start, _)) => Ok(lines[start..end].join("\n")),
+        None => Err(format!("未找到锚点: #{}", anchor)),
+    }
 }
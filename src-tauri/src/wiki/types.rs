@@ -15,4 +15,48 @@ pub struct WikiFileInfo {
 pub struct SearchResult {
     pub file_path: String, // 文件路径
     pub title: String,     // 文件标题
+    pub score: f64,        // 相关度得分（越高越相关）
+    pub snippet: String,   // 匹配片段（已 HTML 转义，命中词以 <mark></mark> 包裹）
+}
+
+/// 搜索响应
+#[derive(Debug, Serialize)]
+pub struct SearchResponse {
+    pub total: usize,               // 匹配的文件总数（不受返回数量上限影响）
+    pub results: Vec<SearchResult>, // 按相关度排序的结果
+}
+
+/// Wiki 文档内容类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WikiContentType {
+    Markdown,
+    /// 纯文本（.txt 等）
+    Text,
+    /// 主题样式表
+    Css,
+    /// 从 PDF 中提取的纯文本
+    Pdf,
+    /// 从 DOCX 中提取的纯文本
+    Docx,
+    /// 不支持预览（二进制文件或无法提取文本），需下载后查看
+    Unsupported,
+}
+
+/// Wiki 文档读取结果
+#[derive(Debug, Serialize)]
+pub struct WikiDocument {
+    pub content: String,               // 文本内容（不支持预览时为空）
+    pub content_type: WikiContentType, // 内容类型，前端据此选择渲染方式
+    pub file_name: String,             // 文件名
+    pub size: u64,                     // 文件大小（字节）
+    pub message: Option<String>,       // 不支持预览的原因
+}
+
+/// 工具对应的 Wiki 候选文件
+#[derive(Debug, Serialize)]
+pub struct WikiMatch {
+    pub path: String,  // 文件路径
+    pub title: String, // 文件标题
+    pub score: f64,    // 匹配得分（0-1，1 表示文件名与工具 ID / 名称完全一致）
 }
@@ -0,0 +1,122 @@
+// Wiki 目录监听：缓存文件树，目录变化时使缓存失效、增量更新搜索索引并通知前端
+use crate::utils::lock_or_recover;
+use crate::wiki::types::WikiFileInfo;
+use notify::{EventKind, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// 文件变化事件名（前端收到后重新获取文件树）
+pub const WIKI_FILES_CHANGED_EVENT: &str = "wiki-files-changed";
+
+/// 连续的文件变化在该间隔内合并为一次通知（如批量复制、编辑器保存时的多次写入）
+const CHANGE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 文件树缓存（None 表示需要重新扫描）
+static FILE_TREE_CACHE: Lazy<Mutex<Option<Vec<WikiFileInfo>>>> = Lazy::new(|| Mutex::new(None));
+
+/// 监听线程是否已启动（保证只启动一次）
+static WATCHER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 监听是否正常工作；未工作时不使用缓存，避免外部变化后文件树一直过期
+static WATCHER_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// 目录监听是否正常工作（索引只在监听正常时常驻，否则无法保持最新）
+pub fn watcher_active() -> bool {
+    WATCHER_ACTIVE.load(Ordering::SeqCst)
+}
+
+/// 获取文件树：监听正常时使用缓存，缓存失效时调用 build 重新扫描
+pub fn cached_file_tree(
+    build: impl FnOnce() -> Result<Vec<WikiFileInfo>, String>,
+) -> Result<Vec<WikiFileInfo>, String> {
+    if !WATCHER_ACTIVE.load(Ordering::SeqCst) {
+        return build();
+    }
+
+    // 扫描期间持有锁，避免并发请求重复扫描；监听线程的失效操作会等待扫描完成
+    let mut cache = lock_or_recover(&FILE_TREE_CACHE, "WikiFileTreeCache");
+    if let Some(files) = cache.as_ref() {
+        return Ok(files.clone());
+    }
+    let files = build()?;
+    *cache = Some(files.clone());
+    Ok(files)
+}
+
+/// 使文件树缓存失效（下次获取时重新扫描）
+pub fn invalidate_file_tree_cache() {
+    *lock_or_recover(&FILE_TREE_CACHE, "WikiFileTreeCache") = None;
+}
+
+/// 启动 Wiki 目录监听线程（重复调用时忽略）
+pub fn start_wiki_watcher(app: AppHandle, wiki_dir: PathBuf) {
+    if WATCHER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let spawn_result = std::thread::Builder::new()
+        .name("wiki-watcher".to_string())
+        .spawn(move || {
+            let (tx, rx) = std::sync::mpsc::channel();
+            let mut watcher = match notify::recommended_watcher(tx) {
+                Ok(watcher) => watcher,
+                Err(e) => {
+                    log::warn!("创建 Wiki 目录监听失败，文件列表将每次重新扫描: {}", e);
+                    return;
+                }
+            };
+            if let Err(e) = watcher.watch(&wiki_dir, RecursiveMode::Recursive) {
+                log::warn!(
+                    "监听 Wiki 目录失败，文件列表将每次重新扫描: {} ({})",
+                    wiki_dir.display(),
+                    e
+                );
+                return;
+            }
+            invalidate_file_tree_cache();
+            WATCHER_ACTIVE.store(true, Ordering::SeqCst);
+            log::info!("Wiki 目录监听已启动: {}", wiki_dir.display());
+            // 监听建立后再建立索引，建立期间的变化会在完成后补充应用
+            crate::wiki::index::rebuild_in_background(wiki_dir.clone());
+
+            while let Ok(result) = rx.recv() {
+                let mut changed_paths = HashSet::new();
+                match result {
+                    // 仅读取文件不影响文件树
+                    Ok(event) if matches!(event.kind, EventKind::Access(_)) => continue,
+                    Ok(event) => changed_paths.extend(event.paths),
+                    Err(e) => log::debug!("Wiki 目录监听错误: {}", e),
+                }
+                while let Ok(result) = rx.recv_timeout(CHANGE_DEBOUNCE) {
+                    if let Ok(event) = result {
+                        if !matches!(event.kind, EventKind::Access(_)) {
+                            changed_paths.extend(event.paths);
+                        }
+                    }
+                }
+
+                invalidate_file_tree_cache();
+                crate::wiki::index::apply_changes(changed_paths);
+                log::debug!("Wiki 目录发生变化，文件列表缓存已失效");
+                if let Err(e) = app.emit(WIKI_FILES_CHANGED_EVENT, ()) {
+                    log::warn!("发送 {} 事件失败: {}", WIKI_FILES_CHANGED_EVENT, e);
+                }
+            }
+
+            // 通道关闭说明监听已停止，回退为每次重新扫描
+            WATCHER_ACTIVE.store(false, Ordering::SeqCst);
+            invalidate_file_tree_cache();
+            crate::wiki::index::clear();
+            log::warn!("Wiki 目录监听已停止");
+        });
+
+    if let Err(e) = spawn_result {
+        log::warn!("启动 Wiki 目录监听线程失败: {}", e);
+        WATCHER_STARTED.store(false, Ordering::SeqCst);
+    }
+}
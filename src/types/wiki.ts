@@ -10,6 +10,19 @@ export interface WikiFileInfo {
   children?: WikiFileInfo[]
 }
 
+/** Wiki 文档内容类型（pdf / docx 为提取的纯文本，unsupported 需下载后查看） */
+export type WikiContentType = 'markdown' | 'text' | 'css' | 'pdf' | 'docx' | 'unsupported'
+
+/** read_wiki_file 返回的文档 */
+export interface WikiDocument {
+  content: string
+  content_type: WikiContentType
+  file_name: string
+  size: number
+  /** 不支持预览的原因 */
+  message?: string | null
+}
+
 export interface RenderResult {
   html: string
   title: string
@@ -26,9 +39,27 @@ export interface TocItem {
 export interface SearchResult {
   file_path: string
   title: string
+  /** 相关度得分（越高越相关） */
+  score?: number
+  /** 匹配片段（已 HTML 转义，命中词以 <mark></mark> 包裹） */
+  snippet?: string
   matches?: SearchMatch[]
 }
 
+export interface SearchResponse {
+  /** 匹配的文件总数（不受返回数量上限影响） */
+  total: number
+  results: SearchResult[]
+}
+
+/** find_wiki_for_tool 返回的候选 Wiki 文件（按得分降序） */
+export interface WikiMatch {
+  path: string
+  title: string
+  /** 匹配得分（0-1，1 表示文件名与工具 ID / 名称完全一致） */
+  score: number
+}
+
 export interface SearchMatch {
   line: number
   text: string
@@ -9,6 +9,32 @@ import { debug, warn, info } from './logger'
 // 所有请求通过 Rust 后端的连接池转发
 const USE_POOL = true // 是否使用连接池模式
 
+/**
+ * 连接池命令返回的结构化错误（GatewayError）
+ */
+export interface GatewayError {
+  code: string
+  message: string
+  /** 请求追踪 ID（与后端日志中的 [trace ...] 对应），非转发请求的错误为 null */
+  trace_id?: string | null
+}
+
+/**
+ * 提取错误信息（支持 Error、连接池返回的 { code, message, trace_id } 与字符串）
+ * 带追踪 ID 的错误在信息末尾附加 trace，便于与日志对应
+ */
+function errorMessage(error: unknown): string {
+  if (error instanceof Error) {
+    return error.message
+  }
+  if (typeof error === 'object' && error !== null && 'message' in error) {
+    const { message, trace_id: traceId } = error as GatewayError
+    const text = String(message)
+    return traceId && !text.includes(traceId) ? `${text}（trace: ${traceId}）` : text
+  }
+  return String(error)
+}
+
 /**
  * OpenAI 消息格式
  */
@@ -65,17 +91,34 @@ export interface OpenAIErrorResponse {
   }
 }
 
+/**
+ * forward_ai_request 的返回结果
+ */
+interface ForwardResponse {
+  status: number
+  content_type: string | null
+  body: number[]
+  trace_id: string
+}
+
 // 健康检查请求计数器（用于追踪）
 let healthCheckCounter = 0
 
 /**
  * 通过连接池转发 HTTP 请求
+ * @param clientId 粘性会话标识（同一会话优先路由到同一 Worker）
+ * @param queueWaitMs 没有空闲 Worker 时排队等待的最长时间（毫秒），不传则快速失败；超时返回 429
+ * @param idempotencyKey 幂等键（重试同一请求时复用，已完成的请求直接返回缓存的响应；用于不同请求时返回 422，仍在处理中时返回 409）
  */
 async function forwardRequest(
   method: string,
   path: string,
   body?: string,
-  headers?: Record<string, string>
+  headers?: Record<string, string>,
+  clientId?: string,
+  queueWaitMs?: number,
+  idempotencyKey?: string,
+  compress?: boolean
 ): Promise<Response> {
   const invoker = getTauriInvoke()
   if (!invoker) {
@@ -87,33 +130,42 @@ async function forwardRequest(
     const headersArray = headers ? Object.entries(headers).map(([k, v]) => [k, v]) : undefined
     const bodyBytes = body ? new TextEncoder().encode(body) : undefined
 
-    const result = await invoker('forward_ai_request', {
+    const result = await invoker<ForwardResponse>('forward_ai_request', {
       method,
       path,
       body: bodyBytes,
       headers: headersArray,
-    }) as [number, number[]] // [status_code, body_bytes]
+      clientId,
+      queueWaitMs,
+      idempotencyKey,
+      compress,
+    })
 
-    const [status, bodyBytesArray] = result
+    const { status, content_type: contentType, body: bodyBytesArray, trace_id: traceId } = result
     const responseBody = new Uint8Array(bodyBytesArray)
 
-    // 构建 Response 对象
+    // 构建 Response 对象（保留上游 Content-Type，便于区分 JSON 与 HTML 错误页；
+    // X-Trace-Id 对应后端日志中的 [trace ...]）
     const response = new Response(responseBody, {
       status,
       headers: {
-        'Content-Type': 'application/json',
+        'Content-Type': contentType || 'application/json',
+        'X-Trace-Id': traceId,
       },
     })
 
     return response
   } catch (error) {
-    debug('连接池转发请求失败，降级到直接访问:', error)
-    // 如果连接池不可用，降级到直接访问（向后兼容）
+    const poolError = errorMessage(error)
+    debug(`连接池转发请求失败，降级到直接访问: ${poolError}`)
+    // 如果连接池不可用，降级到直接访问（向后兼容）；直接访问也失败时保留连接池错误（含 trace）
     const GATEWAY_BASE_URL = 'http://127.0.0.1:8765'
     return fetch(`${GATEWAY_BASE_URL}${path}`, {
       method,
       headers,
       body,
+    }).catch((fetchError: unknown) => {
+      throw new Error(`${poolError}（直接访问也失败: ${errorMessage(fetchError)}）`)
     })
   }
 }
@@ -221,7 +273,7 @@ export async function checkAIServiceHealth(delayMs: number = 100): Promise<boole
     return true
   } catch (error) {
     const totalElapsed = Date.now() - checkStartTime
-    warn(`[健康检查-${checkId}] ❌ 健康检查异常 (总耗时: ${totalElapsed}ms): ${error instanceof Error ? error.message : String(error)}`)
+    warn(`[健康检查-${checkId}] ❌ 健康检查异常 (总耗时: ${totalElapsed}ms): ${errorMessage(error)}`)
     if (error instanceof Error && error.stack) {
       debug(`[健康检查-${checkId}] 异常堆栈: ${error.stack}`)
     }
@@ -263,7 +315,7 @@ export async function reloadAIConfig(): Promise<void> {
     debug('AI Gateway 配置已重新加载')
   } catch (error) {
     // 只记录非 404 错误，404 错误由调用方静默处理
-    const errorMsg = error instanceof Error ? error.message : String(error)
+    const errorMsg = errorMessage(error)
     if (!errorMsg.includes('404') && !errorMsg.includes('Not Found')) {
       warn('重新加载 AI Gateway 配置失败:', error)
     }
@@ -337,19 +389,24 @@ export async function sendAIChat(
     temperature?: number
     max_tokens?: number
     stream?: boolean
+    /** 粘性会话标识（多轮对话传入同一值） */
+    clientId?: string
+    /** 所有 Worker 繁忙时排队等待的最长时间（毫秒），不传则快速失败 */
+    queueWaitMs?: number
   }
 ): Promise<OpenAIChatResponse> {
+  const { clientId, queueWaitMs, ...requestOptions } = options ?? {}
   const request: OpenAIChatRequest = {
     model,
     messages,
     stream: false,
-    ...options,
+    ...requestOptions,
   }
   
   const response = USE_POOL
     ? await forwardRequest('POST', '/v1/chat/completions', JSON.stringify(request), {
         'Content-Type': 'application/json',
-      })
+      }, clientId, queueWaitMs)
     : await fetch('http://127.0.0.1:8765/v1/chat/completions', {
         method: 'POST',
         headers: {
@@ -361,13 +418,27 @@ export async function sendAIChat(
       })
   
   if (!response.ok) {
-    const errorData: OpenAIErrorResponse = await response.json()
-    throw new Error(errorData.error?.message || `HTTP error! status: ${response.status}`)
+    throw new Error(await responseErrorMessage(response))
   }
   
   return response.json()
 }
 
+/**
+ * 从错误响应中提取错误信息：JSON 响应读取 error.message，
+ * 其他类型（如 Worker 不可用时上游返回的 HTML 502 页面）只显示状态码与文本摘要
+ */
+async function responseErrorMessage(response: Response): Promise<string> {
+  const fallback = `HTTP error! status: ${response.status}`
+  const contentType = response.headers.get('Content-Type') || ''
+  if (contentType.includes('json')) {
+    const errorData: OpenAIErrorResponse = await response.json().catch(() => ({}))
+    return errorData.error?.message || fallback
+  }
+  const text = (await response.text().catch(() => '')).replace(/<[^>]*>/g, ' ').replace(/\s+/g, ' ').trim()
+  return text ? `${fallback}（${text.slice(0, 200)}）` : fallback
+}
+
 /**
  * 发送 AI 聊天请求（流式 SSE）
  * @param model 模型 ID
@@ -502,19 +573,34 @@ export async function sendAIChatStream(
 }
 
 /**
- * 初始化 Gateway 连接池
+ * 单个 Worker 的启动结果（init_gateway_pool 返回）
+ */
+export interface WorkerStartResult {
+  worker_id: number
+  /** 实际使用的端口 */
+  port: number
+  ok: boolean
+  message: string
+  /** 启动后的状态，如 Starting / Ready / Dead / FailedPermanent */
+  state: string
+}
+
+/**
+ * 初始化 Gateway 连接池，返回各 Worker 的启动结果
  */
-export async function initGatewayPool(): Promise<void> {
+export async function initGatewayPool(): Promise<WorkerStartResult[]> {
   const invoker = getTauriInvoke()
   if (!invoker) {
     throw new Error('Tauri API 不可用，无法初始化连接池')
   }
   
   try {
-    await invoker('init_gateway_pool')
-    info('[连接池] 连接池已初始化')
+    const results = await invoker<WorkerStartResult[]>('init_gateway_pool')
+    const failed = results.filter(r => !r.ok)
+    info(`[连接池] 连接池已初始化（${results.length - failed.length}/${results.length} 个 Worker 启动成功）`)
+    return results
   } catch (error) {
-    throw new Error(`初始化连接池失败: ${error instanceof Error ? error.message : String(error)}`)
+    throw new Error(`初始化连接池失败: ${errorMessage(error)}`)
   }
 }
 
@@ -524,7 +610,7 @@ export async function initGatewayPool(): Promise<void> {
 export async function startAIService(): Promise<void> {
   if (USE_POOL) {
     // 使用连接池模式
-    return initGatewayPool()
+    await initGatewayPool()
   } else {
     // 旧版单进程模式
     const invoker = getTauriInvoke()
@@ -535,7 +621,7 @@ export async function startAIService(): Promise<void> {
     try {
       await invoker('start_ai_service')
     } catch (error) {
-      throw new Error(`启动 AI Gateway 服务失败: ${error instanceof Error ? error.message : String(error)}`)
+      throw new Error(`启动 AI Gateway 服务失败: ${errorMessage(error)}`)
     }
   }
 }
@@ -559,7 +645,7 @@ export async function stopAIService(): Promise<void> {
     }
     await invoker('stop_ai_service')
   } catch (error) {
-    throw new Error(`停止 AI Gateway 服务失败: ${error instanceof Error ? error.message : String(error)}`)
+    throw new Error(`停止 AI Gateway 服务失败: ${errorMessage(error)}`)
   }
 }
 
@@ -583,7 +669,7 @@ export async function restartAIService(): Promise<void> {
       debug('[服务恢复] 服务已停止')
       await new Promise(resolve => setTimeout(resolve, 500))
     } catch (error) {
-      warn(`[服务恢复] 停止服务失败（可能服务未运行）: ${error instanceof Error ? error.message : String(error)}`)
+      warn(`[服务恢复] 停止服务失败（可能服务未运行）: ${errorMessage(error)}`)
     }
     
     // 然后启动服务
@@ -610,7 +696,7 @@ export async function restartAIService(): Promise<void> {
     
     warn('[服务恢复] ⚠️ 服务重启后仍未就绪，可能需要更多时间')
   } catch (error) {
-    throw new Error(`重启 AI Gateway 服务失败: ${error instanceof Error ? error.message : String(error)}`)
+    throw new Error(`重启 AI Gateway 服务失败: ${errorMessage(error)}`)
   }
 }
 
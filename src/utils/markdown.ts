@@ -171,8 +171,8 @@ export async function renderMarkdown(markdownText: string, basePath?: string): P
       
       // 处理相对路径的图片
       html = html.replace(/<img([^>]*?)src="([^"]+)"([^>]*?)>/g, (match: string, before: string, src: string, after: string) => {
-        // 如果是相对路径且不是 data: 或 http(s):// 开头
-        if (!src.startsWith('data:') && !src.startsWith('http://') && !src.startsWith('https://') && !src.startsWith('/')) {
+        // 如果是相对路径且不是 data: / wiki-image:（由 read_wiki_file 改写）或 http(s):// 开头
+        if (!src.startsWith('data:') && !src.startsWith('wiki-image:') && !src.startsWith('http://') && !src.startsWith('https://') && !src.startsWith('/')) {
           // 使用 URL API 解析相对路径
           try {
             const resolvedUrl = new URL(src.replace(/^\.\//, ''), window.location.origin + baseDir)
@@ -4,7 +4,7 @@
  * 前端通过事件推送接收服务状态变化，无需轮询
  */
 
-import { getTauriInvoke } from './tauri'
+import { getTauriInvoke, listenTauriEvent } from './tauri'
 import type { ServiceEvent, ServiceStateValue, ServiceStatusDTO } from './serviceManager'
 import { debug, warn } from './logger'
 
@@ -19,6 +19,8 @@ class ServiceEventManager {
   private pollingCurrentIntervalMs: number = 5000
   private visibilityHandler: (() => void) | null = null
   private lastServicesSnapshot: Map<string, { state: ServiceStateValue; isHealthy: boolean; isAvailable: boolean }> = new Map()
+  private streamSubscriptionId: string | null = null
+  private streamUnlisten: (() => void) | null = null
 
   /**
    * 订阅服务事件
@@ -78,7 +80,56 @@ class ServiceEventManager {
   }
 
   /**
-   * 启动事件轮询（临时方案，等待 WebSocket/SSE 实现）
+   * 启动事件推送：后端为本次订阅分配专用事件名，事件总线上的事件实时推送到前端
+   * 推送不可用时降级为轮询
+   */
+  async startStreaming(fallbackIntervalMs: number = 5000) {
+    if (this.streamSubscriptionId) {
+      return // 已经在接收推送
+    }
+    try {
+      const invoke = getTauriInvoke()
+      if (!invoke) {
+        throw new Error('Tauri API 不可用')
+      }
+      const subscriptionId = await invoke<string>('subscribe_service_events')
+      const unlisten = await listenTauriEvent<ServiceEvent>(subscriptionId, (event) => {
+        this.emit(event)
+      })
+      if (!unlisten) {
+        await invoke('unsubscribe_service_events', { id: subscriptionId })
+        throw new Error('事件监听不可用')
+      }
+      this.streamSubscriptionId = subscriptionId
+      this.streamUnlisten = unlisten
+      debug('[ServiceEvents] 已订阅服务事件推送:', subscriptionId)
+    } catch (error) {
+      warn('[ServiceEvents] 订阅服务事件推送失败，降级为轮询:', error)
+      this.startPolling(fallbackIntervalMs)
+    }
+  }
+
+  /**
+   * 停止事件推送（同时注销后端订阅）及降级轮询
+   */
+  stopStreaming() {
+    this.stopPolling()
+    if (this.streamUnlisten) {
+      this.streamUnlisten()
+      this.streamUnlisten = null
+    }
+    const subscriptionId = this.streamSubscriptionId
+    this.streamSubscriptionId = null
+    const invoke = getTauriInvoke()
+    if (subscriptionId && invoke) {
+      invoke('unsubscribe_service_events', { id: subscriptionId }).catch((error: unknown) => {
+        debug('[ServiceEvents] 注销服务事件订阅失败:', error)
+      })
+    }
+  }
+
+  /**
+   * 启动事件轮询（事件推送不可用时的降级方案）
    */
   startPolling(intervalMs: number = 5000) {
     if (this.pollingTimer) {
@@ -214,7 +265,21 @@ export function onServiceEvent(callback: ServiceEventCallback): () => void {
 }
 
 /**
- * 启动事件监听（轮询模式，临时方案）
+ * 启动事件监听（后端实时推送，不可用时降级为轮询）
+ */
+export function startServiceEventStream(fallbackIntervalMs: number = 5000) {
+  return serviceEventManager.startStreaming(fallbackIntervalMs)
+}
+
+/**
+ * 停止事件监听（推送模式）
+ */
+export function stopServiceEventStream() {
+  serviceEventManager.stopStreaming()
+}
+
+/**
+ * 启动事件监听（轮询模式）
  */
 export function startServiceEventPolling(intervalMs: number = 5000) {
   serviceEventManager.startPolling(intervalMs)
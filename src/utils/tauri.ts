@@ -193,3 +193,39 @@ export async function openUrlInBrowser(url: string): Promise<void> {
     throw err
   }
 }
+
+/**
+ * 监听后端发出的 Tauri 事件
+ * @param event 事件名
+ * @param handler 事件处理函数（参数为事件 payload）
+ * @returns 取消监听的函数，如果 Tauri API 不可用则返回 null
+ */
+export async function listenTauriEvent<T = unknown>(
+  event: string,
+  handler: (payload: T) => void
+): Promise<(() => void) | null> {
+  const win = window as any
+  const callback = (e: { payload: T }) => handler(e.payload)
+
+  // 1. 启用 withGlobalTauri 时直接使用全局事件 API
+  const globalListen = win.__TAURI__?.event?.listen
+  if (typeof globalListen === 'function') {
+    return await globalListen(event, callback)
+  }
+
+  // 2. 通过 __TAURI_INTERNALS__ 调用事件插件（与 @tauri-apps/api 的实现一致）
+  const internals = win.__TAURI_INTERNALS__
+  if (!internals || typeof internals.invoke !== 'function' || typeof internals.transformCallback !== 'function') {
+    return null
+  }
+  const eventId = await internals.invoke('plugin:event|listen', {
+    event,
+    target: { kind: 'Any' },
+    handler: internals.transformCallback(callback)
+  })
+  return () => {
+    internals.invoke('plugin:event|unlisten', { event, eventId }).catch((err: unknown) => {
+      warn('取消事件监听失败:', event, err)
+    })
+  }
+}
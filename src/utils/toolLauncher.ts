@@ -5,7 +5,7 @@
 
 import type { ToolItem } from '../stores/categories'
 import { getTauriInvoke, waitForTauriAPI, isTauriEnvironment } from './tauri'
-import { error } from './logger'
+import { error, info } from './logger'
 
 /**
  * 显示确认对话框的回调类型
@@ -120,6 +120,9 @@ export async function launchTool(
     const invokeParams: Record<string, unknown> = {
       tool_type: toolTypeStr,
     }
+    if (tool.id) {
+      invokeParams.tool_id = tool.id
+    }
     
     if (execPath !== undefined) {
       invokeParams.exec_path = execPath
@@ -134,9 +137,19 @@ export async function launchTool(
       invokeParams.jar_config = jarConfig
     }
     
-    await invoker('launch_tool', { params: invokeParams })
+    const outcome = await invoker('launch_tool', { params: invokeParams })
+    if (outcome === 'recently_launched') {
+      // 同一工具刚刚启动过（连点），后端已跳过本次启动
+      info('工具刚刚启动过，已跳过重复启动:', tool.name)
+    }
   } catch (err: unknown) {
-    const errorMessage = err instanceof Error ? err.message : String(err) || '未知错误'
+    // 后端返回 { kind, message } 结构的 LaunchError
+    const errorMessage =
+      err instanceof Error
+        ? err.message
+        : typeof err === 'object' && err !== null && 'message' in err
+          ? String((err as { message: unknown }).message)
+          : String(err) || '未知错误'
     error('启动工具失败:', errorMessage, tool)
     showConfirm('错误', `启动工具失败：${errorMessage}`, () => {}, 'warning')
   }
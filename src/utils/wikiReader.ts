@@ -5,6 +5,55 @@
 
 import { getTauriInvoke } from './tauri'
 import { debug, error as logError } from './logger'
+import type { SearchResponse, WikiDocument, WikiMatch } from '../types/wiki'
+
+/**
+ * 将 Wiki 文档转换为可渲染的 Markdown
+ * 纯文本 / PDF / DOCX 提取的文本放入代码块原样显示，不支持预览的格式显示提示
+ */
+export function wikiDocumentToMarkdown(doc: WikiDocument): string {
+  switch (doc.content_type) {
+    case 'markdown':
+      return doc.content
+    case 'unsupported':
+      return `# ${doc.file_name}\n\n> ${doc.message || '不支持预览该格式，请下载后查看'}\n`
+    default: {
+      const longestRun = Math.max(0, ...(doc.content.match(/`+/g) || []).map(run => run.length))
+      const fence = '`'.repeat(Math.max(3, longestRun + 1))
+      return `# ${doc.file_name}\n\n${fence}text\n${doc.content}\n${fence}\n`
+    }
+  }
+}
+
+/** read_wiki_file 改写后的图片链接前缀 */
+const WIKI_IMAGE_SCHEME = 'wiki-image://'
+
+/**
+ * 加载元素中 wiki-image:// 图片（通过 read_wiki_image 读取为 data URI），
+ * 读取失败的图片保留原链接并在 title 中显示原因
+ */
+export async function loadWikiImages(element: HTMLElement): Promise<void> {
+  const invoker = getTauriInvoke()
+  if (!invoker) {
+    return
+  }
+  const images = Array.from(element.querySelectorAll<HTMLImageElement>(`img[src^="${WIKI_IMAGE_SCHEME}"]`))
+  await Promise.all(images.map(async img => {
+    const src = img.getAttribute('src') || ''
+    const wikiRelativePath = src
+      .slice(WIKI_IMAGE_SCHEME.length)
+      .split('/')
+      .map(part => decodeURIComponent(part))
+      .join('/')
+    try {
+      img.src = await invoker<string>('read_wiki_image', { wikiRelativePath })
+    } catch (error) {
+      const errorMsg = error instanceof Error ? error.message : String(error)
+      debug('加载 Wiki 图片失败:', wikiRelativePath, errorMsg)
+      img.title = `图片加载失败: ${errorMsg}`
+    }
+  }))
+}
 
 /**
  * 读取 Wiki 文件内容
@@ -16,8 +65,9 @@ export async function readWikiFile(filePath: string): Promise<string> {
       throw new Error('Tauri API 不可用')
     }
     
-    const content = await invoker<string>('read_wiki_file', { filePath })
-    return content
+    const doc = await invoker<WikiDocument>('read_wiki_file', { filePath })
+    // 不支持预览的格式没有文本内容
+    return doc.content
   } catch (error) {
     const errorMsg = error instanceof Error ? error.message : String(error)
     logError('读取 Wiki 文件失败:', errorMsg)
@@ -35,7 +85,8 @@ export async function searchWiki(query: string): Promise<string> {
       throw new Error('Tauri API 不可用')
     }
     
-    const results = await invoker<any[]>('search_wiki', { query })
+    const response = await invoker<SearchResponse>('search_wiki', { query })
+    const results = response?.results ?? []
     
     // 将搜索结果转换为文本
     if (!results || results.length === 0) {
@@ -73,12 +124,13 @@ export async function getWikiForTool(toolId: string): Promise<string> {
       return ''
     }
     
-    const wikiPath = await invoker<string>('find_wiki_for_tool', { toolId })
-    if (!wikiPath) {
+    const matches = await invoker<WikiMatch[]>('find_wiki_for_tool', { toolId })
+    const best = matches[0]
+    if (!best) {
       return ''
     }
     
-    return await readWikiFile(wikiPath)
+    return await readWikiFile(best.path)
   } catch (error) {
     debug('获取工具 Wiki 失败:', error)
     return ''